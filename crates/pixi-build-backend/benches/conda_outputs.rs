@@ -0,0 +1,111 @@
+//! Benchmarks the backend operations for a trivial package without any
+//! dependencies or variants, so the time is dominated by the overhead of
+//! generating and rendering the recipe.
+//!
+//! Run with `pixi run bench` or `cargo bench -p pixi-build-backend`.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use miette::IntoDiagnostic;
+use pixi_build_backend::{
+    generated_recipe::{
+        BackendConfig, BuildParams, DefaultMetadataProvider, GenerateRecipe, GeneratedRecipe,
+    },
+    intermediate_backend::IntermediateBackend,
+    protocol::Protocol,
+    variants::NormalizedKey,
+};
+use pixi_build_types::{ProjectModelV1, procedures::conda_outputs::CondaOutputsParams};
+use rattler_build::console_utils::LoggingOutputHandler;
+use rattler_conda_types::Platform;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct BenchBackendConfig {
+    debug_dir: Option<PathBuf>,
+}
+
+impl BackendConfig for BenchBackendConfig {
+    fn debug_dir(&self) -> Option<&Path> {
+        self.debug_dir.as_deref()
+    }
+
+    fn merge_with_target_config(&self, _target_config: &Self) -> miette::Result<Self> {
+        Ok(self.clone())
+    }
+}
+
+/// A generator that only uses the project model, so that the benchmark
+/// doesn't depend on any backend specific manifest.
+#[derive(Default, Clone)]
+struct BenchGenerateRecipe;
+
+impl GenerateRecipe for BenchGenerateRecipe {
+    type Config = BenchBackendConfig;
+
+    fn generate_recipe(
+        &self,
+        model: &ProjectModelV1,
+        _config: &Self::Config,
+        _manifest_path: PathBuf,
+        _host_platform: Platform,
+        _build_params: Option<BuildParams>,
+        _variants: &HashSet<NormalizedKey>,
+    ) -> miette::Result<GeneratedRecipe> {
+        GeneratedRecipe::from_model(model.clone(), &mut DefaultMetadataProvider).into_diagnostic()
+    }
+}
+
+fn trivial_backend(root: &Path) -> IntermediateBackend<BenchGenerateRecipe> {
+    let project_model: ProjectModelV1 = serde_json::from_value(serde_json::json!({
+        "name": "trivial",
+        "version": "0.1.0",
+    }))
+    .unwrap();
+    let manifest_path = root.join("pixi.toml");
+    fs_err::write(&manifest_path, "").unwrap();
+
+    IntermediateBackend::new(
+        manifest_path,
+        Some(root.to_path_buf()),
+        project_model,
+        Arc::default(),
+        serde_json::json!({}),
+        Default::default(),
+        LoggingOutputHandler::default(),
+        None,
+    )
+    .unwrap()
+}
+
+fn conda_outputs(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let backend = trivial_backend(tmp_dir.path());
+
+    c.bench_function("conda_outputs trivial package", |b| {
+        b.iter(|| {
+            runtime
+                .block_on(backend.conda_outputs(CondaOutputsParams {
+                    channels: vec![],
+                    host_platform: Platform::Linux64,
+                    build_platform: Platform::Linux64,
+                    variant_configuration: None,
+                    work_directory: tmp_dir.path().join("work"),
+                }))
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, conda_outputs);
+criterion_main!(benches);
@@ -1,28 +1,40 @@
-use std::path::{Path, PathBuf};
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use clap::{Parser, Subcommand};
 use clap_verbosity_flag::{InfoLevel, Verbosity};
+use itertools::Itertools;
 use miette::{Context, IntoDiagnostic};
 use pixi_build_types::{
     BackendCapabilities, ChannelConfiguration, FrontendCapabilities, PlatformAndVirtualPackages,
     procedures::{
         conda_build_v0::CondaBuildParams,
         conda_metadata::{CondaMetadataParams, CondaMetadataResult},
+        conda_outputs::{CondaOutputMetadata, CondaOutputsParams, CondaOutputsResult},
         initialize::InitializeParams,
         negotiate_capabilities::NegotiateCapabilitiesParams,
     },
 };
 use rattler_build::console_utils::{LoggingOutputHandler, get_default_env_filter};
-use rattler_conda_types::{ChannelConfig, GenericVirtualPackage, Platform};
+use rattler_conda_types::{ChannelConfig, GenericVirtualPackage, PackageName, Platform};
 use rattler_virtual_packages::{VirtualPackage, VirtualPackageOverrides};
+use recipe_stage0::recipe::IntermediateRecipe;
 use tempfile::TempDir;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::{
     consts,
-    project::to_project_model,
-    protocol::{Protocol, ProtocolInstantiator},
-    server::Server,
+    progress::ProgressLayer,
+    project::{read_build_configuration, to_project_model},
+    protocol::{Protocol, ProtocolInstantiator, with_operation_timeout},
+    server::{MessageChannel, Server},
+    utils::{
+        ExtraChannelPriority, binary_dependency_names, diff_recipes, missing_packages,
+        with_extra_channels,
+    },
 };
 
 #[allow(missing_docs)]
@@ -37,6 +49,12 @@ pub struct App {
     #[clap(long)]
     http_port: Option<u16>,
 
+    /// Abort operations, like solving or building a package, that take
+    /// longer than this number of seconds. Takes precedence over the
+    /// `operation-timeout` of the backend configuration.
+    #[clap(long, global = true)]
+    timeout: Option<u64>,
+
     /// Enable verbose logging.
     #[command(flatten)]
     verbose: Verbosity<InfoLevel>,
@@ -51,23 +69,93 @@ pub enum Commands {
 
         #[clap(long)]
         host_platform: Option<Platform>,
+
+        /// Instead of the metadata, print for every output the variant values
+        /// and noarch type that were used to compute the hash in its build
+        /// string.
+        #[clap(long)]
+        explain_build_string: bool,
+
+        /// Write the generated recipe to this path.
+        #[clap(long)]
+        recipe_out: Option<PathBuf>,
+
+        /// Write the metadata as JSON to this path instead of printing it as
+        /// YAML. Without a path, or with `-`, the JSON is printed to stdout.
+        #[clap(
+            long,
+            num_args = 0..=1,
+            default_missing_value = "-",
+            conflicts_with = "explain_build_string"
+        )]
+        emit_metadata_json: Option<PathBuf>,
     },
     /// Build a conda package.
     CondaBuild {
         #[clap(env, long, env = "PIXI_PROJECT_MANIFEST", default_value = consts::WORKSPACE_MANIFEST)]
         manifest_path: PathBuf,
+
+        /// Write the generated recipe to this path.
+        #[clap(long)]
+        recipe_out: Option<PathBuf>,
     },
     /// Get the capabilities of the backend.
     Capabilities,
+    /// Check the project model and the backend configuration for problems
+    /// without building or solving anything.
+    Check {
+        #[clap(env, long, env = "PIXI_PROJECT_MANIFEST", default_value = consts::WORKSPACE_MANIFEST)]
+        manifest_path: PathBuf,
+    },
+    /// Generate the recipe and check that rattler-build is able to parse it,
+    /// without building or solving anything.
+    CheckRecipe {
+        #[clap(env, long, env = "PIXI_PROJECT_MANIFEST", default_value = consts::WORKSPACE_MANIFEST)]
+        manifest_path: PathBuf,
+    },
+    /// Compare the generated recipe with a previously generated recipe, e.g.
+    /// one that was written with `--recipe-out`, and print the differences.
+    DiffRecipe {
+        #[clap(env, long, env = "PIXI_PROJECT_MANIFEST", default_value = consts::WORKSPACE_MANIFEST)]
+        manifest_path: PathBuf,
+
+        /// The previously generated recipe to compare with.
+        #[clap(long)]
+        recipe: PathBuf,
+    },
+    /// Check that the binary dependencies of the manifest are available in the
+    /// channels, without solving the environments.
+    CheckDeps {
+        #[clap(env, long, env = "PIXI_PROJECT_MANIFEST", default_value = consts::WORKSPACE_MANIFEST)]
+        manifest_path: PathBuf,
+
+        /// The channels to look for the dependencies in, either as url or as
+        /// channel name.
+        #[clap(long = "channel", short = 'c', required = true)]
+        channels: Vec<String>,
+
+        /// The platform to check the dependencies for. Defaults to the current
+        /// platform.
+        #[clap(long)]
+        host_platform: Option<Platform>,
+    },
 }
 
 /// Run the sever on the specified port or over stdin/stdout.
-async fn run_server<T: ProtocolInstantiator>(port: Option<u16>, protocol: T) -> miette::Result<()> {
-    let server = Server::new(protocol);
+async fn run_server<T: ProtocolInstantiator>(
+    port: Option<u16>,
+    protocol: T,
+    operation_timeout: Option<Duration>,
+    messages: Option<MessageChannel>,
+) -> miette::Result<()> {
+    let mut server = Server::new(protocol).with_operation_timeout(operation_timeout);
     if let Some(port) = port {
         server.run_over_http(port)
     } else {
         // running over stdin/stdout
+        if let Some(messages) = messages {
+            server = server.with_message_channel(messages);
+        }
         server.run().await
     }
 }
@@ -83,12 +171,27 @@ pub(crate) async fn main_impl<T: ProtocolInstantiator, F: FnOnce(LoggingOutputHa
     let registry = tracing_subscriber::registry()
         .with(get_default_env_filter(args.verbose.log_level_filter()).into_diagnostic()?);
 
-    registry.with(log_handler.clone()).init();
+    // Report the progress of builds to the frontend. Notifications can only
+    // be sent if the server communicates over stdin/stdout.
+    let (progress_layer, messages) = if args.command.is_none() && args.http_port.is_none() {
+        let messages = MessageChannel::default();
+        let sender = messages.sender();
+        let layer = ProgressLayer::new(move |notification| sender.send_progress(notification));
+        (Some(layer), Some(messages))
+    } else {
+        (None, None)
+    };
+
+    registry
+        .with(log_handler.clone())
+        .with(progress_layer)
+        .init();
 
     let factory = factory(log_handler);
+    let operation_timeout = args.timeout.map(Duration::from_secs);
 
     match args.command {
-        None => run_server(args.http_port, factory).await,
+        None => run_server(args.http_port, factory, operation_timeout, messages).await,
         Some(Commands::Capabilities) => {
             let backend_capabilities = capabilities::<T>().await?;
             eprintln!(
@@ -128,13 +231,116 @@ pub(crate) async fn main_impl<T: ProtocolInstantiator, F: FnOnce(LoggingOutputHa
             );
             Ok(())
         }
-        Some(Commands::CondaBuild { manifest_path }) => build(factory, &manifest_path).await,
+        Some(Commands::CondaBuild {
+            manifest_path,
+            recipe_out,
+        }) => build(factory, &manifest_path, recipe_out, operation_timeout).await,
+        Some(Commands::Check { manifest_path }) => {
+            let problems = check(factory, &manifest_path).await;
+            if problems.is_empty() {
+                eprintln!("No problems found in {}", manifest_path.display());
+                return Ok(());
+            }
+            for problem in &problems {
+                eprintln!("- {problem}");
+            }
+            miette::bail!(
+                "found {} problem(s) in {}",
+                problems.len(),
+                manifest_path.display()
+            )
+        }
+        Some(Commands::CheckRecipe { manifest_path }) => {
+            let problems = check_recipe(factory, &manifest_path).await?;
+            if problems.is_empty() {
+                eprintln!(
+                    "The generated recipe for {} is valid",
+                    manifest_path.display()
+                );
+                return Ok(());
+            }
+            for problem in &problems {
+                eprintln!("- {problem}");
+            }
+            miette::bail!(
+                "found {} problem(s) in the generated recipe for {}",
+                problems.len(),
+                manifest_path.display()
+            )
+        }
+        Some(Commands::DiffRecipe {
+            manifest_path,
+            recipe,
+        }) => {
+            let diff = diff_recipe(factory, &manifest_path, &recipe).await?;
+            if diff.is_empty() {
+                eprintln!("The generated recipe is equal to {}", recipe.display());
+            } else {
+                print!("{diff}");
+            }
+            Ok(())
+        }
+        Some(Commands::CheckDeps {
+            manifest_path,
+            channels,
+            host_platform,
+        }) => {
+            let missing = check_deps(&manifest_path, &channels, host_platform).await?;
+            if missing.is_empty() {
+                eprintln!(
+                    "All dependencies of {} are available",
+                    manifest_path.display()
+                );
+                return Ok(());
+            }
+            for name in &missing {
+                eprintln!("- {}", name.as_source());
+            }
+            miette::bail!(
+                "{} dependencies of {} are not available in the channels",
+                missing.len(),
+                manifest_path.display()
+            )
+        }
         Some(Commands::GetCondaMetadata {
             manifest_path,
             host_platform,
+            explain_build_string: true,
+            recipe_out,
+            ..
         }) => {
-            let metadata = conda_get_metadata(factory, &manifest_path, host_platform).await?;
-            println!("{}", serde_yaml::to_string(&metadata).unwrap());
+            let result = conda_outputs(
+                factory,
+                &manifest_path,
+                host_platform,
+                recipe_out,
+                operation_timeout,
+            )
+            .await?;
+            for output in &result.outputs {
+                println!("{}", explain_build_string(&output.metadata));
+            }
+            Ok(())
+        }
+        Some(Commands::GetCondaMetadata {
+            manifest_path,
+            host_platform,
+            explain_build_string: false,
+            recipe_out,
+            emit_metadata_json,
+        }) => {
+            let metadata = conda_get_metadata(
+                factory,
+                &manifest_path,
+                host_platform,
+                recipe_out,
+                operation_timeout,
+            )
+            .await?;
+            match emit_metadata_json {
+                Some(path) => write_metadata_json(&metadata, &path)?,
+                None => println!("{}", serde_yaml::to_string(&metadata).unwrap()),
+            }
             Ok(())
         }
     }
@@ -157,10 +363,16 @@ pub async fn main_ext<T: ProtocolInstantiator, F: FnOnce(LoggingOutputHandler) -
     main_impl(factory, args).await
 }
 
-/// Negotiate the capabilities of the backend and initialize the backend.
+/// Negotiate the capabilities of the backend and initialize the backend. If
+/// `recipe_out` is set, the backend writes the generated recipe to it.
+/// `operation_timeout` takes precedence over the timeout of the backend
+/// configuration.
 async fn initialize<T: ProtocolInstantiator>(
     factory: T,
     manifest_path: &Path,
+    configuration: Option<serde_json::Value>,
+    recipe_out: Option<PathBuf>,
+    operation_timeout: Option<Duration>,
 ) -> miette::Result<Box<dyn Protocol + Send + Sync + 'static>> {
     // Negotiate the capabilities of the backend.
     let capabilities = capabilities::<T>().await?;
@@ -186,18 +398,21 @@ async fn initialize<T: ProtocolInstantiator>(
     }
 
     // Initialize the backend
-    let (protocol, _initialize_result) = factory
+    let (mut protocol, _initialize_result) = factory
         .initialize(InitializeParams {
             workspace_root: None,
             source_dir: None,
             manifest_path: manifest_path.to_path_buf(),
             project_model,
             cache_directory: None,
-            configuration: None,
+            configuration,
             target_configuration: None,
         })
         .await?;
-    Ok(protocol)
+    if let Some(recipe_out) = recipe_out {
+        protocol.set_recipe_out(recipe_out);
+    }
+    Ok(with_operation_timeout(protocol, operation_timeout))
 }
 
 /// Frontend implementation for getting conda metadata.
@@ -205,6 +420,8 @@ async fn conda_get_metadata<T: ProtocolInstantiator>(
     factory: T,
     manifest_path: &Path,
     host_platform: Option<Platform>,
+    recipe_out: Option<PathBuf>,
+    operation_timeout: Option<Duration>,
 ) -> miette::Result<CondaMetadataResult> {
     let channel_config = ChannelConfig::default_with_root_dir(
         manifest_path
@@ -213,7 +430,7 @@ async fn conda_get_metadata<T: ProtocolInstantiator>(
             .to_path_buf(),
     );
 
-    let protocol = initialize(factory, manifest_path).await?;
+    let protocol = initialize(factory, manifest_path, None, recipe_out, operation_timeout).await?;
 
     let virtual_packages: Vec<_> = VirtualPackage::detect(&VirtualPackageOverrides::from_env())
         .into_diagnostic()?
@@ -242,6 +459,74 @@ async fn conda_get_metadata<T: ProtocolInstantiator>(
         .await
 }
 
+/// Writes the metadata as JSON to `path`, or to stdout if `path` is `-`.
+fn write_metadata_json(metadata: &CondaMetadataResult, path: &Path) -> miette::Result<()> {
+    let json = serde_json::to_string_pretty(metadata).into_diagnostic()?;
+    if path == Path::new("-") {
+        println!("{json}");
+        return Ok(());
+    }
+    fs_err::write(path, json).into_diagnostic()
+}
+
+/// Frontend implementation for getting the outputs of a package.
+async fn conda_outputs<T: ProtocolInstantiator>(
+    factory: T,
+    manifest_path: &Path,
+    host_platform: Option<Platform>,
+    recipe_out: Option<PathBuf>,
+    operation_timeout: Option<Duration>,
+) -> miette::Result<CondaOutputsResult> {
+    let protocol = initialize(factory, manifest_path, None, recipe_out, operation_timeout).await?;
+
+    let tempdir = TempDir::new_in(".")
+        .into_diagnostic()
+        .context("failed to create a temporary directory in the current directory")?;
+
+    protocol
+        .conda_outputs(CondaOutputsParams {
+            channels: vec![],
+            host_platform: host_platform.unwrap_or(Platform::current()),
+            build_platform: Platform::current(),
+            variant_configuration: None,
+            work_directory: tempdir.path().to_path_buf(),
+        })
+        .await
+}
+
+/// Describes how the build string of an output came to be. The hash in the
+/// build string is computed from the variant values that are used by the
+/// output and its noarch type, so two outputs only share a hash if all of
+/// these are equal.
+fn explain_build_string(metadata: &CondaOutputMetadata) -> String {
+    let noarch = if metadata.noarch.is_python() {
+        "python"
+    } else if metadata.noarch.is_generic() {
+        "generic"
+    } else {
+        "none"
+    };
+
+    let mut explanation = format!(
+        "{}-{}-{} ({})\n",
+        metadata.name.as_normalized(),
+        metadata.version,
+        metadata.build,
+        metadata.subdir
+    );
+    writeln!(explanation, "  build string: {}", metadata.build).unwrap();
+    writeln!(explanation, "  noarch: {noarch}").unwrap();
+    if metadata.variant.is_empty() {
+        writeln!(explanation, "  variant: <none>").unwrap();
+    } else {
+        writeln!(explanation, "  variant:").unwrap();
+        for (key, value) in &metadata.variant {
+            writeln!(explanation, "    {key} = {value}").unwrap();
+        }
+    }
+    explanation
+}
+
 /// Returns the capabilities of the backend.
 async fn capabilities<Factory: ProtocolInstantiator>() -> miette::Result<BackendCapabilities> {
     let result = Factory::negotiate_capabilities(NegotiateCapabilitiesParams {
@@ -253,7 +538,12 @@ async fn capabilities<Factory: ProtocolInstantiator>() -> miette::Result<Backend
 }
 
 /// Frontend implementation for building a conda package.
-async fn build<T: ProtocolInstantiator>(factory: T, manifest_path: &Path) -> miette::Result<()> {
+async fn build<T: ProtocolInstantiator>(
+    factory: T,
+    manifest_path: &Path,
+    recipe_out: Option<PathBuf>,
+    operation_timeout: Option<Duration>,
+) -> miette::Result<()> {
     let channel_config = ChannelConfig::default_with_root_dir(
         manifest_path
             .parent()
@@ -261,7 +551,7 @@ async fn build<T: ProtocolInstantiator>(factory: T, manifest_path: &Path) -> mie
             .to_path_buf(),
     );
 
-    let protocol = initialize(factory, manifest_path).await?;
+    let protocol = initialize(factory, manifest_path, None, recipe_out, operation_timeout).await?;
     let work_dir = TempDir::new_in(".")
         .into_diagnostic()
         .context("failed to create a temporary directory in the current directory")?;
@@ -291,3 +581,133 @@ async fn build<T: ProtocolInstantiator>(factory: T, manifest_path: &Path) -> mie
 
     Ok(())
 }
+
+/// Frontend implementation for checking a project. Every problem that is
+/// found is returned, an empty list means the project is valid.
+async fn check<T: ProtocolInstantiator>(factory: T, manifest_path: &Path) -> Vec<String> {
+    if !manifest_path.is_file() {
+        return vec![format!(
+            "the manifest '{}' does not exist",
+            manifest_path.display()
+        )];
+    }
+
+    let problems = async {
+        let configuration = read_build_configuration(manifest_path)?;
+        let protocol = initialize(factory, manifest_path, configuration, None, None).await?;
+        protocol.check().await
+    }
+    .await;
+
+    problems.unwrap_or_else(|err| vec![err.chain().map(ToString::to_string).join(": ")])
+}
+
+/// Frontend implementation for checking that the generated recipe can be
+/// parsed by rattler-build.
+async fn check_recipe<T: ProtocolInstantiator>(
+    factory: T,
+    manifest_path: &Path,
+) -> miette::Result<Vec<String>> {
+    let configuration = read_build_configuration(manifest_path)?;
+    let protocol = initialize(factory, manifest_path, configuration, None, None).await?;
+    protocol.check_recipe().await
+}
+
+/// Frontend implementation for comparing the generated recipe with the recipe
+/// at `recipe_path`. Returns a unified diff, which is empty if the recipes are
+/// equal.
+async fn diff_recipe<T: ProtocolInstantiator>(
+    factory: T,
+    manifest_path: &Path,
+    recipe_path: &Path,
+) -> miette::Result<String> {
+    let old_recipe = fs_err::read_to_string(recipe_path).into_diagnostic()?;
+    let old_recipe = IntermediateRecipe::from_yaml(&old_recipe)
+        .into_diagnostic()
+        .with_context(|| format!("failed to parse the recipe {}", recipe_path.display()))?;
+
+    let configuration = read_build_configuration(manifest_path)?;
+    let protocol = initialize(factory, manifest_path, configuration, None, None).await?;
+    let new_recipe = protocol.generate_recipe().await?;
+    Ok(diff_recipes(&old_recipe, &new_recipe))
+}
+
+/// Frontend implementation for checking that the binary dependencies of the
+/// manifest are available in the channels.
+async fn check_deps(
+    manifest_path: &Path,
+    channels: &[String],
+    host_platform: Option<Platform>,
+) -> miette::Result<Vec<PackageName>> {
+    let channel_config = ChannelConfig::default_with_root_dir(
+        manifest_path
+            .parent()
+            .expect("manifest should always reside in a directory")
+            .to_path_buf(),
+    );
+    let project_model = to_project_model(manifest_path, &channel_config, None)?
+        .and_then(|project_model| project_model.into_v1())
+        .ok_or_else(|| {
+            miette::miette!(
+                "could not extract the project model from {}",
+                manifest_path.display()
+            )
+        })?;
+
+    let channels = with_extra_channels(
+        Vec::new(),
+        channels,
+        ExtraChannelPriority::default(),
+        &channel_config,
+    )?;
+    let platform = host_platform.unwrap_or_else(Platform::current);
+    let names = binary_dependency_names(&project_model, platform)?;
+    missing_packages(&names, &channels, platform).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use rattler_conda_types::{NoArchType, PackageName};
+
+    use super::*;
+
+    fn output_metadata(variant: BTreeMap<String, String>) -> CondaOutputMetadata {
+        CondaOutputMetadata {
+            name: PackageName::new_unchecked("foobar"),
+            version: "0.1.0".parse().unwrap(),
+            build: String::from("h1234567_0"),
+            build_number: 0,
+            subdir: Platform::Linux64,
+            license: None,
+            license_family: None,
+            noarch: NoArchType::none(),
+            purls: None,
+            python_site_packages_path: None,
+            variant,
+        }
+    }
+
+    #[test]
+    fn test_explain_build_string_lists_variant_keys() {
+        let metadata = output_metadata(BTreeMap::from([
+            (String::from("python"), String::from("3.12")),
+            (String::from("target_platform"), String::from("linux-64")),
+        ]));
+
+        let explanation = explain_build_string(&metadata);
+
+        assert!(explanation.contains("build string: h1234567_0"));
+        assert!(explanation.contains("noarch: none"));
+        assert!(explanation.contains("python = 3.12"));
+        assert!(explanation.contains("target_platform = linux-64"));
+    }
+
+    #[test]
+    fn test_explain_build_string_without_variant() {
+        let explanation = explain_build_string(&output_metadata(BTreeMap::new()));
+
+        assert!(explanation.contains("variant: <none>"));
+    }
+}
@@ -86,10 +86,13 @@ pub fn compiler_requirement(language: &Language) -> Item<PackageDependency> {
 ///   requirements
 /// * `host_platform` - The target platform for determining default compiler
 ///   names
+/// * `stdlibs` - List of standard libraries to add (e.g., ["c", "fortran"]).
+///   If `None`, the standard libraries are derived from the compilers.
 /// * `variants` - The variants available in the recipe, used to determine if
 ///   stdlib is needed
 pub fn add_compilers_and_stdlib_to_requirements(
     compilers: &[String],
+    stdlibs: Option<&[String]>,
     requirements: &mut Vec<Item<PackageDependency>>,
     resolved_build_requirements: &IndexMap<PackageName, PackageDependency>,
     host_platform: &Platform,
@@ -101,7 +104,7 @@ pub fn add_compilers_and_stdlib_to_requirements(
         resolved_build_requirements,
         host_platform,
     );
-    add_stdlib_to_requirements(compilers, requirements, variants);
+    add_stdlib_to_requirements(compilers, stdlibs, requirements, variants);
 }
 
 pub fn add_compilers_to_requirements(
@@ -122,6 +125,57 @@ pub fn add_compilers_to_requirements(
     }
 }
 
+/// Returns the compilers together with the `variant_compilers` for which the
+/// variants contain a `<compiler>_compiler_version` key, e.g. `cuda` is only
+/// added if there is a `cuda_compiler_version` variant. Compilers that occur
+/// in both lists are only returned once.
+pub fn with_variant_compilers(
+    compilers: Vec<String>,
+    variant_compilers: &[String],
+    variants: &HashSet<NormalizedKey>,
+) -> Vec<String> {
+    compilers
+        .into_iter()
+        .chain(
+            variant_compilers
+                .iter()
+                .filter(|compiler| {
+                    variants.contains(&NormalizedKey(format!("{compiler}_compiler_version")))
+                })
+                .cloned(),
+        )
+        .unique()
+        .collect()
+}
+
+/// Merges the compilers of a base configuration with the compilers of a
+/// target-specific configuration.
+///
+/// The compilers of the target are added to the compilers of the base, so a
+/// target can add e.g. `cuda` on top of a base `["cxx"]`. If the base doesn't
+/// configure any compilers, the target's compilers are added to the `default`
+/// compilers of the backend instead. The order is preserved and compilers that
+/// occur in both lists are only returned once.
+pub fn merge_compilers(
+    base: Option<&[String]>,
+    target: Option<&[String]>,
+    default: &[&str],
+) -> Option<Vec<String>> {
+    let Some(target) = target else {
+        return base.map(<[String]>::to_vec);
+    };
+    let base = match base {
+        Some(base) => base.to_vec(),
+        None => default.iter().map(ToString::to_string).collect(),
+    };
+    Some(
+        base.into_iter()
+            .chain(target.iter().cloned())
+            .unique()
+            .collect(),
+    )
+}
+
 /// Returns the standard library for a given language, if applicable.
 ///
 /// The implementation just always returns `c` for all languages except for some
@@ -133,18 +187,30 @@ fn stdlib_for_language(lang: &str) -> Option<&'static str> {
     }
 }
 
+/// Add the standard libraries to the build requirements if the variants
+/// contain a `<stdlib>_stdlib` key for them.
+///
+/// If `stdlibs` is `None`, the standard libraries are derived from the
+/// compilers, which results in the `c` standard library for almost all
+/// languages.
 pub fn add_stdlib_to_requirements(
     compilers: &[String],
+    stdlibs: Option<&[String]>,
     requirements: &mut Vec<Item<PackageDependency>>,
     variants: &HashSet<NormalizedKey>,
 ) {
-    // For each compiler check if there is a variant stdlib(compiler) key.
-    for stdlib in compilers
-        .iter()
-        .map(Deref::deref)
-        .filter_map(stdlib_for_language)
-        .unique()
-    {
+    let stdlibs: Vec<&str> = match stdlibs {
+        Some(stdlibs) => stdlibs.iter().map(Deref::deref).unique().collect(),
+        None => compilers
+            .iter()
+            .map(Deref::deref)
+            .filter_map(stdlib_for_language)
+            .unique()
+            .collect(),
+    };
+
+    // For each stdlib check if there is a variant `<stdlib>_stdlib` key.
+    for stdlib in stdlibs {
         let stdlib_key = format!("{stdlib}_stdlib");
         if !variants.contains(&NormalizedKey(stdlib_key)) {
             continue;
@@ -191,4 +257,100 @@ mod tests {
         let result = compiler_requirement(&Language::Other("python"));
         assert_yaml_snapshot!(result);
     }
+
+    #[test]
+    fn test_merge_compilers() {
+        let base = vec!["cxx".to_string()];
+        let linux = vec!["c".to_string(), "cxx".to_string(), "cuda".to_string()];
+
+        assert_eq!(
+            merge_compilers(Some(&base), Some(&linux), &[]),
+            Some(vec!["cxx".to_string(), "c".to_string(), "cuda".to_string()])
+        );
+        assert_eq!(merge_compilers(Some(&base), None, &[]), Some(base.clone()));
+        assert_eq!(
+            merge_compilers(None, Some(&linux), &[]),
+            Some(linux.clone())
+        );
+        assert_eq!(merge_compilers(None, None, &["cxx"]), None);
+
+        // Without base compilers the target adds to the default compilers.
+        let cuda = vec!["cuda".to_string()];
+        assert_eq!(
+            merge_compilers(None, Some(&cuda), &["cxx"]),
+            Some(vec!["cxx".to_string(), "cuda".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_variant_compilers() {
+        let variant_compilers = vec!["cuda".to_string()];
+        let compilers = |variants: &[&str]| {
+            let variants: HashSet<_> = variants
+                .iter()
+                .map(|key| NormalizedKey(key.to_string()))
+                .collect();
+            with_variant_compilers(vec!["cxx".to_string()], &variant_compilers, &variants)
+        };
+
+        assert_eq!(compilers(&[]), vec!["cxx".to_string()]);
+        assert_eq!(compilers(&["c_stdlib"]), vec!["cxx".to_string()]);
+        assert_eq!(
+            compilers(&["cuda_compiler_version"]),
+            vec!["cxx".to_string(), "cuda".to_string()]
+        );
+    }
+
+    fn stdlib_requirements(
+        compilers: &[&str],
+        stdlibs: Option<&[String]>,
+        variants: &[&str],
+    ) -> Vec<String> {
+        let compilers = compilers.iter().map(ToString::to_string).collect_vec();
+        let variants: HashSet<_> = variants
+            .iter()
+            .map(|key| NormalizedKey(key.to_string()))
+            .collect();
+
+        let mut requirements = Vec::new();
+        add_stdlib_to_requirements(&compilers, stdlibs, &mut requirements, &variants);
+        requirements.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_stdlib_defaults_to_c() {
+        let requirements = stdlib_requirements(
+            &["c", "cxx", "fortran"],
+            None,
+            &["c_stdlib", "fortran_stdlib"],
+        );
+        assert_eq!(requirements, vec!["${{ stdlib('c') }}"]);
+    }
+
+    #[test]
+    fn test_stdlib_requires_variant_key() {
+        let requirements = stdlib_requirements(&["c"], None, &[]);
+        assert!(requirements.is_empty());
+    }
+
+    #[test]
+    fn test_configured_stdlibs() {
+        let stdlibs = vec!["c".to_string(), "fortran".to_string(), "cuda".to_string()];
+        let requirements = stdlib_requirements(
+            &["fortran"],
+            Some(&stdlibs),
+            &["c_stdlib", "fortran_stdlib"],
+        );
+        assert_eq!(
+            requirements,
+            vec!["${{ stdlib('c') }}", "${{ stdlib('fortran') }}"]
+        );
+    }
+
+    #[test]
+    fn test_configured_stdlibs_without_compilers() {
+        let stdlibs = vec!["fortran".to_string()];
+        let requirements = stdlib_requirements(&[], Some(&stdlibs), &["fortran_stdlib"]);
+        assert_eq!(requirements, vec!["${{ stdlib('fortran') }}"]);
+    }
 }
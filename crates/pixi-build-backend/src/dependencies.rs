@@ -266,7 +266,15 @@ fn convert_dependency(
                 .apply(&subpackage.version, &subpackage.build_string)
                 .map_err(ConvertDependencyError::PinApplyError)?
         }
-        _ => todo!("Handle other dependency types"),
+        Dependency::PinCompatible(pin) => {
+            // The pin refers to the version of the package in the host
+            // environment, which is not solved yet when the outputs are
+            // determined. The pin is applied when the package is built.
+            MatchSpec::from_nameless(
+                NamelessMatchSpec::default(),
+                Some(pin.pin_value().name.clone()),
+            )
+        }
     };
 
     let (Some(name), spec) = match_spec.into_nameless() else {
@@ -311,7 +319,10 @@ fn convert_binary_dependency(
                 .apply(&subpackage.version, &subpackage.build_string)
                 .map_err(ConvertDependencyError::PinApplyError)?
         }
-        _ => todo!("Handle other dependency types"),
+        Dependency::PinCompatible(pin) => MatchSpec::from_nameless(
+            NamelessMatchSpec::default(),
+            Some(pin.pin_value().name.clone()),
+        ),
     };
 
     // Apply a variant if it is applicable.
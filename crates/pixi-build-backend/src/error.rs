@@ -0,0 +1,121 @@
+use std::{path::PathBuf, time::Duration};
+
+use miette::Diagnostic;
+use rattler_build::{source_code::Source, variant_config::ParseErrors};
+use rattler_conda_types::{InvalidPackageNameError, ParseVersionError, Platform};
+use thiserror::Error;
+
+use crate::generated_recipe::GenerateRecipeError;
+
+/// Errors that are shared by all backends.
+///
+/// In contrast to ad-hoc [`miette::miette!`] messages these can be matched on,
+/// e.g. to raise a distinct exception from the Python bindings. A
+/// [`miette::Report`] that was created from a [`BackendError`] can be
+/// inspected with [`miette::Report::downcast_ref`].
+#[derive(Debug, Error, Diagnostic)]
+pub enum BackendError {
+    #[error("There was no name defined for the recipe")]
+    #[diagnostic(help("set the `name` of the package in the manifest"))]
+    MissingName,
+
+    #[error("There was no version defined for the recipe")]
+    #[diagnostic(help("set the `version` of the package in the manifest"))]
+    MissingVersion,
+
+    #[error("The default version '{0}' is not a valid version")]
+    #[diagnostic(help("set `default-version` in the backend configuration to a valid version"))]
+    InvalidDefaultVersion(String, #[source] ParseVersionError),
+
+    #[error("The name '{0}' is not a valid package name")]
+    InvalidPackageName(String, #[source] InvalidPackageNameError),
+
+    #[error("An error occurred while querying the {0}")]
+    MetadataProvider(String, #[source] Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("the manifest '{}' does not exist", .0.display())]
+    ManifestNotFound(PathBuf),
+
+    #[error("the backend expects {0} in '{}'", .1.display())]
+    #[diagnostic(help("create the manifest next to the project manifest"))]
+    BackendManifestNotFound(String, PathBuf),
+
+    #[error("failed to parse {0}")]
+    InvalidConfig(String, #[source] serde_json::Error),
+
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    RecipeParse(ParseErrors<Source>),
+
+    #[error("{0} timed out after {}s", .1.as_secs())]
+    #[diagnostic(help(
+        "increase `operation-timeout` in the backend configuration or pass a larger `--timeout`"
+    ))]
+    OperationTimeout(&'static str, Duration),
+
+    #[error("the backend cannot build packages for '{0}', it only supports {1}")]
+    UnsupportedPlatform(Platform, String),
+
+    #[error("the input glob '{0}' is not a valid glob pattern")]
+    #[diagnostic(help("check the `extra-input-globs` in the backend configuration"))]
+    InvalidInputGlob(String, #[source] globset::Error),
+
+    #[error("the license '{0}' is not a valid SPDX license expression")]
+    #[diagnostic(help("use a SPDX license expression like `MIT OR Apache-2.0`"))]
+    InvalidLicense(String, #[source] spdx::ParseError),
+}
+
+impl<E: Diagnostic + Send + Sync + 'static> From<GenerateRecipeError<E>> for BackendError {
+    fn from(err: GenerateRecipeError<E>) -> Self {
+        match err {
+            GenerateRecipeError::NoNameDefined => BackendError::MissingName,
+            GenerateRecipeError::NoVersionDefined => BackendError::MissingVersion,
+            GenerateRecipeError::InvalidDefaultVersion(version, err) => {
+                BackendError::InvalidDefaultVersion(version, err)
+            }
+            GenerateRecipeError::InvalidPackageName(name, err) => {
+                BackendError::InvalidPackageName(name, err)
+            }
+            GenerateRecipeError::MetadataProviderError(field, err) => {
+                BackendError::MetadataProvider(field, Box::new(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pixi_build_types::ProjectModelV1;
+
+    use super::*;
+    use crate::generated_recipe::{DefaultMetadataProvider, GeneratedRecipe};
+
+    fn from_model(model: serde_json::Value) -> BackendError {
+        let model: ProjectModelV1 = serde_json::from_value(model).unwrap();
+        GeneratedRecipe::from_model(model, &mut DefaultMetadataProvider)
+            .err()
+            .expect("the model should be rejected")
+            .into()
+    }
+
+    #[test]
+    fn test_empty_name_is_missing_name() {
+        let err = from_model(serde_json::json!({ "name": "", "version": "0.1.0" }));
+        assert!(matches!(err, BackendError::MissingName));
+    }
+
+    #[test]
+    fn test_missing_version() {
+        let err = from_model(serde_json::json!({ "name": "foobar" }));
+        assert!(matches!(err, BackendError::MissingVersion));
+    }
+
+    #[test]
+    fn test_report_can_be_downcast() {
+        let report = miette::Report::from(BackendError::MissingName);
+        assert!(matches!(
+            report.downcast_ref::<BackendError>(),
+            Some(BackendError::MissingName)
+        ));
+    }
+}
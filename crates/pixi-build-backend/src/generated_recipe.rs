@@ -1,27 +1,54 @@
-use miette::Diagnostic;
+use indexmap::IndexMap;
+use miette::{Context, Diagnostic, IntoDiagnostic};
 use pixi_build_types::ProjectModelV1;
 use rattler_build::{NormalizedKey, recipe::variable::Variable};
-use rattler_conda_types::{Platform, Version};
-use recipe_stage0::recipe::{About, IntermediateRecipe, Package, Value};
-use serde::de::DeserializeOwned;
+use rattler_conda_types::{
+    InvalidPackageNameError, MatchSpec, PackageName, ParseVersionError, Platform, Version,
+};
+use recipe_stage0::{
+    matchspec::PackageDependency,
+    recipe::{
+        About, Conditional, ConditionalList, Extra, IntermediateRecipe, Item, ListOrItem,
+        MultiOutputRecipe, Package, PathSource, Source, SourceFilter, Value,
+    },
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::collections::HashSet;
+use std::str::FromStr;
 use std::{
     collections::{BTreeMap, BTreeSet},
     convert::Infallible,
     fmt::Debug,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
+    time::Duration,
 };
 use thiserror::Error;
 
-use crate::specs_conversion::from_targets_v1_to_conditional_requirements;
+use crate::{
+    specs_conversion::from_targets_v1_to_conditional_requirements,
+    utils::{BuildProfile, ExtraChannelPriority, SolveStrategy, VersionNormalization},
+};
+
+/// The version of a package that doesn't define a version, unless a
+/// [`BackendConfig::default_version`] is configured or a version is required.
+pub const DEFAULT_VERSION: &str = "0.1.0";
 
+/// Parameters of a build that are requested by the frontend or derived from
+/// the configuration, and that every backend maps to its own settings.
 #[derive(Debug, Clone, Default)]
-pub struct PythonParams {
+pub struct BuildParams {
     // Returns whetever the build is editable or not.
     // Default to false
     pub editable: bool,
+    /// The profile that the package is built with, backends map it to their
+    /// own settings. If not set, the backend defaults are used.
+    pub profile: Option<BuildProfile>,
 }
 
+/// The previous name of [`BuildParams`], which is still used by the python
+/// bindings.
+pub type PythonParams = BuildParams;
+
 /// The trait is responsible of converting a certain [`ProjectModelV1`] (or
 /// others in the future) into an [`IntermediateRecipe`].
 /// By implementing this trait, you can create a new backend for `pixi-build`.
@@ -46,8 +73,8 @@ pub trait GenerateRecipe {
     ///   Right now it is used to determine if certain dependencies are present
     ///   for the host platform. Instead, we should rely on recipe selectors and
     ///   offload all the evaluation logic to the rattler-build.
-    /// * `python_params` - Used only by python backend right now and may
-    ///   be removed when profiles will be implemented.
+    /// * `build_params` - Whether the package is installed in editable mode
+    ///   and the [`BuildProfile`] that the package is built with.
     /// * `variants` - The variant names that are available to the recipe. This might
     ///   influence how the recipe is generated.
     fn generate_recipe(
@@ -56,7 +83,7 @@ pub trait GenerateRecipe {
         config: &Self::Config,
         manifest_path: PathBuf,
         host_platform: Platform,
-        python_params: Option<PythonParams>,
+        build_params: Option<BuildParams>,
         variants: &HashSet<NormalizedKey>,
     ) -> miette::Result<GeneratedRecipe>;
 
@@ -64,6 +91,7 @@ pub trait GenerateRecipe {
     /// for the build process.
     /// For example, this could be a list of source files or configuration files
     /// used by Cmake.
+    /// The `editable` flag is the value resolved by [`BackendConfig::editable`].
     fn extract_input_globs_from_build(
         _config: &Self::Config,
         _workdir: impl AsRef<Path>,
@@ -78,16 +106,243 @@ pub trait GenerateRecipe {
     ///
     /// This can be useful to change the default behavior of rattler-build with
     /// regard to compilers. But it also allows setting up default build
-    /// matrices.
-    fn default_variants(&self, _host_platform: Platform) -> BTreeMap<NormalizedKey, Vec<Variable>> {
+    /// matrices. The `config` is the backend configuration for the host
+    /// platform.
+    fn default_variants(
+        &self,
+        _config: &Self::Config,
+        _host_platform: Platform,
+    ) -> BTreeMap<NormalizedKey, Vec<Variable>> {
         BTreeMap::new()
     }
+
+    /// Validates the project model and the configuration without building or
+    /// solving anything. Returns a human readable description of every
+    /// problem that was found, e.g. configuration options that conflict with
+    /// each other.
+    ///
+    /// Problems that are already detected while generating the recipe, like a
+    /// missing name or version, don't have to be reported here.
+    fn validate(&self, _model: &ProjectModelV1, _config: &Self::Config) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// The host platforms the backend can build packages for, or `None` if
+    /// it supports every platform. Requests for other platforms are rejected
+    /// before a recipe is generated.
+    fn supported_platforms(&self) -> Option<&[Platform]> {
+        None
+    }
+
+    /// Urls of channels that the generated packages depend on, e.g. the
+    /// channel that distributes the compiler. A recipe warning is reported if
+    /// none of the channels that are requested by the frontend is one of these
+    /// channels or a channel below it.
+    fn required_channels(&self) -> &[&'static str] {
+        &[]
+    }
+
+    /// The file names of the manifests that the backend reads from the
+    /// directory of the project manifest, e.g. `Cargo.toml`. One of them must
+    /// exist, otherwise initializing the backend fails with a clear error
+    /// instead of failing once the recipe is generated or built. Defaults to
+    /// no manifest.
+    fn expected_manifests(&self, _config: &Self::Config) -> &[&'static str] {
+        &[]
+    }
+
+    /// Returns the name and the version of the backend. Unless disabled with
+    /// [`BackendConfig::record_provenance`], these are recorded in the extra
+    /// metadata of the built packages.
+    fn backend_identifier(&self) -> Option<BackendIdentifier> {
+        None
+    }
+
+    /// Generates a single recipe for a workspace that contains multiple
+    /// packages. Every package is turned into a recipe with
+    /// [`GenerateRecipe::generate_recipe`] and becomes one output of the
+    /// resulting multi-output recipe. The context of the packages is shared,
+    /// packages that define the same context variable with different values
+    /// are rejected.
+    ///
+    /// Each package is given together with the path to its manifest, the
+    /// directory of the manifest is the root of the package.
+    fn generate_workspace_recipe(
+        &self,
+        packages: &[(ProjectModelV1, PathBuf)],
+        config: &Self::Config,
+        host_platform: Platform,
+        build_params: Option<BuildParams>,
+        variants: &HashSet<NormalizedKey>,
+    ) -> miette::Result<GeneratedWorkspaceRecipe> {
+        let recipes = packages
+            .iter()
+            .map(|(model, manifest_path)| {
+                let manifest_root = manifest_path
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default();
+                self.generate_recipe(
+                    model,
+                    config,
+                    manifest_root,
+                    host_platform,
+                    build_params.clone(),
+                    variants,
+                )
+            })
+            .collect::<miette::Result<Vec<_>>>()?;
+        GeneratedWorkspaceRecipe::from_recipes(recipes)
+    }
+}
+
+/// The name and the version of a backend, e.g. `pixi-build-python` `0.3.2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackendIdentifier {
+    pub name: &'static str,
+    pub version: &'static str,
 }
 
 pub trait BackendConfig: DeserializeOwned + Clone {
     /// At least debug dir should be provided by the backend config
     fn debug_dir(&self) -> Option<&Path>;
 
+    /// The number of times a failed build should be retried. Defaults to no
+    /// retries.
+    fn build_retries(&self) -> Option<u32> {
+        None
+    }
+
+    /// The archive types of the built packages, e.g. `["conda", "tar.bz2"]`.
+    /// Defaults to `.conda` packages. Only `conda/build_v0` can return
+    /// multiple packages, `conda/build_v1` only builds the first archive type.
+    fn archive_types(&self) -> &[String] {
+        &[]
+    }
+
+    /// Whether the name and the version of the backend are recorded in the
+    /// extra metadata of the built packages. Defaults to `true`.
+    fn record_provenance(&self) -> bool {
+        true
+    }
+
+    /// Whether the rendered recipe is stored in the `info/recipe` directory
+    /// of the built packages. Defaults to `false`.
+    fn store_recipe(&self) -> bool {
+        false
+    }
+
+    /// The version that is used if neither the project model nor the
+    /// metadata provider define a version.
+    fn default_version(&self) -> Option<&str> {
+        None
+    }
+
+    /// Whether a missing version is an error, even if a
+    /// [`BackendConfig::default_version`] is configured. Defaults to `false`.
+    fn require_version(&self) -> bool {
+        false
+    }
+
+    /// The version to fall back to if no version is defined, taking
+    /// [`BackendConfig::require_version`] into account. Without a
+    /// [`BackendConfig::default_version`] this is [`DEFAULT_VERSION`].
+    fn fallback_version(&self) -> Option<&str> {
+        if self.require_version() {
+            None
+        } else {
+            Some(self.default_version().unwrap_or(DEFAULT_VERSION))
+        }
+    }
+
+    /// How the version that is read by the metadata provider is normalized.
+    /// Defaults to using the version as it is.
+    fn version_normalization(&self) -> VersionNormalization {
+        VersionNormalization::default()
+    }
+
+    /// Whether the warnings that are collected while generating the recipe
+    /// are turned into errors. Defaults to `false`.
+    fn deny_warnings(&self) -> bool {
+        false
+    }
+
+    /// Whether the license of the generated recipe must be a valid SPDX
+    /// license expression. Defaults to `false`.
+    fn validate_license(&self) -> bool {
+        false
+    }
+
+    /// The variant keys that are left out when computing the hash of the
+    /// build string. They are still recorded in the variant of the package.
+    fn hash_ignore_keys(&self) -> &[String] {
+        &[]
+    }
+
+    /// Channels that are used in addition to the channels that are requested
+    /// by the frontend.
+    fn extra_channels(&self) -> &[String] {
+        &[]
+    }
+
+    /// Where the [`Self::extra_channels`] are added relative to the channels
+    /// that are requested by the frontend.
+    fn extra_channel_priority(&self) -> ExtraChannelPriority {
+        ExtraChannelPriority::default()
+    }
+
+    /// A file with channels that are used in addition to the
+    /// [`Self::extra_channels`], relative to the source directory.
+    fn channels_file(&self) -> Option<&Path> {
+        None
+    }
+
+    /// The maximum duration of a single backend operation, e.g. building a
+    /// package. Defaults to no timeout.
+    fn operation_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// A base directory for the build directories of the packages. Defaults
+    /// to the work directory that is requested by the frontend.
+    fn build_dir(&self) -> Option<&Path> {
+        None
+    }
+
+    /// The directory that the rendered recipes are temporarily written to
+    /// while a package is solved and built. Defaults to the output directory
+    /// of the build.
+    fn temp_dir(&self) -> Option<&Path> {
+        None
+    }
+
+    /// The strategy that selects the versions of the packages when the build
+    /// and host environments are solved. Defaults to the highest versions.
+    fn solve_strategy(&self) -> SolveStrategy {
+        SolveStrategy::default()
+    }
+
+    /// Whether the resolved recipe of every output is written to the
+    /// [`Self::debug_dir`]. Defaults to `false`.
+    fn debug_resolved_recipes(&self) -> bool {
+        false
+    }
+
+    /// The profile that the package is built with, e.g. `dev` for a debug
+    /// build. Defaults to the backend defaults.
+    fn profile(&self) -> Option<BuildProfile> {
+        None
+    }
+
+    /// Whether the package is installed in editable mode, given whether the
+    /// frontend requested it. The resolved value is passed to
+    /// [`GenerateRecipe::generate_recipe`] and
+    /// [`GenerateRecipe::extract_input_globs_from_build`]. Defaults to the
+    /// requested value.
+    fn editable(&self, requested: bool) -> bool {
+        requested
+    }
+
     /// Merge this configuration with a target-specific configuration.
     /// Target-specific values typically override base values.
     fn merge_with_target_config(&self, target_config: &Self) -> miette::Result<Self>;
@@ -99,6 +354,10 @@ pub enum GenerateRecipeError<MetadataProviderError: Diagnostic + 'static> {
     NoNameDefined,
     #[error("There was no version defined for the recipe")]
     NoVersionDefined,
+    #[error("The default version '{0}' is not a valid version")]
+    InvalidDefaultVersion(String, #[source] ParseVersionError),
+    #[error("The name '{0}' is not a valid package name")]
+    InvalidPackageName(String, #[source] InvalidPackageNameError),
     #[error("An error occurred while querying the {0}")]
     MetadataProviderError(String, #[source] MetadataProviderError),
 }
@@ -108,6 +367,15 @@ pub struct GeneratedRecipe {
     pub recipe: IntermediateRecipe,
     pub metadata_input_globs: BTreeSet<String>,
     pub build_input_globs: BTreeSet<String>,
+    /// True if the package installs into the version specific `site-packages`
+    /// directory of python. If set, the `python_site_packages_path` of
+    /// non-noarch outputs is derived from the `python` variant.
+    pub python_site_packages_path_aware: bool,
+    /// Problems that were found while generating the recipe but that don't
+    /// prevent the recipe from being built, e.g. a configured package name
+    /// that doesn't match any dependency. These become errors if
+    /// [`BackendConfig::deny_warnings`] is enabled.
+    pub warnings: Vec<String>,
 }
 
 impl GeneratedRecipe {
@@ -117,29 +385,77 @@ impl GeneratedRecipe {
     pub fn from_model<M: MetadataProvider>(
         model: ProjectModelV1,
         provider: &mut M,
+    ) -> Result<Self, GenerateRecipeError<M::Error>> {
+        Self::from_model_with_default_version(
+            model,
+            provider,
+            None,
+            VersionNormalization::default(),
+        )
+    }
+
+    /// Creates a new [`GeneratedRecipe`] from a [`ProjectModelV1`] like
+    /// [`GeneratedRecipe::from_model`], but falls back to `default_version` if
+    /// neither the model nor the provider define a version. A version of the
+    /// provider is normalized with `version_normalization`.
+    pub fn from_model_with_default_version<M: MetadataProvider>(
+        model: ProjectModelV1,
+        provider: &mut M,
+        default_version: Option<&str>,
+        version_normalization: VersionNormalization,
     ) -> Result<Self, GenerateRecipeError<M::Error>> {
         // If the name is not defined in the model, we try to get it from the provider.
         // If the provider cannot provide a name, we return an error.
-        let name = if model.name.is_empty() {
+        let name = if model.name.trim().is_empty() {
             provider
                 .name()
                 .map_err(|e| GenerateRecipeError::MetadataProviderError(String::from("name"), e))?
+                .filter(|name| !name.trim().is_empty())
                 .ok_or(GenerateRecipeError::NoNameDefined)?
         } else {
             model.name
         };
 
+        // Make sure the name is a valid package name before we continue, otherwise
+        // the error only surfaces once rattler-build parses the recipe.
+        if let Err(err) = PackageName::from_str(&name) {
+            return Err(GenerateRecipeError::InvalidPackageName(name, err));
+        }
+
         // If the version is not defined in the model, we try to get it from the
-        // provider. If the provider cannot provide a version, we return an
-        // error.
+        // provider. If the provider cannot provide a version either, we fall
+        // back to the default version or return an error.
+        let mut warnings = Vec::new();
         let version = match model.version {
             Some(v) => v,
-            None => provider
-                .version()
-                .map_err(|e| {
+            None => {
+                let provided_version = provider.version().map_err(|e| {
                     GenerateRecipeError::MetadataProviderError(String::from("version"), e)
-                })?
-                .ok_or(GenerateRecipeError::NoVersionDefined)?,
+                })?;
+                match (provided_version, default_version) {
+                    (Some(v), _) => {
+                        let normalized = version_normalization.normalize(&v.to_string());
+                        match Version::from_str(&normalized) {
+                            Ok(version) => version,
+                            Err(err) => {
+                                warnings.push(format!(
+                                    "the normalized version '{normalized}' is not a valid version ({err}), using '{v}' instead"
+                                ));
+                                v
+                            }
+                        }
+                    }
+                    (None, Some(default_version)) => {
+                        Version::from_str(default_version).map_err(|e| {
+                            GenerateRecipeError::InvalidDefaultVersion(
+                                default_version.to_string(),
+                                e,
+                            )
+                        })?
+                    }
+                    (None, None) => return Err(GenerateRecipeError::NoVersionDefined),
+                }
+            }
         };
 
         let package = Package {
@@ -164,20 +480,26 @@ impl GeneratedRecipe {
             };
         }
 
+        let license = derive_value!(license);
+        let license_family = license
+            .as_deref()
+            .and_then(crate::license::license_family)
+            .map(|family| Value::Concrete(family.to_string()));
         let about = About {
             homepage: derive_value!(homepage).map(Value::Concrete),
-            license: derive_value!(license).map(Value::Concrete),
+            license: license.map(Value::Concrete),
+            license_family,
             description: derive_value!(description).map(Value::Concrete),
             documentation: derive_value!(documentation).map(Value::Concrete),
             repository: derive_value!(repository).map(Value::Concrete),
             license_file: match model.license_file {
-                Some(v) => Some(Value::Concrete(v.display().to_string())),
+                Some(v) => Some(ListOrItem::single(Value::Concrete(v.display().to_string()))),
                 None => provider
                     .license_file()
                     .map_err(|e| {
                         GenerateRecipeError::MetadataProviderError(String::from("license-file"), e)
                     })?
-                    .map(Value::Concrete),
+                    .map(|license_file| ListOrItem::single(Value::Concrete(license_file))),
             },
             summary: provider
                 .summary()
@@ -187,18 +509,725 @@ impl GeneratedRecipe {
                 .map(Value::Concrete),
         };
 
+        let keywords = provider
+            .keywords()
+            .map_err(|e| GenerateRecipeError::MetadataProviderError(String::from("keywords"), e))?;
+        let extra = (!keywords.is_empty()).then(|| Extra {
+            keywords,
+            ..Default::default()
+        });
+
         let ir = IntermediateRecipe {
             package,
             requirements,
             about: Some(about),
+            extra,
             ..Default::default()
         };
 
         Ok(GeneratedRecipe {
             recipe: ir,
+            warnings,
             ..Default::default()
         })
     }
+
+    /// Adds the given run constraints (e.g. `numpy >=1.20`) to the recipe.
+    /// Constraints that are already present in the recipe are skipped.
+    pub fn add_run_constraints(&mut self, run_constraints: &[String]) -> miette::Result<()> {
+        let constraints = &mut self.recipe.requirements.run_constraints;
+        for constraint in run_constraints {
+            let item: Item<PackageDependency> = constraint
+                .parse()
+                .into_diagnostic()
+                .wrap_err_with(|| format!("failed to parse run constraint '{constraint}'"))?;
+            if !constraints.contains(&item) {
+                constraints.push(item);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the names of environment variables that are passed to the build
+    /// script as secrets. Names that are already present are skipped.
+    pub fn add_secrets(&mut self, secrets: &[String]) {
+        let script_secrets = &mut self.recipe.build.script.secrets;
+        for secret in secrets {
+            if !script_secrets.contains(secret) {
+                script_secrets.push(secret.clone());
+            }
+        }
+    }
+
+    /// Adds the given run exports to the recipe. Entries can be regular
+    /// dependencies or jinja expressions, e.g.
+    /// `${{ pin_subpackage('foo', upper_bound='x.x') }}`.
+    pub fn add_run_exports(&mut self, run_exports: &RunExportsConfig) -> miette::Result<()> {
+        let recipe_run_exports = &mut self.recipe.requirements.run_exports;
+        for (kind, entries, target) in [
+            ("weak", &run_exports.weak, &mut recipe_run_exports.weak),
+            (
+                "strong",
+                &run_exports.strong,
+                &mut recipe_run_exports.strong,
+            ),
+            (
+                "noarch",
+                &run_exports.noarch,
+                &mut recipe_run_exports.noarch,
+            ),
+            (
+                "weak-constraints",
+                &run_exports.weak_constraints,
+                &mut recipe_run_exports.weak_constraints,
+            ),
+            (
+                "strong-constraints",
+                &run_exports.strong_constraints,
+                &mut recipe_run_exports.strong_constraints,
+            ),
+        ] {
+            add_unique_items(target, entries, kind)?;
+        }
+        Ok(())
+    }
+
+    /// Re-exports the run exports of the given host dependencies, e.g. of a
+    /// library that the package links statically. Every package is added as a
+    /// weak `${{ pin_compatible('foo') }}` run export, which constrains
+    /// consumers to a version that is compatible with the one the package was
+    /// built against.
+    pub fn inherit_run_exports(&mut self, names: &[String]) -> miette::Result<()> {
+        let names = names
+            .iter()
+            .map(|name| {
+                PackageName::from_str(name)
+                    .into_diagnostic()
+                    .wrap_err_with(|| format!("invalid package name '{name}'"))
+            })
+            .collect::<miette::Result<Vec<_>>>()?;
+
+        let host_names = self
+            .recipe
+            .requirements
+            .host
+            .iter()
+            .flat_map(|item| match item {
+                Item::Value(Value::Concrete(dep)) => vec![dep.package_name()],
+                Item::Value(Value::Template(_)) => Vec::new(),
+                Item::Conditional(conditional) => conditional
+                    .then
+                    .iter()
+                    .chain(conditional.else_value.iter())
+                    .map(PackageDependency::package_name)
+                    .collect(),
+            })
+            .collect::<HashSet<_>>();
+        for name in names.iter().filter(|name| !host_names.contains(*name)) {
+            self.warnings.push(format!(
+                "the package '{}' to inherit run exports from is not a host dependency",
+                name.as_source()
+            ));
+        }
+
+        let weak = names
+            .iter()
+            .map(|name| format!("${{{{ pin_compatible('{}') }}}}", name.as_normalized()))
+            .collect();
+        self.add_run_exports(&RunExportsConfig {
+            weak,
+            ..RunExportsConfig::default()
+        })
+    }
+
+    /// Adds user defined variables to the context of the recipe, e.g. to
+    /// reference `${{ cdn_base }}` from a source url. Variables that are
+    /// already defined by the backend take precedence.
+    pub fn add_context(&mut self, context: &IndexMap<String, String>) {
+        for (key, value) in context {
+            if self.recipe.context.contains_key(key) {
+                self.warnings.push(format!(
+                    "the context variable '{key}' is defined by the backend, the configured value is ignored"
+                ));
+                continue;
+            }
+            self.recipe
+                .context
+                .insert(key.clone(), Value::Concrete(value.clone()));
+        }
+    }
+
+    /// Records that the file at `path`, relative to the manifest root, was read
+    /// while generating the recipe. The file is added to the metadata input
+    /// globs, so that the metadata is regenerated when it changes.
+    ///
+    /// Backends should call this for every file they actually consulted,
+    /// instead of adding globs for files that may or may not exist.
+    pub fn record_input(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        let glob = path
+            .as_ref()
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        self.metadata_input_globs.insert(glob);
+        self
+    }
+
+    /// Adds the directories at `paths`, relative to the manifest root, as
+    /// additional path sources of the recipe, e.g. a data directory that the
+    /// build needs next to the main source. Every directory is copied into a
+    /// target directory that is named after the directory, e.g. `data` for
+    /// `../shared/data`, and changes to its files trigger a rebuild. Files
+    /// that are ignored by git are left out unless `use_gitignore` is
+    /// `Some(false)`. The `filter` globs are relative to every directory and
+    /// select the files that are part of it, the input globs are narrowed
+    /// down accordingly.
+    pub fn add_path_sources(
+        &mut self,
+        manifest_root: &Path,
+        paths: &[String],
+        use_gitignore: Option<bool>,
+        filter: Option<&SourceFilter>,
+    ) -> miette::Result<()> {
+        let mut target_directories = HashSet::new();
+        for path in paths {
+            if Path::new(path).is_absolute() {
+                miette::bail!(
+                    "the additional source '{path}' must be relative to the manifest root"
+                );
+            }
+            let source_path = manifest_root.join(path);
+            if !source_path.exists() {
+                miette::bail!(
+                    "the additional source '{path}' does not exist in '{}'",
+                    manifest_root.display()
+                );
+            }
+
+            // The target directory is always a single normal component, so the
+            // source can't be copied outside of the work directory.
+            let target_directory = match Path::new(path).components().next_back() {
+                Some(Component::Normal(name)) => name.to_string_lossy().into_owned(),
+                _ => miette::bail!(
+                    "the additional source '{path}' must end with the name of a directory"
+                ),
+            };
+            if !target_directories.insert(target_directory.clone()) {
+                miette::bail!(
+                    "the additional sources are copied into '{target_directory}' more than once"
+                );
+            }
+
+            self.recipe.source.push(Item::from(Source::Path(PathSource {
+                path: Value::Concrete(source_path.display().to_string()),
+                sha256: None,
+                use_gitignore,
+                filter: filter.cloned(),
+                target_directory: Some(Value::Concrete(target_directory)),
+            })));
+            let input_glob = path.trim_end_matches(['/', '\\']).replace('\\', "/");
+            match filter {
+                Some(filter) if !filter.include.is_empty() => self.build_input_globs.extend(
+                    filter
+                        .include
+                        .iter()
+                        .map(|glob| format!("{input_glob}/{glob}")),
+                ),
+                _ => {
+                    self.build_input_globs.insert(format!("{input_glob}/**"));
+                }
+            }
+            self.build_input_globs.extend(
+                filter
+                    .into_iter()
+                    .flat_map(|filter| &filter.exclude)
+                    .map(|glob| format!("!{input_glob}/{glob}")),
+            );
+        }
+        Ok(())
+    }
+
+    /// Sets the license files of the recipe to the license files in the root
+    /// of the package, e.g. `LICENSE` or `COPYING`, if neither the project
+    /// model nor the metadata provider defines one.
+    ///
+    /// All license files that are found are included in the package, in the
+    /// order of [`LICENSE_FILE_NAMES`]. They are added to the input globs so
+    /// that adding, removing or changing a license file is picked up.
+    pub fn discover_license_file(&mut self, manifest_root: &Path) {
+        let about = self.recipe.about.get_or_insert_with(Default::default);
+        if about.license_file.is_some() {
+            return;
+        }
+
+        let license_files = find_license_files(manifest_root);
+        if license_files.is_empty() {
+            return;
+        }
+        about.license_file = Some(ListOrItem::new(
+            license_files.iter().cloned().map(Value::Concrete).collect(),
+        ));
+        for license_file in license_files {
+            self.record_input(&license_file);
+            self.build_input_globs.insert(license_file);
+        }
+    }
+
+    /// Sets the build number of the recipe from the environment variable with
+    /// the given name, e.g. a build number that is injected by CI. Nothing
+    /// changes if the variable is not set, values that are not a number are
+    /// ignored with a warning.
+    pub fn set_build_number_from_env(&mut self, env_var: &str) {
+        let Ok(value) = std::env::var(env_var) else {
+            return;
+        };
+        match value.trim().parse::<u64>() {
+            Ok(number) => self.recipe.build.number = Some(Value::Concrete(number)),
+            Err(_) => self.warnings.push(format!(
+                "the build number '{value}' of the environment variable '{env_var}' is not a number, it is ignored"
+            )),
+        }
+    }
+
+    /// Sets the build number of the recipe to the number of commits in the git
+    /// repository that contains `path`, so that the build number increases
+    /// with every commit. The files that point to the `HEAD` commit are
+    /// recorded as metadata inputs, `path` must therefore be the manifest
+    /// root. If the commits cannot be counted, e.g. because git is not
+    /// installed, the build number is set to 0 with a warning.
+    pub fn set_build_number_from_git_count(&mut self, path: &Path) {
+        let counted = git_commit_count(path).and_then(|count| Ok((count, git_head_paths(path)?)));
+        let number = match counted {
+            Ok((count, head_paths)) => {
+                for head_path in head_paths {
+                    self.record_input(head_path);
+                }
+                count
+            }
+            Err(err) => {
+                self.warnings.push(format!(
+                    "failed to count the git commits in '{}', the build number is set to 0: {err}",
+                    path.display()
+                ));
+                0
+            }
+        };
+        self.recipe.build.number = Some(Value::Concrete(number));
+    }
+
+    /// Adds the build requirements of the recipe to the build environment of
+    /// every script test, e.g. so that a test can compile a program with the
+    /// same toolchain as the package.
+    pub fn add_build_requirements_to_tests(&mut self) {
+        let recipe = &mut self.recipe;
+        for test in recipe.tests.iter_mut().filter(|test| test.script.is_some()) {
+            let requirements = test.requirements.get_or_insert_with(Default::default);
+            for item in &recipe.requirements.build {
+                if !requirements.build.contains(item) {
+                    requirements.build.push(item.clone());
+                }
+            }
+        }
+    }
+
+    /// Moves the dependencies with the given package names from the host and
+    /// run requirements into the build requirements. This is used for tools
+    /// that are declared as a regular dependency but are only needed at build
+    /// time, e.g. `make`.
+    pub fn move_to_build_requirements(&mut self, build_only: &[String]) -> miette::Result<()> {
+        if build_only.is_empty() {
+            return Ok(());
+        }
+
+        let names = build_only
+            .iter()
+            .map(|name| {
+                PackageName::from_str(name)
+                    .into_diagnostic()
+                    .wrap_err_with(|| format!("invalid build-only package name '{name}'"))
+            })
+            .collect::<miette::Result<Vec<_>>>()?;
+
+        let requirements = &mut self.recipe.requirements;
+        let mut moved = take_dependencies(&mut requirements.host, &names);
+        moved.extend(take_dependencies(&mut requirements.run, &names));
+
+        let moved_names = moved
+            .iter()
+            .flat_map(|item| match item {
+                Item::Value(Value::Concrete(dep)) => vec![dep.package_name()],
+                Item::Value(Value::Template(_)) => Vec::new(),
+                Item::Conditional(conditional) => conditional
+                    .then
+                    .iter()
+                    .chain(conditional.else_value.iter())
+                    .map(PackageDependency::package_name)
+                    .collect(),
+            })
+            .collect::<HashSet<_>>();
+        for name in names.iter().filter(|name| !moved_names.contains(*name)) {
+            self.warnings.push(format!(
+                "the build-only package '{}' is not a host or run dependency",
+                name.as_source()
+            ));
+        }
+
+        for item in moved {
+            if !requirements.build.contains(&item) {
+                requirements.build.push(item);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes dependencies from the host requirements that are already part
+    /// of the build requirements with the same spec or if they don't have a
+    /// spec at all. Dependencies that are required with different specs are
+    /// kept and a warning is emitted for them. Duplicates within the build,
+    /// host and run requirements are merged the same way. The run
+    /// requirements are not deduplicated against the other sections, the
+    /// build and host environments are not installed at runtime.
+    /// Conditional dependencies are not considered.
+    pub fn dedupe_requirements(&mut self) {
+        let requirements = &mut self.recipe.requirements;
+        for (section, items) in [
+            ("build", &mut requirements.build),
+            ("host", &mut requirements.host),
+            ("run", &mut requirements.run),
+        ] {
+            dedupe_section(section, items, &mut self.warnings);
+        }
+
+        let build = requirements
+            .build
+            .iter()
+            .filter_map(concrete_dependency)
+            .cloned()
+            .collect::<Vec<_>>();
+        requirements.host.retain(|item| {
+            let Some(dep) = concrete_dependency(item) else {
+                return true;
+            };
+            let Some(build_dep) = build
+                .iter()
+                .find(|build_dep| build_dep.package_name() == dep.package_name())
+            else {
+                return true;
+            };
+            if build_dep == dep || is_unconstrained(dep) {
+                return false;
+            }
+            self.warnings.push(format!(
+                "the package '{}' is required with different specs in the build and host requirements",
+                dep.package_name().as_source()
+            ));
+            true
+        });
+    }
+}
+
+/// The names of the files that are picked up as license files, in order of
+/// preference. Files that start with `LICENSE-`, e.g. `LICENSE-MIT`, come
+/// after these.
+pub const LICENSE_FILE_NAMES: &[&str] = &["LICENSE", "LICENSE.txt", "LICENSE.md", "COPYING"];
+
+/// Returns the names of the license files in `dir`, in order of preference.
+fn find_license_files(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs_err::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut prefixed = Vec::new();
+    let mut named = Vec::new();
+    for entry in entries.flatten() {
+        if !entry.path().is_file() {
+            continue;
+        }
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        if let Some(index) = LICENSE_FILE_NAMES.iter().position(|n| *n == name) {
+            named.push((index, name));
+        } else if name.starts_with("LICENSE-") {
+            prefixed.push(name);
+        }
+    }
+    named.sort();
+    prefixed.sort();
+    named
+        .into_iter()
+        .map(|(_, name)| name)
+        .chain(prefixed)
+        .collect()
+}
+
+/// Returns the number of commits of the `HEAD` of the git repository that
+/// contains `path`.
+fn git_commit_count(path: &Path) -> miette::Result<u64> {
+    git(path, &["rev-list", "--count", "HEAD"])?
+        .parse()
+        .into_diagnostic()
+}
+
+/// Returns the paths, relative to `path`, of the files in the git repository
+/// that contains `path` that change when a commit is made: the `HEAD`, the
+/// branch it points to and the packed refs.
+fn git_head_paths(path: &Path) -> miette::Result<Vec<PathBuf>> {
+    let path = fs_err::canonicalize(path).into_diagnostic()?;
+    let top_level =
+        fs_err::canonicalize(git(&path, &["rev-parse", "--show-toplevel"])?).into_diagnostic()?;
+    let git_dir = git_dir(&top_level)?;
+    // The refs of a worktree are stored in the git directory of the main
+    // worktree.
+    let common_dir = match fs_err::read_to_string(git_dir.join("commondir")) {
+        Ok(common_dir) => {
+            fs_err::canonicalize(git_dir.join(common_dir.trim())).into_diagnostic()?
+        }
+        Err(_) => git_dir.clone(),
+    };
+
+    let mut head_paths = vec![git_dir.join("HEAD"), common_dir.join("packed-refs")];
+    // A detached `HEAD` does not point to a branch.
+    let head = fs_err::read_to_string(git_dir.join("HEAD")).into_diagnostic()?;
+    if let Some(branch) = head.trim().strip_prefix("ref: ") {
+        head_paths.push(common_dir.join(branch));
+    }
+
+    head_paths
+        .iter()
+        .map(|head_path| {
+            pathdiff::diff_paths(head_path, &path).ok_or_else(|| {
+                miette::miette!(
+                    "'{}' cannot be expressed relative to '{}'",
+                    head_path.display(),
+                    path.display()
+                )
+            })
+        })
+        .collect()
+}
+
+/// Returns the git directory of the repository with the working tree at
+/// `top_level`. In a worktree or a submodule `.git` is a file that points to
+/// the git directory.
+fn git_dir(top_level: &Path) -> miette::Result<PathBuf> {
+    let dot_git = top_level.join(".git");
+    if dot_git.is_dir() {
+        return Ok(dot_git);
+    }
+    let contents = fs_err::read_to_string(&dot_git).into_diagnostic()?;
+    let Some(git_dir) = contents.trim().strip_prefix("gitdir: ") else {
+        miette::bail!("'{}' does not point to a git directory", dot_git.display());
+    };
+    fs_err::canonicalize(top_level.join(git_dir)).into_diagnostic()
+}
+
+/// Runs git with `args` in `path` and returns its trimmed output.
+fn git(path: &Path, args: &[&str]) -> miette::Result<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(path)
+        .output()
+        .into_diagnostic()?;
+    if !output.status.success() {
+        miette::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Returns the dependency of an item that is not conditional.
+fn concrete_dependency(item: &Item<PackageDependency>) -> Option<&PackageDependency> {
+    match item {
+        Item::Value(Value::Concrete(dep)) => Some(dep),
+        _ => None,
+    }
+}
+
+/// Merges the dependencies of a single requirements section that refer to the
+/// same package. A duplicate with the same spec or without a spec is removed,
+/// a dependency without a spec is replaced by a duplicate with a spec. A
+/// warning is emitted for duplicates with different specs, which are kept.
+fn dedupe_section(
+    section: &str,
+    items: &mut ConditionalList<PackageDependency>,
+    warnings: &mut Vec<String>,
+) {
+    let mut deduped: Vec<Item<PackageDependency>> = Vec::with_capacity(items.len());
+    for item in items.drain(..) {
+        let Some(dep) = concrete_dependency(&item) else {
+            deduped.push(item);
+            continue;
+        };
+        let Some(existing) = deduped.iter_mut().find(|existing| {
+            concrete_dependency(existing)
+                .is_some_and(|existing| existing.package_name() == dep.package_name())
+        }) else {
+            deduped.push(item);
+            continue;
+        };
+        let existing_dep = concrete_dependency(existing).expect("matched a concrete dependency");
+        if existing_dep == dep || is_unconstrained(dep) {
+            continue;
+        }
+        if is_unconstrained(existing_dep) {
+            *existing = item;
+            continue;
+        }
+        warnings.push(format!(
+            "the package '{}' is required with different specs in the {section} requirements",
+            dep.package_name().as_source()
+        ));
+        deduped.push(item);
+    }
+    *items = deduped;
+}
+
+/// Returns true if the dependency only consists of the name of the package.
+fn is_unconstrained(dep: &PackageDependency) -> bool {
+    let PackageDependency::Binary(spec) = dep else {
+        return false;
+    };
+    let unconstrained = MatchSpec {
+        name: spec.name.clone(),
+        ..MatchSpec::default()
+    };
+    *spec == unconstrained
+}
+
+/// Removes the dependencies with one of the given names from `items` and
+/// returns them. Conditional items are split so that the condition is kept
+/// for both the removed and the remaining dependencies.
+fn take_dependencies(
+    items: &mut ConditionalList<PackageDependency>,
+    names: &[PackageName],
+) -> Vec<Item<PackageDependency>> {
+    let mut taken = Vec::new();
+    for item in std::mem::take(items) {
+        match item {
+            Item::Value(Value::Concrete(dep)) if names.contains(&dep.package_name()) => {
+                taken.push(Item::Value(Value::Concrete(dep)));
+            }
+            Item::Conditional(conditional) => {
+                let (then_taken, then_kept): (Vec<_>, Vec<_>) = conditional
+                    .then
+                    .0
+                    .into_iter()
+                    .partition(|dep| names.contains(&dep.package_name()));
+                let (else_taken, else_kept): (Vec<_>, Vec<_>) = conditional
+                    .else_value
+                    .0
+                    .into_iter()
+                    .partition(|dep| names.contains(&dep.package_name()));
+                if !then_taken.is_empty() || !else_taken.is_empty() {
+                    taken.push(Item::Conditional(Conditional {
+                        condition: conditional.condition.clone(),
+                        then: ListOrItem(then_taken),
+                        else_value: ListOrItem(else_taken),
+                    }));
+                }
+                if !then_kept.is_empty() || !else_kept.is_empty() {
+                    items.push(Item::Conditional(Conditional {
+                        condition: conditional.condition,
+                        then: ListOrItem(then_kept),
+                        else_value: ListOrItem(else_kept),
+                    }));
+                }
+            }
+            item => items.push(item),
+        }
+    }
+    taken
+}
+
+/// A multi-output recipe generated for a workspace with multiple packages.
+#[derive(Default, Clone)]
+pub struct GeneratedWorkspaceRecipe {
+    pub recipe: MultiOutputRecipe,
+    pub metadata_input_globs: BTreeSet<String>,
+    pub build_input_globs: BTreeSet<String>,
+}
+
+impl GeneratedWorkspaceRecipe {
+    /// Combines the recipes of the packages in a workspace into a single
+    /// recipe with one output per package. The input globs of all packages
+    /// are merged. Returns an error if multiple packages share the same name
+    /// or define the same context variable with different values.
+    pub fn from_recipes(
+        recipes: impl IntoIterator<Item = GeneratedRecipe>,
+    ) -> miette::Result<Self> {
+        let mut names = HashSet::new();
+        let mut intermediate_recipes = Vec::new();
+        let mut metadata_input_globs = BTreeSet::new();
+        let mut build_input_globs = BTreeSet::new();
+        for recipe in recipes {
+            let name = recipe.recipe.package.name.to_string();
+            if !names.insert(name.clone()) {
+                miette::bail!("the workspace contains multiple packages named '{name}'");
+            }
+            metadata_input_globs.extend(recipe.metadata_input_globs);
+            build_input_globs.extend(recipe.build_input_globs);
+            intermediate_recipes.push(recipe.recipe);
+        }
+
+        Ok(GeneratedWorkspaceRecipe {
+            recipe: MultiOutputRecipe::from_recipes(intermediate_recipes).into_diagnostic()?,
+            metadata_input_globs,
+            build_input_globs,
+        })
+    }
+}
+
+/// Parses every entry and adds it to `items` if it is not already present.
+fn add_unique_items(
+    items: &mut ConditionalList<PackageDependency>,
+    entries: &[String],
+    kind: &str,
+) -> miette::Result<()> {
+    for entry in entries {
+        let item: Item<PackageDependency> = entry
+            .parse()
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to parse {kind} run export '{entry}'"))?;
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+    Ok(())
+}
+
+/// The run exports of the package itself, as they can be configured by the
+/// user for backends that support it.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct RunExportsConfig {
+    /// Run exports that are added to the run requirements of packages that
+    /// have this package in their host requirements.
+    #[serde(default)]
+    pub weak: Vec<String>,
+    /// Run exports that are added to the run requirements of packages that
+    /// have this package in their build or host requirements.
+    #[serde(default)]
+    pub strong: Vec<String>,
+    /// Run exports that are only applied to noarch packages.
+    #[serde(default)]
+    pub noarch: Vec<String>,
+    /// Weak run exports that are added as run constraints.
+    #[serde(default)]
+    pub weak_constraints: Vec<String>,
+    /// Strong run exports that are added as run constraints.
+    #[serde(default)]
+    pub strong_constraints: Vec<String>,
+}
+
+impl RunExportsConfig {
+    /// Returns true if no run exports are configured.
+    pub fn is_empty(&self) -> bool {
+        self.weak.is_empty()
+            && self.strong.is_empty()
+            && self.noarch.is_empty()
+            && self.weak_constraints.is_empty()
+            && self.strong_constraints.is_empty()
+    }
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -243,6 +1272,12 @@ pub trait MetadataProvider {
     fn repository(&mut self) -> Result<Option<String>, Self::Error> {
         Ok(None)
     }
+
+    /// Returns the keywords that describe the package, these are added to the
+    /// `extra` section of the recipe.
+    fn keywords(&mut self) -> Result<Vec<String>, Self::Error> {
+        Ok(Vec::new())
+    }
 }
 
 pub struct DefaultMetadataProvider;
@@ -250,3 +1285,862 @@ pub struct DefaultMetadataProvider;
 impl MetadataProvider for DefaultMetadataProvider {
     type Error = Infallible;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_model(name: &str) -> ProjectModelV1 {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "version": "0.1.0",
+        }))
+        .expect("failed to create project model")
+    }
+
+    struct NameProvider(Option<String>);
+
+    impl MetadataProvider for NameProvider {
+        type Error = Infallible;
+
+        fn name(&mut self) -> Result<Option<String>, Self::Error> {
+            Ok(self.0.clone())
+        }
+    }
+
+    struct VersionProvider(&'static str);
+
+    impl MetadataProvider for VersionProvider {
+        type Error = Infallible;
+
+        fn version(&mut self) -> Result<Option<Version>, Self::Error> {
+            Ok(Some(Version::from_str(self.0).unwrap()))
+        }
+    }
+
+    #[test]
+    fn test_record_input() {
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe
+            .record_input("pyproject.toml")
+            .record_input(Path::new("docs").join("description.txt"));
+
+        // The paths are recorded as `/` separated globs on every platform.
+        assert_eq!(
+            recipe.metadata_input_globs,
+            BTreeSet::from([
+                "docs/description.txt".to_string(),
+                "pyproject.toml".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_empty_name_is_rejected() {
+        let err = GeneratedRecipe::from_model(project_model(""), &mut DefaultMetadataProvider)
+            .err()
+            .expect("an empty name should be rejected");
+        assert!(matches!(err, GenerateRecipeError::NoNameDefined));
+    }
+
+    #[test]
+    fn test_empty_name_from_provider_is_rejected() {
+        let err = GeneratedRecipe::from_model(
+            project_model(""),
+            &mut NameProvider(Some(String::from("  "))),
+        )
+        .err()
+        .expect("an empty name from the provider should be rejected");
+        assert!(matches!(err, GenerateRecipeError::NoNameDefined));
+    }
+
+    #[test]
+    fn test_invalid_name_is_rejected() {
+        let err = GeneratedRecipe::from_model(
+            project_model("not a/valid name"),
+            &mut DefaultMetadataProvider,
+        )
+        .err()
+        .expect("an invalid name should be rejected");
+        assert!(matches!(
+            err,
+            GenerateRecipeError::InvalidPackageName(ref name, _) if name == "not a/valid name"
+        ));
+    }
+
+    #[test]
+    fn test_name_from_provider_is_used() {
+        let recipe = GeneratedRecipe::from_model(
+            project_model(""),
+            &mut NameProvider(Some(String::from("foobar"))),
+        )
+        .expect("the name from the provider should be used");
+        assert_eq!(
+            recipe.recipe.package.name,
+            Value::Concrete(String::from("foobar"))
+        );
+    }
+
+    #[test]
+    fn test_source_dependency_is_converted() {
+        let mut model = project_model("foobar");
+        model.targets = Some(pixi_build_types::TargetsV1 {
+            default_target: Some(pixi_build_types::TargetV1 {
+                build_dependencies: None,
+                host_dependencies: Some(
+                    [(
+                        String::from("bar"),
+                        pixi_build_types::PackageSpecV1::Source(
+                            pixi_build_types::SourcePackageSpecV1::Path(
+                                pixi_build_types::PathSpecV1 {
+                                    path: "../bar".into(),
+                                },
+                            ),
+                        ),
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+                run_dependencies: None,
+            }),
+            targets: None,
+        });
+
+        let recipe = GeneratedRecipe::from_model(model, &mut DefaultMetadataProvider)
+            .expect("a source dependency should not be rejected");
+        let [Item::Value(Value::Concrete(PackageDependency::Source(source)))] =
+            recipe.recipe.requirements.host.as_slice()
+        else {
+            panic!("expected a single source dependency");
+        };
+        assert_eq!(source.spec.name, Some(PackageName::new_unchecked("bar")));
+    }
+
+    #[test]
+    fn test_missing_version_uses_default_version() {
+        let model: ProjectModelV1 =
+            serde_json::from_value(serde_json::json!({ "name": "foobar" })).unwrap();
+
+        let recipe = GeneratedRecipe::from_model_with_default_version(
+            model,
+            &mut DefaultMetadataProvider,
+            Some("1.2.3"),
+            VersionNormalization::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            recipe.recipe.package.version,
+            Value::Concrete(String::from("1.2.3"))
+        );
+    }
+
+    #[test]
+    fn test_provided_version_is_normalized() {
+        let model: ProjectModelV1 =
+            serde_json::from_value(serde_json::json!({ "name": "foobar" })).unwrap();
+
+        let recipe = GeneratedRecipe::from_model_with_default_version(
+            model.clone(),
+            &mut VersionProvider("1.0.0-alpha.1"),
+            None,
+            VersionNormalization::Conda,
+        )
+        .unwrap();
+        assert_eq!(
+            recipe.recipe.package.version,
+            Value::Concrete(String::from("1.0.0a1"))
+        );
+        assert!(recipe.warnings.is_empty(), "{:?}", recipe.warnings);
+
+        // A numeric pre-release cannot be normalized and is kept.
+        let recipe = GeneratedRecipe::from_model_with_default_version(
+            model.clone(),
+            &mut VersionProvider("1.0.0-1"),
+            None,
+            VersionNormalization::Conda,
+        )
+        .unwrap();
+        assert_eq!(
+            recipe.recipe.package.version,
+            Value::Concrete(String::from("1.0.0-1"))
+        );
+
+        // By default the version of the provider is kept as it is.
+        let recipe = GeneratedRecipe::from_model_with_default_version(
+            model,
+            &mut VersionProvider("1.0.0-alpha.1"),
+            None,
+            VersionNormalization::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            recipe.recipe.package.version,
+            Value::Concrete(String::from("1.0.0-alpha.1"))
+        );
+    }
+
+    #[test]
+    fn test_missing_version_without_default_is_rejected() {
+        let model: ProjectModelV1 =
+            serde_json::from_value(serde_json::json!({ "name": "foobar" })).unwrap();
+
+        let err = GeneratedRecipe::from_model_with_default_version(
+            model.clone(),
+            &mut DefaultMetadataProvider,
+            None,
+            VersionNormalization::default(),
+        )
+        .err()
+        .expect("a missing version should be rejected");
+        assert!(matches!(err, GenerateRecipeError::NoVersionDefined));
+
+        let err = GeneratedRecipe::from_model_with_default_version(
+            model,
+            &mut DefaultMetadataProvider,
+            Some("not a version"),
+            VersionNormalization::default(),
+        )
+        .err()
+        .expect("an invalid default version should be rejected");
+        assert!(matches!(
+            err,
+            GenerateRecipeError::InvalidDefaultVersion(..)
+        ));
+    }
+
+    #[derive(Clone, Deserialize)]
+    struct VersionConfig {
+        default_version: Option<String>,
+        require_version: bool,
+    }
+
+    impl BackendConfig for VersionConfig {
+        fn debug_dir(&self) -> Option<&Path> {
+            None
+        }
+
+        fn default_version(&self) -> Option<&str> {
+            self.default_version.as_deref()
+        }
+
+        fn require_version(&self) -> bool {
+            self.require_version
+        }
+
+        fn merge_with_target_config(&self, _target_config: &Self) -> miette::Result<Self> {
+            Ok(self.clone())
+        }
+    }
+
+    #[test]
+    fn test_fallback_version() {
+        let config = |default_version: Option<&str>, require_version| VersionConfig {
+            default_version: default_version.map(ToString::to_string),
+            require_version,
+        };
+
+        assert_eq!(
+            config(None, false).fallback_version(),
+            Some(DEFAULT_VERSION)
+        );
+        assert_eq!(
+            config(Some("1.0.0"), false).fallback_version(),
+            Some("1.0.0")
+        );
+        assert_eq!(config(None, true).fallback_version(), None);
+        assert_eq!(config(Some("1.0.0"), true).fallback_version(), None);
+    }
+
+    #[test]
+    fn test_add_run_constraints() {
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe
+            .add_run_constraints(&[String::from("numpy >=1.20"), String::from("numpy >=1.20")])
+            .unwrap();
+
+        let constraints = recipe
+            .recipe
+            .requirements
+            .run_constraints
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(constraints, vec!["numpy >=1.20"]);
+    }
+
+    #[test]
+    fn test_add_invalid_run_constraint() {
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        assert!(
+            recipe
+                .add_run_constraints(&[String::from("numpy >=>1")])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_add_run_exports() {
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        let run_exports = RunExportsConfig {
+            weak: vec![String::from(
+                "${{ pin_subpackage('foobar', upper_bound='x.x') }}",
+            )],
+            strong_constraints: vec![String::from("numpy >=1.20")],
+            ..Default::default()
+        };
+        recipe.add_run_exports(&run_exports).unwrap();
+        recipe.add_run_exports(&run_exports).unwrap();
+
+        let recipe_run_exports = &recipe.recipe.requirements.run_exports;
+        assert_eq!(recipe_run_exports.weak.len(), 1);
+        assert_eq!(
+            recipe_run_exports.weak[0].to_string(),
+            "${{ pin_subpackage('foobar', upper_bound='x.x') }}"
+        );
+        assert_eq!(recipe_run_exports.strong_constraints.len(), 1);
+        assert!(recipe_run_exports.strong.is_empty());
+    }
+
+    #[test]
+    fn test_inherit_run_exports() {
+        let mut model = project_model("foobar");
+        model.targets = Some(
+            serde_json::from_value(serde_json::json!({
+                "defaultTarget": {
+                    "hostDependencies": {
+                        "zlib": { "binary": { "version": "*" } }
+                    }
+                }
+            }))
+            .unwrap(),
+        );
+        let mut recipe = GeneratedRecipe::from_model(model, &mut DefaultMetadataProvider).unwrap();
+        recipe
+            .inherit_run_exports(&[String::from("zlib"), String::from("openssl")])
+            .unwrap();
+
+        let weak = recipe
+            .recipe
+            .requirements
+            .run_exports
+            .weak
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            weak,
+            vec![
+                "${{ pin_compatible('zlib') }}",
+                "${{ pin_compatible('openssl') }}",
+            ]
+        );
+        assert_eq!(recipe.warnings.len(), 1, "{:?}", recipe.warnings);
+        assert!(recipe.warnings[0].contains("openssl"));
+    }
+
+    #[test]
+    fn test_dedupe_requirements() {
+        let mut model = project_model("foobar");
+        model.targets = Some(
+            serde_json::from_value(serde_json::json!({
+                "defaultTarget": {
+                    "buildDependencies": {
+                        "cmake": { "binary": { "version": ">=3.20" } },
+                        "zlib": { "binary": { "version": ">=1.2" } }
+                    },
+                    "hostDependencies": {
+                        "cmake": { "binary": { "version": ">=3.20" } },
+                        "zlib": { "binary": { "version": ">=1.3" } }
+                    },
+                    "runDependencies": {
+                        "cmake": { "binary": { "version": ">=3.20" } },
+                        "zlib": { "binary": { "version": ">=1.3" } }
+                    }
+                }
+            }))
+            .unwrap(),
+        );
+        let mut recipe = GeneratedRecipe::from_model(model, &mut DefaultMetadataProvider).unwrap();
+        recipe.dedupe_requirements();
+
+        let requirements = &recipe.recipe.requirements;
+        let names = |items: &ConditionalList<PackageDependency>| {
+            items.iter().map(ToString::to_string).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(&requirements.build),
+            vec!["cmake >=3.20", "zlib >=1.2"]
+        );
+        assert_eq!(names(&requirements.host), vec!["zlib >=1.3"]);
+        // The run requirements are installed on their own, so they are kept.
+        assert_eq!(names(&requirements.run), vec!["cmake >=3.20", "zlib >=1.3"]);
+        assert_eq!(recipe.warnings.len(), 1, "{:?}", recipe.warnings);
+        assert!(recipe.warnings[0].contains("'zlib'"));
+    }
+
+    #[test]
+    fn test_dedupe_run_requirements() {
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe.recipe.requirements.run =
+            ["numpy", "numpy >=1.20", "numpy", "zlib >=1.2", "zlib >=1.3"]
+                .into_iter()
+                .map(|spec| spec.parse().unwrap())
+                .collect();
+        recipe.dedupe_requirements();
+
+        let run = recipe
+            .recipe
+            .requirements
+            .run
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(run, vec!["numpy >=1.20", "zlib >=1.2", "zlib >=1.3"]);
+        assert_eq!(recipe.warnings.len(), 1, "{:?}", recipe.warnings);
+        assert!(recipe.warnings[0].contains("'zlib'"));
+        assert!(recipe.warnings[0].contains("run requirements"));
+    }
+
+    #[test]
+    fn test_set_build_number_from_env() {
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+
+        temp_env::with_var("TEST_BUILD_NUMBER", Some("42"), || {
+            recipe.set_build_number_from_env("TEST_BUILD_NUMBER")
+        });
+        assert_eq!(recipe.recipe.build.number, Some(Value::Concrete(42)));
+        let yaml = recipe.recipe.to_yaml_pretty().unwrap();
+        assert!(yaml.contains("number: 42"), "{yaml}");
+
+        // Unset variables and invalid values keep the current build number.
+        temp_env::with_var_unset("TEST_BUILD_NUMBER", || {
+            recipe.set_build_number_from_env("TEST_BUILD_NUMBER")
+        });
+        temp_env::with_var("TEST_BUILD_NUMBER", Some("latest"), || {
+            recipe.set_build_number_from_env("TEST_BUILD_NUMBER")
+        });
+        assert_eq!(recipe.recipe.build.number, Some(Value::Concrete(42)));
+        assert_eq!(recipe.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_set_build_number_from_git_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(dir.path())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+
+        // Without a repository the build number falls back to 0.
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe.set_build_number_from_git_count(dir.path());
+        assert_eq!(recipe.recipe.build.number, Some(Value::Concrete(0)));
+        assert_eq!(recipe.warnings.len(), 1, "{:?}", recipe.warnings);
+
+        git(&["init", "--quiet"]);
+        git(&["symbolic-ref", "HEAD", "refs/heads/main"]);
+        for message in ["first", "second", "third"] {
+            git(&["commit", "--quiet", "--allow-empty", "-m", message]);
+        }
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe.set_build_number_from_git_count(dir.path());
+        assert_eq!(recipe.recipe.build.number, Some(Value::Concrete(3)));
+        assert!(recipe.warnings.is_empty(), "{:?}", recipe.warnings);
+
+        // A new commit changes the branch that `HEAD` points to, so the
+        // metadata is regenerated.
+        assert_eq!(
+            recipe.metadata_input_globs,
+            BTreeSet::from([
+                String::from(".git/HEAD"),
+                String::from(".git/packed-refs"),
+                String::from(".git/refs/heads/main"),
+            ])
+        );
+
+        // The paths are relative to a package in a subdirectory of the
+        // repository.
+        fs_err::create_dir_all(dir.path().join("packages/foobar")).unwrap();
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe.set_build_number_from_git_count(&dir.path().join("packages/foobar"));
+        assert_eq!(recipe.recipe.build.number, Some(Value::Concrete(3)));
+        assert_eq!(
+            recipe.metadata_input_globs,
+            BTreeSet::from([
+                String::from("../../.git/HEAD"),
+                String::from("../../.git/packed-refs"),
+                String::from("../../.git/refs/heads/main"),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_add_path_sources() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::create_dir_all(dir.path().join("data")).unwrap();
+        fs_err::create_dir_all(dir.path().join("assets/icons")).unwrap();
+
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe
+            .add_path_sources(
+                dir.path(),
+                &["data".to_string(), "assets/icons".to_string()],
+                Some(false),
+                None,
+            )
+            .unwrap();
+
+        let sources = recipe
+            .recipe
+            .source
+            .iter()
+            .map(|source| match source {
+                Item::Value(Value::Concrete(Source::Path(path_source))) => (
+                    path_source.path.to_string(),
+                    path_source
+                        .target_directory
+                        .as_ref()
+                        .map(ToString::to_string),
+                    path_source.use_gitignore,
+                ),
+                _ => panic!("expected a path source, got {source:?}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sources,
+            vec![
+                (
+                    dir.path().join("data").display().to_string(),
+                    Some("data".to_string()),
+                    Some(false),
+                ),
+                (
+                    dir.path().join("assets/icons").display().to_string(),
+                    Some("icons".to_string()),
+                    Some(false),
+                ),
+            ]
+        );
+        assert!(recipe.build_input_globs.contains("assets/icons/**"));
+
+        let result = recipe.add_path_sources(dir.path(), &["missing".to_string()], None, None);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_add_path_sources_with_filter() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::create_dir_all(dir.path().join("data")).unwrap();
+
+        let filter = SourceFilter {
+            include: vec!["**/*.csv".to_string()],
+            exclude: vec!["fixtures/**".to_string()],
+        };
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe
+            .add_path_sources(dir.path(), &["data".to_string()], None, Some(&filter))
+            .unwrap();
+
+        let Item::Value(Value::Concrete(Source::Path(source))) = &recipe.recipe.source[0] else {
+            panic!("expected a path source");
+        };
+        assert_eq!(source.filter.as_ref(), Some(&filter));
+        assert_eq!(
+            recipe.build_input_globs,
+            BTreeSet::from(["!data/fixtures/**".to_string(), "data/**/*.csv".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_add_path_sources_rejects_targets_outside_work_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::create_dir_all(dir.path().join("pkg/data")).unwrap();
+        fs_err::create_dir_all(dir.path().join("other/data")).unwrap();
+        let manifest_root = dir.path().join("pkg");
+
+        let add = |paths: &[&str]| {
+            let mut recipe =
+                GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                    .unwrap();
+            let paths = paths.iter().map(ToString::to_string).collect::<Vec<_>>();
+            recipe
+                .add_path_sources(&manifest_root, &paths, None, None)
+                .map(|_| recipe)
+        };
+
+        // A directory outside of the manifest root is copied into a directory
+        // of the same name.
+        let recipe = add(&["../other/data"]).unwrap();
+        assert!(recipe.build_input_globs.contains("../other/data/**"));
+        let Item::Value(Value::Concrete(Source::Path(source))) = &recipe.recipe.source[0] else {
+            panic!("expected a path source");
+        };
+        assert_eq!(
+            source.target_directory.as_ref().map(ToString::to_string),
+            Some("data".to_string())
+        );
+
+        let absolute = manifest_root.join("data").display().to_string();
+        assert!(add(&[absolute.as_str()]).is_err());
+        assert!(add(&[".."]).is_err());
+        assert!(add(&["data/.."]).is_err());
+        // Both directories would be copied into `data`.
+        assert!(add(&["data", "../other/data"]).is_err());
+    }
+
+    #[test]
+    fn test_discover_license_file() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(dir.path().join("LICENSE"), "MIT").unwrap();
+        fs_err::write(dir.path().join("README.md"), "foobar").unwrap();
+
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe.discover_license_file(dir.path());
+        let about = recipe.recipe.about.as_ref().unwrap();
+        assert_eq!(
+            about.license_file,
+            Some(ListOrItem::single(Value::Concrete(String::from("LICENSE"))))
+        );
+        assert!(recipe.metadata_input_globs.contains("LICENSE"));
+        assert!(recipe.build_input_globs.contains("LICENSE"));
+        assert!(recipe.warnings.is_empty(), "{:?}", recipe.warnings);
+    }
+
+    #[test]
+    fn test_discover_license_file_includes_all_and_prefers_existing() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["LICENSE-MIT", "LICENSE-APACHE", "COPYING"] {
+            fs_err::write(dir.path().join(name), "license").unwrap();
+        }
+
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe.discover_license_file(dir.path());
+        let about = recipe.recipe.about.as_ref().unwrap();
+        assert_eq!(
+            about.license_file,
+            Some(ListOrItem::new(
+                ["COPYING", "LICENSE-APACHE", "LICENSE-MIT"]
+                    .into_iter()
+                    .map(|name| Value::Concrete(name.to_string()))
+                    .collect()
+            ))
+        );
+        for name in ["COPYING", "LICENSE-APACHE", "LICENSE-MIT"] {
+            assert!(recipe.metadata_input_globs.contains(name));
+            assert!(recipe.build_input_globs.contains(name));
+        }
+        assert!(recipe.warnings.is_empty(), "{:?}", recipe.warnings);
+
+        // A license file that is already set is kept.
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe.recipe.about.as_mut().unwrap().license_file = Some(ListOrItem::single(
+            Value::Concrete(String::from("docs/LICENSE")),
+        ));
+        recipe.discover_license_file(dir.path());
+        let about = recipe.recipe.about.as_ref().unwrap();
+        assert_eq!(
+            about.license_file,
+            Some(ListOrItem::single(Value::Concrete(String::from(
+                "docs/LICENSE"
+            ))))
+        );
+        assert!(recipe.build_input_globs.is_empty());
+    }
+
+    #[test]
+    fn test_add_context() {
+        let mut recipe =
+            GeneratedRecipe::from_model(project_model("foobar"), &mut DefaultMetadataProvider)
+                .unwrap();
+        recipe.recipe.context.insert(
+            String::from("name"),
+            Value::Concrete(String::from("foobar")),
+        );
+        recipe
+            .recipe
+            .source
+            .push(Item::from(Source::Url(recipe_stage0::recipe::UrlSource {
+                url: Value::Template(String::from("${{ cdn_base }}/${{ name }}.tar.gz")),
+                sha256: None,
+                target_directory: None,
+            })));
+
+        recipe.add_context(&IndexMap::from([
+            (String::from("name"), String::from("overwritten")),
+            (
+                String::from("cdn_base"),
+                String::from("https://cdn.example.com"),
+            ),
+        ]));
+        assert_eq!(recipe.warnings.len(), 1, "{:?}", recipe.warnings);
+
+        // Render the recipe with rattler-build to resolve the templates.
+        let yaml = recipe.recipe.to_yaml_pretty().unwrap();
+        let outputs = rattler_build::recipe::parser::find_outputs_from_src(
+            rattler_build::source_code::Source {
+                name: String::from("recipe"),
+                code: std::sync::Arc::from(yaml.as_str()),
+                path: PathBuf::from("recipe.yaml"),
+            },
+        )
+        .unwrap();
+        let rendered = rattler_build::recipe::Recipe::from_node(
+            &outputs[0],
+            rattler_build::selectors::SelectorConfig::default(),
+        )
+        .unwrap();
+
+        let rattler_build::recipe::parser::Source::Url(source) = &rendered.source[0] else {
+            panic!("expected a url source, got {:?}", rendered.source[0]);
+        };
+        assert_eq!(
+            source.urls()[0].as_str(),
+            "https://cdn.example.com/foobar.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_workspace_recipe_has_an_output_per_package() {
+        let recipes = ["foo", "bar"].map(|name| {
+            let mut recipe =
+                GeneratedRecipe::from_model(project_model(name), &mut DefaultMetadataProvider)
+                    .unwrap();
+            recipe.recipe.context.insert(
+                String::from("shared"),
+                Value::Concrete(String::from("value")),
+            );
+            recipe.build_input_globs.insert(format!("{name}/**"));
+            recipe
+        });
+
+        let workspace = GeneratedWorkspaceRecipe::from_recipes(recipes).unwrap();
+
+        let names = workspace
+            .recipe
+            .outputs
+            .iter()
+            .map(|output| output.package.name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["foo", "bar"]);
+        assert_eq!(workspace.recipe.context.len(), 1);
+        assert_eq!(
+            workspace.build_input_globs,
+            BTreeSet::from([String::from("bar/**"), String::from("foo/**")])
+        );
+
+        let yaml = workspace.recipe.to_yaml().unwrap();
+        let outputs = rattler_build::recipe::parser::find_outputs_from_src(
+            rattler_build::source_code::Source {
+                name: String::from("recipe"),
+                code: std::sync::Arc::from(yaml.as_str()),
+                path: PathBuf::from("recipe.yaml"),
+            },
+        )
+        .unwrap();
+        assert_eq!(outputs.len(), 2);
+    }
+
+    #[derive(Clone, Deserialize)]
+    struct NoConfig;
+
+    impl BackendConfig for NoConfig {
+        fn debug_dir(&self) -> Option<&Path> {
+            None
+        }
+
+        fn merge_with_target_config(&self, _target_config: &Self) -> miette::Result<Self> {
+            Ok(NoConfig)
+        }
+    }
+
+    /// Records the manifest root of every generated recipe as a build input
+    /// glob.
+    struct ManifestRootRecorder;
+
+    impl GenerateRecipe for ManifestRootRecorder {
+        type Config = NoConfig;
+
+        fn generate_recipe(
+            &self,
+            model: &ProjectModelV1,
+            _config: &Self::Config,
+            manifest_root: PathBuf,
+            _host_platform: Platform,
+            _build_params: Option<BuildParams>,
+            _variants: &HashSet<NormalizedKey>,
+        ) -> miette::Result<GeneratedRecipe> {
+            let mut recipe =
+                GeneratedRecipe::from_model(model.clone(), &mut DefaultMetadataProvider)
+                    .into_diagnostic()?;
+            recipe
+                .build_input_globs
+                .insert(manifest_root.display().to_string());
+            Ok(recipe)
+        }
+    }
+
+    #[test]
+    fn test_workspace_recipe_uses_manifest_directory_as_root() {
+        let packages = [("foo", "packages/foo"), ("bar", "packages/bar")]
+            .map(|(name, root)| (project_model(name), Path::new(root).join("pixi.toml")));
+
+        let workspace = ManifestRootRecorder
+            .generate_workspace_recipe(
+                &packages,
+                &NoConfig,
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .unwrap();
+
+        let roots =
+            ["packages/bar", "packages/foo"].map(|root| Path::new(root).display().to_string());
+        assert_eq!(workspace.build_input_globs, BTreeSet::from(roots));
+    }
+
+    #[test]
+    fn test_workspace_recipe_rejects_duplicate_packages() {
+        let recipes = ["foo", "foo"].map(|name| {
+            GeneratedRecipe::from_model(project_model(name), &mut DefaultMetadataProvider).unwrap()
+        });
+
+        let err = GeneratedWorkspaceRecipe::from_recipes(recipes)
+            .err()
+            .expect("duplicate package names should be rejected");
+        assert!(err.to_string().contains("'foo'"), "{err}");
+    }
+}
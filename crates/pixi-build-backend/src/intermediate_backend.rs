@@ -2,11 +2,12 @@ use std::{
     collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use indexmap::{IndexMap, IndexSet};
 use itertools::Itertools;
-use miette::{Context, IntoDiagnostic};
+use miette::IntoDiagnostic;
 use ordermap::OrderMap;
 use pixi_build_types::{
     BackendCapabilities, CondaPackageMetadata, PathSpecV1, ProjectModelV1, SourcePackageSpecV1,
@@ -26,6 +27,7 @@ use pixi_build_types::{
     },
 };
 use rattler_build::{
+    NormalizedKey,
     build::{WorkingDirectoryBehavior, run_build},
     console_utils::LoggingOutputHandler,
     hash::HashInfo,
@@ -34,7 +36,7 @@ use rattler_build::{
         PlatformWithVirtualPackages,
     },
     recipe::{
-        ParsingError, Recipe,
+        Jinja, ParsingError, Recipe,
         parser::{BuildString, find_outputs_from_src},
         variable::Variable,
     },
@@ -46,23 +48,37 @@ use rattler_build::{
     variant_config::{DiscoveredOutput, ParseErrors, VariantConfig},
 };
 use rattler_conda_types::{
-    ChannelConfig, MatchSpec, Platform, compression_level::CompressionLevel, package::ArchiveType,
+    ChannelConfig, MatchSpec, NoArchType, Platform, compression_level::CompressionLevel,
+    package::ArchiveType,
+};
+use recipe_stage0::{
+    matchspec::{PackageDependency, SerializableMatchSpec},
+    recipe::IntermediateRecipe,
 };
-use recipe_stage0::matchspec::{PackageDependency, SerializableMatchSpec};
 use serde::Deserialize;
+use url::Url;
 
 use crate::{
     TargetSelector,
     dependencies::{
         convert_binary_dependencies, convert_dependencies, convert_input_variant_configuration,
     },
-    generated_recipe::{BackendConfig, GenerateRecipe, PythonParams},
+    error::BackendError,
+    generated_recipe::{
+        BackendConfig, BackendIdentifier, BuildParams, GenerateRecipe, GeneratedRecipe,
+    },
     protocol::{Protocol, ProtocolInstantiator},
+    rattler_build_integration::check_generated_recipe,
     specs_conversion::{
         from_build_v1_args_to_finalized_dependencies, from_source_matchspec_into_package_spec,
     },
     tools::{OneOrMultipleOutputs, output_directory},
-    utils::TemporaryRenderedRecipe,
+    utils::{
+        SIDECAR_CONFIG_FILE_NAME, TemporaryRenderedRecipe, missing_channels, output_subdir,
+        package_all_archive_types, parse_archive_types, provenance_extra_meta,
+        python_site_packages_path, read_channels_file, run_with_build_retries,
+        validate_input_globs, with_extra_channels, with_sidecar_config,
+    },
 };
 
 #[derive(Debug, Default, Deserialize)]
@@ -95,11 +111,16 @@ pub struct IntermediateBackend<T: GenerateRecipe> {
     pub(crate) source_dir: PathBuf,
     /// The path to the manifest file relative to the source directory.
     pub(crate) manifest_rel_path: PathBuf,
+    /// The path to the manifest that the backend reads, e.g. `Cargo.toml`,
+    /// relative to the source directory.
+    pub(crate) backend_manifest_rel_path: Option<PathBuf>,
     pub(crate) project_model: ProjectModelV1,
     pub(crate) generate_recipe: Arc<T>,
     pub(crate) config: T::Config,
     pub(crate) target_config: OrderMap<TargetSelectorV1, T::Config>,
     pub(crate) cache_dir: Option<PathBuf>,
+    /// If set, the generated recipe is written to this path.
+    pub(crate) recipe_out: Option<PathBuf>,
 }
 impl<T: GenerateRecipe> IntermediateBackend<T> {
     #[allow(clippy::too_many_arguments)]
@@ -130,41 +151,91 @@ impl<T: GenerateRecipe> IntermediateBackend<T> {
                 (source_dir, manifest_rel_path)
             }
             Some(source_dir) => {
-                let manifest_rel_path = pathdiff::diff_paths(manifest_path, &source_dir)
-                    .ok_or_else(|| {
-                        miette::miette!("the manifest is not relative to the source directory")
-                    })?;
+                let manifest_rel_path = manifest_rel_path(&manifest_path, &source_dir)?;
                 (source_dir, manifest_rel_path)
             }
         };
 
+        // A `pixi-build.toml` next to the manifest provides defaults for the
+        // configuration, e.g. when the backend is used without a frontend.
+        let manifest_path = source_dir.join(&manifest_rel_path);
+        let manifest_dir = manifest_path.parent().unwrap_or(&source_dir);
+        let (config, target_config) = with_sidecar_config(manifest_dir, config, target_config)?;
+
         let config = serde_json::from_value::<T::Config>(config)
-            .into_diagnostic()
-            .context("failed to parse configuration")?;
+            .map_err(|err| BackendError::InvalidConfig(String::from("configuration"), err))?;
 
         let target_config = target_config
             .into_iter()
             .map(|(target, config)| {
+                let context = format!("target configuration for {target}");
                 let config = serde_json::from_value::<T::Config>(config)
-                    .into_diagnostic()
-                    .wrap_err_with(|| {
-                        format!("failed to parse target configuration for {target}")
-                    })?;
+                    .map_err(|err| BackendError::InvalidConfig(context, err))?;
                 Ok((target, config))
             })
-            .collect::<Result<_, miette::Report>>()?;
+            .collect::<Result<_, BackendError>>()?;
+
+        // Make sure that the manifest the backend reads exists, so a missing
+        // manifest is reported before a recipe is generated.
+        let expected_manifests = generate_recipe.expected_manifests(&config);
+        let backend_manifest_rel_path = locate_backend_manifest(manifest_dir, expected_manifests)?
+            .map(|backend_manifest| {
+                manifest_rel_path
+                    .parent()
+                    .unwrap_or(Path::new(""))
+                    .join(backend_manifest)
+            });
 
         Ok(Self {
             source_dir,
             manifest_rel_path,
+            backend_manifest_rel_path,
             project_model,
             generate_recipe,
             config,
             target_config,
             logging_output_handler,
             cache_dir,
+            recipe_out: None,
         })
     }
+
+    /// Returns the input globs of the `pixi-build.toml` next to the manifest
+    /// and of the manifest that the backend reads, relative to the source
+    /// directory.
+    fn manifest_input_globs(&self) -> impl Iterator<Item = String> {
+        let sidecar = self
+            .manifest_rel_path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(SIDECAR_CONFIG_FILE_NAME);
+        std::iter::once(sidecar)
+            .chain(self.backend_manifest_rel_path.clone())
+            .map(|path| {
+                path.components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .join("/")
+            })
+    }
+
+    /// Returns the path to the manifest that the backend reads, e.g.
+    /// `Cargo.toml`, or `None` if the backend doesn't expect a manifest.
+    pub fn backend_manifest_path(&self) -> Option<PathBuf> {
+        self.backend_manifest_rel_path
+            .as_ref()
+            .map(|path| self.source_dir.join(path))
+    }
+
+    /// Writes the generated recipe to the `recipe_out` path, if set.
+    fn write_recipe_out(&self, recipe_yaml: &str) -> miette::Result<()> {
+        let Some(recipe_out) = &self.recipe_out else {
+            return Ok(());
+        };
+        if let Some(parent) = recipe_out.parent() {
+            fs_err::create_dir_all(parent).into_diagnostic()?;
+        }
+        fs_err::write(recipe_out, recipe_yaml).into_diagnostic()
+    }
 }
 
 #[async_trait::async_trait]
@@ -222,6 +293,10 @@ where
             capabilities: default_capabilities(),
         })
     }
+
+    fn backend_identifier(&self) -> Option<BackendIdentifier> {
+        self.generator.backend_identifier()
+    }
 }
 
 #[async_trait::async_trait]
@@ -234,6 +309,14 @@ where
         self.config.debug_dir()
     }
 
+    fn set_recipe_out(&mut self, path: PathBuf) {
+        self.recipe_out = Some(path);
+    }
+
+    fn operation_timeout(&self) -> Option<Duration> {
+        self.config.operation_timeout()
+    }
+
     async fn conda_get_metadata(
         &self,
         params: CondaMetadataParams,
@@ -248,6 +331,7 @@ where
             .as_ref()
             .map(|p| p.platform)
             .unwrap_or(Platform::current());
+        ensure_supported_platform(self.generate_recipe.supported_platforms(), host_platform)?;
 
         let build_platform = params
             .build_platform
@@ -262,6 +346,13 @@ where
             .map(|(_, target_config)| self.config.merge_with_target_config(target_config))
             .unwrap_or_else(|| Ok(self.config.clone()))?;
 
+        let channels = with_extra_channels(
+            params.channel_base_urls.clone().unwrap_or_default(),
+            &extra_channels(&config, &self.source_dir)?,
+            config.extra_channel_priority(),
+            &channel_config,
+        )?;
+
         // Construct a `VariantConfig` based on the input parameters.
         //
         // rattler-build recipes would also load variant.yaml (or
@@ -271,7 +362,9 @@ where
         // Determine the variant configuration to use. This is a combination of defaults
         // from the generator and the user supplied parameters. The parameters
         // from the user take precedence over the default variants.
-        let recipe_variants = self.generate_recipe.default_variants(host_platform);
+        let recipe_variants = self
+            .generate_recipe
+            .default_variants(&config, host_platform);
         let mut param_variant_configuration = params
             .variant_configuration
             .unwrap_or_default()
@@ -287,29 +380,35 @@ where
         variants.append(&mut param_variant_configuration);
 
         // Construct the intermediate recipe
-        let generated_recipe = self.generate_recipe.generate_recipe(
+        let mut generated_recipe = self.generate_recipe.generate_recipe(
             &self.project_model,
             &config,
             self.source_dir.clone(),
             host_platform,
-            Some(PythonParams { editable: false }),
+            Some(BuildParams {
+                editable: config.editable(false),
+                profile: config.profile(),
+            }),
             &variants.keys().cloned().collect(),
         )?;
+        add_missing_channel_warnings(
+            &mut generated_recipe,
+            self.generate_recipe.required_channels(),
+            &channels,
+        );
+        check_recipe_warnings(&generated_recipe, config.deny_warnings())?;
+        check_recipe_license(&generated_recipe, config.validate_license())?;
 
         // Convert the recipe to source code.
         // TODO(baszalmstra): In the future it would be great if we could just
         // immediately use the intermediate recipe for some of this rattler-build
         // functions.
+        let recipe_yaml = generated_recipe.recipe.to_yaml_pretty().into_diagnostic()?;
+        self.write_recipe_out(&recipe_yaml)?;
         let recipe_path = self.source_dir.join(&self.manifest_rel_path);
         let named_source = Source {
             name: self.manifest_rel_path.display().to_string(),
-            code: Arc::from(
-                generated_recipe
-                    .recipe
-                    .to_yaml_pretty()
-                    .into_diagnostic()?
-                    .as_str(),
-            ),
+            code: Arc::from(recipe_yaml.as_str()),
             path: recipe_path.clone(),
         };
 
@@ -336,10 +435,15 @@ where
             pin_run_as_build: None,
             zip_keys: None,
         };
-        let discovered_outputs = variant_config.find_variants(
-            &outputs,
-            named_source.clone(),
+        let discovered_outputs = apply_hash_ignore_keys(
+            variant_config.find_variants(
+                &outputs,
+                named_source.clone(),
+                &selector_config_for_variants,
+            )?,
+            config.hash_ignore_keys(),
             &selector_config_for_variants,
+            &named_source,
         )?;
 
         // Build the tool configuration
@@ -357,9 +461,18 @@ where
         let mut subpackages = BTreeMap::new();
         let mut packages = Vec::new();
         let number_of_outputs = discovered_outputs.len();
+        let single_variant = is_single_variant(
+            &discovered_outputs,
+            selector_config_for_variants.target_platform,
+            config.hash_ignore_keys(),
+        );
         for discovered_output in discovered_outputs {
             let variant = discovered_output.used_vars;
-            let hash = HashInfo::from_variant(&variant, &discovered_output.noarch_type);
+            let hash = variant_hash(
+                &variant,
+                &discovered_output.noarch_type,
+                config.hash_ignore_keys(),
+            );
 
             // Construct the selector config for this particular output. We base this on the
             // selector config that was used to determine the variants.
@@ -370,22 +483,31 @@ where
                 ..selector_config_for_variants.clone()
             };
 
-            // Convert this discovered output into a recipe.
-            let recipe = Recipe::from_node(&discovered_output.node, selector_config.clone())
-                .map_err(|err| {
-                    let errs: ParseErrors<_> = err
-                        .into_iter()
-                        .map(|err| ParsingError::from_partial(named_source.clone(), err))
-                        .collect::<Vec<_>>()
-                        .into();
-                    errs
-                })?;
+            // Convert this discovered output into a recipe. The variant discovery
+            // already rendered the recipe of a single output without variant keys
+            // with an equivalent selector config, so it is reused.
+            let recipe = if single_variant {
+                discovered_output.recipe
+            } else {
+                Recipe::from_node(&discovered_output.node, selector_config.clone()).map_err(
+                    |err| {
+                        let errs: ParseErrors<_> = err
+                            .into_iter()
+                            .map(|err| ParsingError::from_partial(named_source.clone(), err))
+                            .collect::<Vec<_>>()
+                            .into();
+                        BackendError::RecipeParse(errs)
+                    },
+                )?
+            };
 
             // Skip this output if the recipe is marked as skipped
             if recipe.build().skip() {
                 continue;
             }
 
+            write_resolved_recipe(&config, &recipe, &hash)?;
+
             subpackages.insert(
                 recipe.package().name().clone(),
                 PackageIdentifier {
@@ -423,16 +545,10 @@ where
                         } else {
                             OneOrMultipleOutputs::OneOfMany(discovered_output.name.clone())
                         },
-                        params.work_directory.clone(),
+                        build_work_directory(params.work_directory.clone(), config.build_dir()),
                         &named_source.path,
                     ),
-                    channels: params
-                        .channel_base_urls
-                        .iter()
-                        .flatten()
-                        .cloned()
-                        .map(Into::into)
-                        .collect(),
+                    channels: channels.iter().cloned().map(Into::into).collect(),
                     channel_priority: tool_config.channel_priority,
                     timestamp,
                     subpackages: subpackages.clone(),
@@ -444,7 +560,7 @@ where
                     force_colors: false,
                     sandbox_config: None,
                     debug: Debug::default(),
-                    solve_strategy: Default::default(),
+                    solve_strategy: config.solve_strategy().into(),
                     exclude_newer: None,
                 },
                 finalized_dependencies: None,
@@ -456,9 +572,11 @@ where
                 extra_meta: None,
             };
 
+            // The discovered build string is either derived from the hash and the build
+            // number, or rendered from an explicit `build.string` template in the recipe.
             output.recipe.build.string = BuildString::Resolved(discovered_output.build_string);
 
-            let temp_recipe = TemporaryRenderedRecipe::from_output(&output)?;
+            let temp_recipe = TemporaryRenderedRecipe::from_output(&output, config.temp_dir())?;
             let tool_config = tool_config.clone();
             let output = temp_recipe
                 .within_context_async(move || async move {
@@ -517,7 +635,10 @@ where
                 version: output.version().clone(),
                 build: output.build_string().into_owned(),
                 build_number: output.recipe.build.number,
-                subdir: output.build_configuration.target_platform,
+                subdir: output_subdir(
+                    output.recipe.build().noarch(),
+                    output.build_configuration.target_platform,
+                ),
                 depends: finalized_run_deps
                     .iter()
                     .sorted_by_key(|dep| dep.package_name())
@@ -545,9 +666,18 @@ where
             });
         }
 
+        if packages.is_empty() {
+            warn_no_outputs(host_platform);
+        }
+
+        // Changes to the sidecar configuration or the backend manifest change
+        // the metadata.
+        let mut input_globs = generated_recipe.metadata_input_globs;
+        input_globs.extend(self.manifest_input_globs());
+
         Ok(CondaMetadataResult {
             packages,
-            input_globs: Some(generated_recipe.metadata_input_globs),
+            input_globs: Some(input_globs),
         })
     }
 
@@ -562,6 +692,7 @@ where
             .as_ref()
             .map(|p| p.platform)
             .unwrap_or(Platform::current());
+        ensure_supported_platform(self.generate_recipe.supported_platforms(), host_platform)?;
 
         let build_platform = Platform::current();
 
@@ -572,6 +703,22 @@ where
             .map(|(_, target_config)| self.config.merge_with_target_config(target_config))
             .unwrap_or_else(|| Ok(self.config.clone()))?;
 
+        let channels = with_extra_channels(
+            params.channel_base_urls.clone().unwrap_or_default(),
+            &extra_channels(&config, &self.source_dir)?,
+            config.extra_channel_priority(),
+            &channel_config,
+        )?;
+
+        // The package is built in the first archive type and afterwards
+        // converted to the remaining ones.
+        let archive_types = parse_archive_types(config.archive_types())?;
+
+        // Record which backend built the packages.
+        let extra_meta = config
+            .record_provenance()
+            .then(|| provenance_extra_meta(self.generate_recipe.backend_identifier()));
+
         // Construct a `VariantConfig` based on the input parameters.
         //
         // rattler-build recipes would also load variant.yaml (or
@@ -581,37 +728,47 @@ where
         // Determine the variant configuration to use. This is a combination of defaults
         // from the generator and the user supplied parameters. The parameters
         // from the user take precedence over the default variants.
-        let recipe_variants = self.generate_recipe.default_variants(host_platform);
+        let recipe_variants = self
+            .generate_recipe
+            .default_variants(&config, host_platform);
         let param_variants =
             convert_input_variant_configuration(params.variant_configuration).unwrap_or_default();
         let variants = BTreeMap::from_iter(itertools::chain!(recipe_variants, param_variants));
 
+        // Resolve the editable mode once, it is used for the recipe and the
+        // input globs.
+        let editable = config.editable(params.editable);
+
         // Construct the intermediate recipe
         let mut generated_recipe = self.generate_recipe.generate_recipe(
             &self.project_model,
             &config,
             self.source_dir.clone(),
             host_platform,
-            Some(PythonParams {
-                editable: params.editable,
+            Some(BuildParams {
+                editable,
+                profile: config.profile(),
             }),
             &variants.keys().cloned().collect(),
         )?;
+        add_missing_channel_warnings(
+            &mut generated_recipe,
+            self.generate_recipe.required_channels(),
+            &channels,
+        );
+        check_recipe_warnings(&generated_recipe, config.deny_warnings())?;
+        check_recipe_license(&generated_recipe, config.validate_license())?;
 
         // Convert the recipe to source code.
         // TODO(baszalmstra): In the future it would be great if we could just
         // immediately use the intermediate recipe for some of this rattler-build
         // functions.
+        let recipe_yaml = generated_recipe.recipe.to_yaml_pretty().into_diagnostic()?;
+        self.write_recipe_out(&recipe_yaml)?;
         let recipe_path = self.source_dir.join(&self.manifest_rel_path);
         let named_source = Source {
             name: self.manifest_rel_path.display().to_string(),
-            code: Arc::from(
-                generated_recipe
-                    .recipe
-                    .to_yaml_pretty()
-                    .into_diagnostic()?
-                    .as_str(),
-            ),
+            code: Arc::from(recipe_yaml.as_str()),
             path: recipe_path.clone(),
         };
 
@@ -638,10 +795,15 @@ where
             pin_run_as_build: None,
             zip_keys: None,
         };
-        let mut discovered_outputs = variant_config.find_variants(
-            &outputs,
-            named_source.clone(),
+        let mut discovered_outputs = apply_hash_ignore_keys(
+            variant_config.find_variants(
+                &outputs,
+                named_source.clone(),
+                &selector_config_for_variants,
+            )?,
+            config.hash_ignore_keys(),
             &selector_config_for_variants,
+            &named_source,
         )?;
 
         // Build the tool configuration
@@ -673,9 +835,10 @@ where
                             && build
                                 .as_ref()
                                 .is_none_or(|b| output.build_string == b.as_str())
-                            && subdir
-                                .as_ref()
-                                .is_none_or(|s| output.target_platform.as_str() == s)
+                            && subdir.as_ref().is_none_or(|s| {
+                                output_subdir(&output.noarch_type, output.target_platform).as_str()
+                                    == s
+                            })
                     })?;
                     discovered_outputs.swap_remove_index(pos)
                 })
@@ -689,9 +852,18 @@ where
 
         let mut packages = Vec::new();
         let number_of_outputs = selected_outputs.len();
+        let single_variant = is_single_variant(
+            &selected_outputs,
+            selector_config_for_variants.target_platform,
+            config.hash_ignore_keys(),
+        );
         for discovered_output in selected_outputs {
             let variant = discovered_output.used_vars;
-            let hash = HashInfo::from_variant(&variant, &discovered_output.noarch_type);
+            let hash = variant_hash(
+                &variant,
+                &discovered_output.noarch_type,
+                config.hash_ignore_keys(),
+            );
 
             // Construct the selector config for this particular output. We base this on the
             // selector config that was used to determine the variants.
@@ -702,22 +874,31 @@ where
                 ..selector_config_for_variants.clone()
             };
 
-            // Convert this discovered output into a recipe.
-            let recipe = Recipe::from_node(&discovered_output.node, selector_config.clone())
-                .map_err(|err| {
-                    let errs: ParseErrors<_> = err
-                        .into_iter()
-                        .map(|err| ParsingError::from_partial(named_source.clone(), err))
-                        .collect::<Vec<_>>()
-                        .into();
-                    errs
-                })?;
+            // Convert this discovered output into a recipe. The variant discovery
+            // already rendered the recipe of a single output without variant keys
+            // with an equivalent selector config, so it is reused.
+            let recipe = if single_variant {
+                discovered_output.recipe
+            } else {
+                Recipe::from_node(&discovered_output.node, selector_config.clone()).map_err(
+                    |err| {
+                        let errs: ParseErrors<_> = err
+                            .into_iter()
+                            .map(|err| ParsingError::from_partial(named_source.clone(), err))
+                            .collect::<Vec<_>>()
+                            .into();
+                        BackendError::RecipeParse(errs)
+                    },
+                )?
+            };
 
             // Skip this output if the recipe is marked as skipped
             if recipe.build().skip() {
                 continue;
             }
 
+            write_resolved_recipe(&config, &recipe, &hash)?;
+
             subpackages.insert(
                 recipe.package().name().clone(),
                 PackageIdentifier {
@@ -754,28 +935,22 @@ where
                         } else {
                             OneOrMultipleOutputs::OneOfMany(discovered_output.name.clone())
                         },
-                        params.work_directory.clone(),
+                        build_work_directory(params.work_directory.clone(), config.build_dir()),
                         &named_source.path,
                     ),
-                    channels: params
-                        .channel_base_urls
-                        .iter()
-                        .flatten()
-                        .cloned()
-                        .map(Into::into)
-                        .collect(),
+                    channels: channels.iter().cloned().map(Into::into).collect(),
                     channel_priority: tool_config.channel_priority,
                     timestamp,
                     subpackages: subpackages.clone(),
                     packaging_settings: PackagingSettings::from_args(
-                        ArchiveType::Conda,
+                        archive_types[0],
                         CompressionLevel::default(),
                     ),
-                    store_recipe: false,
+                    store_recipe: config.store_recipe(),
                     force_colors: false,
                     sandbox_config: None,
                     debug: Debug::default(),
-                    solve_strategy: Default::default(),
+                    solve_strategy: config.solve_strategy().into(),
                     exclude_newer: None,
                 },
                 finalized_dependencies: None,
@@ -784,33 +959,55 @@ where
                 finalized_cache_sources: None,
                 system_tools: SystemTools::default(),
                 build_summary: Arc::default(),
-                extra_meta: None,
+                extra_meta: extra_meta.clone(),
             };
 
+            // The discovered build string is either derived from the hash and the build
+            // number, or rendered from an explicit `build.string` template in the recipe.
             output.recipe.build.string = BuildString::Resolved(discovered_output.build_string);
 
-            let temp_recipe = TemporaryRenderedRecipe::from_output(&output)?;
+            // Extract the input globs from the build, before building so that
+            // invalid globs are reported right away.
+            let mut input_globs =
+                T::extract_input_globs_from_build(&config, &params.work_directory, editable);
+            validate_input_globs(&input_globs)?;
+
+            let temp_recipe = TemporaryRenderedRecipe::from_output(&output, config.temp_dir())?;
             let tool_config = tool_config.clone();
+            let build_retries = config.build_retries();
             let (output, package) = temp_recipe
                 .within_context_async(move || async move {
-                    run_build(output, &tool_config, WorkingDirectoryBehavior::Preserve).await
+                    let work_dir = output.build_configuration.directories.work_dir.clone();
+                    run_with_build_retries(&work_dir, build_retries, || {
+                        run_build(
+                            output.clone(),
+                            &tool_config,
+                            WorkingDirectoryBehavior::Preserve,
+                        )
+                    })
+                    .await
                 })
                 .await?;
 
-            // Extract the input globs from the build and recipe
-            let mut input_globs =
-                T::extract_input_globs_from_build(&config, &params.work_directory, params.editable);
+            // Add the input globs of the recipe
             input_globs.append(&mut generated_recipe.build_input_globs);
 
-            let built_package = CondaBuiltPackage {
-                output_file: package,
-                input_globs,
-                name: output.name().clone(),
-                version: output.version().to_string(),
-                build: output.build_string().into_owned(),
-                subdir: output.target_platform().to_string(),
-            };
-            packages.push(built_package);
+            // Return a package for every requested archive type.
+            for output_file in package_all_archive_types(&package, &archive_types)? {
+                let built_package = CondaBuiltPackage {
+                    output_file,
+                    input_globs: input_globs.clone(),
+                    name: output.name().clone(),
+                    version: output.version().to_string(),
+                    build: output.build_string().into_owned(),
+                    subdir: output_subdir(
+                        output.recipe.build().noarch(),
+                        *output.target_platform(),
+                    )
+                    .to_string(),
+                };
+                packages.push(built_package);
+            }
         }
 
         Ok(CondaBuildResult { packages })
@@ -820,6 +1017,10 @@ where
         &self,
         params: CondaOutputsParams,
     ) -> miette::Result<CondaOutputsResult> {
+        ensure_supported_platform(
+            self.generate_recipe.supported_platforms(),
+            params.host_platform,
+        )?;
         let build_platform = params.host_platform;
 
         let config = self
@@ -838,29 +1039,43 @@ where
         // Determine the variant configuration to use. This is a combination of defaults
         // from the generator and the user supplied parameters. The parameters
         // from the user take precedence over the default variants.
-        let recipe_variants = self.generate_recipe.default_variants(params.host_platform);
+        let recipe_variants = self
+            .generate_recipe
+            .default_variants(&config, params.host_platform);
         let param_variants =
             convert_input_variant_configuration(params.variant_configuration).unwrap_or_default();
         let variants = BTreeMap::from_iter(itertools::chain!(recipe_variants, param_variants));
 
         // Construct the intermediate recipe
-        let recipe = self.generate_recipe.generate_recipe(
+        let mut recipe = self.generate_recipe.generate_recipe(
             &self.project_model,
             &config,
             self.source_dir.clone(),
             params.host_platform,
-            Some(PythonParams { editable: false }),
+            Some(BuildParams {
+                editable: config.editable(false),
+                profile: config.profile(),
+            }),
             &variants.keys().cloned().collect(),
         )?;
+        add_missing_channel_warnings(
+            &mut recipe,
+            self.generate_recipe.required_channels(),
+            &params.channels,
+        );
+        check_recipe_warnings(&recipe, config.deny_warnings())?;
+        check_recipe_license(&recipe, config.validate_license())?;
 
         // Convert the recipe to source code.
         // TODO(baszalmstra): In the future it would be great if we could just
         // immediately use the intermediate recipe for some of this rattler-build
         // functions.
+        let recipe_yaml = recipe.recipe.to_yaml_pretty().into_diagnostic()?;
+        self.write_recipe_out(&recipe_yaml)?;
         let recipe_path = self.source_dir.join(&self.manifest_rel_path);
         let named_source = Source {
             name: self.manifest_rel_path.display().to_string(),
-            code: Arc::from(recipe.recipe.to_yaml_pretty().into_diagnostic()?.as_str()),
+            code: Arc::from(recipe_yaml.as_str()),
             path: recipe_path.clone(),
         };
 
@@ -887,10 +1102,15 @@ where
             pin_run_as_build: None,
             zip_keys: None,
         };
-        let discovered_outputs = variant_config.find_variants(
-            &outputs,
-            named_source.clone(),
+        let discovered_outputs = apply_hash_ignore_keys(
+            variant_config.find_variants(
+                &outputs,
+                named_source.clone(),
+                &selector_config_for_variants,
+            )?,
+            config.hash_ignore_keys(),
             &selector_config_for_variants,
+            &named_source,
         )?;
 
         // Construct a mapping that for packages that we want from source.
@@ -907,11 +1127,21 @@ where
             })
             .collect();
 
+        let python_site_packages_path_aware = recipe.python_site_packages_path_aware;
         let mut subpackages = HashMap::new();
         let mut outputs = Vec::new();
+        let single_variant = is_single_variant(
+            &discovered_outputs,
+            selector_config_for_variants.target_platform,
+            config.hash_ignore_keys(),
+        );
         for discovered_output in discovered_outputs {
             let variant = discovered_output.used_vars;
-            let hash = HashInfo::from_variant(&variant, &discovered_output.noarch_type);
+            let hash = variant_hash(
+                &variant,
+                &discovered_output.noarch_type,
+                config.hash_ignore_keys(),
+            );
 
             // Construct the selector config for this particular output. We base this on the
             // selector config that was used to determine the variants.
@@ -922,24 +1152,49 @@ where
                 ..selector_config_for_variants.clone()
             };
 
-            // Convert this discovered output into a recipe.
-            let recipe = Recipe::from_node(&discovered_output.node, selector_config.clone())
-                .map_err(|err| {
-                    let errs: ParseErrors<_> = err
-                        .into_iter()
-                        .map(|err| ParsingError::from_partial(named_source.clone(), err))
-                        .collect::<Vec<_>>()
-                        .into();
-                    errs
-                })?;
+            // Convert this discovered output into a recipe. The variant discovery
+            // already rendered the recipe of a single output without variant keys
+            // with an equivalent selector config, so it is reused.
+            let recipe = if single_variant {
+                discovered_output.recipe
+            } else {
+                Recipe::from_node(&discovered_output.node, selector_config.clone()).map_err(
+                    |err| {
+                        let errs: ParseErrors<_> = err
+                            .into_iter()
+                            .map(|err| ParsingError::from_partial(named_source.clone(), err))
+                            .collect::<Vec<_>>()
+                            .into();
+                        BackendError::RecipeParse(errs)
+                    },
+                )?
+            };
 
             // Skip this output if the recipe is marked as skipped
             if recipe.build().skip() {
                 continue;
             }
 
+            write_resolved_recipe(&config, &recipe, &hash)?;
+
             let build_number = recipe.build().number;
 
+            // Compiled python packages install into a site-packages directory
+            // that depends on the python version they were built for.
+            let python_site_packages_path =
+                if python_site_packages_path_aware && recipe.build.noarch.is_none() {
+                    variant
+                        .get(&NormalizedKey::from("python"))
+                        .and_then(|python| {
+                            python_site_packages_path(
+                                &python.to_string(),
+                                discovered_output.target_platform,
+                            )
+                        })
+                } else {
+                    None
+                };
+
             subpackages.insert(
                 recipe.package().name().clone(),
                 PackageIdentifier {
@@ -955,12 +1210,15 @@ where
                     version: recipe.package.version().clone(),
                     build: discovered_output.build_string.clone(),
                     build_number,
-                    subdir: discovered_output.target_platform,
+                    subdir: output_subdir(
+                        &discovered_output.noarch_type,
+                        discovered_output.target_platform,
+                    ),
                     license: recipe.about.license.map(|l| l.to_string()),
                     license_family: recipe.about.license_family,
                     noarch: recipe.build.noarch,
                     purls: None,
-                    python_site_packages_path: None,
+                    python_site_packages_path,
                     variant: variant
                         .iter()
                         .map(|(key, value)| (key.0.clone(), value.to_string()))
@@ -1048,9 +1306,18 @@ where
             });
         }
 
+        if outputs.is_empty() {
+            warn_no_outputs(params.host_platform);
+        }
+
+        // Changes to the sidecar configuration or the backend manifest change
+        // the outputs.
+        let mut input_globs = recipe.metadata_input_globs;
+        input_globs.extend(self.manifest_input_globs());
+
         Ok(CondaOutputsResult {
             outputs,
-            input_globs: recipe.metadata_input_globs,
+            input_globs,
         })
     }
 
@@ -1062,6 +1329,7 @@ where
             .host_prefix
             .as_ref()
             .map_or_else(Platform::current, |prefix| prefix.platform);
+        ensure_supported_platform(self.generate_recipe.supported_platforms(), host_platform)?;
         let build_platform = params
             .build_prefix
             .as_ref()
@@ -1074,6 +1342,21 @@ where
             .map(|(_, target_config)| self.config.merge_with_target_config(target_config))
             .unwrap_or_else(|| Ok(self.config.clone()))?;
 
+        // `conda/build_v1` returns a single package, so only the first archive
+        // type is built.
+        let archive_types = parse_archive_types(config.archive_types())?;
+        if archive_types.len() > 1 {
+            tracing::warn!(
+                "multiple archive types are only supported by `conda/build_v0`, only building a {} package",
+                archive_types[0].extension()
+            );
+        }
+
+        // Record which backend built the package.
+        let extra_meta = config
+            .record_provenance()
+            .then(|| provenance_extra_meta(self.generate_recipe.backend_identifier()));
+
         // Construct the variants based on the input parameters. We only
         // have a single variant here so we can just use the variant from the
         // parameters.
@@ -1084,26 +1367,35 @@ where
             .map(|(k, v)| (k.as_str().into(), vec![Variable::from_string(v)]))
             .collect();
 
+        // Resolve the editable mode once, it is used for the recipe and the
+        // input globs.
+        let editable = config.editable(params.editable.unwrap_or_default());
+
         // Construct the intermediate recipe
         let mut recipe = self.generate_recipe.generate_recipe(
             &self.project_model,
             &config,
             self.source_dir.clone(),
             host_platform,
-            Some(PythonParams {
-                editable: params.editable.unwrap_or_default(),
+            Some(BuildParams {
+                editable,
+                profile: config.profile(),
             }),
             &variants.keys().cloned().collect(),
         )?;
+        check_recipe_warnings(&recipe, config.deny_warnings())?;
+        check_recipe_license(&recipe, config.validate_license())?;
 
         // Convert the recipe to source code.
         // TODO(baszalmstra): In the future it would be great if we could just
         // immediately use the intermediate recipe for some of this rattler-build
         // functions.
+        let recipe_yaml = recipe.recipe.to_yaml_pretty().into_diagnostic()?;
+        self.write_recipe_out(&recipe_yaml)?;
         let recipe_path = self.source_dir.join(&self.manifest_rel_path);
         let named_source = Source {
             name: self.manifest_rel_path.display().to_string(),
-            code: Arc::from(recipe.recipe.to_yaml_pretty().into_diagnostic()?.as_str()),
+            code: Arc::from(recipe_yaml.as_str()),
             path: recipe_path.clone(),
         };
 
@@ -1124,10 +1416,15 @@ where
             pin_run_as_build: None,
             zip_keys: None,
         };
-        let discovered_outputs = variant_config.find_variants(
-            &outputs,
-            named_source.clone(),
+        let discovered_outputs = apply_hash_ignore_keys(
+            variant_config.find_variants(
+                &outputs,
+                named_source.clone(),
+                &selector_config_for_variants,
+            )?,
+            config.hash_ignore_keys(),
             &selector_config_for_variants,
+            &named_source,
         )?;
         let discovered_output = find_matching_output(&params.output, discovered_outputs)?;
 
@@ -1136,6 +1433,7 @@ where
             params.host_prefix.as_ref().map(|p| p.prefix.as_path()),
             params.build_prefix.as_ref().map(|p| p.prefix.as_path()),
             params.work_directory.clone(),
+            config.build_dir(),
             self.cache_dir.as_deref(),
             params.output_directory.as_deref(),
             recipe_path,
@@ -1152,8 +1450,13 @@ where
             .with_environments_externally_managed(true)
             .finish();
 
+        // Make sure an explicit `build.string` template resolves to the same build
+        // string that was used to select this output.
+        let mut recipe = discovered_output.recipe;
+        recipe.build.string = BuildString::Resolved(discovered_output.build_string.clone());
+
         let output = Output {
-            recipe: discovered_output.recipe,
+            recipe,
             build_configuration: BuildConfiguration {
                 target_platform: discovered_output.target_platform,
                 host_platform: PlatformWithVirtualPackages {
@@ -1169,14 +1472,14 @@ where
                 directories,
                 channels: vec![],
                 channel_priority: Default::default(),
-                solve_strategy: Default::default(),
+                solve_strategy: config.solve_strategy().into(),
                 timestamp: chrono::Utc::now(),
                 subpackages: BTreeMap::new(),
                 packaging_settings: PackagingSettings::from_args(
-                    ArchiveType::Conda,
+                    archive_types[0],
                     CompressionLevel::default(),
                 ),
-                store_recipe: false,
+                store_recipe: config.store_recipe(),
                 force_colors: true,
                 sandbox_config: None,
                 debug: Debug::new(false),
@@ -1194,20 +1497,29 @@ where
             finalized_cache_sources: None,
             build_summary: Arc::default(),
             system_tools: Default::default(),
-            extra_meta: None,
+            extra_meta,
         };
 
+        // Extract the input globs from the build, before building so that
+        // invalid globs are reported right away.
+        let mut input_globs =
+            T::extract_input_globs_from_build(&config, &params.work_directory, editable);
+        validate_input_globs(&input_globs)?;
+
+        let work_dir = output.build_configuration.directories.work_dir.clone();
         let (output, output_path) =
-            // WorkingDirectoryBehavior::Preserve is blocked by
-            // https://github.com/prefix-dev/rattler-build/issues/1825
-            run_build(output, &tool_config, WorkingDirectoryBehavior::Cleanup).await?;
+            run_with_build_retries(&work_dir, config.build_retries(), || {
+                // WorkingDirectoryBehavior::Preserve is blocked by
+                // https://github.com/prefix-dev/rattler-build/issues/1825
+                run_build(
+                    output.clone(),
+                    &tool_config,
+                    WorkingDirectoryBehavior::Cleanup,
+                )
+            })
+            .await?;
 
-        // Extract the input globs from the build and recipe
-        let mut input_globs = T::extract_input_globs_from_build(
-            &config,
-            &params.work_directory,
-            params.editable.unwrap_or_default(),
-        );
+        // Add the input globs of the recipe
         input_globs.append(&mut recipe.build_input_globs);
 
         Ok(CondaBuildV1Result {
@@ -1216,9 +1528,350 @@ where
             name: output.name().as_normalized().to_string(),
             version: output.version().clone(),
             build: output.build_string().into_owned(),
-            subdir: *output.target_platform(),
+            subdir: output_subdir(output.recipe.build().noarch(), *output.target_platform()),
         })
     }
+
+    async fn check(&self) -> miette::Result<Vec<String>> {
+        let mut problems = Vec::new();
+
+        // Check the base configuration and every target specific configuration
+        // merged on top of it.
+        let mut configs = vec![(None, self.config.clone())];
+        for (selector, target_config) in &self.target_config {
+            match self.config.merge_with_target_config(target_config) {
+                Ok(config) => configs.push((Some(selector), config)),
+                Err(err) => problems.push(format!("{selector}: {err}")),
+            }
+        }
+
+        for (selector, config) in configs {
+            problems.extend(
+                self.generate_recipe
+                    .validate(&self.project_model, &config)
+                    .into_iter()
+                    .map(|problem| match selector {
+                        Some(selector) => format!("{selector}: {problem}"),
+                        None => problem,
+                    }),
+            );
+        }
+
+        // Generating the recipe surfaces problems with the project model itself,
+        // like a missing name or version. This does not solve or build anything.
+        // Warnings of the generated recipe are reported as problems as well.
+        match self.generate_recipe.generate_recipe(
+            &self.project_model,
+            &self.config,
+            self.source_dir.clone(),
+            Platform::current(),
+            Some(BuildParams {
+                editable: self.config.editable(false),
+                profile: self.config.profile(),
+            }),
+            &Default::default(),
+        ) {
+            Ok(recipe) => problems.extend(recipe.warnings),
+            Err(err) => problems.push(err.chain().map(ToString::to_string).join(": ")),
+        }
+
+        Ok(problems)
+    }
+
+    async fn check_recipe(&self) -> miette::Result<Vec<String>> {
+        let platform = Platform::current();
+        let config = self
+            .target_config
+            .iter()
+            .find(|(selector, _)| selector.matches(platform))
+            .map(|(_, target_config)| self.config.merge_with_target_config(target_config))
+            .unwrap_or_else(|| Ok(self.config.clone()))?;
+
+        let variants = self.generate_recipe.default_variants(&config, platform);
+        let recipe = self.generate_recipe.generate_recipe(
+            &self.project_model,
+            &config,
+            self.source_dir.clone(),
+            platform,
+            Some(BuildParams {
+                editable: config.editable(false),
+                profile: config.profile(),
+            }),
+            &variants.keys().cloned().collect(),
+        )?;
+        check_recipe_warnings(&recipe, config.deny_warnings())?;
+        check_recipe_license(&recipe, config.validate_license())?;
+
+        check_generated_recipe(&recipe, &self.source_dir.join(&self.manifest_rel_path))
+    }
+
+    async fn generate_recipe(&self) -> miette::Result<IntermediateRecipe> {
+        let platform = Platform::current();
+        let config = self
+            .target_config
+            .iter()
+            .find(|(selector, _)| selector.matches(platform))
+            .map(|(_, target_config)| self.config.merge_with_target_config(target_config))
+            .unwrap_or_else(|| Ok(self.config.clone()))?;
+
+        let variants = self.generate_recipe.default_variants(&config, platform);
+        let recipe = self.generate_recipe.generate_recipe(
+            &self.project_model,
+            &config,
+            self.source_dir.clone(),
+            platform,
+            Some(BuildParams {
+                editable: config.editable(false),
+                profile: config.profile(),
+            }),
+            &variants.keys().cloned().collect(),
+        )?;
+        Ok(recipe.recipe)
+    }
+}
+
+/// Returns the path of the manifest relative to the source directory. If no
+/// relative path exists, e.g. because they are on different drives on
+/// windows, the manifest is assumed to be in the root of the source directory.
+fn manifest_rel_path(manifest_path: &Path, source_dir: &Path) -> miette::Result<PathBuf> {
+    if let Some(manifest_rel_path) = pathdiff::diff_paths(manifest_path, source_dir) {
+        return Ok(manifest_rel_path);
+    }
+    let file_name = manifest_path.file_name().ok_or_else(|| {
+        miette::miette!(
+            "the manifest '{}' is not relative to the source directory",
+            manifest_path.display()
+        )
+    })?;
+    tracing::warn!(
+        "the manifest '{}' is not relative to the source directory '{}', assuming it is in the \
+         root of the source directory",
+        manifest_path.display(),
+        source_dir.display()
+    );
+    Ok(PathBuf::from(file_name))
+}
+
+/// Returns the file name of the first of the `expected` manifests of a backend
+/// that exists in `manifest_dir`, or `None` if the backend doesn't expect a
+/// manifest.
+fn locate_backend_manifest(
+    manifest_dir: &Path,
+    expected: &[&str],
+) -> Result<Option<PathBuf>, BackendError> {
+    if expected.is_empty() {
+        return Ok(None);
+    }
+    expected
+        .iter()
+        .map(PathBuf::from)
+        .find(|name| manifest_dir.join(name).is_file())
+        .map(Some)
+        .ok_or_else(|| {
+            let expected = expected.iter().map(|name| format!("`{name}`")).join(" or ");
+            BackendError::BackendManifestNotFound(expected, manifest_dir.to_path_buf())
+        })
+}
+
+/// Reports the warnings that were collected while generating the recipe.
+/// Returns an error that lists all warnings if `deny_warnings` is set,
+/// otherwise they are only logged.
+fn check_recipe_warnings(recipe: &GeneratedRecipe, deny_warnings: bool) -> miette::Result<()> {
+    if recipe.warnings.is_empty() {
+        return Ok(());
+    }
+    if deny_warnings {
+        miette::bail!(
+            "generating the recipe resulted in warnings, which are denied by `deny-warnings`:\n{}",
+            recipe
+                .warnings
+                .iter()
+                .map(|warning| format!("  - {warning}"))
+                .join("\n")
+        );
+    }
+    for warning in &recipe.warnings {
+        tracing::warn!("{warning}");
+    }
+    Ok(())
+}
+
+/// Returns an error if `validate_license` is set and the license of the
+/// recipe is not a valid SPDX license expression.
+fn check_recipe_license(
+    recipe: &GeneratedRecipe,
+    validate_license: bool,
+) -> Result<(), BackendError> {
+    if !validate_license {
+        return Ok(());
+    }
+    match recipe
+        .recipe
+        .about
+        .as_ref()
+        .and_then(|about| about.license.as_ref())
+    {
+        Some(license) => crate::license::validate_license(license),
+        None => Ok(()),
+    }
+}
+
+/// Returns an error if the host platform is not one of the platforms the
+/// backend supports. `None` means that every platform is supported.
+fn ensure_supported_platform(
+    supported_platforms: Option<&[Platform]>,
+    host_platform: Platform,
+) -> Result<(), BackendError> {
+    let Some(platforms) = supported_platforms else {
+        return Ok(());
+    };
+    if platforms.contains(&host_platform) {
+        return Ok(());
+    }
+    let supported = platforms.iter().join(", ");
+    Err(BackendError::UnsupportedPlatform(host_platform, supported))
+}
+
+/// Returns the extra channels of the configuration, followed by the channels
+/// of its channels file, if any.
+fn extra_channels(config: &impl BackendConfig, source_dir: &Path) -> miette::Result<Vec<String>> {
+    let mut extra_channels = config.extra_channels().to_vec();
+    if let Some(channels_file) = config.channels_file() {
+        extra_channels.extend(read_channels_file(&source_dir.join(channels_file))?);
+    }
+    Ok(extra_channels)
+}
+
+/// Adds a warning to the recipe for every channel that the backend requires,
+/// but that is not part of the channels of the request.
+fn add_missing_channel_warnings(
+    recipe: &mut GeneratedRecipe,
+    required_channels: &[&str],
+    channels: &[Url],
+) {
+    for channel in missing_channels(required_channels, channels) {
+        recipe.warnings.push(format!(
+            "the packages of this backend depend on the channel '{channel}', which is not part of the configured channels"
+        ));
+    }
+}
+
+/// Writes the resolved recipe of an output to the `resolved-recipes`
+/// directory in the debug directory, if enabled in the configuration. Unlike
+/// the generated recipe, all templates and selectors are evaluated for the
+/// variant of the output.
+fn write_resolved_recipe(
+    config: &impl BackendConfig,
+    recipe: &Recipe,
+    hash: &HashInfo,
+) -> miette::Result<()> {
+    let Some(debug_dir) = config.debug_dir() else {
+        return Ok(());
+    };
+    if !config.debug_resolved_recipes() {
+        return Ok(());
+    }
+    let resolved_recipes = debug_dir.join("resolved-recipes");
+    fs_err::create_dir_all(&resolved_recipes).into_diagnostic()?;
+    let file_name = format!("{}-{hash}.yaml", recipe.package().name().as_normalized());
+    let recipe_yaml = serde_yaml::to_string(recipe).into_diagnostic()?;
+    fs_err::write(resolved_recipes.join(file_name), recipe_yaml).into_diagnostic()
+}
+
+/// Warns that a recipe has no outputs for the platform, e.g. because all
+/// outputs are skipped on it. This is not an error, a package can simply not
+/// be available for some platforms.
+pub fn warn_no_outputs(platform: Platform) {
+    tracing::warn!(
+        "the recipe has no outputs for '{platform}', all outputs are skipped on this platform"
+    );
+}
+
+/// Computes the hash of a variant, leaving out the keys that should not
+/// influence the build string.
+fn variant_hash(
+    variant: &BTreeMap<NormalizedKey, Variable>,
+    noarch: &NoArchType,
+    ignore_keys: &[String],
+) -> HashInfo {
+    let variant = variant
+        .iter()
+        .filter(|(key, _)| {
+            !ignore_keys
+                .iter()
+                .any(|ignored| NormalizedKey::from(ignored.as_str()) == **key)
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    HashInfo::from_variant(&variant, noarch)
+}
+
+/// Recomputes the hashes and the build strings of the discovered outputs
+/// without the variant keys that should not influence the hash. The build
+/// string of the recipe is resolved again with the recomputed hash, so only
+/// the `${{ hash }}` of the build string changes. The keys are still part of
+/// the variant of the outputs.
+fn apply_hash_ignore_keys(
+    discovered_outputs: IndexSet<DiscoveredOutput>,
+    ignore_keys: &[String],
+    selector_config_for_variants: &SelectorConfig,
+    named_source: &Source,
+) -> Result<IndexSet<DiscoveredOutput>, BackendError> {
+    if ignore_keys.is_empty() {
+        return Ok(discovered_outputs);
+    }
+
+    discovered_outputs
+        .into_iter()
+        .map(|mut output| {
+            let hash = variant_hash(&output.used_vars, &output.noarch_type, ignore_keys);
+            let selector_config = SelectorConfig {
+                variant: output.used_vars.clone(),
+                hash: Some(hash.clone()),
+                target_platform: output.target_platform,
+                ..selector_config_for_variants.clone()
+            };
+            let recipe =
+                Recipe::from_node(&output.node, selector_config.clone()).map_err(|err| {
+                    let errs: ParseErrors<_> = err
+                        .into_iter()
+                        .map(|err| ParsingError::from_partial(named_source.clone(), err))
+                        .collect::<Vec<_>>()
+                        .into();
+                    BackendError::RecipeParse(errs)
+                })?;
+            output.build_string = recipe
+                .build()
+                .string()
+                .resolve(&hash, recipe.build().number(), &Jinja::new(selector_config))
+                .into_owned();
+            output.hash = hash;
+            Ok(output)
+        })
+        .collect()
+}
+
+/// Returns true if the variant discovery resulted in a single output for
+/// `target_platform` that does not depend on any variant key other than the
+/// target platform. This is the common case for simple packages, for which the
+/// recipe that was rendered while discovering the variants can be reused.
+///
+/// When the target platform is one of the `hash_ignore_keys` the hash of the
+/// output differs from the one used during the discovery, so the recipe has
+/// to be rendered again.
+fn is_single_variant(
+    discovered_outputs: &IndexSet<DiscoveredOutput>,
+    target_platform: Platform,
+    hash_ignore_keys: &[String],
+) -> bool {
+    discovered_outputs.len() == 1
+        && discovered_outputs.iter().all(|output| {
+            output.target_platform == target_platform
+                && output
+                    .used_vars
+                    .keys()
+                    .all(|key| key.0 == "target_platform" && !hash_ignore_keys.contains(&key.0))
+        })
 }
 
 pub fn find_matching_output(
@@ -1238,7 +1891,8 @@ pub fn find_matching_output(
                     .version
                     .as_ref()
                     .is_none_or(|version| version == &output.recipe.package.version)
-                && expected_output.subdir == output.target_platform
+                && expected_output.subdir
+                    == output_subdir(&output.noarch_type, output.target_platform)
                 && !output.recipe.build.skip()
         })
         .ok_or_else(|| {
@@ -1256,14 +1910,29 @@ pub fn find_matching_output(
     Ok(discovered_output)
 }
 
+/// Returns the directory that a package is built in. If a `build_dir` is
+/// given, this is a subdirectory of it that is named after the work directory,
+/// so that builds of different packages don't collide. Otherwise it is the
+/// work directory itself.
+fn build_work_directory(work_directory: PathBuf, build_dir: Option<&Path>) -> PathBuf {
+    match (build_dir, work_directory.file_name()) {
+        (Some(build_dir), Some(name)) => build_dir.join(name),
+        _ => work_directory,
+    }
+}
+
+/// Composes the directories of a build. The directories that are not given
+/// explicitly are derived from the [`build_work_directory`].
 pub fn conda_build_v1_directories(
     host_prefix: Option<&Path>,
     build_prefix: Option<&Path>,
     work_directory: PathBuf,
+    build_dir: Option<&Path>,
     cache_dir: Option<&Path>,
     output_dir: Option<&Path>,
     recipe_path: PathBuf,
 ) -> Directories {
+    let work_directory = build_work_directory(work_directory, build_dir);
     Directories {
         recipe_dir: recipe_path
             .parent()
@@ -1299,3 +1968,402 @@ fn default_capabilities() -> BackendCapabilities {
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPE: &str = r#"
+package:
+  name: foobar
+  version: "0.1.0"
+
+build:
+  script: echo ${{ target_platform }}
+
+requirements:
+  run:
+    - if: unix
+      then: bash
+"#;
+
+    fn discover_outputs(platform: Platform) -> (SelectorConfig, IndexSet<DiscoveredOutput>) {
+        let named_source = Source {
+            name: "recipe.yaml".to_string(),
+            code: Arc::from(RECIPE),
+            path: PathBuf::from("recipe.yaml"),
+        };
+        let selector_config = SelectorConfig {
+            target_platform: platform,
+            host_platform: platform,
+            build_platform: platform,
+            hash: None,
+            variant: Default::default(),
+            experimental: false,
+            allow_undefined: false,
+            recipe_path: None,
+        };
+        let outputs = find_outputs_from_src(named_source.clone()).unwrap();
+        let discovered_outputs = VariantConfig::default()
+            .find_variants(&outputs, named_source, &selector_config)
+            .unwrap();
+        (selector_config, discovered_outputs)
+    }
+
+    /// Discovers the outputs of a recipe that is built for two python
+    /// versions.
+    fn discover_python_variants() -> IndexSet<DiscoveredOutput> {
+        discover_python_variants_of(&python_recipe_source(""))
+    }
+
+    /// A recipe that depends on python, with the given `build` section.
+    fn python_recipe_source(build: &str) -> Source {
+        Source {
+            name: "recipe.yaml".to_string(),
+            code: Arc::from(format!(
+                r#"
+package:
+  name: foobar
+  version: "0.1.0"
+{build}
+requirements:
+  host:
+    - python
+"#
+            )),
+            path: PathBuf::from("recipe.yaml"),
+        }
+    }
+
+    /// Discovers the outputs of `named_source` for two python versions.
+    fn discover_python_variants_of(named_source: &Source) -> IndexSet<DiscoveredOutput> {
+        let (selector_config, _) = discover_outputs(Platform::Linux64);
+        let named_source = named_source.clone();
+        let outputs = find_outputs_from_src(named_source.clone()).unwrap();
+        let variant_config = VariantConfig {
+            variants: BTreeMap::from([(
+                "python".into(),
+                vec![Variable::from_string("3.11"), Variable::from_string("3.12")],
+            )]),
+            pin_run_as_build: None,
+            zip_keys: None,
+        };
+        variant_config
+            .find_variants(&outputs, named_source, &selector_config)
+            .unwrap()
+    }
+
+    /// Renders the recipe of a discovered output like the general path does,
+    /// with a selector config for the variant of the output.
+    fn render_general_path(
+        selector_config_for_variants: &SelectorConfig,
+        output: &DiscoveredOutput,
+    ) -> String {
+        let selector_config = SelectorConfig {
+            variant: output.used_vars.clone(),
+            hash: Some(variant_hash(&output.used_vars, &output.noarch_type, &[])),
+            target_platform: output.target_platform,
+            ..selector_config_for_variants.clone()
+        };
+        let recipe = Recipe::from_node(&output.node, selector_config).unwrap();
+        serde_yaml::to_string(&recipe).unwrap()
+    }
+
+    #[test]
+    fn test_single_variant_reuses_identical_recipe() {
+        let (selector_config, discovered_outputs) = discover_outputs(Platform::Linux64);
+        assert!(is_single_variant(
+            &discovered_outputs,
+            Platform::Linux64,
+            &[]
+        ));
+
+        let output = discovered_outputs.first().unwrap();
+        assert_eq!(
+            serde_yaml::to_string(&output.recipe).unwrap(),
+            render_general_path(&selector_config, output)
+        );
+    }
+
+    #[test]
+    fn test_single_output_build_recipe_matches_general_path() {
+        // A recipe like the ones that are built by `conda_build_v0`, with a
+        // context, a path source and a build script that uses templates.
+        let named_source = Source {
+            name: "recipe.yaml".to_string(),
+            code: Arc::from(
+                r#"
+context:
+  name: foobar
+  version: "0.1.0"
+
+package:
+  name: ${{ name }}
+  version: ${{ version }}
+
+source:
+  - path: .
+
+build:
+  number: 3
+  script:
+    - echo building ${{ name }} ${{ version }} for ${{ target_platform }}
+    - if: win
+      then: copy foobar.bat %LIBRARY_BIN%
+      else: install foobar.sh $PREFIX/bin
+
+requirements:
+  host:
+    - openssl
+  run:
+    - openssl >=3
+"#,
+            ),
+            path: PathBuf::from("recipe.yaml"),
+        };
+        let (selector_config, _) = discover_outputs(Platform::Linux64);
+        let outputs = find_outputs_from_src(named_source.clone()).unwrap();
+        let discovered_outputs = VariantConfig::default()
+            .find_variants(&outputs, named_source, &selector_config)
+            .unwrap();
+        assert!(is_single_variant(
+            &discovered_outputs,
+            Platform::Linux64,
+            &[]
+        ));
+
+        let output = discovered_outputs.first().unwrap();
+        assert_eq!(
+            serde_yaml::to_string(&output.recipe).unwrap(),
+            render_general_path(&selector_config, output)
+        );
+    }
+
+    #[test]
+    fn test_multiple_variants_are_not_single_variant() {
+        let discovered_outputs = discover_python_variants();
+        assert!(!is_single_variant(
+            &discovered_outputs,
+            Platform::Linux64,
+            &[]
+        ));
+    }
+
+    #[test]
+    fn test_noarch_outputs_are_built_for_their_subdir() {
+        let (selector_config, _) = discover_outputs(Platform::Linux64);
+        let named_source = Source {
+            name: "recipe.yaml".to_string(),
+            code: Arc::from(
+                r#"
+package:
+  name: foobar
+  version: "0.1.0"
+
+build:
+  noarch: generic
+"#,
+            ),
+            path: PathBuf::from("recipe.yaml"),
+        };
+        let outputs = find_outputs_from_src(named_source.clone()).unwrap();
+        let discovered_outputs = VariantConfig::default()
+            .find_variants(&outputs, named_source, &selector_config)
+            .unwrap();
+
+        // The platform an output is built for determines where the package
+        // ends up, so it has to match the subdir that is reported for it.
+        let output = discovered_outputs.first().unwrap();
+        assert_eq!(output.target_platform, Platform::NoArch);
+        assert_eq!(
+            output_subdir(&output.noarch_type, output.target_platform),
+            output.target_platform
+        );
+    }
+
+    #[test]
+    fn test_hash_ignore_keys() {
+        let discovered_outputs = discover_python_variants();
+        let hashes = |ignore_keys: &[String]| {
+            discovered_outputs
+                .iter()
+                .map(|output| {
+                    variant_hash(&output.used_vars, &output.noarch_type, ignore_keys).to_string()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The python version is part of the hash by default.
+        let default_hashes = hashes(&[]);
+        assert_ne!(default_hashes[0], default_hashes[1]);
+
+        // Ignoring the python version results in the same hash for both variants.
+        let ignore_keys = vec!["python".to_string()];
+        let ignored_hashes = hashes(&ignore_keys);
+        assert_eq!(ignored_hashes[0], ignored_hashes[1]);
+
+        // The build strings no longer depend on the python version, but the python
+        // version is still recorded in the variant.
+        let (selector_config, _) = discover_outputs(Platform::Linux64);
+        let outputs = apply_hash_ignore_keys(
+            discovered_outputs.clone(),
+            &ignore_keys,
+            &selector_config,
+            &python_recipe_source(""),
+        )
+        .unwrap();
+        for output in &outputs {
+            assert_eq!(output.build_string, format!("{}_0", ignored_hashes[0]));
+            assert!(
+                output
+                    .used_vars
+                    .contains_key(&NormalizedKey::from("python"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_hash_ignore_keys_only_replaces_the_hash() {
+        // A build string with a prefix that contains the hashes of both variants.
+        let default_hashes = discover_python_variants()
+            .iter()
+            .map(|output| output.hash.to_string())
+            .collect::<Vec<_>>();
+        let prefix = format!("{}{}", default_hashes[0], default_hashes[1]);
+        let named_source =
+            python_recipe_source(&format!("build:\n  string: \"{prefix}_${{{{ hash }}}}\"\n"));
+        let discovered_outputs = discover_python_variants_of(&named_source);
+        assert_ne!(
+            discovered_outputs[0].build_string,
+            discovered_outputs[1].build_string
+        );
+
+        let (selector_config, _) = discover_outputs(Platform::Linux64);
+        let outputs = apply_hash_ignore_keys(
+            discovered_outputs,
+            &["python".to_string()],
+            &selector_config,
+            &named_source,
+        )
+        .unwrap();
+
+        // The prefix is kept as it is, and both variants get the same hash.
+        for output in &outputs {
+            assert!(
+                output.build_string.starts_with(&format!("{prefix}_")),
+                "{}",
+                output.build_string
+            );
+        }
+        assert_eq!(outputs[0].build_string, outputs[1].build_string);
+        assert_eq!(outputs[0].hash.to_string(), outputs[1].hash.to_string());
+    }
+
+    #[test]
+    fn test_unsupported_platform_is_rejected() {
+        let supported = [Platform::Linux64, Platform::OsxArm64];
+        assert!(ensure_supported_platform(None, Platform::Win64).is_ok());
+        assert!(ensure_supported_platform(Some(&supported), Platform::OsxArm64).is_ok());
+
+        let err = ensure_supported_platform(Some(&supported), Platform::Win64).unwrap_err();
+        assert!(matches!(
+            err,
+            BackendError::UnsupportedPlatform(Platform::Win64, _)
+        ));
+        assert_eq!(
+            err.to_string(),
+            "the backend cannot build packages for 'win-64', it only supports linux-64, osx-arm64"
+        );
+    }
+
+    #[test]
+    fn test_manifest_rel_path() {
+        let source_dir = std::env::temp_dir().join("project");
+        assert_eq!(
+            manifest_rel_path(&source_dir.join("sub/pixi.toml"), &source_dir).unwrap(),
+            PathBuf::from("sub/pixi.toml")
+        );
+    }
+
+    #[test]
+    fn test_manifest_rel_path_falls_back_to_file_name() {
+        // Like a manifest on another drive, there is no relative path from an
+        // absolute source directory to a relative manifest path.
+        let source_dir = std::env::temp_dir().join("project");
+        assert_eq!(
+            manifest_rel_path(Path::new("other/pixi.toml"), &source_dir).unwrap(),
+            PathBuf::from("pixi.toml")
+        );
+    }
+
+    #[test]
+    fn test_locate_backend_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(locate_backend_manifest(dir.path(), &[]).unwrap(), None);
+
+        let err = locate_backend_manifest(dir.path(), &["pyproject.toml", "setup.py"]).unwrap_err();
+        assert!(matches!(err, BackendError::BackendManifestNotFound(..)));
+        assert!(
+            err.to_string()
+                .starts_with("the backend expects `pyproject.toml` or `setup.py` in"),
+            "{err}"
+        );
+
+        // The first manifest that exists is used.
+        fs_err::write(dir.path().join("setup.py"), "").unwrap();
+        assert_eq!(
+            locate_backend_manifest(dir.path(), &["pyproject.toml", "setup.py"]).unwrap(),
+            Some(PathBuf::from("setup.py"))
+        );
+    }
+
+    #[test]
+    fn test_build_dir_overrides_directories() {
+        let work_directory = PathBuf::from("/slow/work/foobar-abc123");
+        let recipe_path = PathBuf::from("/project/recipe.yaml");
+
+        let directories = conda_build_v1_directories(
+            None,
+            None,
+            work_directory.clone(),
+            None,
+            None,
+            None,
+            recipe_path.clone(),
+        );
+        assert_eq!(directories.build_dir, work_directory);
+        assert_eq!(directories.work_dir, work_directory.join("work"));
+
+        let directories = conda_build_v1_directories(
+            Some(Path::new("/slow/host")),
+            None,
+            work_directory,
+            Some(Path::new("/fast/builds")),
+            None,
+            None,
+            recipe_path,
+        );
+        let build_dir = PathBuf::from("/fast/builds/foobar-abc123");
+        assert_eq!(directories.build_dir, build_dir);
+        assert_eq!(directories.work_dir, build_dir.join("work"));
+        assert_eq!(directories.build_prefix, build_dir.join("build"));
+        assert_eq!(directories.output_dir, build_dir.join("output"));
+        // Explicitly requested prefixes are not moved.
+        assert_eq!(directories.host_prefix, PathBuf::from("/slow/host"));
+    }
+
+    #[test]
+    fn test_build_dir_overrides_v0_directories() {
+        let work_directory = PathBuf::from("/slow/work/foobar-abc123");
+        let recipe_path = PathBuf::from("/project/recipe.yaml");
+
+        let directories = output_directory(
+            OneOrMultipleOutputs::Single(String::from("foobar")),
+            build_work_directory(work_directory, Some(Path::new("/fast/builds"))),
+            &recipe_path,
+        );
+        let build_dir = PathBuf::from("/fast/builds/foobar-abc123");
+        assert_eq!(directories.build_dir, build_dir);
+        assert_eq!(directories.work_dir, build_dir.join("work"));
+    }
+}
@@ -12,6 +12,9 @@ pub mod compilers;
 mod consts;
 pub mod dependencies;
 mod encoded_source_spec_url;
+pub mod error;
+pub mod license;
+pub mod progress;
 pub mod project;
 pub mod source;
 pub mod tools;
@@ -0,0 +1,114 @@
+//! Helpers to derive conda specific license metadata from SPDX license
+//! identifiers.
+
+use recipe_stage0::recipe::Value;
+
+use crate::error::BackendError;
+
+/// Returns the conda license family for a SPDX license identifier, e.g. `MIT`
+/// for `MIT` and `APACHE` for `Apache-2.0`. The families match the ones that
+/// are known to conda-build.
+///
+/// Returns `None` for unknown licenses and for compound SPDX expressions like
+/// `MIT OR Apache-2.0`, because these do not belong to a single family.
+pub fn license_family(license: &str) -> Option<&'static str> {
+    let license = license.trim();
+    if license.is_empty() || license.contains(|c: char| c.is_whitespace() || c == '(') {
+        return None;
+    }
+
+    let license = license.to_ascii_uppercase();
+    let family = if license.starts_with("AGPL") {
+        "AGPL"
+    } else if license.starts_with("LGPL") {
+        "LGPL"
+    } else if license.starts_with("GPL-2") {
+        "GPL2"
+    } else if license.starts_with("GPL-3") {
+        "GPL3"
+    } else if license.starts_with("GPL") {
+        "GPL"
+    } else if license.starts_with("BSD") || license == "0BSD" {
+        "BSD"
+    } else if license.starts_with("MIT") {
+        "MIT"
+    } else if license.starts_with("APACHE") {
+        "APACHE"
+    } else if license.starts_with("PSF") || license == "PYTHON-2.0" {
+        "PSF"
+    } else if license.starts_with("CC") {
+        "CC"
+    } else if license.starts_with("MPL") {
+        "MOZILLA"
+    } else if license == "UNLICENSE" {
+        "PUBLIC-DOMAIN"
+    } else {
+        return None;
+    };
+
+    Some(family)
+}
+
+/// Returns an error if the license is not a valid SPDX license expression,
+/// e.g. `Apache 2` instead of `Apache-2.0`. Templates can only be checked
+/// once they are rendered, so they are always accepted.
+pub fn validate_license(license: &Value<String>) -> Result<(), BackendError> {
+    let Value::Concrete(license) = license else {
+        return Ok(());
+    };
+    if license.contains("${{") {
+        return Ok(());
+    }
+    spdx::Expression::parse(license)
+        .map(|_| ())
+        .map_err(|err| BackendError::InvalidLicense(license.clone(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_license_family() {
+        assert_eq!(license_family("MIT"), Some("MIT"));
+        assert_eq!(license_family("Apache-2.0"), Some("APACHE"));
+        assert_eq!(license_family("BSD-3-Clause"), Some("BSD"));
+        assert_eq!(license_family("GPL-3.0-or-later"), Some("GPL3"));
+        assert_eq!(license_family("GPL-2.0-only"), Some("GPL2"));
+        assert_eq!(license_family("LGPL-2.1-or-later"), Some("LGPL"));
+        assert_eq!(license_family("AGPL-3.0-only"), Some("AGPL"));
+        assert_eq!(license_family("MPL-2.0"), Some("MOZILLA"));
+        assert_eq!(license_family("PSF-2.0"), Some("PSF"));
+    }
+
+    #[test]
+    fn test_unknown_license_family() {
+        assert_eq!(license_family("LicenseRef-Proprietary"), None);
+        assert_eq!(license_family("MIT OR Apache-2.0"), None);
+        assert_eq!(license_family(""), None);
+    }
+
+    #[test]
+    fn test_validate_valid_license() {
+        let license = Value::Concrete("MIT OR Apache-2.0".to_string());
+        assert!(validate_license(&license).is_ok());
+    }
+
+    #[test]
+    fn test_validate_invalid_license() {
+        let license = Value::Concrete("Apache 2".to_string());
+        let err = validate_license(&license).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the license 'Apache 2' is not a valid SPDX license expression"
+        );
+    }
+
+    #[test]
+    fn test_validate_templated_license() {
+        let license = Value::Template("${{ license }}".to_string());
+        assert!(validate_license(&license).is_ok());
+        let license = Value::Concrete("${{ license | upper }}".to_string());
+        assert!(validate_license(&license).is_ok());
+    }
+}
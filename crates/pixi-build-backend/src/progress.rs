@@ -0,0 +1,187 @@
+//! Progress notifications that are sent to the frontend while a package is
+//! being built.
+//!
+//! rattler-build doesn't report progress itself, instead it opens a tracing
+//! span for every step of a build. The [`ProgressLayer`] listens for these
+//! spans and turns them into [`ProgressNotification`]s.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tracing::{Subscriber, span};
+use tracing_subscriber::{Layer, layer::Context};
+
+/// The name of the JSON-RPC notification that reports the progress of a
+/// build.
+pub const PROGRESS_METHOD_NAME: &str = "build/progress";
+
+/// A phase of a build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildPhase {
+    /// The build and host environments are resolved.
+    Solving,
+    /// The sources of the package are fetched.
+    Fetching,
+    /// The build script is running.
+    Building,
+    /// The built files are packaged.
+    Packaging,
+}
+
+impl BuildPhase {
+    /// Returns the phase that starts with the rattler-build span of the given
+    /// name, if any.
+    pub fn from_span_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.starts_with("resolving") {
+            Some(BuildPhase::Solving)
+        } else if name.starts_with("fetching source") {
+            Some(BuildPhase::Fetching)
+        } else if name.starts_with("running build script") {
+            Some(BuildPhase::Building)
+        } else if name.starts_with("packaging") {
+            Some(BuildPhase::Packaging)
+        } else {
+            None
+        }
+    }
+}
+
+/// The parameters of the [`PROGRESS_METHOD_NAME`] notification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressNotification {
+    /// The phase the build entered.
+    pub phase: BuildPhase,
+}
+
+/// A [`Layer`] that reports a [`ProgressNotification`] every time a build
+/// enters a new phase.
+pub struct ProgressLayer {
+    notify: Box<dyn Fn(ProgressNotification) + Send + Sync>,
+    last_phase: Mutex<Option<BuildPhase>>,
+}
+
+impl ProgressLayer {
+    /// Creates a new layer that calls `notify` for every phase change.
+    pub fn new(notify: impl Fn(ProgressNotification) + Send + Sync + 'static) -> Self {
+        Self {
+            notify: Box::new(notify),
+            last_phase: Mutex::new(None),
+        }
+    }
+}
+
+/// The name of the span that rattler-build opens for every build.
+const BUILD_SPAN_NAME: &str = "running build for recipe";
+
+impl<S: Subscriber> Layer<S> for ProgressLayer {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, _id: &span::Id, _ctx: Context<'_, S>) {
+        let name = attrs.metadata().name();
+
+        // Every build reports its phases from the start, even if the previous
+        // build ended in the same phase.
+        if name.to_lowercase().starts_with(BUILD_SPAN_NAME) {
+            *self.last_phase.lock().expect("lock is poisoned") = None;
+            return;
+        }
+
+        let Some(phase) = BuildPhase::from_span_name(name) else {
+            return;
+        };
+
+        // Steps like resolving the environments open multiple spans, only
+        // report the first one.
+        let mut last_phase = self.last_phase.lock().expect("lock is poisoned");
+        if *last_phase == Some(phase) {
+            return;
+        }
+        *last_phase = Some(phase);
+        drop(last_phase);
+
+        (self.notify)(ProgressNotification { phase });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn test_progress_is_reported_in_order() {
+        let notifications = Arc::new(Mutex::new(Vec::new()));
+        let layer = ProgressLayer::new({
+            let notifications = notifications.clone();
+            move |notification| notifications.lock().unwrap().push(notification.phase)
+        });
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        // The spans that rattler-build opens while building a small package.
+        tracing::subscriber::with_default(subscriber, || {
+            let _build = tracing::info_span!("Running build for recipe").entered();
+            drop(tracing::info_span!("Resolving build environment").entered());
+            drop(tracing::info_span!("Resolving host environment").entered());
+            drop(tracing::info_span!("Fetching source code").entered());
+            drop(tracing::info_span!("Running build script").entered());
+            drop(tracing::info_span!("Packaging new files").entered());
+        });
+
+        assert_eq!(
+            *notifications.lock().unwrap(),
+            vec![
+                BuildPhase::Solving,
+                BuildPhase::Fetching,
+                BuildPhase::Building,
+                BuildPhase::Packaging,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_progress_is_reported_for_consecutive_builds() {
+        let notifications = Arc::new(Mutex::new(Vec::new()));
+        let layer = ProgressLayer::new({
+            let notifications = notifications.clone();
+            move |notification| notifications.lock().unwrap().push(notification.phase)
+        });
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            // The first build fails while resolving the environments.
+            {
+                let _build = tracing::info_span!("Running build for recipe").entered();
+                drop(tracing::info_span!("Resolving build environment").entered());
+            }
+            {
+                let _build = tracing::info_span!("Running build for recipe").entered();
+                drop(tracing::info_span!("Resolving build environment").entered());
+                drop(tracing::info_span!("Fetching source code").entered());
+            }
+        });
+
+        assert_eq!(
+            *notifications.lock().unwrap(),
+            vec![
+                BuildPhase::Solving,
+                BuildPhase::Solving,
+                BuildPhase::Fetching,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_progress_notification_format() {
+        let notification = ProgressNotification {
+            phase: BuildPhase::Building,
+        };
+        assert_eq!(
+            serde_json::to_value(&notification).unwrap(),
+            serde_json::json!({ "phase": "building" })
+        );
+    }
+}
@@ -5,12 +5,18 @@ use pixi_build_type_conversions::to_project_model_v1;
 use pixi_build_types::VersionedProjectModel;
 use rattler_conda_types::ChannelConfig;
 
+use crate::error::BackendError;
+
 /// Convert manifest to project model
 pub fn to_project_model(
     manifest_path: &Path,
     channel_config: &ChannelConfig,
     highest_supported_project_model: Option<u32>,
 ) -> miette::Result<Option<VersionedProjectModel>> {
+    if !manifest_path.is_file() {
+        return Err(BackendError::ManifestNotFound(manifest_path.to_path_buf()).into());
+    }
+
     // Load the manifest
     let manifest =
         pixi_manifest::Manifests::from_workspace_manifest_path(manifest_path.to_path_buf())?;
@@ -53,3 +59,214 @@ pub fn to_project_model(
 
     Ok(versioned)
 }
+
+/// Reads the backend configuration from the `[package.build.configuration]`
+/// table of the manifest, or `[tool.pixi.package.build.configuration]` in case
+/// of a `pyproject.toml`. Returns `None` if the manifest does not contain a
+/// configuration.
+pub fn read_build_configuration(manifest_path: &Path) -> miette::Result<Option<serde_json::Value>> {
+    if !manifest_path.is_file() {
+        return Err(BackendError::ManifestNotFound(manifest_path.to_path_buf()).into());
+    }
+
+    let contents = fs_err::read_to_string(manifest_path).into_diagnostic()?;
+    let document = contents
+        .parse::<toml_edit::DocumentMut>()
+        .into_diagnostic()?;
+
+    let root = document
+        .get("tool")
+        .and_then(|tool| tool.get("pixi"))
+        .unwrap_or(document.as_item());
+    let Some(configuration) = root
+        .get("package")
+        .and_then(|package| package.get("build"))
+        .and_then(|build| build.get("configuration"))
+    else {
+        return Ok(None);
+    };
+
+    match configuration.as_table_like() {
+        Some(table) => table_to_json(table).map(Some),
+        None => miette::bail!("`package.build.configuration` must be a table"),
+    }
+}
+
+/// Converts a toml table, either a regular or an inline table, to json.
+fn table_to_json(table: &dyn toml_edit::TableLike) -> miette::Result<serde_json::Value> {
+    table
+        .iter()
+        .map(|(key, item)| Ok((key.to_string(), item_to_json(item)?)))
+        .collect::<miette::Result<serde_json::Map<_, _>>>()
+        .map(serde_json::Value::Object)
+}
+
+/// Converts a toml item to json. Datetimes are converted to strings in their
+/// toml representation.
+fn item_to_json(item: &toml_edit::Item) -> miette::Result<serde_json::Value> {
+    match item {
+        toml_edit::Item::None => Ok(serde_json::Value::Null),
+        toml_edit::Item::Table(table) => table_to_json(table),
+        toml_edit::Item::ArrayOfTables(tables) => tables
+            .iter()
+            .map(|table| table_to_json(table))
+            .collect::<miette::Result<_>>()
+            .map(serde_json::Value::Array),
+        toml_edit::Item::Value(value) => value_to_json(value),
+    }
+}
+
+fn value_to_json(value: &toml_edit::Value) -> miette::Result<serde_json::Value> {
+    Ok(match value {
+        toml_edit::Value::String(string) => serde_json::Value::from(string.value().as_str()),
+        toml_edit::Value::Integer(integer) => serde_json::Value::from(*integer.value()),
+        toml_edit::Value::Float(float) => serde_json::Number::from_f64(*float.value())
+            .map(serde_json::Value::Number)
+            .ok_or_else(|| miette::miette!("`{}` cannot be represented in json", float.value()))?,
+        toml_edit::Value::Boolean(boolean) => serde_json::Value::from(*boolean.value()),
+        toml_edit::Value::Datetime(datetime) => {
+            serde_json::Value::from(datetime.value().to_string())
+        }
+        toml_edit::Value::Array(array) => array
+            .iter()
+            .map(value_to_json)
+            .collect::<miette::Result<_>>()
+            .map(serde_json::Value::Array)?,
+        toml_edit::Value::InlineTable(table) => table_to_json(table)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_build_configuration() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let manifest_path = tmp_dir.path().join("pixi.toml");
+        fs_err::write(
+            &manifest_path,
+            r#"
+[package.build]
+backend = { name = "pixi-build-cmake", version = "*" }
+
+[package.build.configuration]
+extra-args = ["-DFOO=ON"]
+build-retries = 2
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_build_configuration(&manifest_path).unwrap(),
+            Some(serde_json::json!({
+                "extra-args": ["-DFOO=ON"],
+                "build-retries": 2,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_read_build_configuration_from_pyproject() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let manifest_path = tmp_dir.path().join("pyproject.toml");
+        fs_err::write(
+            &manifest_path,
+            r#"
+[project]
+name = "foobar"
+
+[tool.pixi.package.build.configuration]
+noarch = false
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_build_configuration(&manifest_path).unwrap(),
+            Some(serde_json::json!({ "noarch": false }))
+        );
+    }
+
+    #[test]
+    fn test_read_build_configuration_with_comments_and_dotted_keys() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let manifest_path = tmp_dir.path().join("pixi.toml");
+        fs_err::write(
+            &manifest_path,
+            r#"
+[package.build.configuration]
+# Arguments that are passed to cargo
+extra-args = [
+    "--all-features", # all of them
+    "-F", "foo",
+]
+env.RUSTFLAGS = "-C target-cpu=native"
+
+[package.build.configuration.run-exports]
+weak = ["foo"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_build_configuration(&manifest_path).unwrap(),
+            Some(serde_json::json!({
+                "extra-args": ["--all-features", "-F", "foo"],
+                "env": { "RUSTFLAGS": "-C target-cpu=native" },
+                "run-exports": { "weak": ["foo"] },
+            }))
+        );
+    }
+
+    #[test]
+    fn test_read_build_configuration_with_inline_tables_and_datetimes() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let manifest_path = tmp_dir.path().join("pixi.toml");
+        fs_err::write(
+            &manifest_path,
+            r#"
+[package.build]
+backend = { name = "pixi-build-rust", version = "*" }
+configuration = { env = { SOURCE_DATE = 1979-05-27T07:32:00Z }, channels = [{ name = "foo", priority = 1.5 }] }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_build_configuration(&manifest_path).unwrap(),
+            Some(serde_json::json!({
+                "env": { "SOURCE_DATE": "1979-05-27T07:32:00Z" },
+                "channels": [{ "name": "foo", "priority": 1.5 }],
+            }))
+        );
+    }
+
+    #[test]
+    fn test_read_missing_build_configuration() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let manifest_path = tmp_dir.path().join("pixi.toml");
+        fs_err::write(&manifest_path, "[package]\nname = \"foobar\"\n").unwrap();
+
+        assert_eq!(read_build_configuration(&manifest_path).unwrap(), None);
+    }
+
+    #[test]
+    fn test_missing_manifest() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let manifest_path = tmp_dir.path().join("pixi.toml");
+        let channel_config = ChannelConfig::default_with_root_dir(tmp_dir.path().to_path_buf());
+
+        let err = to_project_model(&manifest_path, &channel_config, None).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BackendError>(),
+            Some(BackendError::ManifestNotFound(path)) if path == &manifest_path
+        ));
+
+        let err = read_build_configuration(&manifest_path).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BackendError>(),
+            Some(BackendError::ManifestNotFound(path)) if path == &manifest_path
+        ));
+    }
+}
@@ -1,13 +1,21 @@
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
 use pixi_build_types::procedures::conda_build_v1::{CondaBuildV1Params, CondaBuildV1Result};
 use pixi_build_types::procedures::conda_outputs::{CondaOutputsParams, CondaOutputsResult};
 use pixi_build_types::procedures::{
+    self,
     conda_build_v0::{CondaBuildParams, CondaBuildResult},
     conda_metadata::{CondaMetadataParams, CondaMetadataResult},
     initialize::{InitializeParams, InitializeResult},
     negotiate_capabilities::{NegotiateCapabilitiesParams, NegotiateCapabilitiesResult},
 };
+use recipe_stage0::recipe::IntermediateRecipe;
+
+use crate::{error::BackendError, generated_recipe::BackendIdentifier};
 
 /// A trait that is used to instantiate a new protocol connection
 /// and endpoint that can handle the RPC calls.
@@ -29,6 +37,12 @@ pub trait ProtocolInstantiator: Send + Sync + 'static {
         &self,
         params: InitializeParams,
     ) -> miette::Result<(Box<dyn Protocol + Send + Sync + 'static>, InitializeResult)>;
+
+    /// Returns the name and the version of the backend, if known. This is
+    /// reported by the `ping` method.
+    fn backend_identifier(&self) -> Option<BackendIdentifier> {
+        None
+    }
 }
 
 /// A trait that defines the protocol for a pixi build backend.
@@ -40,6 +54,17 @@ pub trait Protocol {
     /// If set, internal state will be logged as files in that directory
     fn debug_dir(&self) -> Option<&Path>;
 
+    /// Sets a path that the generated recipe is written to every time a
+    /// recipe is generated. Backends that don't generate a recipe ignore it.
+    fn set_recipe_out(&mut self, _path: PathBuf) {}
+
+    /// The maximum duration of a single operation, e.g. building a package.
+    /// Operations that take longer fail with
+    /// [`BackendError::OperationTimeout`]. `None` means no timeout.
+    fn operation_timeout(&self) -> Option<Duration> {
+        None
+    }
+
     /// Called when the client requests metadata for a Conda package.
     async fn conda_get_metadata(
         &self,
@@ -68,4 +93,251 @@ pub trait Protocol {
     ) -> miette::Result<CondaBuildV1Result> {
         unimplemented!("conda_build_v1 not implemented");
     }
+
+    /// Checks the project model and the configuration for problems without
+    /// building or solving anything. Returns a human readable description of
+    /// every problem that was found.
+    async fn check(&self) -> miette::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    /// Generates the recipe and parses it the same way rattler-build does,
+    /// without resolving variants or dependencies. Returns every parse error
+    /// together with the location in the generated YAML.
+    async fn check_recipe(&self) -> miette::Result<Vec<String>> {
+        miette::bail!("checking the recipe is not supported by this backend")
+    }
+
+    /// Generates the recipe for the current platform without building or
+    /// solving anything, e.g. to compare it against a previously generated
+    /// recipe.
+    async fn generate_recipe(&self) -> miette::Result<IntermediateRecipe> {
+        miette::bail!("generating the recipe is not supported by this backend")
+    }
+}
+
+/// Wraps `protocol` so that its operations fail with
+/// [`BackendError::OperationTimeout`] if they take longer than `timeout`, or
+/// the [`Protocol::operation_timeout`] of the protocol if `timeout` is not
+/// set.
+///
+/// This is a soft timeout. Every operation runs as a separate task, so the
+/// timeout error is returned on time even if the operation is stuck in
+/// synchronous code. The timed out task is aborted, which drops it and cleans
+/// up its temporary directories at its next `.await`. Synchronous work that is
+/// running at that moment is not interrupted, and child processes, e.g. a
+/// build script, are not killed but keep running until they exit.
+pub fn with_operation_timeout(
+    protocol: Box<dyn Protocol + Send + Sync + 'static>,
+    timeout: Option<Duration>,
+) -> Box<dyn Protocol + Send + Sync + 'static> {
+    match timeout.or_else(|| protocol.operation_timeout()) {
+        Some(timeout) => Box::new(TimeoutProtocol {
+            inner: Arc::from(protocol),
+            timeout,
+        }),
+        None => protocol,
+    }
+}
+
+/// A [`Protocol`] that applies a timeout to the operations of another
+/// protocol.
+struct TimeoutProtocol {
+    inner: Arc<dyn Protocol + Send + Sync + 'static>,
+    timeout: Duration,
+}
+
+impl TimeoutProtocol {
+    /// Runs the operation that `f` creates for the inner protocol on a
+    /// separate task, and aborts that task if it doesn't finish in time.
+    async fn run<T, Fut>(
+        &self,
+        operation: &'static str,
+        f: impl FnOnce(Arc<dyn Protocol + Send + Sync + 'static>) -> Fut,
+    ) -> miette::Result<T>
+    where
+        T: Send + 'static,
+        Fut: Future<Output = miette::Result<T>> + Send + 'static,
+    {
+        let mut task = tokio::spawn(f(self.inner.clone()));
+        match tokio::time::timeout(self.timeout, &mut task).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(err)) => std::panic::resume_unwind(err.into_panic()),
+            Err(_) => {
+                task.abort();
+                Err(BackendError::OperationTimeout(operation, self.timeout).into())
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Protocol for TimeoutProtocol {
+    fn debug_dir(&self) -> Option<&Path> {
+        self.inner.debug_dir()
+    }
+
+    fn set_recipe_out(&mut self, path: PathBuf) {
+        Arc::get_mut(&mut self.inner)
+            .expect("the protocol is only configured while no operation is running")
+            .set_recipe_out(path);
+    }
+
+    fn operation_timeout(&self) -> Option<Duration> {
+        Some(self.timeout)
+    }
+
+    async fn conda_get_metadata(
+        &self,
+        params: CondaMetadataParams,
+    ) -> miette::Result<CondaMetadataResult> {
+        self.run(
+            procedures::conda_metadata::METHOD_NAME,
+            |inner| async move { inner.conda_get_metadata(params).await },
+        )
+        .await
+    }
+
+    async fn conda_build_v0(&self, params: CondaBuildParams) -> miette::Result<CondaBuildResult> {
+        self.run(
+            procedures::conda_build_v0::METHOD_NAME,
+            |inner| async move { inner.conda_build_v0(params).await },
+        )
+        .await
+    }
+
+    async fn conda_outputs(
+        &self,
+        params: CondaOutputsParams,
+    ) -> miette::Result<CondaOutputsResult> {
+        self.run(procedures::conda_outputs::METHOD_NAME, |inner| async move {
+            inner.conda_outputs(params).await
+        })
+        .await
+    }
+
+    async fn conda_build_v1(
+        &self,
+        params: CondaBuildV1Params,
+    ) -> miette::Result<CondaBuildV1Result> {
+        self.run(
+            procedures::conda_build_v1::METHOD_NAME,
+            |inner| async move { inner.conda_build_v1(params).await },
+        )
+        .await
+    }
+
+    async fn check(&self) -> miette::Result<Vec<String>> {
+        self.inner.check().await
+    }
+
+    async fn check_recipe(&self) -> miette::Result<Vec<String>> {
+        self.inner.check_recipe().await
+    }
+
+    async fn generate_recipe(&self) -> miette::Result<IntermediateRecipe> {
+        self.inner.generate_recipe().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rattler_conda_types::Platform;
+
+    use super::*;
+
+    /// A protocol that takes a long time to determine its outputs.
+    struct SlowProtocol;
+
+    #[async_trait::async_trait]
+    impl Protocol for SlowProtocol {
+        fn debug_dir(&self) -> Option<&Path> {
+            None
+        }
+
+        async fn conda_outputs(
+            &self,
+            _params: CondaOutputsParams,
+        ) -> miette::Result<CondaOutputsResult> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            unreachable!("the operation should have timed out")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_operation_timeout() {
+        let protocol =
+            with_operation_timeout(Box::new(SlowProtocol), Some(Duration::from_millis(10)));
+
+        let err = protocol
+            .conda_outputs(CondaOutputsParams {
+                channels: vec![],
+                host_platform: Platform::current(),
+                build_platform: Platform::current(),
+                variant_configuration: None,
+                work_directory: PathBuf::from("work"),
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<BackendError>(),
+            Some(BackendError::OperationTimeout(
+                procedures::conda_outputs::METHOD_NAME,
+                _
+            ))
+        ));
+    }
+
+    /// A protocol that blocks the thread while it determines its outputs.
+    struct BlockingProtocol;
+
+    #[async_trait::async_trait]
+    impl Protocol for BlockingProtocol {
+        fn debug_dir(&self) -> Option<&Path> {
+            None
+        }
+
+        async fn conda_outputs(
+            &self,
+            _params: CondaOutputsParams,
+        ) -> miette::Result<CondaOutputsResult> {
+            std::thread::sleep(Duration::from_secs(2));
+            miette::bail!("the operation should have timed out")
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_operation_timeout_of_blocking_operation() {
+        let protocol =
+            with_operation_timeout(Box::new(BlockingProtocol), Some(Duration::from_millis(10)));
+
+        let start = std::time::Instant::now();
+        let err = protocol
+            .conda_outputs(CondaOutputsParams {
+                channels: vec![],
+                host_platform: Platform::current(),
+                build_platform: Platform::current(),
+                variant_configuration: None,
+                work_directory: PathBuf::from("work"),
+            })
+            .await
+            .unwrap_err();
+
+        // The timeout is reported without waiting for the blocking operation.
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(matches!(
+            err.downcast_ref::<BackendError>(),
+            Some(BackendError::OperationTimeout(
+                procedures::conda_outputs::METHOD_NAME,
+                _
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_no_operation_timeout() {
+        let protocol = with_operation_timeout(Box::new(SlowProtocol), None);
+        assert_eq!(protocol.operation_timeout(), None);
+    }
 }
@@ -1,13 +1,18 @@
-use std::{collections::BTreeMap, path::PathBuf, str::FromStr, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
 
-use miette::IntoDiagnostic;
+use miette::{Diagnostic, IntoDiagnostic};
 use rattler_build::{
     NormalizedKey,
     metadata::{
         BuildConfiguration, Debug, Directories, Output, PackageIdentifier, PackagingSettings,
         PlatformWithVirtualPackages,
     },
-    recipe::parser::find_outputs_from_src,
+    recipe::{ParsingError, Recipe, parser::find_outputs_from_src},
     selectors::SelectorConfig,
     source_code::Source,
     system_tools::SystemTools,
@@ -167,7 +172,7 @@ pub async fn get_build_output(
             extra_meta: None,
         };
 
-        let temp_recipe = TemporaryRenderedRecipe::from_output(&output)?;
+        let temp_recipe = TemporaryRenderedRecipe::from_output(&output, None)?;
         let tool_config = tool_config.clone();
         let output = temp_recipe
             .within_context_async(move || async move {
@@ -183,3 +188,139 @@ pub async fn get_build_output(
 
     Ok(outputs)
 }
+
+/// Parses a generated recipe the same way rattler-build does, without
+/// resolving any variants or dependencies. This is a fast way to find out if
+/// the generated YAML is a valid rattler-build recipe.
+///
+/// Every parse error is returned as a human readable message that is prefixed
+/// with the location of the offending YAML in the generated recipe, e.g.
+/// `recipe.yaml (generated from pixi.toml):4:11: ...`. The generated recipe
+/// can be written to disk with `--recipe-out` to inspect the location. An
+/// empty list means the recipe parsed without problems.
+pub fn check_generated_recipe(
+    generated_recipe: &GeneratedRecipe,
+    recipe_path: &Path,
+) -> miette::Result<Vec<String>> {
+    let named_source = Source {
+        name: match recipe_path.file_name() {
+            Some(manifest) => format!(
+                "recipe.yaml (generated from {})",
+                manifest.to_string_lossy()
+            ),
+            None => "recipe.yaml".to_string(),
+        },
+        code: Arc::from(
+            generated_recipe
+                .recipe
+                .to_yaml_pretty()
+                .into_diagnostic()?
+                .as_str(),
+        ),
+        path: recipe_path.to_path_buf(),
+    };
+
+    let outputs = match find_outputs_from_src(named_source.clone()) {
+        Ok(outputs) => outputs,
+        Err(errs) => return Ok(describe_parse_errors(&named_source, &errs)),
+    };
+
+    let selector_config = SelectorConfig {
+        recipe_path: Some(recipe_path.to_path_buf()),
+        ..SelectorConfig::default()
+    };
+
+    let mut problems = Vec::new();
+    for output in &outputs {
+        if let Err(errs) = Recipe::from_node(output, selector_config.clone()) {
+            problems.extend(errs.into_iter().map(|err| {
+                describe_parse_error(
+                    &named_source,
+                    &ParsingError::from_partial(named_source.clone(), err),
+                )
+            }));
+        }
+    }
+
+    Ok(problems)
+}
+
+/// Describes every error that is related to the given diagnostic, or the
+/// diagnostic itself if it has no related errors.
+fn describe_parse_errors(source: &Source, errs: &dyn Diagnostic) -> Vec<String> {
+    match errs.related() {
+        Some(related) => related
+            .map(|err| describe_parse_error(source, err))
+            .collect(),
+        None => vec![describe_parse_error(source, errs)],
+    }
+}
+
+/// Formats a parse error as `<name>:<line>:<column>: <message>` using the
+/// first label of the error to determine the location.
+fn describe_parse_error(source: &Source, err: &dyn Diagnostic) -> String {
+    let Some(label) = err.labels().and_then(|mut labels| labels.next()) else {
+        return format!("{}: {err}", source.name);
+    };
+
+    let (line, column) = line_and_column(&source.code, label.offset());
+    match label.label() {
+        Some(label) => format!("{}:{line}:{column}: {err} ({label})", source.name),
+        None => format!("{}:{line}:{column}: {err}", source.name),
+    }
+}
+
+/// Converts a byte offset into a one-based line and column.
+fn line_and_column(code: &str, offset: usize) -> (usize, usize) {
+    let before = &code[..offset.min(code.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |idx| idx + 1) + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use recipe_stage0::recipe::Value;
+
+    use super::*;
+
+    #[test]
+    fn test_line_and_column() {
+        let code = "package:\n  name: foo\n";
+        assert_eq!(line_and_column(code, 0), (1, 1));
+        assert_eq!(line_and_column(code, 17), (2, 9));
+    }
+
+    #[test]
+    fn test_check_valid_generated_recipe() {
+        let generated_recipe = GeneratedRecipe::default();
+
+        let problems = check_generated_recipe(&generated_recipe, Path::new("pixi.toml")).unwrap();
+        assert!(problems.is_empty(), "unexpected problems: {problems:?}");
+    }
+
+    #[test]
+    fn test_check_malformed_generated_recipe() {
+        let mut generated_recipe = GeneratedRecipe::default();
+        generated_recipe.recipe.build.number =
+            Some(Value::Template("${{ 'not-a-number' }}".to_string()));
+
+        let problems = check_generated_recipe(&generated_recipe, Path::new("pixi.toml")).unwrap();
+        assert_eq!(problems.len(), 1, "expected a single problem: {problems:?}");
+
+        // The error should point at the `number` field in the generated YAML.
+        let yaml = generated_recipe.recipe.to_yaml_pretty().unwrap();
+        let number_line = yaml
+            .lines()
+            .position(|line| line.trim_start().starts_with("number:"))
+            .unwrap()
+            + 1;
+        assert!(
+            problems[0].starts_with(&format!(
+                "recipe.yaml (generated from pixi.toml):{number_line}:"
+            )),
+            "unexpected location: {}",
+            problems[0]
+        );
+    }
+}
@@ -1,23 +1,51 @@
-use std::{net::SocketAddr, path::Path, sync::Arc};
+use std::{net::SocketAddr, path::Path, sync::Arc, time::Duration};
 
 use fs_err::tokio as tokio_fs;
 use jsonrpc_core::{Error, IoHandler, Params, serde_json, to_value};
 use miette::{Context, IntoDiagnostic, JSONReportHandler};
 use pixi_build_types::{
-    VersionedProjectModel,
+    BackendCapabilities, FrontendCapabilities, VersionedProjectModel,
     procedures::{
         self, conda_build_v0::CondaBuildParams, conda_build_v1::CondaBuildV1Params,
         conda_metadata::CondaMetadataParams, conda_outputs::CondaOutputsParams,
         initialize::InitializeParams, negotiate_capabilities::NegotiateCapabilitiesParams,
     },
 };
-use tokio::sync::RwLock;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    sync::{RwLock, mpsc, oneshot},
+};
+
+use crate::{
+    progress::{PROGRESS_METHOD_NAME, ProgressNotification},
+    protocol::{Protocol, ProtocolInstantiator, with_operation_timeout},
+};
 
-use crate::protocol::{Protocol, ProtocolInstantiator};
+/// The name of the method that can be used to check that the backend is alive
+/// before it is initialized.
+pub const PING_METHOD_NAME: &str = "ping";
+
+/// The result of the `ping` method.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PingResult {
+    /// The name of the backend, e.g. `pixi-build-python`, if known.
+    pub backend_name: Option<String>,
+    /// The version of the backend, if known.
+    pub backend_version: Option<String>,
+    /// The version of the `pixi-build-backend` crate the backend was built
+    /// with.
+    pub pixi_build_backend_version: String,
+    /// The capabilities of the backend.
+    pub capabilities: BackendCapabilities,
+}
 
 /// A JSONRPC server that can be used to communicate with a client.
 pub struct Server<T: ProtocolInstantiator> {
     instatiator: T,
+    operation_timeout: Option<Duration>,
+    messages: Option<MessageChannel>,
 }
 
 enum ServerState<T: ProtocolInstantiator> {
@@ -41,14 +69,33 @@ impl<T: ProtocolInstantiator> ServerState<T> {
 
 impl<T: ProtocolInstantiator> Server<T> {
     pub fn new(instatiator: T) -> Self {
-        Self { instatiator }
+        Self {
+            instatiator,
+            operation_timeout: None,
+            messages: None,
+        }
+    }
+
+    /// Sets the maximum duration of a single operation. This takes precedence
+    /// over the timeout in the configuration of the backend.
+    pub fn with_operation_timeout(mut self, operation_timeout: Option<Duration>) -> Self {
+        self.operation_timeout = operation_timeout;
+        self
+    }
+
+    /// Sets the channel that the messages to the client are written from when
+    /// communicating over stdin/stdout. Notifications that are sent with a
+    /// [`MessageSender`] of the channel are written in between the responses.
+    pub fn with_message_channel(mut self, messages: MessageChannel) -> Self {
+        self.messages = Some(messages);
+        self
     }
 
     /// Run the server, communicating over stdin/stdout.
-    pub async fn run(self) -> miette::Result<()> {
+    pub async fn run(mut self) -> miette::Result<()> {
+        let messages = self.messages.take().unwrap_or_default();
         let io = self.setup_io();
-        jsonrpc_stdio_server::ServerBuilder::new(io).build().await;
-        Ok(())
+        serve_lines(io, messages, tokio::io::stdin(), tokio::io::stdout()).await
     }
 
     /// Run the server, communicating over HTTP.
@@ -76,8 +123,27 @@ impl<T: ProtocolInstantiator> Server<T> {
             },
         );
 
+        // `ping` does not require the server to be initialized.
+        let backend = self.instatiator.backend_identifier();
+        io.add_method(PING_METHOD_NAME, move |_params: Params| async move {
+            let capabilities = T::negotiate_capabilities(NegotiateCapabilitiesParams {
+                capabilities: FrontendCapabilities {},
+            })
+            .await
+            .map_err(convert_error)?
+            .capabilities;
+            let result = PingResult {
+                backend_name: backend.map(|backend| backend.name.to_string()),
+                backend_version: backend.map(|backend| backend.version.to_string()),
+                pixi_build_backend_version: env!("CARGO_PKG_VERSION").to_string(),
+                capabilities,
+            };
+            Ok(to_value(result).expect("failed to convert to json"))
+        });
+
         let state = Arc::new(RwLock::new(ServerState::Uninitialized(self.instatiator)));
         let initialize_state = state.clone();
+        let operation_timeout = self.operation_timeout;
         io.add_method(
             procedures::initialize::METHOD_NAME,
             move |params: Params| {
@@ -98,7 +164,10 @@ impl<T: ProtocolInstantiator> Server<T> {
                         .initialize(params)
                         .await
                         .map_err(convert_error)?;
-                    *state = ServerState::Initialized(protocol_endpoint);
+                    *state = ServerState::Initialized(with_operation_timeout(
+                        protocol_endpoint,
+                        operation_timeout,
+                    ));
 
                     Ok(to_value(result).expect("failed to convert to json"))
                 }
@@ -209,6 +278,117 @@ impl<T: ProtocolInstantiator> Server<T> {
     }
 }
 
+/// Converts a [`ProgressNotification`] into a JSON-RPC notification.
+pub fn progress_notification(notification: &ProgressNotification) -> jsonrpc_core::Notification {
+    let params = match to_value(notification).expect("failed to convert to json") {
+        serde_json::Value::Object(map) => Params::Map(map),
+        _ => Params::None,
+    };
+    jsonrpc_core::Notification {
+        jsonrpc: Some(jsonrpc_core::Version::V2),
+        method: PROGRESS_METHOD_NAME.to_string(),
+        params,
+    }
+}
+
+/// Sends messages to the client of a server that communicates over
+/// stdin/stdout. The messages are written in the order they are sent, one
+/// message per line, by the same writer as the responses of the server.
+#[derive(Clone)]
+pub struct MessageSender(mpsc::UnboundedSender<String>);
+
+impl MessageSender {
+    /// Sends a [`ProgressNotification`] to the client.
+    pub fn send_progress(&self, notification: ProgressNotification) {
+        let message = serde_json::to_string(&progress_notification(&notification))
+            .expect("failed to convert to json");
+        // The server stopped if the receiver is gone, nobody is listening.
+        let _ = self.0.send(message);
+    }
+}
+
+/// The channel of the messages that a server writes to stdout.
+pub struct MessageChannel {
+    sender: MessageSender,
+    receiver: mpsc::UnboundedReceiver<String>,
+}
+
+impl Default for MessageChannel {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            sender: MessageSender(sender),
+            receiver,
+        }
+    }
+}
+
+impl MessageChannel {
+    /// Returns a sender that writes messages to the client.
+    pub fn sender(&self) -> MessageSender {
+        self.sender.clone()
+    }
+}
+
+/// Handles the requests that are read from `input`, one per line, and writes
+/// the responses and the other messages of the channel to `output`.
+///
+/// Requests are handled one after another like the jsonrpc stdio server does.
+/// All messages are written by a single writer, one message per line, so that
+/// notifications that are sent while a request is handled never interleave
+/// with a response.
+async fn serve_lines(
+    io: IoHandler,
+    messages: MessageChannel,
+    input: impl AsyncRead + Unpin,
+    mut output: impl AsyncWrite + Unpin,
+) -> miette::Result<()> {
+    let MessageChannel {
+        sender,
+        mut receiver,
+    } = messages;
+    let (stopped, mut input_closed) = oneshot::channel::<()>();
+
+    let read = async move {
+        // Signals the writer to stop once all requests are handled.
+        let _stopped = stopped;
+        let mut lines = BufReader::new(input).lines();
+        while let Some(line) = lines.next_line().await.into_diagnostic()? {
+            // Like the jsonrpc stdio server, an empty line is written for
+            // requests without a response.
+            let response = io.handle_request(&line).await.unwrap_or_default();
+            let _ = sender.0.send(response);
+        }
+        Ok::<_, miette::Report>(())
+    };
+
+    let write = async move {
+        loop {
+            tokio::select! {
+                Some(message) = receiver.recv() => write_line(&mut output, &message).await?,
+                _ = &mut input_closed => break,
+            }
+        }
+        // Write the responses that were sent right before the input closed.
+        while let Ok(message) = receiver.try_recv() {
+            write_line(&mut output, &message).await?;
+        }
+        Ok::<_, miette::Report>(())
+    };
+
+    let (read, write) = tokio::join!(read, write);
+    read.and(write)
+}
+
+/// Writes a single message as a line and flushes it.
+async fn write_line(output: &mut (impl AsyncWrite + Unpin), message: &str) -> miette::Result<()> {
+    output
+        .write_all(format!("{message}\n").as_bytes())
+        .await
+        .into_diagnostic()?;
+    output.flush().await.into_diagnostic()
+}
+
 fn convert_error(err: miette::Report) -> jsonrpc_core::Error {
     let rendered = JSONReportHandler::new();
     let mut json_str = String::new();
@@ -347,3 +527,125 @@ async fn log_conda_build_v1(
         .context("failed to write JSON to file")?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use pixi_build_types::procedures::{
+        initialize::InitializeResult, negotiate_capabilities::NegotiateCapabilitiesResult,
+    };
+
+    use super::*;
+    use crate::{generated_recipe::BackendIdentifier, progress::BuildPhase};
+
+    struct PingInstantiator;
+
+    #[async_trait::async_trait]
+    impl ProtocolInstantiator for PingInstantiator {
+        fn debug_dir(_configuration: Option<serde_json::Value>) -> Option<PathBuf> {
+            None
+        }
+
+        async fn negotiate_capabilities(
+            _params: NegotiateCapabilitiesParams,
+        ) -> miette::Result<NegotiateCapabilitiesResult> {
+            Ok(NegotiateCapabilitiesResult {
+                capabilities: BackendCapabilities {
+                    provides_conda_metadata: Some(true),
+                    provides_conda_build: None,
+                    provides_conda_outputs: None,
+                    provides_conda_build_v1: None,
+                    highest_supported_project_model: None,
+                },
+            })
+        }
+
+        async fn initialize(
+            &self,
+            _params: InitializeParams,
+        ) -> miette::Result<(Box<dyn Protocol + Send + Sync + 'static>, InitializeResult)> {
+            miette::bail!("initialization is not supported")
+        }
+
+        fn backend_identifier(&self) -> Option<BackendIdentifier> {
+            Some(BackendIdentifier {
+                name: "pixi-build-test",
+                version: "1.2.3",
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ping_before_initialize() {
+        let io = Server::new(PingInstantiator).setup_io();
+        let response = io
+            .handle_request(r#"{"jsonrpc": "2.0", "method": "ping", "id": 1}"#)
+            .await
+            .expect("ping should return a response");
+
+        let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+        let result: PingResult = serde_json::from_value(response["result"].clone()).unwrap();
+        assert_eq!(result.backend_name.as_deref(), Some("pixi-build-test"));
+        assert_eq!(result.backend_version.as_deref(), Some("1.2.3"));
+        assert_eq!(result.pixi_build_backend_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(result.capabilities.provides_conda_metadata, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_notifications_are_framed_with_responses() {
+        let messages = MessageChannel::default();
+        let notify = messages.sender();
+        let mut io = IoHandler::new();
+        io.add_method("build", move |_params: Params| {
+            let notify = notify.clone();
+            async move {
+                for phase in [BuildPhase::Solving, BuildPhase::Packaging] {
+                    notify.send_progress(ProgressNotification { phase });
+                    tokio::task::yield_now().await;
+                }
+                Ok(serde_json::Value::from("built"))
+            }
+        });
+
+        let input = [1, 2]
+            .map(|id| format!(r#"{{"jsonrpc": "2.0", "method": "build", "id": {id}}}"#))
+            .join("\n");
+        let mut output = Vec::new();
+        serve_lines(io, messages, input.as_bytes(), &mut output)
+            .await
+            .unwrap();
+
+        // Every line is a single complete message, in the order it was sent.
+        let messages = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        let notification = |phase: &str| {
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "build/progress",
+                "params": { "phase": phase },
+            })
+        };
+        let response = |id: u64| {
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": "built",
+                "id": id,
+            })
+        };
+        assert_eq!(
+            messages,
+            vec![
+                notification("solving"),
+                notification("packaging"),
+                response(1),
+                notification("solving"),
+                notification("packaging"),
+                response(2),
+            ]
+        );
+    }
+}
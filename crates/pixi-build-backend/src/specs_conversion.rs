@@ -37,99 +37,66 @@ pub fn from_source_matchspec_into_package_spec(
 }
 
 pub fn from_targets_v1_to_conditional_requirements(targets: &TargetsV1) -> ConditionalRequirements {
-    let mut build_items = ConditionalList::new();
-    let mut host_items = ConditionalList::new();
-    let mut run_items = ConditionalList::new();
-    let run_constraints_items = ConditionalList::new();
+    let mut requirements = ConditionalRequirements::default();
 
     // Add default target
     if let Some(default_target) = &targets.default_target {
-        let package_requirements = target_to_package_spec(default_target);
-
-        // source_target_requirements.default_target = source_requirements;
-
-        build_items.extend(
-            package_requirements
-                .build
-                .into_iter()
-                .map(|spec| spec.1)
-                .map(Item::from),
-        );
-
-        host_items.extend(
-            package_requirements
-                .host
-                .into_iter()
-                .map(|spec| spec.1)
-                .map(Item::from),
-        );
-
-        run_items.extend(
-            package_requirements
-                .run
-                .into_iter()
-                .map(|spec| spec.1)
-                .map(Item::from),
+        extend_conditional_requirements(
+            &mut requirements,
+            target_to_package_spec(default_target),
+            None,
         );
     }
 
     // Add specific targets
     if let Some(specific_targets) = &targets.targets {
         for (selector, target) in specific_targets {
-            let package_requirements = target_to_package_spec(target);
-
-            // add the binary requirements
-            build_items.extend(
-                package_requirements
-                    .build
-                    .into_iter()
-                    .map(|spec| spec.1)
-                    .map(|spec| {
-                        Conditional {
-                            condition: selector.to_string(),
-                            then: ListOrItem(vec![spec]),
-                            else_value: ListOrItem::default(),
-                        }
-                        .into()
-                    }),
-            );
-            host_items.extend(
-                package_requirements
-                    .host
-                    .into_iter()
-                    .map(|spec| spec.1)
-                    .map(|spec| {
-                        Conditional {
-                            condition: selector.to_string(),
-                            then: ListOrItem(vec![spec]),
-                            else_value: ListOrItem::default(),
-                        }
-                        .into()
-                    }),
-            );
-            run_items.extend(
-                package_requirements
-                    .run
-                    .into_iter()
-                    .map(|spec| spec.1)
-                    .map(|spec| {
-                        Conditional {
-                            condition: selector.to_string(),
-                            then: ListOrItem(vec![spec]),
-                            else_value: ListOrItem::default(),
-                        }
-                        .into()
-                    }),
+            extend_conditional_requirements(
+                &mut requirements,
+                target_to_package_spec(target),
+                Some(&selector.to_string()),
             );
         }
     }
 
-    ConditionalRequirements {
-        build: build_items,
-        host: host_items,
-        run: run_items,
-        run_constraints: run_constraints_items,
-    }
+    requirements
+}
+
+/// Adds the requirements of a target, including its run constraints, to the
+/// conditional requirements. The requirements of a specific target are
+/// wrapped in a conditional with the selector of the target.
+fn extend_conditional_requirements(
+    requirements: &mut ConditionalRequirements,
+    package_requirements: PackageSpecDependencies<PackageDependency>,
+    selector: Option<&str>,
+) {
+    let to_item = |spec: PackageDependency| -> Item<PackageDependency> {
+        match selector {
+            Some(selector) => Conditional {
+                condition: selector.to_string(),
+                then: ListOrItem(vec![spec]),
+                else_value: ListOrItem::default(),
+            }
+            .into(),
+            None => Item::from(spec),
+        }
+    };
+
+    requirements
+        .build
+        .extend(package_requirements.build.into_values().map(to_item));
+    requirements
+        .host
+        .extend(package_requirements.host.into_values().map(to_item));
+    requirements
+        .run
+        .extend(package_requirements.run.into_values().map(to_item));
+    requirements.run_constraints.extend(
+        package_requirements
+            .run_constraints
+            .into_values()
+            .map(to_item),
+    );
 }
 
 pub(crate) fn source_package_spec_to_package_dependency(
@@ -245,6 +212,10 @@ pub fn target_to_package_spec(target: &TargetV1) -> PackageSpecDependencies<Pack
         bin_reqs.run.insert(spec.package_name(), spec.clone());
     }
 
+    // `TargetV1` does not carry run constraints, they are only added from the
+    // backend configuration, e.g. with `GeneratedRecipe::add_run_constraints`.
+    // The `run_constraints` of the returned dependencies stay empty.
+
     bin_reqs
 }
 
@@ -347,6 +318,8 @@ pub fn from_build_v1_args_to_finalized_dependencies(
 
 #[cfg(test)]
 mod test {
+    use pixi_build_types::TargetSelectorV1;
+
     use super::*;
 
     #[test]
@@ -370,4 +343,84 @@ mod test {
         let match_spec = binary_package_spec_to_package_dependency(name, spec);
         assert_eq!(match_spec.to_string(), "python");
     }
+
+    #[test]
+    fn test_target_run_constraints_are_kept() {
+        let mut default_target = PackageSpecDependencies::default();
+        default_target.run.insert(
+            PackageName::new_unchecked("numpy"),
+            "numpy >=1.20".parse().unwrap(),
+        );
+        let mut linux_target = PackageSpecDependencies::default();
+        linux_target.run_constraints.insert(
+            PackageName::new_unchecked("cuda-version"),
+            "cuda-version >=12".parse().unwrap(),
+        );
+
+        let mut requirements = ConditionalRequirements::default();
+        extend_conditional_requirements(&mut requirements, default_target, None);
+        extend_conditional_requirements(&mut requirements, linux_target, Some("linux"));
+
+        assert_eq!(requirements.run.len(), 1);
+        assert_eq!(requirements.run_constraints.len(), 1);
+        let Item::Conditional(conditional) = &requirements.run_constraints[0] else {
+            panic!("expected a conditional run constraint");
+        };
+        assert_eq!(conditional.condition, "linux");
+        assert_eq!(conditional.then.0.len(), 1);
+        assert_eq!(
+            conditional.then.0[0].package_name(),
+            PackageName::new_unchecked("cuda-version")
+        );
+    }
+
+    #[test]
+    fn test_targets_are_converted_to_conditional_requirements() {
+        let binary = |version: &str| {
+            PackageSpecV1::Binary(Box::new(BinaryPackageSpecV1 {
+                version: Some(version.parse().unwrap()),
+                ..BinaryPackageSpecV1::default()
+            }))
+        };
+        let targets = TargetsV1 {
+            default_target: Some(TargetV1 {
+                build_dependencies: None,
+                host_dependencies: None,
+                run_dependencies: Some(
+                    [(String::from("numpy"), binary(">=1.20"))]
+                        .into_iter()
+                        .collect(),
+                ),
+            }),
+            targets: Some(
+                [(
+                    TargetSelectorV1::Linux,
+                    TargetV1 {
+                        build_dependencies: None,
+                        host_dependencies: Some(
+                            [(String::from("openssl"), binary(">=3"))]
+                                .into_iter()
+                                .collect(),
+                        ),
+                        run_dependencies: None,
+                    },
+                )]
+                .into_iter()
+                .collect(),
+            ),
+        };
+
+        let requirements = from_targets_v1_to_conditional_requirements(&targets);
+
+        assert_eq!(requirements.run.len(), 1);
+        assert!(requirements.run_constraints.is_empty());
+        let [Item::Conditional(conditional)] = requirements.host.as_slice() else {
+            panic!("expected a single conditional host requirement");
+        };
+        assert_eq!(conditional.condition, "linux");
+        assert_eq!(
+            conditional.then.0[0].package_name(),
+            PackageName::new_unchecked("openssl")
+        );
+    }
 }
@@ -0,0 +1,185 @@
+use std::path::{Path, PathBuf};
+
+use miette::IntoDiagnostic;
+use rattler_conda_types::{compression_level::CompressionLevel, package::ArchiveType};
+use rattler_package_streaming::write::{write_conda_package, write_tar_bz2_package};
+
+/// Parses the archive types from the backend configuration, e.g.
+/// `["conda", "tar.bz2"]`. The order is preserved and duplicates are removed.
+/// If no archive types are configured, only `.conda` packages are built.
+pub fn parse_archive_types(archive_types: &[String]) -> miette::Result<Vec<ArchiveType>> {
+    let mut parsed = Vec::new();
+    for archive_type in archive_types {
+        let archive_type = match archive_type.trim_start_matches('.') {
+            "conda" => ArchiveType::Conda,
+            "tar.bz2" => ArchiveType::TarBz2,
+            _ => miette::bail!(
+                "unsupported archive type '{archive_type}', expected `conda` or `tar.bz2`"
+            ),
+        };
+        if !parsed.contains(&archive_type) {
+            parsed.push(archive_type);
+        }
+    }
+
+    if parsed.is_empty() {
+        parsed.push(ArchiveType::Conda);
+    }
+    Ok(parsed)
+}
+
+/// Makes sure that the built `package` is available in all the given archive
+/// types. The package is converted to every archive type that differs from
+/// its own. Returns the paths of all the packages, starting with `package`.
+pub fn package_all_archive_types(
+    package: &Path,
+    archive_types: &[ArchiveType],
+) -> miette::Result<Vec<PathBuf>> {
+    let mut packages = vec![package.to_path_buf()];
+    for &archive_type in archive_types {
+        let converted = convert_package(package, archive_type)?;
+        if !packages.contains(&converted) {
+            packages.push(converted);
+        }
+    }
+    Ok(packages)
+}
+
+/// Converts a package to the given archive type. The converted package is
+/// written next to the original package. If the package already has the
+/// requested archive type, the path of the package is returned as is.
+pub fn convert_package(package: &Path, archive_type: ArchiveType) -> miette::Result<PathBuf> {
+    let file_name = package
+        .file_name()
+        .and_then(|file_name| file_name.to_str())
+        .unwrap_or_default();
+    let Some((stem, current_archive_type)) = ArchiveType::split_str(file_name) else {
+        miette::bail!("'{}' is not a conda package", package.display());
+    };
+    if current_archive_type == archive_type {
+        return Ok(package.to_path_buf());
+    }
+
+    let extracted = tempfile::tempdir().into_diagnostic()?;
+    rattler_package_streaming::fs::extract(package, extracted.path()).into_diagnostic()?;
+    let mut paths = Vec::new();
+    collect_files(extracted.path(), &mut paths)?;
+    paths.sort();
+
+    let output_path = package.with_file_name(format!("{stem}{}", archive_type.extension()));
+    let file = fs_err::File::create(&output_path).into_diagnostic()?;
+    match archive_type {
+        ArchiveType::TarBz2 => write_tar_bz2_package(
+            file,
+            extracted.path(),
+            &paths,
+            CompressionLevel::default(),
+            None,
+            None,
+        ),
+        ArchiveType::Conda => write_conda_package(
+            file,
+            extracted.path(),
+            &paths,
+            CompressionLevel::default(),
+            None,
+            stem,
+            None,
+            None,
+        ),
+    }
+    .into_diagnostic()?;
+
+    Ok(output_path)
+}
+
+/// Recursively collects all the files and symlinks in a directory.
+fn collect_files(dir: &Path, paths: &mut Vec<PathBuf>) -> miette::Result<()> {
+    for entry in fs_err::read_dir(dir).into_diagnostic()? {
+        let path = entry.into_diagnostic()?.path();
+        if fs_err::symlink_metadata(&path).into_diagnostic()?.is_dir() {
+            collect_files(&path, paths)?;
+        } else {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a minimal `.conda` package to `dir` and returns its path.
+    fn write_test_package(dir: &Path) -> PathBuf {
+        let contents = tempfile::tempdir().unwrap();
+        fs_err::create_dir_all(contents.path().join("info")).unwrap();
+        fs_err::create_dir_all(contents.path().join("bin")).unwrap();
+        fs_err::write(
+            contents.path().join("info/index.json"),
+            r#"{"name": "foo", "version": "1.0", "build": "0", "build_number": 0}"#,
+        )
+        .unwrap();
+        fs_err::write(contents.path().join("bin/foo"), "echo foo").unwrap();
+
+        let mut paths = Vec::new();
+        collect_files(contents.path(), &mut paths).unwrap();
+
+        let package = dir.join("foo-1.0-0.conda");
+        write_conda_package(
+            fs_err::File::create(&package).unwrap(),
+            contents.path(),
+            &paths,
+            CompressionLevel::default(),
+            None,
+            "foo-1.0-0",
+            None,
+            None,
+        )
+        .unwrap();
+        package
+    }
+
+    #[test]
+    fn test_parse_archive_types() {
+        assert_eq!(parse_archive_types(&[]).unwrap(), vec![ArchiveType::Conda]);
+        assert_eq!(
+            parse_archive_types(&[
+                "tar.bz2".to_string(),
+                ".conda".to_string(),
+                "conda".to_string(),
+            ])
+            .unwrap(),
+            vec![ArchiveType::TarBz2, ArchiveType::Conda]
+        );
+
+        let error = parse_archive_types(&["zip".to_string()]).unwrap_err();
+        assert!(error.to_string().contains("unsupported archive type 'zip'"));
+    }
+
+    #[test]
+    fn test_package_all_archive_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = write_test_package(dir.path());
+
+        let archive_types = [ArchiveType::Conda, ArchiveType::TarBz2];
+        let packages = package_all_archive_types(&package, &archive_types).unwrap();
+
+        assert_eq!(
+            packages,
+            vec![
+                dir.path().join("foo-1.0-0.conda"),
+                dir.path().join("foo-1.0-0.tar.bz2"),
+            ]
+        );
+        assert!(packages.iter().all(|package| package.is_file()));
+
+        // The converted package has the same contents as the original one.
+        let extracted = tempfile::tempdir().unwrap();
+        rattler_package_streaming::fs::extract(&packages[1], extracted.path()).unwrap();
+        assert_eq!(
+            fs_err::read_to_string(extracted.path().join("bin/foo")).unwrap(),
+            "echo foo"
+        );
+    }
+}
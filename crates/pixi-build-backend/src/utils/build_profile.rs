@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// The profile that a package is built with. Every backend maps the profile
+/// to its own settings, e.g. the build type of CMake or the cargo profile.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BuildProfile {
+    /// A build for development, e.g. an editable install or a debug build
+    /// that is not optimized.
+    Dev,
+    /// An optimized build for distribution.
+    #[default]
+    Release,
+}
+
+impl BuildProfile {
+    /// Returns true if this is the [`BuildProfile::Dev`] profile.
+    pub fn is_dev(self) -> bool {
+        self == BuildProfile::Dev
+    }
+}
@@ -0,0 +1,257 @@
+use std::{path::Path, str::FromStr};
+
+use itertools::Itertools;
+use miette::{Context, IntoDiagnostic};
+use rattler_conda_types::{Channel, ChannelConfig};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Where the extra channels of a backend configuration are added relative to
+/// the channels that are requested by the frontend.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExtraChannelPriority {
+    /// The extra channels are added after the channels of the frontend.
+    #[default]
+    Lowest,
+    /// The extra channels are added before the channels of the frontend.
+    Highest,
+}
+
+/// Adds the `extra_channels` to the `channels` that are requested by the
+/// frontend. Extra channels are either urls or channel names, names are
+/// resolved with the given channel config. Channels that are already part of
+/// `channels` are only added once.
+pub fn with_extra_channels(
+    channels: Vec<Url>,
+    extra_channels: &[String],
+    priority: ExtraChannelPriority,
+    channel_config: &ChannelConfig,
+) -> miette::Result<Vec<Url>> {
+    let extra_channels = extra_channels
+        .iter()
+        .map(|channel| {
+            Channel::from_str(channel, channel_config)
+                .map(|channel| channel.base_url.url().clone())
+                .into_diagnostic()
+                .with_context(|| format!("invalid extra channel '{channel}'"))
+        })
+        .collect::<miette::Result<Vec<_>>>()?;
+
+    let channels = match priority {
+        ExtraChannelPriority::Lowest => channels.into_iter().chain(extra_channels),
+        ExtraChannelPriority::Highest => extra_channels.into_iter().chain(channels),
+    };
+    Ok(channels.unique().collect())
+}
+
+/// The contents of a channels file, either a plain list of channels or a
+/// mapping with a `channels` key like in a `.condarc` file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChannelsFile {
+    List(Vec<String>),
+    Condarc { channels: Vec<String> },
+}
+
+/// Reads the channels from a channels file. The file is a YAML file that
+/// contains either a list of channels or, like a `.condarc` file, a mapping
+/// with a `channels` key. Other keys of the mapping are ignored.
+pub fn read_channels_file(path: &Path) -> miette::Result<Vec<String>> {
+    let content = fs_err::read_to_string(path).into_diagnostic()?;
+    let channels_file: ChannelsFile = serde_yaml::from_str(&content)
+        .into_diagnostic()
+        .with_context(|| format!("failed to parse the channels file '{}'", path.display()))?;
+    Ok(match channels_file {
+        ChannelsFile::List(channels) | ChannelsFile::Condarc { channels } => channels,
+    })
+}
+
+/// Returns the urls of the `required` channels that are not part of
+/// `channels`. A required channel is part of `channels` if one of the
+/// channels is the required channel or a channel below it, e.g.
+/// `https://conda.modular.com/` matches `https://conda.modular.com/max-nightly/`.
+pub fn missing_channels<'a>(required: &[&'a str], channels: &[Url]) -> Vec<&'a str> {
+    required
+        .iter()
+        .copied()
+        .filter(|required| {
+            let Ok(required) = Url::parse(required) else {
+                return true;
+            };
+            !channels
+                .iter()
+                .any(|channel| contains_channel(&required, channel))
+        })
+        .collect()
+}
+
+/// Returns true if `channel` is `parent` or a channel below it. The scheme,
+/// host, port and path segments of the urls are compared, so e.g.
+/// `https://prefix.dev/conda-forge` does not contain
+/// `https://prefix.dev/conda-forge-nightly`.
+fn contains_channel(parent: &Url, channel: &Url) -> bool {
+    fn segments(url: &Url) -> Vec<&str> {
+        url.path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|segment| !segment.is_empty())
+            .collect()
+    }
+
+    parent.scheme() == channel.scheme()
+        && parent.host_str() == channel.host_str()
+        && parent.port_or_known_default() == channel.port_or_known_default()
+        && segments(channel).starts_with(&segments(parent))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn channel_config() -> ChannelConfig {
+        ChannelConfig::default_with_root_dir(PathBuf::from("/"))
+    }
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn test_extra_channels_are_added() {
+        let frontend_channels = vec![url("https://prefix.dev/conda-forge/")];
+        let extra_channels = vec![
+            "https://prefix.dev/build-tools".to_string(),
+            "bioconda".to_string(),
+        ];
+
+        let channels = with_extra_channels(
+            frontend_channels.clone(),
+            &extra_channels,
+            ExtraChannelPriority::Lowest,
+            &channel_config(),
+        )
+        .unwrap();
+        assert_eq!(
+            channels,
+            vec![
+                url("https://prefix.dev/conda-forge/"),
+                url("https://prefix.dev/build-tools/"),
+                url("https://conda.anaconda.org/bioconda/"),
+            ]
+        );
+
+        let channels = with_extra_channels(
+            frontend_channels,
+            &extra_channels,
+            ExtraChannelPriority::Highest,
+            &channel_config(),
+        )
+        .unwrap();
+        assert_eq!(
+            channels,
+            vec![
+                url("https://prefix.dev/build-tools/"),
+                url("https://conda.anaconda.org/bioconda/"),
+                url("https://prefix.dev/conda-forge/"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extra_channels_are_not_duplicated() {
+        let channels = with_extra_channels(
+            vec![url("https://prefix.dev/conda-forge/")],
+            &["https://prefix.dev/conda-forge".to_string()],
+            ExtraChannelPriority::Lowest,
+            &channel_config(),
+        )
+        .unwrap();
+        assert_eq!(channels, vec![url("https://prefix.dev/conda-forge/")]);
+    }
+
+    #[test]
+    fn test_missing_channels() {
+        let channels = vec![
+            url("https://prefix.dev/conda-forge/"),
+            url("https://conda.modular.com/max-nightly/"),
+        ];
+        assert!(missing_channels(&["https://conda.modular.com/"], &channels).is_empty());
+        assert!(missing_channels(&["https://prefix.dev/conda-forge"], &channels).is_empty());
+        assert_eq!(
+            missing_channels(&["https://prefix.dev/modular-community"], &channels),
+            vec!["https://prefix.dev/modular-community"]
+        );
+
+        // Channels are compared by their parsed urls, not by string prefixes.
+        assert_eq!(
+            missing_channels(&["https://prefix.dev/conda"], &channels),
+            vec!["https://prefix.dev/conda"]
+        );
+        let lookalike = vec![url("https://conda.modular.com.example.org/max/")];
+        assert_eq!(
+            missing_channels(&["https://conda.modular.com/"], &lookalike),
+            vec!["https://conda.modular.com/"]
+        );
+        let other_scheme = vec![url("http://conda.modular.com/max/")];
+        assert_eq!(
+            missing_channels(&["https://conda.modular.com/"], &other_scheme),
+            vec!["https://conda.modular.com/"]
+        );
+    }
+
+    #[test]
+    fn test_read_channels_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let condarc = dir.path().join(".condarc");
+        fs_err::write(
+            &condarc,
+            "channels:\n  - https://prefix.dev/build-tools\n  - bioconda\nssl_verify: true\n",
+        )
+        .unwrap();
+        let list = dir.path().join("channels.yaml");
+        fs_err::write(&list, "- bioconda\n").unwrap();
+
+        let channels = read_channels_file(&condarc).unwrap();
+        assert_eq!(channels, vec!["https://prefix.dev/build-tools", "bioconda"]);
+        assert_eq!(read_channels_file(&list).unwrap(), vec!["bioconda"]);
+
+        // The channels are used in addition to the channels of the frontend.
+        let channels = with_extra_channels(
+            vec![url("https://prefix.dev/conda-forge/")],
+            &channels,
+            ExtraChannelPriority::Lowest,
+            &channel_config(),
+        )
+        .unwrap();
+        assert_eq!(
+            channels,
+            vec![
+                url("https://prefix.dev/conda-forge/"),
+                url("https://prefix.dev/build-tools/"),
+                url("https://conda.anaconda.org/bioconda/"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_invalid_channels_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("channels.yaml");
+        fs_err::write(&path, "channels: conda-forge\n").unwrap();
+        assert!(read_channels_file(&path).is_err());
+    }
+
+    #[test]
+    fn test_invalid_extra_channel() {
+        let result = with_extra_channels(
+            vec![],
+            &["https://".to_string()],
+            ExtraChannelPriority::Lowest,
+            &channel_config(),
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use recipe_stage0::recipe::{DynamicLinking, LinkingCheckBehavior};
+use serde::{Deserialize, Serialize};
+
+/// Configuration of the overlinking and overdepending checks that
+/// rattler-build performs on the binaries of a package. It maps to the
+/// `build.dynamic_linking` section of the generated recipe.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DynamicLinkingConfig {
+    /// The rpaths that are set on the binaries, relative to the prefix.
+    #[serde(default)]
+    pub rpaths: Vec<String>,
+    /// Globs of shared libraries that may be linked without being provided
+    /// by a run dependency, e.g. system libraries.
+    #[serde(default)]
+    pub missing_dso_allowlist: Vec<String>,
+    /// Globs of rpaths that are allowed even though they point outside of the
+    /// prefix.
+    #[serde(default)]
+    pub rpath_allowlist: Vec<String>,
+    /// What happens when a run dependency is not linked by any binary.
+    #[serde(default)]
+    pub overdepending_behavior: Option<LinkingCheckBehavior>,
+    /// What happens when a binary links a library that is not provided by a
+    /// run dependency.
+    #[serde(default)]
+    pub overlinking_behavior: Option<LinkingCheckBehavior>,
+}
+
+impl From<DynamicLinkingConfig> for DynamicLinking {
+    fn from(config: DynamicLinkingConfig) -> Self {
+        DynamicLinking {
+            rpaths: config.rpaths,
+            missing_dso_allowlist: config.missing_dso_allowlist,
+            rpath_allowlist: config.rpath_allowlist,
+            overdepending_behavior: config.overdepending_behavior,
+            overlinking_behavior: config.overlinking_behavior,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_dynamic_linking() {
+        let config: DynamicLinkingConfig = serde_json::from_str(
+            r#"{"missing-dso-allowlist": ["/usr/lib/libGL.so*"], "overlinking-behavior": "error"}"#,
+        )
+        .unwrap();
+        let dynamic_linking = DynamicLinking::from(config);
+        assert_eq!(
+            dynamic_linking.missing_dso_allowlist,
+            vec!["/usr/lib/libGL.so*".to_string()]
+        );
+        assert_eq!(
+            dynamic_linking.overlinking_behavior,
+            Some(LinkingCheckBehavior::Error)
+        );
+        assert!(dynamic_linking.overdepending_behavior.is_none());
+
+        let err =
+            serde_json::from_str::<DynamicLinkingConfig>(r#"{"overlinking-behavior": "warn"}"#)
+                .unwrap_err();
+        assert!(err.to_string().contains("unknown variant `warn`"), "{err}");
+    }
+}
@@ -0,0 +1,80 @@
+use std::{collections::BTreeSet, io::ErrorKind, path::Path};
+
+use miette::IntoDiagnostic;
+
+/// Returns input globs for the files that are tracked by git-lfs according to
+/// the `.gitattributes` file in `root`. Returns no globs if the file does not
+/// exist.
+pub fn lfs_input_globs(root: &Path) -> miette::Result<BTreeSet<String>> {
+    let gitattributes = match fs_err::read_to_string(root.join(".gitattributes")) {
+        Ok(gitattributes) => gitattributes,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(BTreeSet::new()),
+        Err(err) => return Err(err).into_diagnostic(),
+    };
+
+    Ok(gitattributes
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let pattern = parts.next()?;
+            if pattern.starts_with('#') || pattern.starts_with("[attr]") {
+                return None;
+            }
+            parts
+                .any(|attribute| attribute == "filter=lfs")
+                .then(|| pattern_to_glob(pattern))
+        })
+        .collect())
+}
+
+/// Converts a `.gitattributes` pattern to a glob that is relative to the
+/// directory of the `.gitattributes` file. Like in git, a pattern without a
+/// slash matches at any depth.
+fn pattern_to_glob(pattern: &str) -> String {
+    let pattern = match pattern.strip_suffix('/') {
+        Some(directory) => format!("{directory}/**"),
+        None => pattern.to_string(),
+    };
+    if let Some(anchored) = pattern.strip_prefix('/') {
+        anchored.to_string()
+    } else if pattern.trim_end_matches("/**").contains('/') {
+        pattern
+    } else {
+        format!("**/{pattern}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lfs_input_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(
+            dir.path().join(".gitattributes"),
+            "# Large assets\n\
+             *.bin filter=lfs diff=lfs merge=lfs -text\n\
+             /models/*.onnx filter=lfs diff=lfs merge=lfs -text\n\
+             data/raw/** filter=lfs diff=lfs merge=lfs -text\n\
+             *.sh text eol=lf\n",
+        )
+        .unwrap();
+
+        let globs = lfs_input_globs(dir.path()).unwrap();
+        assert_eq!(
+            globs,
+            BTreeSet::from([
+                "**/*.bin".to_string(),
+                "data/raw/**".to_string(),
+                "models/*.onnx".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_lfs_input_globs_without_gitattributes() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(lfs_input_globs(dir.path()).unwrap().is_empty());
+    }
+}
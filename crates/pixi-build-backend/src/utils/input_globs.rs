@@ -0,0 +1,79 @@
+use globset::GlobBuilder;
+
+use crate::error::BackendError;
+
+/// Checks that every input glob is a valid glob pattern, including the
+/// alternatives in braces like `**/*.{c,h}`. An invalid glob would otherwise
+/// silently match no files at all.
+pub fn validate_input_globs(
+    globs: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Result<(), BackendError> {
+    for glob in globs {
+        let glob = glob.as_ref();
+        GlobBuilder::new(glob)
+            .literal_separator(true)
+            .build()
+            .map_err(|err| BackendError::InvalidInputGlob(glob.to_string(), err))?;
+    }
+    Ok(())
+}
+
+/// Scopes an input glob to a subdirectory of the source, e.g. `**/*.rs`
+/// becomes `crates/foo/**/*.rs` for the subdirectory `crates/foo`. Leading
+/// `../` components of the glob are resolved against the subdirectory, e.g.
+/// `../../**/Cargo.toml` becomes `**/Cargo.toml`.
+pub fn scope_input_glob(subdir: &str, glob: &str) -> String {
+    let subdir = subdir.replace('\\', "/");
+    let mut components = subdir
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .collect::<Vec<_>>();
+    let mut glob = glob;
+    while let Some(rest) = glob.strip_prefix("../") {
+        if components.pop().is_none() {
+            break;
+        }
+        glob = rest;
+    }
+    components.push(glob);
+    components.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_brace_glob() {
+        validate_input_globs(["**/*.{c,cc,cxx,cpp,h,hpp,hxx}", "include/**"]).unwrap();
+    }
+
+    #[test]
+    fn test_unbalanced_brace_glob() {
+        let err = validate_input_globs(["**/*.txt", "**/*.{c,h"]).unwrap_err();
+        assert!(
+            matches!(&err, BackendError::InvalidInputGlob(glob, _) if glob == "**/*.{c,h"),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn test_scope_input_glob() {
+        assert_eq!(
+            scope_input_glob("crates/foo/", "**/*.rs"),
+            "crates/foo/**/*.rs"
+        );
+        assert_eq!(
+            scope_input_glob("crates\\foo", "Cargo.toml"),
+            "crates/foo/Cargo.toml"
+        );
+        assert_eq!(
+            scope_input_glob("crates/foo", "../../**/Cargo.toml"),
+            "**/Cargo.toml"
+        );
+        assert_eq!(
+            scope_input_glob("crates/foo", "../../../Cargo.toml"),
+            "../Cargo.toml"
+        );
+    }
+}
@@ -0,0 +1,276 @@
+use std::path::{Component, Path, PathBuf};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use miette::IntoDiagnostic;
+use rattler_digest::{Sha256, digest::Digest};
+
+/// Directories that are never considered when hashing the input files. These
+/// contain environments or version control data that are not part of the
+/// sources.
+const IGNORED_DIRECTORIES: [&str; 2] = [".pixi", ".git"];
+
+/// The file that marks a directory as a cache or build directory, e.g. the
+/// `target` directory of cargo. See <https://bford.info/cachedir/>.
+const CACHE_DIRECTORY_TAG: &str = "CACHEDIR.TAG";
+
+/// Computes a stable content hash of all the files in `base_dir` that match
+/// any of the given `globs`.
+///
+/// The globs are interpreted relative to `base_dir` in the same way as the
+/// `input_globs` that are returned to the frontend. Globs that start with `!`
+/// exclude the files they match, and globs can refer to files outside of
+/// `base_dir` with leading `../` components. Build directories that are
+/// tagged with a `CACHEDIR.TAG`, like the `target` directory of cargo, are
+/// skipped.
+///
+/// Matched files are hashed in the order of their relative path, and both the
+/// path and the contents of every file contribute to the hash, so renaming,
+/// adding, removing or modifying a matched file all result in a different
+/// hash. The result can be compared with a previously computed hash to skip a
+/// rebuild when none of the inputs changed.
+pub fn compute_input_globs_hash(
+    base_dir: &Path,
+    globs: impl IntoIterator<Item = impl AsRef<str>>,
+) -> miette::Result<String> {
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    for glob in globs {
+        let glob = glob.as_ref();
+        match glob.strip_prefix('!') {
+            Some(glob) => exclude.push(glob.to_string()),
+            None => include.push(glob.to_string()),
+        }
+    }
+
+    // Walk from the directory that the glob with the most leading `../`
+    // components refers to, so files outside of `base_dir` are found as well.
+    let parent_depth = include
+        .iter()
+        .map(|glob| glob.split('/').take_while(|part| *part == "..").count())
+        .max()
+        .unwrap_or_default();
+    let base_dir = fs_err::canonicalize(base_dir).into_diagnostic()?;
+    let mut walk_root = base_dir.as_path();
+    for _ in 0..parent_depth {
+        walk_root = walk_root.parent().unwrap_or(walk_root);
+    }
+    let base_components = relative_components(&base_dir, walk_root);
+
+    let include = build_glob_set(&include)?;
+    let exclude = build_glob_set(&exclude)?;
+
+    let mut matched_files = Vec::new();
+    collect_files(walk_root, walk_root, &mut |path, components| {
+        let relative_path = relative_path_to(&base_components, components);
+        if include.is_match(&relative_path) && !exclude.is_match(&relative_path) {
+            matched_files.push((relative_path, path));
+        }
+    })?;
+    matched_files.sort();
+
+    let mut hasher = Sha256::default();
+    for (relative_path, path) in matched_files {
+        let contents = fs_err::read(&path).into_diagnostic()?;
+        hasher.update(relative_path.as_bytes());
+        hasher.update(b"\0");
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(&contents);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+fn build_glob_set(globs: &[String]) -> miette::Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        let glob = GlobBuilder::new(glob.trim_start_matches("./"))
+            .literal_separator(true)
+            .build()
+            .into_diagnostic()?;
+        builder.add(glob);
+    }
+    builder.build().into_diagnostic()
+}
+
+/// Returns the components of `path` relative to `root`.
+fn relative_components(path: &Path, root: &Path) -> Vec<String> {
+    path.strip_prefix(root)
+        .expect("path must be inside the root directory")
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the `/` separated path of a file, given by its components
+/// relative to the walk root, relative to the base directory, given by its
+/// components relative to the walk root. Files outside of the base directory
+/// get leading `..` components.
+fn relative_path_to(base_components: &[String], components: &[String]) -> String {
+    let common = base_components
+        .iter()
+        .zip(components)
+        .take_while(|(base, component)| base == component)
+        .count();
+    std::iter::repeat_n("..", base_components.len() - common)
+        .chain(components[common..].iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Recursively calls `on_file` with the full path and the components
+/// relative to `root` of every file in `dir`, skipping ignored and build
+/// directories.
+fn collect_files(
+    root: &Path,
+    dir: &Path,
+    on_file: &mut impl FnMut(PathBuf, &[String]),
+) -> miette::Result<()> {
+    for entry in fs_err::read_dir(dir).into_diagnostic()? {
+        let entry = entry.into_diagnostic()?;
+        let path = entry.path();
+        let file_type = entry.file_type().into_diagnostic()?;
+
+        if file_type.is_dir() {
+            let ignored = entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| IGNORED_DIRECTORIES.contains(&name))
+                || path.join(CACHE_DIRECTORY_TAG).is_file();
+            if !ignored {
+                collect_files(root, &path, on_file)?;
+            }
+            continue;
+        }
+
+        if path.is_file() {
+            let components = relative_components(&path, root);
+            on_file(path, &components);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GLOBS: [&str; 2] = ["**/*.rs", "Cargo.toml"];
+
+    fn create_project(dir: &Path) {
+        fs_err::create_dir_all(dir.join("src/nested")).unwrap();
+        fs_err::write(dir.join("Cargo.toml"), "[package]\nname = \"foo\"").unwrap();
+        fs_err::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+        fs_err::write(dir.join("src/nested/lib.rs"), "pub fn foo() {}").unwrap();
+        fs_err::write(dir.join("README.md"), "# foo").unwrap();
+    }
+
+    #[test]
+    fn test_hash_is_stable() {
+        let dir = tempfile::tempdir().unwrap();
+        create_project(dir.path());
+
+        let first = compute_input_globs_hash(dir.path(), GLOBS).unwrap();
+        let second = compute_input_globs_hash(dir.path(), GLOBS).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_hash_changes_when_matched_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        create_project(dir.path());
+        let before = compute_input_globs_hash(dir.path(), GLOBS).unwrap();
+
+        fs_err::write(dir.path().join("src/nested/lib.rs"), "pub fn bar() {}").unwrap();
+        let after = compute_input_globs_hash(dir.path(), GLOBS).unwrap();
+        assert_ne!(before, after);
+
+        // Adding a new matching file also changes the hash.
+        fs_err::write(dir.path().join("src/new.rs"), "").unwrap();
+        assert_ne!(after, compute_input_globs_hash(dir.path(), GLOBS).unwrap());
+    }
+
+    #[test]
+    fn test_hash_ignores_unmatched_files() {
+        let dir = tempfile::tempdir().unwrap();
+        create_project(dir.path());
+        let before = compute_input_globs_hash(dir.path(), GLOBS).unwrap();
+
+        fs_err::write(dir.path().join("README.md"), "# bar").unwrap();
+        fs_err::create_dir_all(dir.path().join(".pixi")).unwrap();
+        fs_err::write(dir.path().join(".pixi/ignored.rs"), "").unwrap();
+        let after = compute_input_globs_hash(dir.path(), GLOBS).unwrap();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_hash_ignores_negated_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        create_project(dir.path());
+        let globs = ["**/*.rs", "!src/nested/**"];
+        let before = compute_input_globs_hash(dir.path(), globs).unwrap();
+
+        fs_err::write(dir.path().join("src/nested/lib.rs"), "pub fn bar() {}").unwrap();
+        assert_eq!(before, compute_input_globs_hash(dir.path(), globs).unwrap());
+
+        fs_err::write(dir.path().join("src/main.rs"), "fn main() { }").unwrap();
+        assert_ne!(before, compute_input_globs_hash(dir.path(), globs).unwrap());
+    }
+
+    #[test]
+    fn test_hash_includes_files_in_parent_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let package = dir.path().join("workspace/package");
+        create_project(&package);
+        fs_err::create_dir_all(dir.path().join("workspace/shared")).unwrap();
+        fs_err::write(dir.path().join("workspace/shared/data.txt"), "a").unwrap();
+        fs_err::write(dir.path().join("unrelated.txt"), "a").unwrap();
+
+        let globs = ["**/*.rs", "../shared/**"];
+        let before = compute_input_globs_hash(&package, globs).unwrap();
+
+        fs_err::write(dir.path().join("workspace/shared/data.txt"), "b").unwrap();
+        let after = compute_input_globs_hash(&package, globs).unwrap();
+        assert_ne!(before, after);
+
+        // Files outside of the parent directory glob are not hashed.
+        fs_err::write(dir.path().join("unrelated.txt"), "b").unwrap();
+        assert_eq!(after, compute_input_globs_hash(&package, globs).unwrap());
+    }
+
+    #[test]
+    fn test_hash_skips_build_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        create_project(dir.path());
+        // Cargo tags its `target` directory as a cache directory.
+        fs_err::create_dir_all(dir.path().join("target/debug/build")).unwrap();
+        fs_err::write(
+            dir.path().join("target/CACHEDIR.TAG"),
+            "Signature: 8a477f597d28d172789f06886806bc55",
+        )
+        .unwrap();
+        let before = compute_input_globs_hash(dir.path(), GLOBS).unwrap();
+
+        fs_err::write(dir.path().join("target/debug/build/out.rs"), "").unwrap();
+        assert_eq!(before, compute_input_globs_hash(dir.path(), GLOBS).unwrap());
+    }
+
+    #[test]
+    fn test_hash_is_independent_of_creation_order() {
+        let first = tempfile::tempdir().unwrap();
+        fs_err::write(first.path().join("a.rs"), "a").unwrap();
+        fs_err::write(first.path().join("b.rs"), "b").unwrap();
+
+        let second = tempfile::tempdir().unwrap();
+        fs_err::write(second.path().join("b.rs"), "b").unwrap();
+        fs_err::write(second.path().join("a.rs"), "a").unwrap();
+
+        assert_eq!(
+            compute_input_globs_hash(first.path(), GLOBS).unwrap(),
+            compute_input_globs_hash(second.path(), GLOBS).unwrap()
+        );
+    }
+}
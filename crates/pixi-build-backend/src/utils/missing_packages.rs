@@ -0,0 +1,122 @@
+use std::{collections::BTreeSet, str::FromStr};
+
+use miette::{Context, IntoDiagnostic};
+use pixi_build_types::{PackageSpecV1, ProjectModelV1};
+use rattler_conda_types::{Channel, MatchSpec, PackageName, Platform};
+use rattler_repodata_gateway::Gateway;
+use url::Url;
+
+use crate::ProjectModel;
+
+/// Returns the names of the binary dependencies of the project model for the
+/// given platform. These are the dependencies that must be available in the
+/// channels, source dependencies are built instead.
+pub fn binary_dependency_names(
+    model: &ProjectModelV1,
+    platform: Platform,
+) -> miette::Result<BTreeSet<PackageName>> {
+    let dependencies = model.dependencies(Some(platform));
+    dependencies
+        .build
+        .iter()
+        .chain(&dependencies.host)
+        .chain(&dependencies.run)
+        .filter(|(_, spec)| matches!(spec, PackageSpecV1::Binary(_)))
+        .map(|(name, _)| {
+            PackageName::from_str(name.as_str())
+                .into_diagnostic()
+                .with_context(|| format!("invalid package name '{name}'"))
+        })
+        .collect()
+}
+
+/// Queries the repodata of the channels and returns the packages of `names`
+/// that are neither available for `platform` nor as noarch package. This is
+/// much faster than solving the environments, but does not check whether the
+/// version constraints can be satisfied.
+pub async fn missing_packages(
+    names: &BTreeSet<PackageName>,
+    channels: &[Url],
+    platform: Platform,
+) -> miette::Result<Vec<PackageName>> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let specs = names.iter().map(|name| MatchSpec {
+        name: Some(name.clone()),
+        ..MatchSpec::default()
+    });
+    let repo_data = Gateway::new()
+        .query(
+            channels.iter().cloned().map(Channel::from_url),
+            [platform, Platform::NoArch],
+            specs,
+        )
+        .recursive(false)
+        .await
+        .into_diagnostic()
+        .context("failed to query the repodata of the channels")?;
+
+    let found = repo_data
+        .iter()
+        .flat_map(|repo_data| repo_data.iter())
+        .map(|record| &record.package_record.name)
+        .collect::<BTreeSet<_>>();
+    Ok(names
+        .iter()
+        .filter(|name| !found.contains(name))
+        .cloned()
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_binary_dependency_names() {
+        let model: ProjectModelV1 = serde_json::from_value(serde_json::json!({
+            "name": "foobar",
+            "version": "0.1.0",
+            "targets": {
+                "defaultTarget": {
+                    "hostDependencies": {
+                        "python": { "binary": { "version": "*" } }
+                    },
+                    "runDependencies": {
+                        "boltons": { "binary": { "version": ">=24" } }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let names = binary_dependency_names(&model, Platform::Linux64).unwrap();
+        assert_eq!(
+            names,
+            BTreeSet::from([
+                PackageName::new_unchecked("boltons"),
+                PackageName::new_unchecked("python"),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_packages() {
+        let channel = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/channel");
+        let channel = Url::from_directory_path(channel).unwrap();
+
+        let names = BTreeSet::from([
+            PackageName::new_unchecked("boltons"),
+            PackageName::new_unchecked("cmake"),
+            PackageName::new_unchecked("bolton"),
+        ]);
+        let missing = missing_packages(&names, &[channel], Platform::Linux64)
+            .await
+            .unwrap();
+        assert_eq!(missing, vec![PackageName::new_unchecked("bolton")]);
+    }
+}
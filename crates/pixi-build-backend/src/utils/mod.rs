@@ -1,4 +1,38 @@
+mod archive;
+mod build_profile;
+mod channels;
+mod dynamic_linking;
+mod git_lfs;
+mod input_globs;
+mod input_hash;
+mod missing_packages;
+mod provenance;
+mod recipe_diff;
+mod retry;
+mod sidecar_config;
+mod site_packages;
+mod solve_strategy;
+mod target_platform;
 mod temporary_recipe;
 pub mod test;
+mod version_normalization;
 
+pub use archive::{convert_package, package_all_archive_types, parse_archive_types};
+pub use build_profile::BuildProfile;
+pub use channels::{
+    ExtraChannelPriority, missing_channels, read_channels_file, with_extra_channels,
+};
+pub use dynamic_linking::DynamicLinkingConfig;
+pub use git_lfs::lfs_input_globs;
+pub use input_globs::{scope_input_glob, validate_input_globs};
+pub use input_hash::compute_input_globs_hash;
+pub use missing_packages::{binary_dependency_names, missing_packages};
+pub use provenance::{PROVENANCE_KEY, provenance_extra_meta};
+pub use recipe_diff::diff_recipes;
+pub use retry::{MAX_BUILD_RETRIES, run_with_build_retries};
+pub use sidecar_config::{SIDECAR_CONFIG_FILE_NAME, with_sidecar_config};
+pub use site_packages::python_site_packages_path;
+pub use solve_strategy::SolveStrategy;
+pub use target_platform::output_subdir;
 pub use temporary_recipe::TemporaryRenderedRecipe;
+pub use version_normalization::VersionNormalization;
@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use crate::generated_recipe::BackendIdentifier;
+
+/// The key of the extra metadata under which the provenance is recorded.
+pub const PROVENANCE_KEY: &str = "pixi_build";
+
+/// Returns the extra metadata that records which backend built a package.
+/// rattler-build stores this in the `extra` section of the `about.json` of the
+/// package.
+pub fn provenance_extra_meta(
+    backend: Option<BackendIdentifier>,
+) -> BTreeMap<String, serde_json::Value> {
+    let mut provenance = serde_json::Map::new();
+    if let Some(backend) = backend {
+        provenance.insert("backend".to_string(), backend.name.into());
+        provenance.insert("backend_version".to_string(), backend.version.into());
+    }
+    provenance.insert(
+        "pixi_build_backend_version".to_string(),
+        env!("CARGO_PKG_VERSION").into(),
+    );
+
+    BTreeMap::from([(PROVENANCE_KEY.to_string(), provenance.into())])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provenance_contains_backend_identifier() {
+        let extra_meta = provenance_extra_meta(Some(BackendIdentifier {
+            name: "pixi-build-foo",
+            version: "1.2.3",
+        }));
+
+        assert_eq!(
+            extra_meta[PROVENANCE_KEY],
+            serde_json::json!({
+                "backend": "pixi-build-foo",
+                "backend_version": "1.2.3",
+                "pixi_build_backend_version": env!("CARGO_PKG_VERSION"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_provenance_without_backend_identifier() {
+        let extra_meta = provenance_extra_meta(None);
+
+        assert_eq!(
+            extra_meta[PROVENANCE_KEY],
+            serde_json::json!({ "pixi_build_backend_version": env!("CARGO_PKG_VERSION") })
+        );
+    }
+}
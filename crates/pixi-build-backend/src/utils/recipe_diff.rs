@@ -0,0 +1,55 @@
+use recipe_stage0::recipe::IntermediateRecipe;
+use similar::TextDiff;
+
+/// Returns a unified diff of the YAML representation of two recipes, e.g. to
+/// review how a change of the configuration affects the generated recipe.
+/// Returns an empty string if the recipes are equal.
+pub fn diff_recipes(old: &IntermediateRecipe, new: &IntermediateRecipe) -> String {
+    let old = old.to_yaml().expect("a recipe can always be serialized");
+    let new = new.to_yaml().expect("a recipe can always be serialized");
+    TextDiff::from_lines(&old, &new)
+        .unified_diff()
+        .header("old", "new")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPE: &str = r#"
+package:
+  name: foobar
+  version: "0.1.0"
+
+requirements:
+  run:
+    - boltons >=24
+"#;
+
+    #[test]
+    fn test_diff_detects_changed_dependency() {
+        let old = IntermediateRecipe::from_yaml(RECIPE).unwrap();
+        let new =
+            IntermediateRecipe::from_yaml(&RECIPE.replace("boltons >=24", "boltons >=25")).unwrap();
+
+        let diff = diff_recipes(&old, &new);
+        assert!(diff.starts_with("--- old\n+++ new\n"), "{diff}");
+        assert!(
+            diff.lines()
+                .any(|line| line.starts_with('-') && line.contains("boltons >=24")),
+            "{diff}"
+        );
+        assert!(
+            diff.lines()
+                .any(|line| line.starts_with('+') && line.contains("boltons >=25")),
+            "{diff}"
+        );
+    }
+
+    #[test]
+    fn test_diff_of_equal_recipes_is_empty() {
+        let recipe = IntermediateRecipe::from_yaml(RECIPE).unwrap();
+        assert_eq!(diff_recipes(&recipe, &recipe), "");
+    }
+}
@@ -0,0 +1,105 @@
+use std::{future::Future, path::Path};
+
+use miette::IntoDiagnostic;
+
+/// The maximum number of times a build is retried, regardless of the value
+/// that was configured by the user.
+pub const MAX_BUILD_RETRIES: u32 = 5;
+
+/// Runs the given build function and retries it up to `retries` times (capped
+/// at [`MAX_BUILD_RETRIES`]) if it fails.
+///
+/// Between attempts the `work_dir` is removed so that every attempt starts
+/// from a clean state. Only the build itself should be wrapped by this
+/// function, errors that occur while parsing the recipe or the configuration
+/// are deterministic and retrying them is pointless.
+pub async fn run_with_build_retries<T, F, Fut>(
+    work_dir: &Path,
+    retries: Option<u32>,
+    mut build: F,
+) -> miette::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = miette::Result<T>>,
+{
+    let max_retries = retries.unwrap_or_default().min(MAX_BUILD_RETRIES);
+    let mut attempt = 0;
+    loop {
+        match build().await {
+            Ok(result) => return Ok(result),
+            Err(err) if attempt < max_retries => {
+                attempt += 1;
+                tracing::warn!("build failed, retrying ({attempt}/{max_retries}): {err}");
+                if work_dir.exists() {
+                    fs_err::remove_dir_all(work_dir).into_diagnostic()?;
+                }
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_build_is_retried_after_failure() {
+        let work_dir = tempfile::tempdir().unwrap();
+        fs_err::write(work_dir.path().join("leftover.txt"), "stale").unwrap();
+
+        let attempts = Cell::new(0);
+        let result = run_with_build_retries(work_dir.path(), Some(2), || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move {
+                if attempt == 1 {
+                    miette::bail!("transient failure")
+                }
+                Ok(attempt)
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(result, 2);
+        assert_eq!(attempts.get(), 2);
+        assert!(
+            !work_dir.path().join("leftover.txt").exists(),
+            "the work directory should be cleaned between attempts"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_is_not_retried_by_default() {
+        let work_dir = tempfile::tempdir().unwrap();
+
+        let attempts = Cell::new(0);
+        let result: miette::Result<()> = run_with_build_retries(work_dir.path(), None, || {
+            attempts.set(attempts.get() + 1);
+            async { miette::bail!("failure") }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_build_retries_are_capped() {
+        let work_dir = tempfile::tempdir().unwrap();
+
+        let attempts = Cell::new(0);
+        let result: miette::Result<()> =
+            run_with_build_retries(work_dir.path(), Some(u32::MAX), || {
+                attempts.set(attempts.get() + 1);
+                async { miette::bail!("failure") }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), MAX_BUILD_RETRIES + 1);
+    }
+}
@@ -0,0 +1,172 @@
+use std::{io::ErrorKind, path::Path};
+
+use miette::{Context, IntoDiagnostic};
+use ordermap::OrderMap;
+use pixi_build_types::TargetSelectorV1;
+
+/// The name of the file next to the manifest that contains backend
+/// configuration, in the same format as `[package.build.configuration]`.
+/// Platform specific configuration goes in `[target.<selector>]` tables, like
+/// `[package.build.target.<selector>.configuration]` in the manifest.
+pub const SIDECAR_CONFIG_FILE_NAME: &str = "pixi-build.toml";
+
+/// Merges the configuration of the `pixi-build.toml` file in `manifest_dir`, if
+/// any, under the configuration and the target configuration that were sent by
+/// the frontend. Tables are merged recursively, for all other values the
+/// frontend takes precedence.
+pub fn with_sidecar_config(
+    manifest_dir: &Path,
+    config: serde_json::Value,
+    target_config: OrderMap<TargetSelectorV1, serde_json::Value>,
+) -> miette::Result<(
+    serde_json::Value,
+    OrderMap<TargetSelectorV1, serde_json::Value>,
+)> {
+    let path = manifest_dir.join(SIDECAR_CONFIG_FILE_NAME);
+    let contents = match fs_err::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok((config, target_config)),
+        Err(err) => return Err(err).into_diagnostic(),
+    };
+    let mut sidecar_config = toml_edit::de::from_str::<serde_json::Value>(&contents)
+        .into_diagnostic()
+        .with_context(|| format!("failed to parse '{}'", path.display()))?;
+
+    // The target tables are merged under the target configuration of the
+    // frontend, they are not part of the base configuration.
+    let sidecar_targets = match sidecar_config.as_object_mut() {
+        Some(sidecar_config) => sidecar_config.remove("target"),
+        None => None,
+    };
+    let mut merged_target_config = OrderMap::new();
+    if let Some(sidecar_targets) = sidecar_targets {
+        let serde_json::Value::Object(sidecar_targets) = sidecar_targets else {
+            miette::bail!(
+                "failed to parse '{}': `target` must be a table",
+                path.display()
+            );
+        };
+        for (selector, config) in sidecar_targets {
+            let selector =
+                serde_json::from_value::<TargetSelectorV1>(serde_json::Value::String(selector))
+                    .into_diagnostic()
+                    .with_context(|| format!("failed to parse '{}'", path.display()))?;
+            merged_target_config.insert(selector, config);
+        }
+    }
+    for (selector, config) in target_config {
+        match merged_target_config.get_mut(&selector) {
+            Some(sidecar_config) => merge_config(sidecar_config, config),
+            None => {
+                merged_target_config.insert(selector, config);
+            }
+        }
+    }
+
+    merge_config(&mut sidecar_config, config);
+    Ok((sidecar_config, merged_target_config))
+}
+
+/// Merges `overrides` into `base`, values in `overrides` take precedence.
+fn merge_config(base: &mut serde_json::Value, overrides: serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge_config(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        // A frontend without configuration sends `null`.
+        (_, serde_json::Value::Null) => {}
+        (base, overrides) => *base = overrides,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_sidecar_config_is_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(
+            dir.path().join(SIDECAR_CONFIG_FILE_NAME),
+            r#"
+debug-dir = "/tmp/debug"
+env = { SIDECAR_VAR = "sidecar", SHARED_VAR = "sidecar" }
+"#,
+        )
+        .unwrap();
+
+        let (config, target_config) = with_sidecar_config(
+            dir.path(),
+            json!({ "env": { "SHARED_VAR": "frontend" }, "noarch": false }),
+            OrderMap::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            config,
+            json!({
+                "debug-dir": "/tmp/debug",
+                "env": { "SIDECAR_VAR": "sidecar", "SHARED_VAR": "frontend" },
+                "noarch": false,
+            })
+        );
+        assert!(target_config.is_empty());
+    }
+
+    #[test]
+    fn test_sidecar_target_config_is_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(
+            dir.path().join(SIDECAR_CONFIG_FILE_NAME),
+            r#"
+noarch = false
+
+[target.linux-64]
+env = { SIDECAR_VAR = "sidecar", SHARED_VAR = "sidecar" }
+
+[target.win-64]
+env = { WIN_VAR = "sidecar" }
+"#,
+        )
+        .unwrap();
+
+        let linux_64 = TargetSelectorV1::Platform(String::from("linux-64"));
+        let win_64 = TargetSelectorV1::Platform(String::from("win-64"));
+        let (config, target_config) = with_sidecar_config(
+            dir.path(),
+            json!({}),
+            OrderMap::from_iter([(
+                linux_64.clone(),
+                json!({ "env": { "SHARED_VAR": "frontend" } }),
+            )]),
+        )
+        .unwrap();
+        assert_eq!(config, json!({ "noarch": false }));
+        assert_eq!(
+            target_config.get(&linux_64),
+            Some(&json!({ "env": { "SIDECAR_VAR": "sidecar", "SHARED_VAR": "frontend" } }))
+        );
+        assert_eq!(
+            target_config.get(&win_64),
+            Some(&json!({ "env": { "WIN_VAR": "sidecar" } }))
+        );
+    }
+
+    #[test]
+    fn test_without_sidecar_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = json!({ "noarch": false });
+        let (merged, target_config) =
+            with_sidecar_config(dir.path(), config.clone(), OrderMap::new()).unwrap();
+        assert_eq!(merged, config);
+        assert!(target_config.is_empty());
+    }
+}
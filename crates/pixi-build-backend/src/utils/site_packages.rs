@@ -0,0 +1,44 @@
+use rattler_conda_types::Platform;
+
+/// Returns the location of the `site-packages` directory of python, relative
+/// to the prefix, e.g. `lib/python3.12/site-packages`. The `python_version` is
+/// the value of the `python` variant, e.g. `3.12` or `3.12.* *_cpython`.
+///
+/// Returns `None` if the major and minor version cannot be determined.
+pub fn python_site_packages_path(python_version: &str, platform: Platform) -> Option<String> {
+    let version = python_version.split_whitespace().next()?;
+    let mut components = version.split('.');
+    let is_number =
+        |component: &&str| !component.is_empty() && component.chars().all(|c| c.is_ascii_digit());
+    let major = components.next().filter(is_number)?;
+    let minor = components.next().filter(is_number)?;
+
+    if platform.is_windows() {
+        Some(String::from("Lib/site-packages"))
+    } else {
+        Some(format!("lib/python{major}.{minor}/site-packages"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_python_site_packages_path() {
+        assert_eq!(
+            python_site_packages_path("3.12", Platform::Linux64).as_deref(),
+            Some("lib/python3.12/site-packages")
+        );
+        assert_eq!(
+            python_site_packages_path("3.11.* *_cpython", Platform::OsxArm64).as_deref(),
+            Some("lib/python3.11/site-packages")
+        );
+        assert_eq!(
+            python_site_packages_path("3.12", Platform::Win64).as_deref(),
+            Some("Lib/site-packages")
+        );
+        assert_eq!(python_site_packages_path("3", Platform::Linux64), None);
+        assert_eq!(python_site_packages_path("*", Platform::Linux64), None);
+    }
+}
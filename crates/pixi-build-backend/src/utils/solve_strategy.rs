@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// The strategy that selects the versions of the packages when the build and
+/// host environments are solved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SolveStrategy {
+    /// Selects the highest compatible version of every package.
+    #[default]
+    Highest,
+    /// Selects the lowest compatible version of every package.
+    Lowest,
+    /// Selects the lowest compatible version of the direct dependencies and
+    /// the highest compatible version of all other packages.
+    LowestDirect,
+}
+
+impl From<SolveStrategy> for rattler_solve::SolveStrategy {
+    fn from(strategy: SolveStrategy) -> Self {
+        match strategy {
+            SolveStrategy::Highest => rattler_solve::SolveStrategy::Highest,
+            SolveStrategy::Lowest => rattler_solve::SolveStrategy::LowestVersion,
+            SolveStrategy::LowestDirect => rattler_solve::SolveStrategy::LowestVersionDirect,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_solve_strategy() {
+        let strategy: SolveStrategy = serde_json::from_str("\"lowest-direct\"").unwrap();
+        assert_eq!(strategy, SolveStrategy::LowestDirect);
+        assert!(matches!(
+            rattler_solve::SolveStrategy::from(strategy),
+            rattler_solve::SolveStrategy::LowestVersionDirect
+        ));
+
+        let err = serde_json::from_str::<SolveStrategy>("\"newest\"").unwrap_err();
+        assert!(
+            err.to_string().contains("unknown variant `newest`"),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use rattler_conda_types::{NoArchType, Platform};
+
+/// Returns the subdir that an output is placed in. Noarch outputs, both
+/// `noarch: python` and `noarch: generic`, always belong to the `noarch`
+/// subdir. Other outputs belong to the subdir of the `target_platform`.
+pub fn output_subdir(noarch: &NoArchType, target_platform: Platform) -> Platform {
+    if noarch.is_none() {
+        target_platform
+    } else {
+        Platform::NoArch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noarch_subdir() {
+        assert_eq!(
+            output_subdir(&NoArchType::python(), Platform::Linux64),
+            Platform::NoArch
+        );
+        assert_eq!(
+            output_subdir(&NoArchType::generic(), Platform::Win64),
+            Platform::NoArch
+        );
+        assert_eq!(
+            output_subdir(&NoArchType::none(), Platform::OsxArm64),
+            Platform::OsxArm64
+        );
+    }
+}
@@ -2,7 +2,7 @@ use miette::{IntoDiagnostic, WrapErr};
 use rattler_build::metadata::Output;
 use std::future::Future;
 use std::io::BufWriter;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// A helper struct that owns a temporary file containing a rendered recipe.
 /// If `finish` is not called, the temporary file will stay on disk for
@@ -12,16 +12,19 @@ pub struct TemporaryRenderedRecipe {
 }
 
 impl TemporaryRenderedRecipe {
-    pub fn from_output(output: &Output) -> miette::Result<Self> {
-        // Ensure that the output directory exists
-        std::fs::create_dir_all(&output.build_configuration.directories.output_dir)
+    /// Writes the rendered recipe of `output` to a temporary file in
+    /// `temp_dir`, or in the output directory of the build if not set.
+    pub fn from_output(output: &Output, temp_dir: Option<&Path>) -> miette::Result<Self> {
+        // Ensure that the directory of the temporary file exists
+        let temp_dir = temp_dir.unwrap_or(&output.build_configuration.directories.output_dir);
+        std::fs::create_dir_all(temp_dir)
             .into_diagnostic()
-            .context("failed to create output directory")?;
+            .context("failed to create the directory for the rendered recipe")?;
 
         let (recipe_file, recipe_path) = tempfile::Builder::new()
             .prefix(".rendered-recipe")
             .suffix(".yaml")
-            .tempfile_in(&output.build_configuration.directories.output_dir)
+            .tempfile_in(temp_dir)
             .into_diagnostic()
             .context("failed to create temporary file for recipe")?
             .into_parts();
@@ -1,4 +1,8 @@
-use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use pixi_build_types::procedures::{
     conda_outputs::{CondaOutputsParams, CondaOutputsResult},
@@ -9,7 +13,8 @@ use rattler_conda_types::Platform;
 use serde_json::Value;
 
 use crate::{
-    generated_recipe::GenerateRecipe, intermediate_backend::IntermediateBackendInstantiator,
+    generated_recipe::GenerateRecipe,
+    intermediate_backend::{IntermediateBackend, IntermediateBackendInstantiator},
     protocol::ProtocolInstantiator,
 };
 
@@ -52,6 +57,28 @@ pub fn intermediate_conda_outputs_snapshot<T>(
     host_platform: Platform,
     variant_configuration: Option<BTreeMap<String, Vec<String>>>,
 ) -> String
+where
+    T: GenerateRecipe + Default + Clone + Send + Sync + 'static,
+    <T as GenerateRecipe>::Config: Send + Sync + 'static,
+{
+    conda_outputs_snapshot(intermediate_conda_outputs::<T>(
+        project_model,
+        source_dir,
+        host_platform,
+        variant_configuration,
+        None,
+    ))
+}
+
+/// Calls the `conda/outputs` procedure of the `IntermediateBackend` for the
+/// given recipe generator, project model and backend configuration.
+pub fn intermediate_conda_outputs<T>(
+    project_model: Option<pixi_build_types::ProjectModelV1>,
+    source_dir: Option<PathBuf>,
+    host_platform: Platform,
+    variant_configuration: Option<BTreeMap<String, Vec<String>>>,
+    configuration: Option<Value>,
+) -> CondaOutputsResult
 where
     T: GenerateRecipe + Default + Clone + Send + Sync + 'static,
     <T as GenerateRecipe>::Config: Send + Sync + 'static,
@@ -73,7 +100,7 @@ where
             source_dir,
             manifest_path,
             project_model: project_model.map(Into::into),
-            configuration: None,
+            configuration,
             target_configuration: None,
             cache_directory: None,
         })
@@ -81,7 +108,7 @@ where
         .unwrap();
 
         let current_dir = std::env::current_dir().unwrap();
-        let result = protocol
+        protocol
             .conda_outputs(CondaOutputsParams {
                 channels: vec![],
                 host_platform,
@@ -90,12 +117,38 @@ where
                 work_directory: current_dir,
             })
             .await
-            .unwrap();
-
-        conda_outputs_snapshot(result)
+            .unwrap()
     })
 }
 
+/// Creates an `IntermediateBackend` for a minimal project with a `pixi.toml`
+/// in `manifest_dir` and returns the path of the manifest that the backend
+/// located, or the error if the expected manifest is missing.
+pub fn backend_manifest_path<T>(
+    manifest_dir: &Path,
+    configuration: Value,
+) -> miette::Result<Option<PathBuf>>
+where
+    T: GenerateRecipe + Default,
+{
+    let project_model = serde_json::from_value(serde_json::json!({
+        "name": "foobar",
+        "version": "0.1.0",
+    }))
+    .unwrap();
+    let backend = IntermediateBackend::<T>::new(
+        manifest_dir.join("pixi.toml"),
+        None,
+        project_model,
+        Arc::new(T::default()),
+        configuration,
+        Default::default(),
+        LoggingOutputHandler::default(),
+        None,
+    )?;
+    Ok(backend.backend_manifest_path())
+}
+
 /// A function to convert a `CondaOutputsResult` into a pretty-printed JSON
 /// string.
 pub fn conda_outputs_snapshot(result: CondaOutputsResult) -> String {
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+/// How a version that is read by a metadata provider, e.g. from a
+/// `Cargo.toml` or a `pyproject.toml`, is converted to the version of the
+/// recipe.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionNormalization {
+    /// The version is used as it is written in the manifest.
+    #[default]
+    SemverPassthrough,
+    /// Semver pre-release identifiers are appended to the release without
+    /// separators, e.g. `1.0.0-alpha.1` becomes `1.0.0a1`, and build metadata
+    /// is dropped. This sorts pre-releases before their release. Versions
+    /// with a numeric identifier that does not follow a tag, e.g. `1.0.0-1`,
+    /// are kept as they are.
+    Conda,
+    /// The version is normalized according to PEP 440, e.g. `1.0-Alpha.1`
+    /// becomes `1.0a1` and `1.0-r2` becomes `1.0.post2`.
+    Pep440,
+}
+
+impl VersionNormalization {
+    /// Normalizes the given version. Versions that cannot be normalized are
+    /// returned unchanged.
+    pub fn normalize(self, version: &str) -> String {
+        let normalized = match self {
+            VersionNormalization::SemverPassthrough => None,
+            VersionNormalization::Conda => normalize_conda(version),
+            VersionNormalization::Pep440 => normalize_pep440(version),
+        };
+        normalized.unwrap_or_else(|| version.to_string())
+    }
+}
+
+/// Strips the optional `v` prefix of a version, e.g. `v1.0.0`.
+fn strip_v_prefix(version: &str) -> &str {
+    version
+        .strip_prefix(['v', 'V'])
+        .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        .unwrap_or(version)
+}
+
+/// Returns the PEP 440 spelling of a pre-release, post-release or
+/// development release tag.
+fn pep440_tag(tag: &str) -> Option<&'static str> {
+    match tag {
+        "a" | "alpha" => Some("a"),
+        "b" | "beta" => Some("b"),
+        "c" | "rc" | "pre" | "preview" => Some("rc"),
+        "post" | "rev" | "r" => Some(".post"),
+        "dev" => Some(".dev"),
+        _ => None,
+    }
+}
+
+fn normalize_conda(version: &str) -> Option<String> {
+    let version = strip_v_prefix(version.trim());
+    let version = version
+        .split_once('+')
+        .map_or(version, |(version, _)| version);
+    let Some((release, pre_release)) = version.split_once('-') else {
+        return Some(version.to_string());
+    };
+    if release.is_empty() || pre_release.is_empty() {
+        return None;
+    }
+
+    // A number is only unambiguous directly after a tag, `1.0.0-1` would
+    // otherwise become `1.0.01` and `1.0.0-rc1.2` would become `1.0.0rc12`.
+    let mut normalized = release.to_string();
+    let mut after_tag = false;
+    for identifier in pre_release.split('.') {
+        let identifier = identifier.to_ascii_lowercase();
+        if identifier.chars().all(|c| c.is_ascii_digit()) && !after_tag {
+            return None;
+        }
+        match identifier.as_str() {
+            "alpha" => normalized.push('a'),
+            "beta" => normalized.push('b'),
+            _ => normalized.extend(identifier.chars().filter(char::is_ascii_alphanumeric)),
+        }
+        after_tag = normalized.ends_with(|c: char| c.is_ascii_alphabetic());
+    }
+    Some(normalized)
+}
+
+fn normalize_pep440(version: &str) -> Option<String> {
+    let version = strip_v_prefix(version.trim()).to_ascii_lowercase();
+    let (public, local) = match version.split_once('+') {
+        Some((public, local)) => (public, Some(local)),
+        None => (version.as_str(), None),
+    };
+
+    // Split the public version into runs of digits and runs of letters, the
+    // separators between them are not significant.
+    let mut tokens = Vec::new();
+    let mut chars = public.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if matches!(c, '.' | '-' | '_') {
+            continue;
+        }
+        let is_digit = c.is_ascii_digit();
+        if !is_digit && !c.is_ascii_alphabetic() {
+            return None;
+        }
+        let mut end = start + c.len_utf8();
+        while let Some(&(index, next)) = chars.peek() {
+            if next.is_ascii_digit() != is_digit || !next.is_ascii_alphanumeric() {
+                break;
+            }
+            end = index + next.len_utf8();
+            chars.next();
+        }
+        tokens.push((is_digit, &public[start..end]));
+    }
+
+    let mut tokens = tokens.into_iter().peekable();
+    let mut release = Vec::new();
+    while let Some((_, number)) = tokens.next_if(|(is_digit, _)| *is_digit) {
+        release.push(number.parse::<u64>().ok()?.to_string());
+    }
+    if release.is_empty() {
+        return None;
+    }
+
+    let mut normalized = release.join(".");
+    while let Some((_, tag)) = tokens.next() {
+        let tag = pep440_tag(tag)?;
+        let number = match tokens.next_if(|(is_digit, _)| *is_digit) {
+            Some((_, number)) => number.parse::<u64>().ok()?,
+            None => 0,
+        };
+        normalized.push_str(&format!("{tag}{number}"));
+    }
+
+    if let Some(local) = local {
+        normalized.push('+');
+        normalized.push_str(&local.replace(['-', '_'], "."));
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rattler_conda_types::Version;
+
+    use super::*;
+
+    #[test]
+    fn test_conda_normalization() {
+        let conda = VersionNormalization::Conda;
+        assert_eq!(conda.normalize("1.0.0-alpha.1"), "1.0.0a1");
+        assert_eq!(conda.normalize("2.1.0-beta.3+build.5"), "2.1.0b3");
+        assert_eq!(conda.normalize("v0.3.0-rc.2"), "0.3.0rc2");
+        assert_eq!(conda.normalize("1.2.3"), "1.2.3");
+        // Numbers that do not follow a tag are not concatenated.
+        assert_eq!(conda.normalize("1.0.0-1"), "1.0.0-1");
+        assert_eq!(conda.normalize("1.0.0-rc1.2"), "1.0.0-rc1.2");
+    }
+
+    #[test]
+    fn test_conda_normalization_sorts_pre_releases_first() {
+        let conda = VersionNormalization::Conda;
+        let alpha = Version::from_str(&conda.normalize("1.0.0-alpha.1")).unwrap();
+        let beta = Version::from_str(&conda.normalize("1.0.0-beta.1")).unwrap();
+        let release = Version::from_str(&conda.normalize("1.0.0")).unwrap();
+        assert!(alpha < beta);
+        assert!(beta < release);
+    }
+
+    #[test]
+    fn test_pep440_normalization() {
+        let pep440 = VersionNormalization::Pep440;
+        assert_eq!(pep440.normalize("1.0-Alpha.1"), "1.0a1");
+        assert_eq!(pep440.normalize("1.0.0-r2"), "1.0.0.post2");
+        assert_eq!(pep440.normalize("v2.0.dev"), "2.0.dev0");
+        assert_eq!(pep440.normalize("1.01+ubuntu-1"), "1.1+ubuntu.1");
+        // Versions that are not PEP 440 compatible are kept.
+        assert_eq!(pep440.normalize("1.0-snapshot"), "1.0-snapshot");
+    }
+
+    #[test]
+    fn test_semver_passthrough_keeps_version() {
+        let passthrough = VersionNormalization::default();
+        assert_eq!(passthrough.normalize("1.0.0-alpha.1"), "1.0.0-alpha.1");
+    }
+}
@@ -4,11 +4,15 @@ use crate::common::model::{convert_test_model_to_project_model_v1, load_project_
 use imp::TestGenerateRecipe;
 use pixi_build_backend::{intermediate_backend::IntermediateBackend, protocol::Protocol};
 use pixi_build_types::{
-    ChannelConfiguration, PlatformAndVirtualPackages,
-    procedures::{conda_build_v0::CondaBuildParams, conda_metadata::CondaMetadataParams},
+    ChannelConfiguration, PackageSpecV1, PlatformAndVirtualPackages,
+    procedures::{
+        conda_build_v0::CondaBuildParams, conda_metadata::CondaMetadataParams,
+        conda_outputs::CondaOutputsParams,
+    },
 };
 use rattler_build::console_utils::LoggingOutputHandler;
 use rattler_conda_types::Platform;
+use recipe_stage0::recipe::IntermediateRecipe;
 use serde_json::json;
 use tempfile::TempDir;
 use url::Url;
@@ -16,8 +20,12 @@ use url::Url;
 #[cfg(test)]
 mod imp {
     use miette::IntoDiagnostic;
-    use pixi_build_backend::generated_recipe::{
-        BackendConfig, DefaultMetadataProvider, GenerateRecipe, GeneratedRecipe, PythonParams,
+    use pixi_build_backend::{
+        generated_recipe::{
+            BackendConfig, BackendIdentifier, BuildParams, DefaultMetadataProvider, GenerateRecipe,
+            GeneratedRecipe,
+        },
+        utils::SolveStrategy,
     };
     use serde::{Deserialize, Serialize};
     use std::{
@@ -30,6 +38,18 @@ mod imp {
     pub struct TestBackendConfig {
         /// If set, internal state will be logged as files in that directory
         pub debug_dir: Option<PathBuf>,
+        #[serde(default)]
+        pub build_only: Vec<String>,
+        #[serde(default)]
+        pub inherit_run_exports: Vec<String>,
+        #[serde(default)]
+        pub deny_warnings: bool,
+        #[serde(default)]
+        pub debug_resolved_recipes: bool,
+        #[serde(default)]
+        pub store_recipe: bool,
+        pub temp_dir: Option<PathBuf>,
+        pub solve_strategy: Option<SolveStrategy>,
     }
 
     #[cfg(test)]
@@ -41,6 +61,26 @@ mod imp {
             self.debug_dir.as_deref()
         }
 
+        fn deny_warnings(&self) -> bool {
+            self.deny_warnings
+        }
+
+        fn debug_resolved_recipes(&self) -> bool {
+            self.debug_resolved_recipes
+        }
+
+        fn store_recipe(&self) -> bool {
+            self.store_recipe
+        }
+
+        fn temp_dir(&self) -> Option<&Path> {
+            self.temp_dir.as_deref()
+        }
+
+        fn solve_strategy(&self) -> SolveStrategy {
+            self.solve_strategy.unwrap_or_default()
+        }
+
         fn merge_with_target_config(&self, target_config: &Self) -> miette::Result<Self> {
             if target_config.debug_dir.is_some() {
                 miette::bail!("`debug_dir` cannot have a target specific value");
@@ -48,6 +88,14 @@ mod imp {
 
             Ok(Self {
                 debug_dir: self.debug_dir.clone(),
+                build_only: self.build_only.clone(),
+                inherit_run_exports: self.inherit_run_exports.clone(),
+                deny_warnings: self.deny_warnings || target_config.deny_warnings,
+                debug_resolved_recipes: self.debug_resolved_recipes
+                    || target_config.debug_resolved_recipes,
+                store_recipe: self.store_recipe || target_config.store_recipe,
+                temp_dir: self.temp_dir.clone(),
+                solve_strategy: target_config.solve_strategy.or(self.solve_strategy),
             })
         }
     }
@@ -58,14 +106,25 @@ mod imp {
         fn generate_recipe(
             &self,
             model: &pixi_build_types::ProjectModelV1,
-            _config: &Self::Config,
+            config: &Self::Config,
             _manifest_path: PathBuf,
             _host_platform: rattler_conda_types::Platform,
-            _python_params: Option<PythonParams>,
+            _build_params: Option<BuildParams>,
             _variants: &HashSet<pixi_build_backend::variants::NormalizedKey>,
         ) -> miette::Result<GeneratedRecipe> {
-            GeneratedRecipe::from_model(model.clone(), &mut DefaultMetadataProvider)
-                .into_diagnostic()
+            let mut recipe =
+                GeneratedRecipe::from_model(model.clone(), &mut DefaultMetadataProvider)
+                    .into_diagnostic()?;
+            recipe.move_to_build_requirements(&config.build_only)?;
+            recipe.inherit_run_exports(&config.inherit_run_exports)?;
+            Ok(recipe)
+        }
+
+        fn backend_identifier(&self) -> Option<BackendIdentifier> {
+            Some(BackendIdentifier {
+                name: "pixi-build-test",
+                version: "0.1.0",
+            })
         }
     }
 }
@@ -199,3 +258,449 @@ async fn test_conda_build() {
         ".packages[0].subdir" => "[redacted]",
     });
 }
+
+#[tokio::test]
+async fn test_store_recipe() {
+    let tmp_dir = TempDir::new().unwrap();
+    let project_model_v1 = convert_test_model_to_project_model_v1(load_project_model_from_json(
+        "minimal_project_model_for_build.json",
+    ));
+    let backend =
+        test_backend_with_config(&tmp_dir, project_model_v1, json!({ "store-recipe": true }));
+
+    let build_params = CondaBuildParams {
+        build_platform_virtual_packages: None,
+        host_platform: None,
+        channel_base_urls: Some(vec![Url::parse("https://prefix.dev/conda-forge").unwrap()]),
+        channel_configuration: ChannelConfiguration {
+            base_url: Url::parse("https://prefix.dev").unwrap(),
+        },
+        outputs: None,
+        variant_configuration: None,
+        work_directory: tmp_dir.path().join("build"),
+        editable: false,
+    };
+    let conda_build_result = backend.conda_build_v0(build_params).await.unwrap();
+
+    // The rendered recipe is part of the package.
+    let package_dir = tmp_dir.path().join("package");
+    rattler_package_streaming::fs::extract(
+        &conda_build_result.packages[0].output_file,
+        &package_dir,
+    )
+    .unwrap();
+    assert!(package_dir.join("info/recipe/recipe.yaml").is_file());
+}
+
+#[tokio::test]
+async fn test_provenance_is_recorded_in_package() {
+    let tmp_dir = TempDir::new().unwrap();
+    let project_model_v1 = convert_test_model_to_project_model_v1(load_project_model_from_json(
+        "minimal_project_model_for_build.json",
+    ));
+    let backend = test_backend(&tmp_dir, project_model_v1);
+
+    let build_params = CondaBuildParams {
+        build_platform_virtual_packages: None,
+        host_platform: None,
+        channel_base_urls: Some(vec![Url::parse("https://prefix.dev/conda-forge").unwrap()]),
+        channel_configuration: ChannelConfiguration {
+            base_url: Url::parse("https://prefix.dev").unwrap(),
+        },
+        outputs: None,
+        variant_configuration: None,
+        work_directory: tmp_dir.path().join("build"),
+        editable: false,
+    };
+    let conda_build_result = backend.conda_build_v0(build_params).await.unwrap();
+
+    // The backend that built the package is part of its extra metadata.
+    let package_dir = tmp_dir.path().join("package");
+    rattler_package_streaming::fs::extract(
+        &conda_build_result.packages[0].output_file,
+        &package_dir,
+    )
+    .unwrap();
+    let about: serde_json::Value =
+        serde_json::from_str(&fs_err::read_to_string(package_dir.join("info/about.json")).unwrap())
+            .unwrap();
+    let provenance = &about["extra"][pixi_build_backend::utils::PROVENANCE_KEY];
+    assert_eq!(provenance["backend"], "pixi-build-test");
+    assert_eq!(provenance["backend_version"], "0.1.0");
+}
+
+#[tokio::test]
+async fn test_solve_strategy_reaches_build_configuration() {
+    let tmp_dir = TempDir::new().unwrap();
+    let project_model_v1 = convert_test_model_to_project_model_v1(load_project_model_from_json(
+        "minimal_project_model_for_build.json",
+    ));
+    let backend = test_backend_with_config(
+        &tmp_dir,
+        project_model_v1,
+        json!({ "store-recipe": true, "solve-strategy": "lowest-direct" }),
+    );
+
+    let build_params = CondaBuildParams {
+        build_platform_virtual_packages: None,
+        host_platform: None,
+        channel_base_urls: Some(vec![Url::parse("https://prefix.dev/conda-forge").unwrap()]),
+        channel_configuration: ChannelConfiguration {
+            base_url: Url::parse("https://prefix.dev").unwrap(),
+        },
+        outputs: None,
+        variant_configuration: None,
+        work_directory: tmp_dir.path().join("build"),
+        editable: false,
+    };
+    let conda_build_result = backend.conda_build_v0(build_params).await.unwrap();
+
+    // The rendered recipe in the package records the build configuration
+    // that was used to build it.
+    let package_dir = tmp_dir.path().join("package");
+    rattler_package_streaming::fs::extract(
+        &conda_build_result.packages[0].output_file,
+        &package_dir,
+    )
+    .unwrap();
+    let rendered_recipe: serde_yaml::Value = serde_yaml::from_str(
+        &fs_err::read_to_string(package_dir.join("info/recipe/rendered_recipe.yaml")).unwrap(),
+    )
+    .unwrap();
+    let solve_strategy: rattler_solve::SolveStrategy =
+        serde_yaml::from_value(rendered_recipe["build_configuration"]["solve_strategy"].clone())
+            .unwrap();
+    assert!(matches!(
+        solve_strategy,
+        rattler_solve::SolveStrategy::LowestVersionDirect
+    ));
+}
+
+#[tokio::test]
+async fn test_temp_dir() {
+    let tmp_dir = TempDir::new().unwrap();
+    let temp_dir = tmp_dir.path().join("rendered");
+    let project_model_v1: pixi_build_types::ProjectModelV1 = serde_json::from_value(json!({
+        "name": "foobar",
+        "version": "0.1.0",
+        "targets": {
+            "defaultTarget": {
+                "hostDependencies": {
+                    "this-package-does-not-exist": { "binary": { "version": "*" } }
+                }
+            }
+        }
+    }))
+    .unwrap();
+    let backend =
+        test_backend_with_config(&tmp_dir, project_model_v1, json!({ "temp-dir": temp_dir }));
+
+    let build_params = CondaBuildParams {
+        build_platform_virtual_packages: None,
+        host_platform: None,
+        channel_base_urls: Some(vec![Url::parse("https://prefix.dev/conda-forge").unwrap()]),
+        channel_configuration: ChannelConfiguration {
+            base_url: Url::parse("https://prefix.dev").unwrap(),
+        },
+        outputs: None,
+        variant_configuration: None,
+        work_directory: tmp_dir.path().join("build"),
+        editable: false,
+    };
+    backend.conda_build_v0(build_params).await.unwrap_err();
+
+    // The host environment cannot be solved, so the rendered recipe is kept
+    // in the configured directory for debugging.
+    let rendered_recipes = fs_err::read_dir(&temp_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(rendered_recipes.len(), 1);
+    assert!(rendered_recipes[0].starts_with(".rendered-recipe"));
+}
+
+fn test_backend(
+    tmp_dir: &TempDir,
+    project_model_v1: pixi_build_types::ProjectModelV1,
+) -> IntermediateBackend<TestGenerateRecipe> {
+    test_backend_with_config(tmp_dir, project_model_v1, json!({}))
+}
+
+fn test_backend_with_config(
+    tmp_dir: &TempDir,
+    project_model_v1: pixi_build_types::ProjectModelV1,
+    config: serde_json::Value,
+) -> IntermediateBackend<TestGenerateRecipe> {
+    let pixi_manifest = tmp_dir.path().join("pixi.toml");
+    fs_err::write(&pixi_manifest, toml::to_string(&project_model_v1).unwrap()).unwrap();
+
+    IntermediateBackend::new(
+        pixi_manifest,
+        Some(tmp_dir.path().to_path_buf()),
+        project_model_v1,
+        Arc::default(),
+        config,
+        Default::default(),
+        LoggingOutputHandler::default(),
+        None,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_check_valid_project_model() {
+    let tmp_dir = TempDir::new().unwrap();
+    let project_model_v1 = convert_test_model_to_project_model_v1(load_project_model_from_json(
+        "minimal_project_model.json",
+    ));
+
+    let problems = test_backend(&tmp_dir, project_model_v1)
+        .check()
+        .await
+        .unwrap();
+    assert!(problems.is_empty(), "unexpected problems: {problems:?}");
+}
+
+#[tokio::test]
+async fn test_check_missing_version() {
+    let tmp_dir = TempDir::new().unwrap();
+    let mut project_model_v1 = convert_test_model_to_project_model_v1(
+        load_project_model_from_json("minimal_project_model.json"),
+    );
+    project_model_v1.version = None;
+
+    let problems = test_backend(&tmp_dir, project_model_v1)
+        .check()
+        .await
+        .unwrap();
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].contains("no version defined"), "{problems:?}");
+}
+
+#[tokio::test]
+async fn test_check_missing_name() {
+    let tmp_dir = TempDir::new().unwrap();
+    let mut project_model_v1 = convert_test_model_to_project_model_v1(
+        load_project_model_from_json("minimal_project_model.json"),
+    );
+    project_model_v1.name = String::new();
+
+    let problems = test_backend(&tmp_dir, project_model_v1)
+        .check()
+        .await
+        .unwrap();
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].contains("no name defined"), "{problems:?}");
+}
+
+#[tokio::test]
+async fn test_deny_warnings() {
+    let tmp_dir = TempDir::new().unwrap();
+    let project_model_v1 = convert_test_model_to_project_model_v1(load_project_model_from_json(
+        "minimal_project_model.json",
+    ));
+
+    // Moving a package that is not a dependency to the build requirements
+    // results in a warning.
+    let problems = test_backend_with_config(
+        &tmp_dir,
+        project_model_v1.clone(),
+        json!({ "build-only": ["not-a-dependency"] }),
+    )
+    .check_recipe()
+    .await
+    .unwrap();
+    assert!(
+        !problems
+            .iter()
+            .any(|problem| problem.contains("not-a-dependency")),
+        "{problems:?}"
+    );
+
+    let err = test_backend_with_config(
+        &tmp_dir,
+        project_model_v1,
+        json!({ "build-only": ["not-a-dependency"], "deny-warnings": true }),
+    )
+    .check_recipe()
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("not-a-dependency"), "{err}");
+}
+
+#[tokio::test]
+async fn test_sidecar_config() {
+    let tmp_dir = TempDir::new().unwrap();
+    let project_model_v1 = convert_test_model_to_project_model_v1(load_project_model_from_json(
+        "minimal_project_model.json",
+    ));
+    fs_err::write(
+        tmp_dir.path().join("pixi-build.toml"),
+        "deny-warnings = true\nbuild-only = [\"ignored\"]\n",
+    )
+    .unwrap();
+
+    // The frontend configuration takes precedence over the sidecar, but
+    // `deny-warnings` is only set by the sidecar.
+    let err = test_backend_with_config(
+        &tmp_dir,
+        project_model_v1,
+        json!({ "build-only": ["not-a-dependency"] }),
+    )
+    .check_recipe()
+    .await
+    .unwrap_err();
+    assert!(err.to_string().contains("not-a-dependency"), "{err}");
+    assert!(!err.to_string().contains("ignored"), "{err}");
+}
+
+#[tokio::test]
+async fn test_sidecar_config_is_an_input() {
+    let tmp_dir = TempDir::new().unwrap();
+    let project_model_v1 = convert_test_model_to_project_model_v1(load_project_model_from_json(
+        "minimal_project_model.json",
+    ));
+
+    let platform = PlatformAndVirtualPackages {
+        platform: Platform::Linux64,
+        virtual_packages: None,
+    };
+    let result = test_backend(&tmp_dir, project_model_v1)
+        .conda_get_metadata(CondaMetadataParams {
+            build_platform: Some(platform.clone()),
+            host_platform: Some(platform),
+            channel_base_urls: Some(vec![Url::parse("https://prefix.dev/conda-forge").unwrap()]),
+            channel_configuration: ChannelConfiguration {
+                base_url: Url::parse("https://prefix.dev").unwrap(),
+            },
+            variant_configuration: None,
+            work_directory: tmp_dir.path().join("build"),
+        })
+        .await
+        .unwrap();
+
+    // Creating a sidecar later on must invalidate the metadata.
+    assert!(result.input_globs.unwrap().contains("pixi-build.toml"));
+}
+
+#[tokio::test]
+async fn test_recipe_out() {
+    let tmp_dir = TempDir::new().unwrap();
+    let project_model_v1 = convert_test_model_to_project_model_v1(load_project_model_from_json(
+        "minimal_project_model.json",
+    ));
+    let recipe_out = tmp_dir.path().join("out").join("recipe.yaml");
+
+    let mut backend = test_backend(&tmp_dir, project_model_v1);
+    backend.set_recipe_out(recipe_out.clone());
+
+    let platform = PlatformAndVirtualPackages {
+        platform: Platform::Linux64,
+        virtual_packages: None,
+    };
+    backend
+        .conda_get_metadata(CondaMetadataParams {
+            build_platform: Some(platform.clone()),
+            host_platform: Some(platform),
+            channel_base_urls: Some(vec![Url::parse("https://prefix.dev/conda-forge").unwrap()]),
+            channel_configuration: ChannelConfiguration {
+                base_url: Url::parse("https://prefix.dev").unwrap(),
+            },
+            variant_configuration: None,
+            work_directory: tmp_dir.path().join("build"),
+        })
+        .await
+        .unwrap();
+
+    let recipe: IntermediateRecipe =
+        serde_yaml::from_str(&fs_err::read_to_string(&recipe_out).unwrap()).unwrap();
+    assert_eq!(recipe.package.name.to_string(), "minimal-package");
+}
+
+#[tokio::test]
+async fn test_debug_resolved_recipes() {
+    let tmp_dir = TempDir::new().unwrap();
+    let project_model_v1 = convert_test_model_to_project_model_v1(load_project_model_from_json(
+        "minimal_project_model.json",
+    ));
+    let debug_dir = tmp_dir.path().join("debug");
+
+    let backend = test_backend_with_config(
+        &tmp_dir,
+        project_model_v1,
+        json!({ "debug-dir": debug_dir, "debug-resolved-recipes": true }),
+    );
+
+    let platform = PlatformAndVirtualPackages {
+        platform: Platform::Linux64,
+        virtual_packages: None,
+    };
+    backend
+        .conda_get_metadata(CondaMetadataParams {
+            build_platform: Some(platform.clone()),
+            host_platform: Some(platform),
+            channel_base_urls: Some(vec![Url::parse("https://prefix.dev/conda-forge").unwrap()]),
+            channel_configuration: ChannelConfiguration {
+                base_url: Url::parse("https://prefix.dev").unwrap(),
+            },
+            variant_configuration: None,
+            work_directory: tmp_dir.path().join("build"),
+        })
+        .await
+        .unwrap();
+
+    // Every output has its own resolved recipe, named after the package and
+    // the hash of its variant.
+    let resolved_recipes = fs_err::read_dir(debug_dir.join("resolved-recipes"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert_eq!(resolved_recipes.len(), 1, "{resolved_recipes:?}");
+    let file_name = resolved_recipes[0].file_name().unwrap().to_string_lossy();
+    assert!(file_name.starts_with("minimal-package-"), "{file_name}");
+    assert!(file_name.ends_with(".yaml"), "{file_name}");
+
+    let recipe: serde_yaml::Value =
+        serde_yaml::from_str(&fs_err::read_to_string(&resolved_recipes[0]).unwrap()).unwrap();
+    assert_eq!(recipe["package"]["name"], "minimal-package");
+}
+
+#[tokio::test]
+async fn test_conda_outputs_inherit_run_exports() {
+    let tmp_dir = TempDir::new().unwrap();
+    let project_model_v1: pixi_build_types::ProjectModelV1 = serde_json::from_value(json!({
+        "name": "foobar",
+        "version": "0.1.0",
+        "targets": {
+            "defaultTarget": {
+                "hostDependencies": {
+                    "zlib": { "binary": { "version": "*" } }
+                }
+            }
+        }
+    }))
+    .unwrap();
+    let backend = test_backend_with_config(
+        &tmp_dir,
+        project_model_v1,
+        json!({ "inherit-run-exports": ["zlib"] }),
+    );
+
+    let result = backend
+        .conda_outputs(CondaOutputsParams {
+            channels: vec![],
+            host_platform: Platform::Linux64,
+            build_platform: Platform::Linux64,
+            variant_configuration: None,
+            work_directory: tmp_dir.path().join("work"),
+        })
+        .await
+        .unwrap();
+
+    // The `pin_compatible` run export is reported as a dependency on the
+    // package, the pin itself is applied when the package is built.
+    let weak = &result.outputs[0].run_exports.weak;
+    assert_eq!(weak.len(), 1, "{weak:?}");
+    assert_eq!(weak[0].name, "zlib");
+    assert!(matches!(weak[0].spec, PackageSpecV1::Binary(_)));
+}
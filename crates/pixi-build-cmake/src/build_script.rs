@@ -5,11 +5,17 @@ use serde::Serialize;
 pub struct BuildScriptContext {
     pub build_platform: BuildPlatform,
     pub source_dir: String,
+    /// A subdirectory of the source that the build runs in, if set.
+    pub build_subdir: Option<String>,
     pub extra_args: Vec<String>,
     /// The package has a host dependency on Python.
     /// This is used to determine if the build script
     /// should include Python-related logic.
     pub has_host_python: bool,
+    /// Commands that are run before the generated commands.
+    pub prelude: Vec<String>,
+    /// Build with the `Debug` instead of the `Release` build type.
+    pub debug: bool,
 }
 
 #[derive(Copy, Clone, Serialize)]
@@ -47,8 +53,11 @@ mod test {
         let context = BuildScriptContext {
             build_platform,
             source_dir: String::from("my-prefix-dir"),
+            build_subdir: None,
             extra_args: extra_args.clone(),
             has_host_python,
+            prelude: vec![],
+            debug: false,
         };
         let script = context.render();
 
@@ -71,4 +80,51 @@ mod test {
             insta::assert_snapshot!(script.join("\n"));
         });
     }
+
+    #[rstest]
+    fn test_prelude(
+        #[values(BuildPlatform::Windows, BuildPlatform::Unix)] build_platform: BuildPlatform,
+    ) {
+        let context = BuildScriptContext {
+            build_platform,
+            source_dir: String::from("my-prefix-dir"),
+            build_subdir: None,
+            extra_args: vec![],
+            has_host_python: false,
+            prelude: vec![
+                String::from("echo Starting build"),
+                String::from("setup-env"),
+            ],
+            debug: false,
+        };
+        let script = context.render();
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_suffix(build_platform.to_string());
+        settings.bind(|| {
+            insta::assert_snapshot!(script.join("\n"));
+        });
+    }
+
+    #[rstest]
+    fn test_build_subdir(
+        #[values(BuildPlatform::Windows, BuildPlatform::Unix)] build_platform: BuildPlatform,
+    ) {
+        let context = BuildScriptContext {
+            build_platform,
+            source_dir: String::from("my-prefix-dir"),
+            build_subdir: Some(String::from("packages/foo")),
+            extra_args: vec![],
+            has_host_python: false,
+            prelude: vec![],
+            debug: false,
+        };
+        let script = context.render();
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_suffix(build_platform.to_string());
+        settings.bind(|| {
+            insta::assert_snapshot!(script.join("\n"));
+        });
+    }
 }
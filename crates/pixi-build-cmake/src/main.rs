@@ -2,12 +2,17 @@ mod build_script;
 mod config;
 
 use build_script::{BuildPlatform, BuildScriptContext};
-use config::CMakeBackendConfig;
+use config::{CMakeBackendConfig, DEFAULT_COMPILERS};
 use miette::IntoDiagnostic;
 use pixi_build_backend::{
-    compilers::add_compilers_and_stdlib_to_requirements,
-    generated_recipe::{DefaultMetadataProvider, GenerateRecipe, GeneratedRecipe, PythonParams},
+    compilers::{add_compilers_and_stdlib_to_requirements, with_variant_compilers},
+    error::BackendError,
+    generated_recipe::{
+        BackendConfig, BackendIdentifier, BuildParams, DefaultMetadataProvider, GenerateRecipe,
+        GeneratedRecipe,
+    },
     intermediate_backend::IntermediateBackendInstantiator,
+    utils::{BuildProfile, lfs_input_globs, scope_input_glob},
 };
 use rattler_build::{NormalizedKey, recipe::variable::Variable};
 use rattler_conda_types::{PackageName, Platform};
@@ -31,12 +36,16 @@ impl GenerateRecipe for CMakeGenerator {
         config: &Self::Config,
         manifest_root: std::path::PathBuf,
         host_platform: rattler_conda_types::Platform,
-        _python_params: Option<PythonParams>,
+        build_params: Option<BuildParams>,
         variants: &HashSet<NormalizedKey>,
     ) -> miette::Result<GeneratedRecipe> {
-        let mut generated_recipe =
-            GeneratedRecipe::from_model(model.clone(), &mut DefaultMetadataProvider)
-                .into_diagnostic()?;
+        let mut generated_recipe = GeneratedRecipe::from_model_with_default_version(
+            model.clone(),
+            &mut DefaultMetadataProvider,
+            config.fallback_version(),
+            config.version_normalization(),
+        )
+        .map_err(BackendError::from)?;
 
         // we need to add compilers
 
@@ -54,11 +63,13 @@ impl GenerateRecipe for CMakeGenerator {
         let compilers = config
             .compilers
             .clone()
-            .unwrap_or_else(|| vec!["cxx".to_string()]);
+            .unwrap_or_else(|| DEFAULT_COMPILERS.iter().map(ToString::to_string).collect());
+        let compilers = with_variant_compilers(compilers, &config.variant_compilers, variants);
 
         // Add configured compilers to build requirements
         add_compilers_and_stdlib_to_requirements(
             &compilers,
+            config.stdlibs.as_deref(),
             &mut requirements.build,
             &resolved_requirements.build,
             &host_platform,
@@ -78,6 +89,17 @@ impl GenerateRecipe for CMakeGenerator {
         // executable
         let has_host_python = resolved_requirements.contains(&PackageName::new_unchecked("python"));
 
+        let missing_subdir = config
+            .build_subdir
+            .as_ref()
+            .filter(|build_subdir| !manifest_root.join(build_subdir).is_dir());
+        if let Some(build_subdir) = missing_subdir {
+            miette::bail!(
+                "the build subdirectory '{build_subdir}' does not exist in '{}'",
+                manifest_root.display()
+            );
+        }
+
         let build_script = BuildScriptContext {
             build_platform: if Platform::current().is_windows() {
                 BuildPlatform::Windows
@@ -85,8 +107,13 @@ impl GenerateRecipe for CMakeGenerator {
                 BuildPlatform::Unix
             },
             source_dir: manifest_root.display().to_string(),
+            build_subdir: config.build_subdir.clone(),
             extra_args: config.extra_args.clone(),
             has_host_python,
+            prelude: config.script_prelude.clone(),
+            debug: build_params
+                .and_then(|params| params.profile)
+                .is_some_and(BuildProfile::is_dev),
         }
         .render();
 
@@ -95,6 +122,53 @@ impl GenerateRecipe for CMakeGenerator {
             env: config.env.clone(),
             ..Default::default()
         };
+        generated_recipe.recipe.build.merge_build_and_host_envs =
+            config.merge_build_and_host_envs.unwrap_or(false);
+        generated_recipe.add_run_constraints(&config.run_constraints)?;
+        generated_recipe.add_secrets(&config.secrets);
+        generated_recipe.move_to_build_requirements(&config.build_only)?;
+        generated_recipe.recipe.build.always_copy_files = config.always_copy_files.clone();
+        generated_recipe.recipe.build.dynamic_linking = config
+            .dynamic_linking
+            .clone()
+            .map(Into::into)
+            .unwrap_or_default();
+        generated_recipe.add_path_sources(
+            &manifest_root,
+            &config.additional_sources,
+            config.additional_sources_use_gitignore,
+            config.additional_sources_filter.as_ref(),
+        )?;
+        if config.dedupe_requirements {
+            generated_recipe.dedupe_requirements();
+        }
+        generated_recipe.add_run_exports(&config.run_exports)?;
+        generated_recipe.inherit_run_exports(&config.inherit_run_exports_from)?;
+        generated_recipe.add_context(&config.context);
+        generated_recipe.discover_license_file(&manifest_root);
+        if let Some(build_string) = &config.build_string {
+            generated_recipe.recipe.build.string = Some(build_string.parse().into_diagnostic()?);
+        }
+        if config.build_number_from_git_count {
+            generated_recipe.set_build_number_from_git_count(&manifest_root);
+        }
+        if let Some(build_number_env) = &config.build_number_env {
+            generated_recipe.set_build_number_from_env(build_number_env);
+        }
+        if let Some(license_family) = &config.license_family {
+            generated_recipe
+                .recipe
+                .about
+                .get_or_insert_with(Default::default)
+                .license_family = Some(license_family.parse().into_diagnostic()?);
+        }
+
+        // Changes to files that are tracked by git-lfs should trigger a rebuild.
+        if config.lfs_input_globs {
+            generated_recipe
+                .build_input_globs
+                .extend(lfs_input_globs(&manifest_root)?);
+        }
 
         Ok(generated_recipe)
     }
@@ -113,23 +187,93 @@ impl GenerateRecipe for CMakeGenerator {
         ]
         .iter()
         .map(|s: &&str| s.to_string())
+        .filter(|glob| !config.remove_input_globs.contains(glob))
+        .map(|glob| match &config.build_subdir {
+            Some(build_subdir) => scope_input_glob(build_subdir, &glob),
+            None => glob,
+        })
         .chain(config.extra_input_globs.clone())
         .collect()
     }
 
-    fn default_variants(&self, host_platform: Platform) -> BTreeMap<NormalizedKey, Vec<Variable>> {
+    fn default_variants(
+        &self,
+        config: &Self::Config,
+        host_platform: Platform,
+    ) -> BTreeMap<NormalizedKey, Vec<Variable>> {
         let mut variants = BTreeMap::new();
 
         if host_platform.is_windows() {
-            // Default to the Visual Studio 2019 compiler on Windows
+            // Default to the Visual Studio 2019 compiler on Windows, unless another
+            // one is configured.
             //
             // rattler-build will default to vs2017 which for most github runners is too
             // old.
-            variants.insert(NormalizedKey::from("cxx_compiler"), vec!["vs2019".into()]);
+            let cxx_compiler = config.windows_cxx_compiler.as_deref().unwrap_or("vs2019");
+            variants.insert(
+                NormalizedKey::from("cxx_compiler"),
+                vec![cxx_compiler.into()],
+            );
+        } else if host_platform.is_osx() {
+            // Apple Silicon is only supported from macOS 11 on, so the default
+            // deployment target differs from the one of Intel Macs.
+            let cxx_compiler = config.osx_cxx_compiler.as_deref().unwrap_or("clangxx");
+            let default_deployment_target = if host_platform == Platform::OsxArm64 {
+                "11.0"
+            } else {
+                "10.13"
+            };
+            let deployment_target = config
+                .macos_deployment_target
+                .as_deref()
+                .unwrap_or(default_deployment_target);
+            variants.insert(
+                NormalizedKey::from("cxx_compiler"),
+                vec![cxx_compiler.into()],
+            );
+            variants.insert(
+                NormalizedKey::from("c_stdlib_version"),
+                vec![deployment_target.into()],
+            );
+            variants.insert(
+                NormalizedKey::from("MACOSX_DEPLOYMENT_TARGET"),
+                vec![deployment_target.into()],
+            );
+        }
+
+        // Pin the CUDA version if the package is compiled with CUDA. A
+        // `cuda` variant compiler is added because of this variant.
+        let uses_cuda = config
+            .compilers
+            .iter()
+            .flatten()
+            .chain(&config.variant_compilers)
+            .any(|compiler| compiler == "cuda");
+        if let Some(cuda_version) = config.cuda_version.as_deref().filter(|_| uses_cuda) {
+            variants.insert(
+                NormalizedKey::from("cuda_compiler_version"),
+                vec![cuda_version.into()],
+            );
         }
 
         variants
     }
+
+    fn expected_manifests(&self, config: &Self::Config) -> &[&'static str] {
+        // A subdirectory brings its own `CMakeLists.txt`.
+        if config.build_subdir.is_some() {
+            &[]
+        } else {
+            &["CMakeLists.txt"]
+        }
+    }
+
+    fn backend_identifier(&self) -> Option<BackendIdentifier> {
+        Some(BackendIdentifier {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        })
+    }
 }
 
 #[tokio::main]
@@ -149,13 +293,19 @@ mod tests {
     use std::path::PathBuf;
 
     use indexmap::IndexMap;
-    use pixi_build_backend::protocol::ProtocolInstantiator;
+    use pixi_build_backend::{
+        protocol::ProtocolInstantiator,
+        utils::{DynamicLinkingConfig, test::backend_manifest_path},
+    };
     use pixi_build_types::{
-        ProjectModelV1,
-        procedures::{conda_outputs::CondaOutputsParams, initialize::InitializeParams},
+        PackageSpecV1, ProjectModelV1,
+        procedures::{
+            conda_outputs::{CondaOutputsParams, CondaOutputsResult},
+            initialize::InitializeParams,
+        },
     };
     use rattler_build::console_utils::LoggingOutputHandler;
-    use recipe_stage0::recipe::{Item, Value};
+    use recipe_stage0::recipe::{Item, LinkingCheckBehavior, Value};
 
     use super::*;
 
@@ -171,6 +321,18 @@ mod tests {
         insta::assert_debug_snapshot!(result);
     }
 
+    #[test]
+    fn test_build_subdir_scopes_input_globs() {
+        let config = CMakeBackendConfig {
+            build_subdir: Some("packages/foo".to_string()),
+            ..Default::default()
+        };
+
+        let result = CMakeGenerator::extract_input_globs_from_build(&config, PathBuf::new(), false);
+
+        insta::assert_debug_snapshot!(result);
+    }
+
     #[macro_export]
     macro_rules! project_fixture {
         ($($json:tt)+) => {
@@ -345,6 +507,8 @@ mod tests {
             "version": "0.1.0",
         });
 
+        let project_dir = cmake_project_dir();
+
         let factory = IntermediateBackendInstantiator::<CMakeGenerator>::new(
             LoggingOutputHandler::default(),
             Arc::default(),
@@ -352,7 +516,7 @@ mod tests {
         .initialize(InitializeParams {
             workspace_root: None,
             source_dir: None,
-            manifest_path: PathBuf::from("pixi.toml"),
+            manifest_path: project_dir.path().join("pixi.toml"),
             project_model: Some(project_model.into()),
             configuration: None,
             target_configuration: None,
@@ -385,6 +549,438 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_windows_cxx_compiler_override() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let project_dir = cmake_project_dir();
+
+        let factory = IntermediateBackendInstantiator::<CMakeGenerator>::new(
+            LoggingOutputHandler::default(),
+            Arc::default(),
+        )
+        .initialize(InitializeParams {
+            workspace_root: None,
+            source_dir: None,
+            manifest_path: project_dir.path().join("pixi.toml"),
+            project_model: Some(project_model.into()),
+            configuration: Some(serde_json::json!({
+                "windows-cxx-compiler": "vs2022",
+            })),
+            target_configuration: None,
+            cache_directory: None,
+        })
+        .await
+        .unwrap();
+
+        let current_dir = std::env::current_dir().unwrap();
+        let outputs = factory
+            .0
+            .conda_outputs(CondaOutputsParams {
+                channels: vec![],
+                host_platform: Platform::Win64,
+                build_platform: Platform::Win64,
+                variant_configuration: None,
+                work_directory: current_dir,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            outputs.outputs[0]
+                .metadata
+                .variant
+                .get("cxx_compiler")
+                .map(String::as_str),
+            Some("vs2022")
+        );
+    }
+
+    /// Returns a directory with the `CMakeLists.txt` that the backend expects.
+    fn cmake_project_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(dir.path().join("CMakeLists.txt"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_expected_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let err =
+            backend_manifest_path::<CMakeGenerator>(dir.path(), serde_json::json!({})).unwrap_err();
+        assert!(err.to_string().contains("`CMakeLists.txt`"), "{err}");
+
+        let dir = cmake_project_dir();
+        assert_eq!(
+            backend_manifest_path::<CMakeGenerator>(dir.path(), serde_json::json!({})).unwrap(),
+            Some(dir.path().join("CMakeLists.txt"))
+        );
+
+        // A subdirectory brings its own `CMakeLists.txt`.
+        let dir = tempfile::tempdir().unwrap();
+        let config = serde_json::json!({ "build-subdir": "packages/foo" });
+        assert_eq!(
+            backend_manifest_path::<CMakeGenerator>(dir.path(), config).unwrap(),
+            None
+        );
+    }
+
+    /// Returns the outputs of a minimal project for the given backend
+    /// configuration.
+    async fn minimal_conda_outputs(configuration: serde_json::Value) -> CondaOutputsResult {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let project_dir = cmake_project_dir();
+
+        let factory = IntermediateBackendInstantiator::<CMakeGenerator>::new(
+            LoggingOutputHandler::default(),
+            Arc::default(),
+        )
+        .initialize(InitializeParams {
+            workspace_root: None,
+            source_dir: None,
+            manifest_path: project_dir.path().join("pixi.toml"),
+            project_model: Some(project_model.into()),
+            configuration: Some(configuration),
+            target_configuration: None,
+            cache_directory: None,
+        })
+        .await
+        .unwrap();
+
+        factory
+            .0
+            .conda_outputs(CondaOutputsParams {
+                channels: vec![],
+                host_platform: Platform::Linux64,
+                build_platform: Platform::Linux64,
+                variant_configuration: None,
+                work_directory: std::env::current_dir().unwrap(),
+            })
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_default_build_string() {
+        let outputs = minimal_conda_outputs(serde_json::json!({})).await;
+
+        let build = &outputs.outputs[0].metadata.build;
+        assert!(
+            build.starts_with('h') && build.ends_with("_0"),
+            "expected a build string derived from the hash, got {build}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cmake_lists_is_an_input() {
+        let outputs = minimal_conda_outputs(serde_json::json!({})).await;
+        assert!(
+            outputs.input_globs.contains("CMakeLists.txt"),
+            "{:?}",
+            outputs.input_globs
+        );
+    }
+
+    #[tokio::test]
+    async fn test_custom_build_string() {
+        let outputs = minimal_conda_outputs(serde_json::json!({
+            "build-string": "custom",
+        }))
+        .await;
+        assert_eq!(outputs.outputs[0].metadata.build, "custom");
+
+        let outputs = minimal_conda_outputs(serde_json::json!({
+            "build-string": "${{ hash }}_custom",
+        }))
+        .await;
+        let build = &outputs.outputs[0].metadata.build;
+        assert!(
+            build.starts_with('h') && build.ends_with("_custom"),
+            "expected the hash to be rendered into the build string, got {build}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_configuration_key_is_rejected() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let project_dir = cmake_project_dir();
+
+        let result = IntermediateBackendInstantiator::<CMakeGenerator>::new(
+            LoggingOutputHandler::default(),
+            Arc::default(),
+        )
+        .initialize(InitializeParams {
+            workspace_root: None,
+            source_dir: None,
+            manifest_path: project_dir.path().join("pixi.toml"),
+            project_model: Some(project_model.into()),
+            configuration: Some(serde_json::json!({
+                "unknown-key": true,
+            })),
+            target_configuration: None,
+            cache_directory: None,
+        })
+        .await;
+
+        let error = format!(
+            "{:?}",
+            result.err().expect("unknown keys should be rejected")
+        );
+        assert!(error.contains("unknown-key"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_run_constraints_are_added_to_outputs() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let project_dir = cmake_project_dir();
+
+        let factory = IntermediateBackendInstantiator::<CMakeGenerator>::new(
+            LoggingOutputHandler::default(),
+            Arc::default(),
+        )
+        .initialize(InitializeParams {
+            workspace_root: None,
+            source_dir: None,
+            manifest_path: project_dir.path().join("pixi.toml"),
+            project_model: Some(project_model.into()),
+            configuration: Some(serde_json::json!({
+                "run-constraints": ["numpy >=1.20"],
+            })),
+            target_configuration: None,
+            cache_directory: None,
+        })
+        .await
+        .unwrap();
+
+        let current_dir = std::env::current_dir().unwrap();
+        let outputs = factory
+            .0
+            .conda_outputs(CondaOutputsParams {
+                channels: vec![],
+                host_platform: Platform::Linux64,
+                build_platform: Platform::Linux64,
+                variant_configuration: None,
+                work_directory: current_dir,
+            })
+            .await
+            .unwrap();
+
+        let constraints = &outputs.outputs[0].run_dependencies.constraints;
+        assert_eq!(constraints.len(), 1);
+        assert_eq!(constraints[0].name, "numpy");
+        assert_eq!(
+            constraints[0]
+                .spec
+                .version
+                .as_ref()
+                .map(ToString::to_string)
+                .as_deref(),
+            Some(">=1.20")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_exports_pin_the_package_itself() {
+        let outputs = minimal_conda_outputs(serde_json::json!({
+            "run-exports": {
+                "weak": ["${{ pin_subpackage('foobar', upper_bound='x.x') }}"],
+            },
+        }))
+        .await;
+
+        let weak = &outputs.outputs[0].run_exports.weak;
+        assert_eq!(weak.len(), 1);
+        assert_eq!(weak[0].name, "foobar");
+        let PackageSpecV1::Binary(spec) = &weak[0].spec else {
+            panic!("expected a binary spec, got {:?}", weak[0].spec);
+        };
+        assert_eq!(
+            spec.version.as_ref().map(ToString::to_string).as_deref(),
+            Some(">=0.1.0,<0.2.0a0")
+        );
+    }
+
+    #[test]
+    fn test_license_family() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+            "license": "Apache-2.0",
+        });
+
+        let license_family = |config: &CMakeBackendConfig| {
+            let generated_recipe = CMakeGenerator::default()
+                .generate_recipe(
+                    &project_model,
+                    config,
+                    PathBuf::from("."),
+                    Platform::Linux64,
+                    None,
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe");
+            generated_recipe
+                .recipe
+                .about
+                .and_then(|about| about.license_family)
+                .map(|family| family.to_string())
+        };
+
+        // Derived from the license by default.
+        assert_eq!(
+            license_family(&CMakeBackendConfig::default()),
+            Some("APACHE".to_string())
+        );
+
+        // The configuration takes precedence.
+        assert_eq!(
+            license_family(&CMakeBackendConfig {
+                license_family: Some("OTHER".to_string()),
+                ..Default::default()
+            }),
+            Some("OTHER".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cuda_version_variant() {
+        let cuda_variant = |compilers: Option<Vec<&str>>, cuda_version: Option<&str>| {
+            let config = CMakeBackendConfig {
+                compilers: compilers
+                    .map(|compilers| compilers.into_iter().map(String::from).collect()),
+                cuda_version: cuda_version.map(String::from),
+                ..Default::default()
+            };
+            cuda_version_variant(&config)
+        };
+
+        assert_eq!(
+            cuda_variant(Some(vec!["cxx", "cuda"]), Some("12.4")),
+            Some(vec!["12.4".to_string()])
+        );
+        assert_eq!(cuda_variant(Some(vec!["cxx"]), Some("12.4")), None);
+        assert_eq!(cuda_variant(Some(vec!["cxx", "cuda"]), None), None);
+        assert_eq!(cuda_variant(None, Some("12.4")), None);
+    }
+
+    #[test]
+    fn test_cuda_version_variant_with_variant_compilers() {
+        let config = CMakeBackendConfig {
+            variant_compilers: vec!["cuda".to_string()],
+            cuda_version: Some("12.4".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            cuda_version_variant(&config),
+            Some(vec!["12.4".to_string()])
+        );
+
+        // The pinned version makes the variant key available, so the `cuda`
+        // compiler is added.
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+        let variants = CMakeGenerator::default()
+            .default_variants(&config, Platform::Linux64)
+            .into_keys()
+            .collect::<HashSet<_>>();
+        let generated_recipe = CMakeGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &config,
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &variants,
+            )
+            .expect("Failed to generate recipe");
+        let build = generated_recipe
+            .recipe
+            .requirements
+            .build
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert!(
+            build.contains(&"${{ compiler('cuda') }}".to_string()),
+            "{build:?}"
+        );
+    }
+
+    fn cuda_version_variant(config: &CMakeBackendConfig) -> Option<Vec<String>> {
+        CMakeGenerator::default()
+            .default_variants(config, Platform::Linux64)
+            .get(&NormalizedKey::from("cuda_compiler_version"))
+            .map(|values| values.iter().map(ToString::to_string).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_osx_variants_differ_per_arch() {
+        let variant = |platform: Platform, config: &CMakeBackendConfig, key: &str| {
+            CMakeGenerator::default()
+                .default_variants(config, platform)
+                .get(&NormalizedKey::from(key))
+                .map(|values| values[0].to_string())
+        };
+        let some = |value: &str| Some(value.to_string());
+
+        let config = CMakeBackendConfig::default();
+        assert_eq!(
+            variant(Platform::Osx64, &config, "cxx_compiler"),
+            some("clangxx")
+        );
+        assert_eq!(
+            variant(Platform::OsxArm64, &config, "cxx_compiler"),
+            some("clangxx")
+        );
+        assert_eq!(
+            variant(Platform::Osx64, &config, "c_stdlib_version"),
+            some("10.13")
+        );
+        assert_eq!(
+            variant(Platform::OsxArm64, &config, "c_stdlib_version"),
+            some("11.0")
+        );
+        assert_eq!(
+            variant(Platform::OsxArm64, &config, "MACOSX_DEPLOYMENT_TARGET"),
+            some("11.0")
+        );
+        assert_eq!(
+            variant(Platform::Linux64, &config, "c_stdlib_version"),
+            None
+        );
+
+        let config = CMakeBackendConfig {
+            osx_cxx_compiler: Some("clang_bootstrap".to_string()),
+            macos_deployment_target: Some("12.0".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            variant(Platform::OsxArm64, &config, "cxx_compiler"),
+            some("clang_bootstrap")
+        );
+        assert_eq!(
+            variant(Platform::OsxArm64, &config, "c_stdlib_version"),
+            some("12.0")
+        );
+    }
+
     #[test]
     fn test_multiple_compilers_configuration() {
         let project_model = project_fixture!({
@@ -519,4 +1115,165 @@ mod tests {
             "Default stdlib should be c"
         );
     }
+
+    #[test]
+    fn test_dev_profile_builds_debug() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+        let generate = |profile| {
+            CMakeGenerator::default()
+                .generate_recipe(
+                    &project_model,
+                    &CMakeBackendConfig::default(),
+                    PathBuf::from("."),
+                    Platform::Linux64,
+                    Some(BuildParams {
+                        editable: false,
+                        profile,
+                    }),
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe")
+                .recipe
+                .build
+                .script
+                .content
+                .join("\n")
+        };
+
+        let script = generate(Some(BuildProfile::Dev));
+        assert!(script.contains("-DCMAKE_BUILD_TYPE=Debug"), "{script}");
+        assert!(script.contains("-DCMAKE_INSTALL_DO_STRIP=OFF"), "{script}");
+
+        let script = generate(None);
+        assert!(script.contains("-DCMAKE_BUILD_TYPE=Release"), "{script}");
+        assert!(!script.contains("CMAKE_INSTALL_DO_STRIP"), "{script}");
+    }
+
+    #[test]
+    fn test_variant_compilers() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+        let compiler_templates = |variants: HashSet<NormalizedKey>| {
+            let generated_recipe = CMakeGenerator::default()
+                .generate_recipe(
+                    &project_model,
+                    &CMakeBackendConfig {
+                        variant_compilers: vec!["cuda".to_string()],
+                        ..Default::default()
+                    },
+                    PathBuf::from("."),
+                    Platform::Linux64,
+                    None,
+                    &variants,
+                )
+                .expect("Failed to generate recipe");
+            generated_recipe
+                .recipe
+                .requirements
+                .build
+                .iter()
+                .filter_map(|item| match item {
+                    Item::Value(Value::Template(s)) if s.contains("compiler") => Some(s.clone()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // cuda is only added if there is a `cuda_compiler_version` variant.
+        assert_eq!(
+            compiler_templates(HashSet::new()),
+            vec!["${{ compiler('cxx') }}"]
+        );
+        let cuda = HashSet::from_iter([NormalizedKey("cuda_compiler_version".into())]);
+        assert_eq!(
+            compiler_templates(cuda),
+            vec!["${{ compiler('cxx') }}", "${{ compiler('cuda') }}"]
+        );
+    }
+
+    #[test]
+    fn test_dynamic_linking() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+        let generated_recipe = CMakeGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &CMakeBackendConfig {
+                    dynamic_linking: Some(DynamicLinkingConfig {
+                        missing_dso_allowlist: vec!["/usr/lib/libGL.so*".to_string()],
+                        overlinking_behavior: Some(LinkingCheckBehavior::Error),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let dynamic_linking = &generated_recipe.recipe.build.dynamic_linking;
+        assert_eq!(
+            dynamic_linking.missing_dso_allowlist,
+            vec!["/usr/lib/libGL.so*".to_string()]
+        );
+        assert_eq!(
+            dynamic_linking.overlinking_behavior,
+            Some(LinkingCheckBehavior::Error)
+        );
+    }
+
+    #[test]
+    fn test_error_handling_empty_name() {
+        let project_model = project_fixture!({
+            "name": "",
+            "version": "0.1.0",
+            "targets": {
+                "defaultTarget": {}
+            }
+        });
+
+        let result = CMakeGenerator::default().generate_recipe(
+            &project_model,
+            &CMakeBackendConfig::default(),
+            PathBuf::from("."),
+            Platform::Linux64,
+            None,
+            &HashSet::new(),
+        );
+
+        let error_message = result.err().unwrap().to_string();
+        assert!(error_message.contains("no name defined"));
+    }
+
+    #[test]
+    fn test_error_handling_invalid_name() {
+        let project_model = project_fixture!({
+            "name": "foo bar",
+            "version": "0.1.0",
+            "targets": {
+                "defaultTarget": {}
+            }
+        });
+
+        let result = CMakeGenerator::default().generate_recipe(
+            &project_model,
+            &CMakeBackendConfig::default(),
+            PathBuf::from("."),
+            Platform::Linux64,
+            None,
+            &HashSet::new(),
+        );
+
+        let error_message = result.err().unwrap().to_string();
+        assert!(error_message.contains("'foo bar' is not a valid package name"));
+    }
 }
@@ -0,0 +1,53 @@
+use std::process::Command;
+
+const MANIFEST: &str = r#"
+[workspace]
+channels = ["https://prefix.dev/conda-forge"]
+platforms = ["linux-64"]
+preview = ["pixi-build"]
+
+[package]
+name = "cmake-metadata"
+version = "0.1.0"
+
+[package.build]
+backend = { name = "pixi-build-cmake", version = "*" }
+
+[package.run-dependencies]
+zlib = ">=1.3"
+"#;
+
+#[test]
+fn test_emit_metadata_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest_path = dir.path().join("pixi.toml");
+    fs_err::write(&manifest_path, MANIFEST).unwrap();
+    fs_err::write(dir.path().join("CMakeLists.txt"), "").unwrap();
+    let metadata_path = dir.path().join("metadata.json");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_pixi-build-cmake"))
+        .current_dir(dir.path())
+        .arg("get-conda-metadata")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .args(["--host-platform", "linux-64"])
+        .arg("--emit-metadata-json")
+        .arg(&metadata_path)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let metadata: serde_json::Value =
+        serde_json::from_str(&fs_err::read_to_string(&metadata_path).unwrap()).unwrap();
+    let package = &metadata["packages"][0];
+    assert_eq!(package["name"], "cmake-metadata");
+    let depends = package["depends"].as_array().unwrap();
+    assert!(
+        depends.iter().any(|spec| spec == "zlib >=1.3"),
+        "{depends:?}"
+    );
+}
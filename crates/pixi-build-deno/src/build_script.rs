@@ -0,0 +1,98 @@
+use minijinja::Environment;
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct BuildScriptContext {
+    /// The location of the source
+    pub source_dir: String,
+
+    /// The module that is compiled into the executable
+    pub entry: String,
+
+    /// The name of the executable that is installed
+    pub binary_name: String,
+
+    /// The permission flags of the executable, e.g. `--allow-net`
+    pub permissions: Vec<String>,
+
+    /// Any additional args to pass to `deno compile`
+    pub extra_args: Vec<String>,
+
+    /// True if the `deno.json` defines a `build` task that has to run before
+    /// compiling.
+    pub has_build_task: bool,
+
+    /// True if the package is built with the `dev` profile, in which case
+    /// type checking is skipped to speed up the build.
+    pub dev: bool,
+
+    /// The platform that is running the build.
+    pub is_bash: bool,
+}
+
+impl BuildScriptContext {
+    pub fn render(&self) -> Vec<String> {
+        let env = Environment::new();
+        let template = env
+            .template_from_str(include_str!("build_script.j2"))
+            .unwrap();
+        let rendered = template.render(self).unwrap().to_string();
+        rendered
+            .lines()
+            .map(|s| s.to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+}
+
+/// Converts a permission like `allow-net` or `--allow-net=example.com` to the
+/// flag that is passed to `deno compile`.
+pub fn permission_flag(permission: &str) -> String {
+    if permission.starts_with('-') {
+        permission.to_string()
+    } else {
+        format!("--{permission}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rstest::*;
+
+    use super::*;
+
+    #[rstest]
+    fn test_build_script(
+        #[values(true, false)] is_bash: bool,
+        #[values(true, false)] has_build_task: bool,
+    ) {
+        let context = BuildScriptContext {
+            source_dir: String::from("my-source-dir"),
+            entry: String::from("main.ts"),
+            binary_name: String::from("foobar"),
+            permissions: vec![
+                permission_flag("allow-net"),
+                permission_flag("--allow-read=."),
+            ],
+            extra_args: vec![String::from("--no-check")],
+            has_build_task,
+            dev: false,
+            is_bash,
+        };
+        let script = context.render();
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_suffix(format!(
+            "{}-{}",
+            if is_bash { "bash" } else { "cmdexe" },
+            if has_build_task {
+                "build-task"
+            } else {
+                "no-build-task"
+            }
+        ));
+        settings.bind(|| {
+            insta::assert_snapshot!(script.join("\n"));
+        });
+    }
+}
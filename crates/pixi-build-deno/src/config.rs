@@ -0,0 +1,523 @@
+use indexmap::IndexMap;
+use pixi_build_backend::{
+    generated_recipe::BackendConfig,
+    utils::{BuildProfile, SolveStrategy, VersionNormalization},
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DenoBackendConfig {
+    /// The module that is compiled into the executable, relative to the
+    /// manifest root. If not set, the `exports` of the `deno.json` is used.
+    pub entry: Option<String>,
+    /// Permissions that are granted to the compiled executable, e.g.
+    /// `["allow-net", "allow-read"]`.
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Extra args for the `deno compile` invocation
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+    /// Environment Variables
+    #[serde(default)]
+    pub env: IndexMap<String, String>,
+    /// Names of environment variables that are passed to the build script as
+    /// secrets, their values are masked in the build log.
+    #[serde(default)]
+    pub secrets: Vec<String>,
+    /// If set, internal state will be logged as files in that directory
+    pub debug_dir: Option<PathBuf>,
+    /// Extra input globs to include in addition to the default ones
+    #[serde(default)]
+    pub extra_input_globs: Vec<String>,
+    /// Default input globs of the backend that are removed, e.g. to not
+    /// rebuild the package when a json data file changes.
+    #[serde(default)]
+    pub remove_input_globs: Vec<String>,
+    /// Ignore the `deno.json` manifest and rely only on the project model for
+    /// the name and version of the package.
+    #[serde(default)]
+    pub ignore_deno_manifest: Option<bool>,
+    /// Extra variables that are added to the context of the recipe, e.g. a
+    /// `cdn_base` that is referenced by the recipe as `${{ cdn_base }}`.
+    /// Variables that are defined by the backend take precedence.
+    #[serde(default)]
+    pub context: IndexMap<String, String>,
+    /// The strategy that selects the versions of the packages when the build
+    /// and host environments are solved, `highest` (the default), `lowest` or
+    /// `lowest-direct`.
+    pub solve_strategy: Option<SolveStrategy>,
+    /// Whether the rendered recipe is stored in the `info/recipe` directory of
+    /// the built packages.
+    #[serde(default)]
+    pub store_recipe: bool,
+    /// The version that is used if neither the manifest nor the `deno.json`
+    /// define a version.
+    pub default_version: Option<String>,
+    /// Whether a missing version is an error, even if a `default_version` is
+    /// set.
+    #[serde(default)]
+    pub require_version: bool,
+    /// How the version that is read from the `deno.json` is converted to the
+    /// version of the recipe, e.g. `conda` to turn `1.0.0-alpha.1` into
+    /// `1.0.0a1`. Defaults to using the version as it is.
+    pub version_normalization: Option<VersionNormalization>,
+    /// Number of times a failed build is retried before giving up. The
+    /// work directory is cleaned between attempts. Capped at 5.
+    pub build_retries: Option<u32>,
+    /// Constraints that are added to the run requirements of the package
+    /// without making them a hard dependency (e.g. `typescript >=5`).
+    #[serde(default)]
+    pub run_constraints: Vec<String>,
+    /// The archive types of the built packages, e.g. `["conda", "tar.bz2"]`.
+    /// Defaults to `["conda"]`.
+    #[serde(default)]
+    pub archive_types: Vec<String>,
+    /// Whether the name and the version of the backend are recorded in the
+    /// extra metadata of the built packages. Defaults to `true`.
+    pub record_provenance: Option<bool>,
+    /// Turns warnings that occur while generating the recipe into errors.
+    #[serde(default)]
+    pub deny_warnings: bool,
+    /// Variant keys that don't influence the hash of the build string, e.g.
+    /// keys that only have a cosmetic effect on the package.
+    #[serde(default)]
+    pub hash_ignore_keys: Vec<String>,
+    /// Channels that are used in addition to the channels that are requested
+    /// by the frontend, either as url or as channel name.
+    #[serde(default)]
+    pub extra_channels: Vec<String>,
+    /// The profile that the package is built with, `dev` skips type checking.
+    /// If not set, the package is type checked.
+    pub profile: Option<BuildProfile>,
+}
+
+impl DenoBackendConfig {
+    /// Creates a new [`DenoBackendConfig`] with default values and
+    /// `ignore_deno_manifest` set to `true`.
+    #[cfg(test)]
+    pub fn default_with_ignore_deno_manifest() -> Self {
+        Self {
+            ignore_deno_manifest: Some(true),
+            ..Default::default()
+        }
+    }
+}
+
+impl BackendConfig for DenoBackendConfig {
+    fn debug_dir(&self) -> Option<&Path> {
+        self.debug_dir.as_deref()
+    }
+
+    fn solve_strategy(&self) -> SolveStrategy {
+        self.solve_strategy.unwrap_or_default()
+    }
+
+    fn store_recipe(&self) -> bool {
+        self.store_recipe
+    }
+
+    fn default_version(&self) -> Option<&str> {
+        self.default_version.as_deref()
+    }
+
+    fn require_version(&self) -> bool {
+        self.require_version
+    }
+
+    fn version_normalization(&self) -> VersionNormalization {
+        self.version_normalization.unwrap_or_default()
+    }
+
+    fn build_retries(&self) -> Option<u32> {
+        self.build_retries
+    }
+
+    fn archive_types(&self) -> &[String] {
+        &self.archive_types
+    }
+
+    fn record_provenance(&self) -> bool {
+        self.record_provenance.unwrap_or(true)
+    }
+
+    fn deny_warnings(&self) -> bool {
+        self.deny_warnings
+    }
+
+    fn hash_ignore_keys(&self) -> &[String] {
+        &self.hash_ignore_keys
+    }
+
+    fn extra_channels(&self) -> &[String] {
+        &self.extra_channels
+    }
+
+    fn profile(&self) -> Option<BuildProfile> {
+        self.profile
+    }
+
+    /// Merge this configuration with a target-specific configuration.
+    /// Target-specific values override base values using the following rules:
+    /// - entry: Platform-specific takes precedence
+    /// - permissions: Platform-specific completely replaces base
+    /// - extra_args: Platform-specific completely replaces base
+    /// - env: Platform env vars override base, others merge
+    /// - secrets: Platform-specific completely replaces base
+    /// - debug_dir: Not allowed to have target specific value
+    /// - extra_input_globs: Platform-specific completely replaces base
+    /// - remove_input_globs: Platform-specific completely replaces base
+    /// - ignore_deno_manifest: Platform-specific takes precedence
+    /// - context: Platform context variables override base, others merge
+    /// - solve_strategy: Platform-specific takes precedence
+    /// - store_recipe: Enabled if either base or target enables it
+    /// - default_version: Platform-specific takes precedence
+    /// - require_version: Enabled if either base or target enables it
+    /// - version_normalization: Platform-specific takes precedence
+    /// - build_retries: Platform-specific takes precedence
+    /// - run_constraints: Platform-specific completely replaces base
+    /// - archive_types: Platform-specific completely replaces base
+    /// - record_provenance: Platform-specific takes precedence
+    /// - deny_warnings: Enabled if either base or target enables it
+    /// - hash_ignore_keys: Platform-specific completely replaces base
+    /// - extra_channels: Platform-specific completely replaces base
+    /// - profile: Platform-specific takes precedence
+    fn merge_with_target_config(&self, target_config: &Self) -> miette::Result<Self> {
+        if target_config.debug_dir.is_some() {
+            miette::bail!("`debug_dir` cannot have a target specific value");
+        }
+
+        Ok(Self {
+            entry: target_config.entry.clone().or_else(|| self.entry.clone()),
+            permissions: if target_config.permissions.is_empty() {
+                self.permissions.clone()
+            } else {
+                target_config.permissions.clone()
+            },
+            extra_args: if target_config.extra_args.is_empty() {
+                self.extra_args.clone()
+            } else {
+                target_config.extra_args.clone()
+            },
+            env: {
+                let mut merged_env = self.env.clone();
+                merged_env.extend(target_config.env.clone());
+                merged_env
+            },
+            secrets: if target_config.secrets.is_empty() {
+                self.secrets.clone()
+            } else {
+                target_config.secrets.clone()
+            },
+            debug_dir: self.debug_dir.clone(),
+            extra_input_globs: if target_config.extra_input_globs.is_empty() {
+                self.extra_input_globs.clone()
+            } else {
+                target_config.extra_input_globs.clone()
+            },
+            remove_input_globs: if target_config.remove_input_globs.is_empty() {
+                self.remove_input_globs.clone()
+            } else {
+                target_config.remove_input_globs.clone()
+            },
+            ignore_deno_manifest: target_config
+                .ignore_deno_manifest
+                .or(self.ignore_deno_manifest),
+            context: {
+                let mut merged_context = self.context.clone();
+                merged_context.extend(target_config.context.clone());
+                merged_context
+            },
+            solve_strategy: target_config.solve_strategy.or(self.solve_strategy),
+            store_recipe: self.store_recipe || target_config.store_recipe,
+            default_version: target_config
+                .default_version
+                .clone()
+                .or(self.default_version.clone()),
+            require_version: self.require_version || target_config.require_version,
+            version_normalization: target_config
+                .version_normalization
+                .or(self.version_normalization),
+            build_retries: target_config.build_retries.or(self.build_retries),
+            run_constraints: if target_config.run_constraints.is_empty() {
+                self.run_constraints.clone()
+            } else {
+                target_config.run_constraints.clone()
+            },
+            archive_types: if target_config.archive_types.is_empty() {
+                self.archive_types.clone()
+            } else {
+                target_config.archive_types.clone()
+            },
+            record_provenance: target_config.record_provenance.or(self.record_provenance),
+            deny_warnings: self.deny_warnings || target_config.deny_warnings,
+            hash_ignore_keys: if target_config.hash_ignore_keys.is_empty() {
+                self.hash_ignore_keys.clone()
+            } else {
+                target_config.hash_ignore_keys.clone()
+            },
+            extra_channels: if target_config.extra_channels.is_empty() {
+                self.extra_channels.clone()
+            } else {
+                target_config.extra_channels.clone()
+            },
+            profile: target_config.profile.or(self.profile),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pixi_build_backend::{
+        generated_recipe::BackendConfig,
+        utils::{BuildProfile, SolveStrategy, VersionNormalization},
+    };
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    use super::DenoBackendConfig;
+
+    #[test]
+    fn test_ensure_deseralize_from_empty() {
+        let json_data = json!({});
+        serde_json::from_value::<DenoBackendConfig>(json_data).unwrap();
+    }
+
+    #[test]
+    fn test_merge_with_target_config() {
+        let mut base_env = indexmap::IndexMap::new();
+        base_env.insert("BASE_VAR".to_string(), "base_value".to_string());
+        base_env.insert("SHARED_VAR".to_string(), "base_shared".to_string());
+
+        let base_config = DenoBackendConfig {
+            entry: Some("main.ts".to_string()),
+            permissions: vec!["allow-read".to_string()],
+            extra_args: vec!["--base-arg".to_string()],
+            env: base_env,
+            secrets: vec!["BASE_TOKEN".to_string()],
+            debug_dir: Some(PathBuf::from("/base/debug")),
+            extra_input_globs: vec!["*.base".to_string()],
+            remove_input_globs: vec!["deno.lock".to_string()],
+            ignore_deno_manifest: None,
+            context: indexmap::IndexMap::from([("cdn".to_string(), "base".to_string())]),
+            solve_strategy: None,
+            store_recipe: false,
+            default_version: Some("0.1.0".to_string()),
+            require_version: false,
+            version_normalization: None,
+            build_retries: Some(2),
+            run_constraints: vec!["typescript >=5".to_string()],
+            archive_types: vec![],
+            record_provenance: None,
+            deny_warnings: false,
+            hash_ignore_keys: vec!["flavor".to_string()],
+            extra_channels: vec![],
+            profile: None,
+        };
+
+        let mut target_env = indexmap::IndexMap::new();
+        target_env.insert("TARGET_VAR".to_string(), "target_value".to_string());
+        target_env.insert("SHARED_VAR".to_string(), "target_shared".to_string());
+
+        let target_config = DenoBackendConfig {
+            entry: Some("main_windows.ts".to_string()),
+            permissions: vec!["allow-net".to_string()],
+            extra_args: vec!["--target-arg".to_string()],
+            env: target_env,
+            secrets: vec!["TARGET_TOKEN".to_string()],
+            debug_dir: None,
+            extra_input_globs: vec!["*.target".to_string()],
+            remove_input_globs: vec!["deno.jsonc".to_string()],
+            ignore_deno_manifest: Some(true),
+            context: indexmap::IndexMap::from([("cdn".to_string(), "target".to_string())]),
+            solve_strategy: Some(SolveStrategy::Lowest),
+            store_recipe: true,
+            default_version: Some("0.2.0".to_string()),
+            require_version: true,
+            version_normalization: Some(VersionNormalization::Conda),
+            build_retries: None,
+            run_constraints: vec!["typescript >=5.4".to_string()],
+            archive_types: vec!["conda".to_string(), "tar.bz2".to_string()],
+            record_provenance: Some(false),
+            deny_warnings: true,
+            hash_ignore_keys: vec!["deno".to_string()],
+            extra_channels: vec!["https://prefix.dev/build-tools".to_string()],
+            profile: Some(BuildProfile::Dev),
+        };
+
+        let merged = base_config
+            .merge_with_target_config(&target_config)
+            .unwrap();
+
+        // entry should use the target value
+        assert_eq!(merged.entry, Some("main_windows.ts".to_string()));
+
+        // permissions should be completely overridden
+        assert_eq!(merged.permissions, vec!["allow-net".to_string()]);
+
+        // extra_args should be completely overridden
+        assert_eq!(merged.extra_args, vec!["--target-arg".to_string()]);
+
+        // env should merge with target taking precedence
+        assert_eq!(merged.env.get("BASE_VAR"), Some(&"base_value".to_string()));
+        assert_eq!(
+            merged.env.get("TARGET_VAR"),
+            Some(&"target_value".to_string())
+        );
+        assert_eq!(
+            merged.env.get("SHARED_VAR"),
+            Some(&"target_shared".to_string())
+        );
+
+        // secrets should be completely overridden
+        assert_eq!(merged.secrets, vec!["TARGET_TOKEN".to_string()]);
+
+        // debug_dir should use base value
+        assert_eq!(merged.debug_dir, Some(PathBuf::from("/base/debug")));
+
+        // extra_input_globs should be completely overridden
+        assert_eq!(merged.extra_input_globs, vec!["*.target".to_string()]);
+
+        // remove_input_globs should be completely overridden
+        assert_eq!(merged.remove_input_globs, vec!["deno.jsonc".to_string()]);
+
+        // ignore_deno_manifest should use the target value
+        assert_eq!(merged.ignore_deno_manifest, Some(true));
+
+        // context should merge with target taking precedence
+        assert_eq!(merged.context.get("cdn"), Some(&"target".to_string()));
+
+        // solve_strategy should use the target value
+        assert_eq!(merged.solve_strategy, Some(SolveStrategy::Lowest));
+
+        // store_recipe should be enabled by the target
+        assert!(merged.store_recipe);
+
+        // default_version should use the target value
+        assert_eq!(merged.default_version, Some("0.2.0".to_string()));
+
+        // require_version should be enabled by the target
+        assert!(merged.require_version);
+
+        // version_normalization should use the target value
+        assert_eq!(
+            merged.version_normalization,
+            Some(VersionNormalization::Conda)
+        );
+
+        // build_retries should fall back to the base value
+        assert_eq!(merged.build_retries, Some(2));
+
+        // run_constraints should be completely overridden
+        assert_eq!(merged.run_constraints, vec!["typescript >=5.4".to_string()]);
+
+        // archive_types should be completely overridden
+        assert_eq!(
+            merged.archive_types,
+            vec!["conda".to_string(), "tar.bz2".to_string()]
+        );
+
+        // record_provenance should use the target value
+        assert_eq!(merged.record_provenance, Some(false));
+
+        // deny_warnings should be enabled by the target
+        assert!(merged.deny_warnings);
+
+        // hash_ignore_keys should be completely overridden
+        assert_eq!(merged.hash_ignore_keys, vec!["deno".to_string()]);
+
+        // extra_channels should be completely overridden
+        assert_eq!(
+            merged.extra_channels,
+            vec!["https://prefix.dev/build-tools".to_string()]
+        );
+
+        // profile should use the target value
+        assert_eq!(merged.profile, Some(BuildProfile::Dev));
+    }
+
+    #[test]
+    fn test_merge_with_empty_target_config() {
+        let mut base_env = indexmap::IndexMap::new();
+        base_env.insert("BASE_VAR".to_string(), "base_value".to_string());
+
+        let base_config = DenoBackendConfig {
+            entry: Some("main.ts".to_string()),
+            permissions: vec!["allow-read".to_string()],
+            extra_args: vec!["--base-arg".to_string()],
+            env: base_env,
+            secrets: vec!["BASE_TOKEN".to_string()],
+            debug_dir: Some(PathBuf::from("/base/debug")),
+            extra_input_globs: vec!["*.base".to_string()],
+            remove_input_globs: vec!["deno.lock".to_string()],
+            ignore_deno_manifest: Some(false),
+            context: indexmap::IndexMap::new(),
+            solve_strategy: Some(SolveStrategy::LowestDirect),
+            store_recipe: true,
+            default_version: Some("0.1.0".to_string()),
+            require_version: true,
+            version_normalization: Some(VersionNormalization::Pep440),
+            build_retries: Some(2),
+            run_constraints: vec!["typescript >=5".to_string()],
+            archive_types: vec!["tar.bz2".to_string()],
+            record_provenance: Some(true),
+            deny_warnings: false,
+            hash_ignore_keys: vec!["flavor".to_string()],
+            extra_channels: vec!["bioconda".to_string()],
+            profile: Some(BuildProfile::Release),
+        };
+
+        let empty_target_config = DenoBackendConfig::default();
+
+        let merged = base_config
+            .merge_with_target_config(&empty_target_config)
+            .unwrap();
+
+        // Should keep base values when target is empty
+        assert_eq!(merged.entry, Some("main.ts".to_string()));
+        assert_eq!(merged.permissions, vec!["allow-read".to_string()]);
+        assert_eq!(merged.extra_args, vec!["--base-arg".to_string()]);
+        assert_eq!(merged.env.get("BASE_VAR"), Some(&"base_value".to_string()));
+        assert_eq!(merged.secrets, vec!["BASE_TOKEN".to_string()]);
+        assert_eq!(merged.debug_dir, Some(PathBuf::from("/base/debug")));
+        assert_eq!(merged.extra_input_globs, vec!["*.base".to_string()]);
+        assert_eq!(merged.remove_input_globs, vec!["deno.lock".to_string()]);
+        assert_eq!(merged.ignore_deno_manifest, Some(false));
+        assert!(merged.context.is_empty());
+        assert_eq!(merged.solve_strategy, Some(SolveStrategy::LowestDirect));
+        assert!(merged.store_recipe);
+        assert_eq!(merged.default_version, Some("0.1.0".to_string()));
+        assert!(merged.require_version);
+        assert_eq!(
+            merged.version_normalization,
+            Some(VersionNormalization::Pep440)
+        );
+        assert_eq!(merged.build_retries, Some(2));
+        assert_eq!(merged.run_constraints, vec!["typescript >=5".to_string()]);
+        assert_eq!(merged.archive_types, vec!["tar.bz2".to_string()]);
+        assert_eq!(merged.record_provenance, Some(true));
+        assert!(!merged.deny_warnings);
+        assert_eq!(merged.hash_ignore_keys, vec!["flavor".to_string()]);
+        assert_eq!(merged.extra_channels, vec!["bioconda".to_string()]);
+        assert_eq!(merged.profile, Some(BuildProfile::Release));
+    }
+
+    #[test]
+    fn test_merge_target_debug_dir_error() {
+        let base_config = DenoBackendConfig {
+            debug_dir: Some(PathBuf::from("/base/debug")),
+            ..Default::default()
+        };
+
+        let target_config = DenoBackendConfig {
+            debug_dir: Some(PathBuf::from("/target/debug")),
+            ..Default::default()
+        };
+
+        let result = base_config.merge_with_target_config(&target_config);
+        assert!(result.is_err());
+        let error_msg = result.unwrap_err().to_string();
+        assert!(error_msg.contains("`debug_dir` cannot have a target specific value"));
+    }
+}
@@ -0,0 +1,610 @@
+mod build_script;
+mod config;
+mod metadata;
+
+use build_script::{BuildScriptContext, permission_flag};
+use config::DenoBackendConfig;
+use metadata::DenoMetadataProvider;
+use miette::IntoDiagnostic;
+use pixi_build_backend::{
+    error::BackendError,
+    generated_recipe::{
+        BackendConfig, BackendIdentifier, BuildParams, GenerateRecipe, GeneratedRecipe,
+    },
+    intermediate_backend::IntermediateBackendInstantiator,
+};
+use pixi_build_backend::{utils::BuildProfile, variants::NormalizedKey};
+use pixi_build_types::ProjectModelV1;
+use rattler_conda_types::{PackageName, Platform};
+use recipe_stage0::recipe::{ConditionalRequirements, Script};
+use std::collections::HashSet;
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+#[derive(Default, Clone)]
+pub struct DenoGenerator {}
+
+impl GenerateRecipe for DenoGenerator {
+    type Config = DenoBackendConfig;
+
+    fn generate_recipe(
+        &self,
+        model: &ProjectModelV1,
+        config: &Self::Config,
+        manifest_root: PathBuf,
+        host_platform: Platform,
+        build_params: Option<BuildParams>,
+        _variants: &HashSet<NormalizedKey>,
+    ) -> miette::Result<GeneratedRecipe> {
+        // Construct a DenoMetadataProvider to read the deno.json file and
+        // extract metadata from it.
+        let mut deno_metadata = DenoMetadataProvider::new(
+            &manifest_root,
+            config.ignore_deno_manifest.is_some_and(|ignore| ignore),
+        );
+
+        let mut generated_recipe = GeneratedRecipe::from_model_with_default_version(
+            model.clone(),
+            &mut deno_metadata,
+            config.fallback_version(),
+            config.version_normalization(),
+        )
+        .map_err(BackendError::from)?;
+
+        let requirements = &mut generated_recipe.recipe.requirements;
+
+        let resolved_requirements = ConditionalRequirements::resolve(
+            requirements.build.as_ref(),
+            requirements.host.as_ref(),
+            requirements.run.as_ref(),
+            requirements.run_constraints.as_ref(),
+            Some(host_platform),
+        );
+
+        // Add deno to the build requirements, it is used to compile the
+        // package.
+        if !resolved_requirements
+            .build
+            .contains_key(&PackageName::new_unchecked("deno"))
+        {
+            requirements.build.push("deno".parse().into_diagnostic()?);
+        }
+
+        // The manifest is also read when its metadata is ignored because it
+        // defines the entry point and the tasks of the package.
+        let manifest = deno_metadata.manifest().into_diagnostic()?;
+        let entry = config
+            .entry
+            .as_deref()
+            .or_else(|| manifest.and_then(|manifest| manifest.main_export()))
+            .ok_or_else(|| {
+                miette::miette!(
+                    "no entry point found, set `entry` in the build configuration or `exports` in the deno.json"
+                )
+            })?;
+
+        let build_script = BuildScriptContext {
+            source_dir: manifest_root.display().to_string(),
+            entry: entry.to_string(),
+            binary_name: generated_recipe.recipe.package.name.to_string(),
+            permissions: config
+                .permissions
+                .iter()
+                .map(|permission| permission_flag(permission))
+                .collect(),
+            extra_args: config.extra_args.clone(),
+            has_build_task: manifest.is_some_and(|manifest| manifest.has_task("build")),
+            dev: build_params
+                .and_then(|params| params.profile)
+                .is_some_and(BuildProfile::is_dev),
+            is_bash: !Platform::current().is_windows(),
+        }
+        .render();
+
+        generated_recipe.recipe.build.script = Script {
+            content: build_script,
+            env: config.env.clone(),
+            ..Default::default()
+        };
+        generated_recipe.add_secrets(&config.secrets);
+        generated_recipe.add_run_constraints(&config.run_constraints)?;
+        generated_recipe.add_context(&config.context);
+        generated_recipe.discover_license_file(&manifest_root);
+
+        // Record the manifest that was read by the deno metadata provider
+        for path in deno_metadata.input_globs() {
+            generated_recipe.record_input(path);
+        }
+
+        Ok(generated_recipe)
+    }
+
+    /// Returns the build input globs used by the backend.
+    fn extract_input_globs_from_build(
+        config: &Self::Config,
+        _workdir: impl AsRef<Path>,
+        _editable: bool,
+    ) -> BTreeSet<String> {
+        [
+            // Source files
+            "**/*.{ts,js,json}",
+            // Deno configuration files
+            "deno.json",
+            "deno.jsonc",
+            "deno.lock",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .filter(|glob| !config.remove_input_globs.contains(glob))
+        .chain(config.extra_input_globs.clone())
+        .collect()
+    }
+
+    fn expected_manifests(&self, config: &Self::Config) -> &[&'static str] {
+        // The `deno.json` defines the entry module, unless one is configured.
+        if config.entry.is_some() {
+            &[]
+        } else {
+            &metadata::MANIFEST_FILE_NAMES
+        }
+    }
+
+    fn backend_identifier(&self) -> Option<BackendIdentifier> {
+        Some(BackendIdentifier {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        })
+    }
+}
+
+#[tokio::main]
+pub async fn main() {
+    if let Err(err) = pixi_build_backend::cli::main(|log| {
+        IntermediateBackendInstantiator::<DenoGenerator>::new(log, Arc::default())
+    })
+    .await
+    {
+        eprintln!("{err:?}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+    use pixi_build_backend::utils::{VersionNormalization, test::backend_manifest_path};
+
+    use super::*;
+
+    #[test]
+    fn test_input_globs_includes_extra_globs() {
+        let config = DenoBackendConfig {
+            extra_input_globs: vec!["assets/**/*.css".to_string()],
+            ..Default::default()
+        };
+
+        let result = DenoGenerator::extract_input_globs_from_build(&config, PathBuf::new(), false);
+
+        insta::assert_debug_snapshot!(result);
+    }
+
+    #[test]
+    fn test_input_globs_removes_default_globs() {
+        let config = DenoBackendConfig {
+            remove_input_globs: vec!["deno.lock".to_string()],
+            ..Default::default()
+        };
+
+        let result = DenoGenerator::extract_input_globs_from_build(&config, PathBuf::new(), false);
+
+        assert!(!result.contains("deno.lock"));
+        assert!(result.contains("deno.json"));
+        assert!(result.contains("**/*.{ts,js,json}"));
+    }
+
+    #[test]
+    fn test_expected_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let err =
+            backend_manifest_path::<DenoGenerator>(dir.path(), serde_json::json!({})).unwrap_err();
+        assert!(
+            err.to_string().contains("`deno.json` or `deno.jsonc`"),
+            "{err}"
+        );
+
+        fs_err::write(dir.path().join("deno.jsonc"), "{}").unwrap();
+        assert_eq!(
+            backend_manifest_path::<DenoGenerator>(dir.path(), serde_json::json!({})).unwrap(),
+            Some(dir.path().join("deno.jsonc"))
+        );
+
+        // A configured entry module doesn't need a `deno.json`.
+        let dir = tempfile::tempdir().unwrap();
+        let config = serde_json::json!({ "entry": "main.ts" });
+        assert_eq!(
+            backend_manifest_path::<DenoGenerator>(dir.path(), config).unwrap(),
+            None
+        );
+    }
+
+    #[macro_export]
+    macro_rules! project_fixture {
+        ($($json:tt)+) => {
+            serde_json::from_value::<ProjectModelV1>(
+                serde_json::json!($($json)+)
+            ).expect("Failed to create TestProjectModel from JSON fixture.")
+        };
+    }
+
+    fn config_with_entry() -> DenoBackendConfig {
+        DenoBackendConfig {
+            entry: Some("main.ts".to_string()),
+            ..DenoBackendConfig::default_with_ignore_deno_manifest()
+        }
+    }
+
+    fn build_requirements(generated_recipe: &GeneratedRecipe) -> Vec<String> {
+        generated_recipe
+            .recipe
+            .requirements
+            .build
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_deno_is_in_build_requirements() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+            "targets": {
+                "defaultTarget": {
+                    "runDependencies": {
+                        "boltons": {
+                            "binary": {
+                                "version": "*"
+                            }
+                        }
+                    }
+                },
+            }
+        });
+
+        let generated_recipe = DenoGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &config_with_entry(),
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        assert_eq!(build_requirements(&generated_recipe), vec!["deno"]);
+    }
+
+    #[test]
+    fn test_deno_is_not_added_if_already_present() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+            "targets": {
+                "defaultTarget": {
+                    "buildDependencies": {
+                        "deno": {
+                            "binary": {
+                                "version": ">=2"
+                            }
+                        }
+                    }
+                },
+            }
+        });
+
+        let generated_recipe = DenoGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &config_with_entry(),
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        assert_eq!(build_requirements(&generated_recipe), vec!["deno >=2"]);
+    }
+
+    #[test]
+    fn test_env_vars_are_set() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let env = IndexMap::from([("foo".to_string(), "bar".to_string())]);
+
+        let generated_recipe = DenoGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &DenoBackendConfig {
+                    env: env.clone(),
+                    ..config_with_entry()
+                },
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        insta::assert_yaml_snapshot!(generated_recipe.recipe.build.script,
+        {
+            ".content" => "[ ... script ... ]",
+        });
+    }
+
+    #[test]
+    fn test_secrets_are_added_to_script() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let generated_recipe = DenoGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &DenoBackendConfig {
+                    secrets: vec!["NPM_TOKEN".to_string()],
+                    ..config_with_entry()
+                },
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        assert_eq!(
+            generated_recipe.recipe.build.script.secrets,
+            vec!["NPM_TOKEN".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_run_constraints_are_added() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let generated_recipe = DenoGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &DenoBackendConfig {
+                    run_constraints: vec!["typescript >=5".to_string()],
+                    ..config_with_entry()
+                },
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let run_constraints = generated_recipe
+            .recipe
+            .requirements
+            .run_constraints
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(run_constraints, vec!["typescript >=5"]);
+    }
+
+    #[test]
+    fn test_context_is_added_to_recipe() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let generated_recipe = DenoGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &DenoBackendConfig {
+                    context: IndexMap::from([(
+                        "cdn_base".to_string(),
+                        "https://cdn.example.com".to_string(),
+                    )]),
+                    ..config_with_entry()
+                },
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        assert_eq!(
+            generated_recipe.recipe.context["cdn_base"],
+            recipe_stage0::recipe::Value::Concrete("https://cdn.example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn test_recipe_from_deno_json() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(
+            dir.path().join("deno.json"),
+            r#"{
+                "name": "@scope/foobar",
+                "version": "1.2.3",
+                "exports": "./cli.ts",
+                "tasks": { "build": "deno run -A generate.ts" }
+            }"#,
+        )
+        .unwrap();
+
+        let project_model = project_fixture!({
+            "name": "",
+        });
+
+        let generated_recipe = DenoGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &DenoBackendConfig {
+                    permissions: vec!["allow-net".to_string()],
+                    ..Default::default()
+                },
+                dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let package = &generated_recipe.recipe.package;
+        assert_eq!(package.name.to_string(), "foobar");
+        assert_eq!(package.version.to_string(), "1.2.3");
+        assert_eq!(
+            generated_recipe.metadata_input_globs,
+            BTreeSet::from(["deno.json".to_string()])
+        );
+
+        let script = generated_recipe.recipe.build.script.content.join("\n");
+        assert!(script.contains("deno task build"), "{script}");
+        assert!(script.contains("--allow-net"), "{script}");
+        assert!(script.contains("\"./cli.ts\""), "{script}");
+    }
+
+    #[test]
+    fn test_version_from_deno_json_is_normalized() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(
+            dir.path().join("deno.json"),
+            r#"{ "name": "foobar", "version": "1.0.0-alpha.1", "exports": "./cli.ts" }"#,
+        )
+        .unwrap();
+
+        let generated_recipe = DenoGenerator::default()
+            .generate_recipe(
+                &project_fixture!({ "name": "" }),
+                &DenoBackendConfig {
+                    version_normalization: Some(VersionNormalization::Conda),
+                    ..Default::default()
+                },
+                dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        assert_eq!(
+            generated_recipe.recipe.package.version.to_string(),
+            "1.0.0a1"
+        );
+    }
+
+    #[test]
+    fn test_default_version() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(
+            dir.path().join("deno.json"),
+            r#"{ "name": "foobar", "exports": "./cli.ts" }"#,
+        )
+        .unwrap();
+        let generate = |config: DenoBackendConfig| {
+            DenoGenerator::default().generate_recipe(
+                &project_fixture!({ "name": "" }),
+                &config,
+                dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+        };
+
+        let generated_recipe = generate(DenoBackendConfig {
+            default_version: Some("0.1.0".to_string()),
+            ..Default::default()
+        })
+        .expect("Failed to generate recipe");
+        assert_eq!(generated_recipe.recipe.package.version.to_string(), "0.1.0");
+
+        // A required version doesn't fall back to the default version.
+        assert!(
+            generate(DenoBackendConfig {
+                default_version: Some("0.1.0".to_string()),
+                require_version: true,
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_dev_profile_skips_type_check() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+        let generate = |profile| {
+            DenoGenerator::default()
+                .generate_recipe(
+                    &project_model,
+                    &config_with_entry(),
+                    PathBuf::from("."),
+                    Platform::Linux64,
+                    Some(BuildParams {
+                        editable: false,
+                        profile,
+                    }),
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe")
+                .recipe
+                .build
+                .script
+                .content
+                .join("\n")
+        };
+
+        let script = generate(Some(BuildProfile::Dev));
+        assert!(script.contains("--no-check"), "{script}");
+
+        let script = generate(Some(BuildProfile::Release));
+        assert!(!script.contains("--no-check"), "{script}");
+        let script = generate(None);
+        assert!(!script.contains("--no-check"), "{script}");
+    }
+
+    #[test]
+    fn test_missing_entry_point() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let result = DenoGenerator::default().generate_recipe(
+            &project_model,
+            &DenoBackendConfig::default_with_ignore_deno_manifest(),
+            PathBuf::from("."),
+            Platform::Linux64,
+            None,
+            &HashSet::new(),
+        );
+
+        let error = result
+            .err()
+            .expect("an entry point is required")
+            .to_string();
+        assert!(error.contains("no entry point found"), "{error}");
+    }
+}
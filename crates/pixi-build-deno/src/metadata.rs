@@ -0,0 +1,282 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use indexmap::IndexMap;
+use miette::Diagnostic;
+use once_cell::unsync::OnceCell;
+use pixi_build_backend::generated_recipe::MetadataProvider;
+use rattler_conda_types::{ParseVersionError, Version};
+use serde::Deserialize;
+
+/// The names of the manifest files that are recognized, in order of
+/// preference.
+pub(crate) const MANIFEST_FILE_NAMES: [&str; 2] = ["deno.json", "deno.jsonc"];
+
+#[derive(Debug, thiserror::Error, Diagnostic)]
+pub enum MetadataError {
+    #[error("failed to parse {0}, {1}")]
+    ParseManifestError(String, serde_json::Error),
+    #[error("failed to parse version from {0}, {1}")]
+    ParseVersionError(String, ParseVersionError),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+/// The parts of a `deno.json` file that are relevant to the backend.
+#[derive(Debug, Default, Deserialize)]
+pub struct DenoManifest {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub exports: Option<serde_json::Value>,
+    #[serde(default)]
+    pub tasks: IndexMap<String, serde_json::Value>,
+}
+
+impl DenoManifest {
+    /// Returns the module that is exported by the package if the package has
+    /// a single export, e.g. `"exports": "./main.ts"` or
+    /// `"exports": { ".": "./main.ts" }`.
+    pub fn main_export(&self) -> Option<&str> {
+        match self.exports.as_ref()? {
+            serde_json::Value::String(export) => Some(export),
+            serde_json::Value::Object(exports) => exports.get(".")?.as_str(),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the manifest defines a task with the given name.
+    pub fn has_task(&self, name: &str) -> bool {
+        self.tasks.contains_key(name)
+    }
+}
+
+/// An implementation of [`MetadataProvider`] that reads metadata from a
+/// `deno.json` or `deno.jsonc` file.
+pub struct DenoMetadataProvider {
+    manifest_root: PathBuf,
+    manifest: OnceCell<Option<(DenoManifest, &'static str)>>,
+    ignore_deno_manifest: bool,
+}
+
+impl DenoMetadataProvider {
+    /// Constructs a new `DenoMetadataProvider` with the given manifest root.
+    ///
+    /// # Arguments
+    ///
+    /// * `manifest_root` - The directory that contains the `deno.json` file
+    /// * `ignore_deno_manifest` - If `true`, all metadata methods will return
+    ///   `None`, effectively disabling `deno.json` metadata extraction
+    pub fn new(manifest_root: impl Into<PathBuf>, ignore_deno_manifest: bool) -> Self {
+        Self {
+            manifest_root: manifest_root.into(),
+            manifest: OnceCell::default(),
+            ignore_deno_manifest,
+        }
+    }
+
+    /// Returns the parsed manifest or `None` if the manifest root does not
+    /// contain a `deno.json` or `deno.jsonc` file.
+    pub fn manifest(&self) -> Result<Option<&DenoManifest>, MetadataError> {
+        let manifest = self.manifest.get_or_try_init(|| {
+            let Some(file_name) = MANIFEST_FILE_NAMES
+                .into_iter()
+                .find(|file_name| self.manifest_root.join(file_name).is_file())
+            else {
+                return Ok(None);
+            };
+            let manifest = read_manifest(&self.manifest_root.join(file_name))?;
+            Ok::<_, MetadataError>(Some((manifest, file_name)))
+        })?;
+        Ok(manifest.as_ref().map(|(manifest, _)| manifest))
+    }
+
+    /// Returns the set of globs that match files that influence the metadata of
+    /// this package, i.e. the manifest file if it was read.
+    pub fn input_globs(&self) -> BTreeSet<String> {
+        self.manifest
+            .get()
+            .and_then(Option::as_ref)
+            .map(|(_, file_name)| file_name.to_string())
+            .into_iter()
+            .collect()
+    }
+}
+
+impl MetadataProvider for DenoMetadataProvider {
+    type Error = MetadataError;
+
+    /// Returns the package name from the `deno.json` manifest.
+    ///
+    /// JSR package names are scoped, e.g. `@scope/foo`. The scope is not part
+    /// of the name of the conda package, so only `foo` is returned.
+    fn name(&mut self) -> Result<Option<String>, Self::Error> {
+        if self.ignore_deno_manifest {
+            return Ok(None);
+        }
+        Ok(self
+            .manifest()?
+            .and_then(|manifest| manifest.name.as_deref())
+            .map(|name| name.rsplit('/').next().unwrap_or(name).to_string()))
+    }
+
+    /// Returns the package version from the `deno.json` manifest.
+    fn version(&mut self) -> Result<Option<Version>, Self::Error> {
+        if self.ignore_deno_manifest {
+            return Ok(None);
+        }
+        let Some(version) = self
+            .manifest()?
+            .and_then(|manifest| manifest.version.as_deref())
+        else {
+            return Ok(None);
+        };
+        Ok(Some(Version::from_str(version).map_err(|err| {
+            MetadataError::ParseVersionError(String::from("deno.json"), err)
+        })?))
+    }
+}
+
+/// Reads a `deno.json` or `deno.jsonc` file. Comments and trailing commas are
+/// allowed in both.
+fn read_manifest(path: &Path) -> Result<DenoManifest, MetadataError> {
+    let contents = fs_err::read_to_string(path)?;
+    serde_json::from_str(&strip_jsonc(&contents))
+        .map_err(|err| MetadataError::ParseManifestError(path.display().to_string(), err))
+}
+
+/// Converts JSONC to JSON by removing comments and trailing commas.
+fn strip_jsonc(contents: &str) -> String {
+    let mut without_comments = String::with_capacity(contents.len());
+    let mut chars = contents.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            without_comments.push(c);
+            match c {
+                '\\' => without_comments.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek().copied()) {
+            ('"', _) => {
+                in_string = true;
+                without_comments.push(c);
+            }
+            ('/', Some('/')) => {
+                // Skip until the end of the line but keep the newline.
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = None;
+                for c in chars.by_ref() {
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+            }
+            _ => without_comments.push(c),
+        }
+    }
+
+    // Remove commas that are only followed by whitespace and a closing bracket.
+    let mut json = String::with_capacity(without_comments.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (index, c) in without_comments.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ','
+            && without_comments[index + 1..]
+                .trim_start()
+                .starts_with(['}', ']'])
+        {
+            continue;
+        }
+        json.push(c);
+    }
+
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let jsonc = r#"{
+            // The name of the package
+            "name": "@scope/foo", /* trailing comment */
+            "url": "https://example.com/a,}",
+            "tasks": {
+                "build": "deno run build.ts",
+            },
+        }"#;
+        let value: serde_json::Value = serde_json::from_str(&strip_jsonc(jsonc)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "name": "@scope/foo",
+                "url": "https://example.com/a,}",
+                "tasks": { "build": "deno run build.ts" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_metadata_from_deno_jsonc() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(
+            dir.path().join("deno.jsonc"),
+            r#"{
+                // JSR packages are scoped
+                "name": "@scope/foo",
+                "version": "1.2.3",
+                "exports": { ".": "./main.ts" },
+                "tasks": { "build": "deno run -A build.ts" },
+            }"#,
+        )
+        .unwrap();
+
+        let mut provider = DenoMetadataProvider::new(dir.path(), false);
+        assert_eq!(provider.name().unwrap(), Some("foo".to_string()));
+        assert_eq!(provider.version().unwrap(), Some("1.2.3".parse().unwrap()));
+
+        let manifest = provider.manifest().unwrap().unwrap();
+        assert_eq!(manifest.main_export(), Some("./main.ts"));
+        assert!(manifest.has_task("build"));
+        assert_eq!(
+            provider.input_globs(),
+            BTreeSet::from(["deno.jsonc".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_ignore_deno_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(
+            dir.path().join("deno.json"),
+            r#"{ "name": "foo", "version": "1.2.3" }"#,
+        )
+        .unwrap();
+
+        let mut provider = DenoMetadataProvider::new(dir.path(), true);
+        assert_eq!(provider.name().unwrap(), None);
+        assert_eq!(provider.version().unwrap(), None);
+    }
+}
@@ -10,6 +10,11 @@ pub struct BuildScriptContext {
     pub bins: Option<Vec<MojoBinConfig>>,
     /// Any packages to create.
     pub pkg: Option<MojoPkgConfig>,
+    /// Build the binaries without optimizations and with full debug
+    /// information.
+    pub debug: bool,
+    /// Commands that are run before the generated commands.
+    pub prelude: Vec<String>,
 }
 
 impl BuildScriptContext {
@@ -1,13 +1,22 @@
 use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
+    time::Duration,
 };
 
 use indexmap::IndexMap;
 use miette::Error;
-use pixi_build_backend::generated_recipe::BackendConfig;
+use pixi_build_backend::{
+    compilers::merge_compilers,
+    generated_recipe::BackendConfig,
+    utils::{BuildProfile, ExtraChannelPriority, SolveStrategy},
+};
+use recipe_stage0::recipe::SourceFilter;
 use serde::{Deserialize, Serialize};
 
+/// The compilers that are used if `compilers` is not configured.
+pub(crate) const DEFAULT_COMPILERS: &[&str] = &["mojo"];
+
 /// Top level config struct for the Mojo backend.
 #[derive(Debug, Default, Deserialize, Clone)]
 #[serde(rename_all = "kebab-case")]
@@ -16,6 +25,11 @@ pub struct MojoBackendConfig {
     #[serde(default)]
     pub env: IndexMap<String, String>,
 
+    /// Names of environment variables that are passed to the build script as
+    /// secrets, their values are masked in the build log.
+    #[serde(default)]
+    pub secrets: Vec<String>,
+
     /// Dir that can be specified for outputting pixi debug state.
     pub debug_dir: Option<PathBuf>,
 
@@ -23,6 +37,11 @@ pub struct MojoBackendConfig {
     #[serde(default)]
     pub extra_input_globs: Vec<String>,
 
+    /// Default input globs of the backend that are removed, e.g. to not
+    /// rebuild the package when the documentation changes.
+    #[serde(default)]
+    pub remove_input_globs: Vec<String>,
+
     /// Binary executables to produce.
     pub bins: Option<Vec<MojoBinConfig>>,
 
@@ -32,6 +51,166 @@ pub struct MojoBackendConfig {
     /// List of compilers to use (e.g., ["mojo", "c", "cxx"])
     /// If not specified, defaults to ["mojo"]
     pub compilers: Option<Vec<String>>,
+
+    /// List of standard libraries to add (e.g., ["c", "fortran"]). Each one is
+    /// only added if the variants contain a matching `<name>_stdlib` key.
+    /// If not specified, the `c` standard library is used for the compilers
+    pub stdlibs: Option<Vec<String>>,
+
+    /// Compilers that are only added if the variants contain a matching
+    /// `<name>_compiler_version` key, e.g. `cuda` for `cuda_compiler_version`.
+    #[serde(default)]
+    pub variant_compilers: Vec<String>,
+
+    /// Number of times a failed build is retried before giving up. The
+    /// work directory is cleaned between attempts. Capped at 5.
+    pub build_retries: Option<u32>,
+
+    /// Merge the build and host environments into a single environment.
+    /// Required for some cross-compilation setups. Defaults to `false`.
+    pub merge_build_and_host_envs: Option<bool>,
+
+    /// Constraints that are added to the run requirements of the package
+    /// without making them a hard dependency (e.g. `numpy >=1.20`).
+    #[serde(default)]
+    pub run_constraints: Vec<String>,
+
+    /// Names of declared dependencies that are only needed at build time.
+    /// These are moved from the host and run requirements into the build
+    /// requirements (e.g. `make`).
+    #[serde(default)]
+    pub build_only: Vec<String>,
+
+    /// Globs of files that are copied into the package instead of being
+    /// hardlinked, e.g. data files that are modified after installation.
+    #[serde(default)]
+    pub always_copy_files: Vec<String>,
+
+    /// Directories, relative to the manifest root, that are added as
+    /// additional path sources, e.g. a data directory that the build needs.
+    #[serde(default)]
+    pub additional_sources: Vec<String>,
+
+    /// Whether files that are ignored by git are left out of the
+    /// `additional_sources`. Defaults to `true`.
+    pub additional_sources_use_gitignore: Option<bool>,
+
+    /// Include and exclude globs, relative to every directory of the
+    /// `additional_sources`, that select the files that are part of it.
+    pub additional_sources_filter: Option<SourceFilter>,
+
+    /// Whether dependencies are removed from the host requirements if they
+    /// are already part of the build requirements with the same spec.
+    #[serde(default)]
+    pub dedupe_requirements: bool,
+
+    /// An explicit build string template, e.g. `${{ hash }}_custom`. If not
+    /// set, the build string is derived from the hash and the build number.
+    pub build_string: Option<String>,
+
+    /// The name of an environment variable that holds the build number, e.g.
+    /// `CONDA_BUILD_NUMBER`. If the variable is set, its value is used as the
+    /// build number of the package.
+    pub build_number_env: Option<String>,
+
+    /// Whether the build number is set to the number of commits in the git
+    /// repository of the manifest. The `build_number_env` takes precedence.
+    #[serde(default)]
+    pub build_number_from_git_count: bool,
+
+    /// The conda license family of the package, e.g. `MIT` or `APACHE`. If not
+    /// set, it is derived from the license when possible.
+    pub license_family: Option<String>,
+
+    /// The archive types of the built packages, e.g. `["conda", "tar.bz2"]`.
+    /// Defaults to `["conda"]`.
+    #[serde(default)]
+    pub archive_types: Vec<String>,
+
+    /// Whether the name and the version of the backend are recorded in the
+    /// extra metadata of the built packages. Defaults to `true`.
+    pub record_provenance: Option<bool>,
+
+    /// Whether the rendered recipe is stored in the `info/recipe` directory of
+    /// the built packages.
+    #[serde(default)]
+    pub store_recipe: bool,
+
+    /// The version that is used if neither the manifest nor the package
+    /// metadata define a version.
+    pub default_version: Option<String>,
+
+    /// Whether a missing version is an error, even if a `default_version` is
+    /// set.
+    #[serde(default)]
+    pub require_version: bool,
+
+    /// Turns warnings that occur while generating the recipe into errors.
+    #[serde(default)]
+    pub deny_warnings: bool,
+
+    /// Whether the license of the package must be a valid SPDX license
+    /// expression. Templated licenses are not checked.
+    #[serde(default)]
+    pub validate_license: bool,
+
+    /// Variant keys that don't influence the hash of the build string, e.g.
+    /// keys that only have a cosmetic effect on the package.
+    #[serde(default)]
+    pub hash_ignore_keys: Vec<String>,
+
+    /// The maximum duration of a single backend operation in seconds, e.g.
+    /// solving the environments and building the package. Operations that
+    /// take longer fail with a timeout error.
+    pub operation_timeout: Option<u64>,
+
+    /// A base directory for the build directories of the packages, e.g. on a
+    /// faster local disk. Every package is built in a subdirectory named after
+    /// the work directory that is requested by the frontend.
+    pub build_dir: Option<PathBuf>,
+
+    /// The directory that the rendered recipes are temporarily written to,
+    /// e.g. if the default location is on a small or slow disk. Defaults to the
+    /// output directory of the build.
+    pub temp_dir: Option<PathBuf>,
+
+    /// The strategy that selects the versions of the packages when the build
+    /// and host environments are solved, `highest` (the default), `lowest` or
+    /// `lowest-direct`.
+    pub solve_strategy: Option<SolveStrategy>,
+
+    /// The profile that the package is built with, e.g. `dev` for a debug
+    /// build. If not set, the defaults of the backend are used.
+    pub profile: Option<BuildProfile>,
+
+    /// Whether the fully resolved recipe of every output is written to the
+    /// `debug_dir`, after all templates and selectors have been evaluated.
+    #[serde(default)]
+    pub debug_resolved_recipes: bool,
+
+    /// Channels that are used in addition to the channels that are requested
+    /// by the frontend, either as url or as channel name.
+    #[serde(default)]
+    pub extra_channels: Vec<String>,
+
+    /// Whether the `extra_channels` are added after (`lowest`, the default)
+    /// or before (`highest`) the channels of the frontend.
+    pub extra_channel_priority: Option<ExtraChannelPriority>,
+
+    /// A YAML file with channels that are used in addition to the
+    /// `extra_channels`, either a list of channels or a `.condarc` file with a
+    /// `channels` key. Relative paths are resolved against the source directory.
+    pub channels_file: Option<PathBuf>,
+
+    /// Extra variables that are added to the context of the recipe, e.g. a
+    /// `cdn_base` that is referenced by the sources as `${{ cdn_base }}`.
+    /// Variables that are defined by the backend take precedence.
+    #[serde(default)]
+    pub context: IndexMap<String, String>,
+    /// Commands that are run at the start of the build script, before the
+    /// generated commands, e.g. `set -euo pipefail` or sourcing a setup script.
+    #[serde(default)]
+    pub script_prelude: Vec<String>,
 }
 
 impl BackendConfig for MojoBackendConfig {
@@ -39,20 +218,139 @@ impl BackendConfig for MojoBackendConfig {
         self.debug_dir.as_deref()
     }
 
+    fn build_retries(&self) -> Option<u32> {
+        self.build_retries
+    }
+
+    fn archive_types(&self) -> &[String] {
+        &self.archive_types
+    }
+
+    fn record_provenance(&self) -> bool {
+        self.record_provenance.unwrap_or(true)
+    }
+
+    fn store_recipe(&self) -> bool {
+        self.store_recipe
+    }
+
+    fn default_version(&self) -> Option<&str> {
+        self.default_version.as_deref()
+    }
+
+    fn require_version(&self) -> bool {
+        self.require_version
+    }
+
+    fn deny_warnings(&self) -> bool {
+        self.deny_warnings
+    }
+
+    fn validate_license(&self) -> bool {
+        self.validate_license
+    }
+
+    fn hash_ignore_keys(&self) -> &[String] {
+        &self.hash_ignore_keys
+    }
+
+    fn operation_timeout(&self) -> Option<Duration> {
+        self.operation_timeout.map(Duration::from_secs)
+    }
+
+    fn build_dir(&self) -> Option<&Path> {
+        self.build_dir.as_deref()
+    }
+
+    fn temp_dir(&self) -> Option<&Path> {
+        self.temp_dir.as_deref()
+    }
+
+    fn solve_strategy(&self) -> SolveStrategy {
+        self.solve_strategy.unwrap_or_default()
+    }
+
+    fn profile(&self) -> Option<BuildProfile> {
+        self.profile
+    }
+
+    fn debug_resolved_recipes(&self) -> bool {
+        self.debug_resolved_recipes
+    }
+
+    fn extra_channels(&self) -> &[String] {
+        &self.extra_channels
+    }
+
+    fn extra_channel_priority(&self) -> ExtraChannelPriority {
+        self.extra_channel_priority.unwrap_or_default()
+    }
+
+    fn channels_file(&self) -> Option<&Path> {
+        self.channels_file.as_deref()
+    }
+
     /// Merge this configuration with a target-specific configuration.
     /// Target-specific values override base values using the following rules:
     ///
     /// - env: Platform env vars override base, others merge
+    /// - secrets: Platform-specific completely replaces base
     /// - debug_dir: Not allowed to have target specific value
     /// - extra_input_globs: Platform-specific completely replaces base
+    /// - remove_input_globs: Platform-specific completely replaces base
     /// - bins: Any bins with matching not-None names will be merged,
     ///   Any set-settings on the platform specific pkg override base
     ///   Any bins found only in target_config will be kept
     /// - pkg: Any set-settings on the platform specific pkg override base
+    /// - build_retries: Platform-specific takes precedence
+    /// - merge_build_and_host_envs: Platform-specific takes precedence
+    /// - run_constraints: Platform-specific completely replaces base
+    /// - build_only: Platform-specific completely replaces base
+    /// - always_copy_files: Platform-specific completely replaces base
+    /// - additional_sources: Platform-specific completely replaces base
+    /// - additional_sources_use_gitignore: Platform-specific takes precedence
+    /// - additional_sources_filter: Platform-specific takes precedence
+    /// - dedupe_requirements: Enabled if either base or target enables it
+    /// - build_string: Platform-specific takes precedence
+    /// - build_number_env: Platform-specific takes precedence
+    /// - build_number_from_git_count: Enabled if either base or target enables it
+    /// - license_family: Platform-specific takes precedence
+    /// - compilers: Platform-specific compilers are added to base, or to the
+    ///   default compilers if base doesn't set any
+    /// - stdlibs: Platform-specific completely replaces base
+    /// - variant_compilers: Platform-specific completely replaces base
+    /// - archive_types: Platform-specific completely replaces base
+    /// - record_provenance: Platform-specific takes precedence
+    /// - store_recipe: Enabled if either base or target enables it
+    /// - default_version: Platform-specific takes precedence
+    /// - require_version: Enabled if either base or target enables it
+    /// - deny_warnings: Enabled if either base or target enables it
+    /// - validate_license: Enabled if either base or target enables it
+    /// - hash_ignore_keys: Platform-specific completely replaces base
+    /// - operation_timeout: Not allowed to have target specific value
+    /// - build_dir: Not allowed to have target specific value
+    /// - temp_dir: Not allowed to have target specific value
+    /// - solve_strategy: Platform-specific takes precedence
+    /// - profile: Platform-specific takes precedence
+    /// - debug_resolved_recipes: Enabled if either base or target enables it
+    /// - extra_channels: Platform-specific completely replaces base
+    /// - extra_channel_priority: Platform-specific takes precedence
+    /// - channels_file: Platform-specific takes precedence
+    /// - context: Platform context variables override base, others merge
+    /// - script_prelude: Platform-specific completely replaces base
     fn merge_with_target_config(&self, target_config: &Self) -> miette::Result<Self> {
         if target_config.debug_dir.is_some() {
             miette::bail!("`debug_dir` cannot have a target specific value");
         }
+        if target_config.operation_timeout.is_some() {
+            miette::bail!("`operation_timeout` cannot have a target specific value");
+        }
+        if target_config.build_dir.is_some() {
+            miette::bail!("`build_dir` cannot have a target specific value");
+        }
+        if target_config.temp_dir.is_some() {
+            miette::bail!("`temp_dir` cannot have a target specific value");
+        }
 
         let pkg = if target_config.pkg.is_some() {
             if self.pkg.is_some() {
@@ -115,18 +413,132 @@ impl BackendConfig for MojoBackendConfig {
                 merged_env.extend(target_config.env.clone());
                 merged_env
             },
+            secrets: if target_config.secrets.is_empty() {
+                self.secrets.clone()
+            } else {
+                target_config.secrets.clone()
+            },
             debug_dir: self.debug_dir.clone(),
             extra_input_globs: if target_config.extra_input_globs.is_empty() {
                 self.extra_input_globs.clone()
             } else {
                 target_config.extra_input_globs.clone()
             },
+            remove_input_globs: if target_config.remove_input_globs.is_empty() {
+                self.remove_input_globs.clone()
+            } else {
+                target_config.remove_input_globs.clone()
+            },
             bins,
             pkg,
-            compilers: target_config
-                .compilers
+            compilers: merge_compilers(
+                self.compilers.as_deref(),
+                target_config.compilers.as_deref(),
+                DEFAULT_COMPILERS,
+            ),
+            stdlibs: target_config
+                .stdlibs
                 .clone()
-                .or_else(|| self.compilers.clone()),
+                .or_else(|| self.stdlibs.clone()),
+            variant_compilers: if target_config.variant_compilers.is_empty() {
+                self.variant_compilers.clone()
+            } else {
+                target_config.variant_compilers.clone()
+            },
+            build_retries: target_config.build_retries.or(self.build_retries),
+            merge_build_and_host_envs: target_config
+                .merge_build_and_host_envs
+                .or(self.merge_build_and_host_envs),
+            run_constraints: if target_config.run_constraints.is_empty() {
+                self.run_constraints.clone()
+            } else {
+                target_config.run_constraints.clone()
+            },
+            build_only: if target_config.build_only.is_empty() {
+                self.build_only.clone()
+            } else {
+                target_config.build_only.clone()
+            },
+            always_copy_files: if target_config.always_copy_files.is_empty() {
+                self.always_copy_files.clone()
+            } else {
+                target_config.always_copy_files.clone()
+            },
+            additional_sources: if target_config.additional_sources.is_empty() {
+                self.additional_sources.clone()
+            } else {
+                target_config.additional_sources.clone()
+            },
+            additional_sources_use_gitignore: target_config
+                .additional_sources_use_gitignore
+                .or(self.additional_sources_use_gitignore),
+            additional_sources_filter: target_config
+                .additional_sources_filter
+                .clone()
+                .or_else(|| self.additional_sources_filter.clone()),
+            dedupe_requirements: self.dedupe_requirements || target_config.dedupe_requirements,
+            build_string: target_config
+                .build_string
+                .clone()
+                .or_else(|| self.build_string.clone()),
+            build_number_env: target_config
+                .build_number_env
+                .clone()
+                .or_else(|| self.build_number_env.clone()),
+            build_number_from_git_count: self.build_number_from_git_count
+                || target_config.build_number_from_git_count,
+            license_family: target_config
+                .license_family
+                .clone()
+                .or_else(|| self.license_family.clone()),
+            archive_types: if target_config.archive_types.is_empty() {
+                self.archive_types.clone()
+            } else {
+                target_config.archive_types.clone()
+            },
+            record_provenance: target_config.record_provenance.or(self.record_provenance),
+            store_recipe: self.store_recipe || target_config.store_recipe,
+            default_version: target_config
+                .default_version
+                .clone()
+                .or(self.default_version.clone()),
+            require_version: self.require_version || target_config.require_version,
+            deny_warnings: self.deny_warnings || target_config.deny_warnings,
+            validate_license: self.validate_license || target_config.validate_license,
+            hash_ignore_keys: if target_config.hash_ignore_keys.is_empty() {
+                self.hash_ignore_keys.clone()
+            } else {
+                target_config.hash_ignore_keys.clone()
+            },
+            operation_timeout: self.operation_timeout,
+            build_dir: self.build_dir.clone(),
+            temp_dir: self.temp_dir.clone(),
+            solve_strategy: target_config.solve_strategy.or(self.solve_strategy),
+            profile: target_config.profile.or(self.profile),
+            debug_resolved_recipes: self.debug_resolved_recipes
+                || target_config.debug_resolved_recipes,
+            extra_channels: if target_config.extra_channels.is_empty() {
+                self.extra_channels.clone()
+            } else {
+                target_config.extra_channels.clone()
+            },
+            extra_channel_priority: target_config
+                .extra_channel_priority
+                .or(self.extra_channel_priority),
+            channels_file: target_config
+                .channels_file
+                .clone()
+                .or_else(|| self.channels_file.clone()),
+            context: {
+                let mut merged_context = self.context.clone();
+                merged_context.extend(target_config.context.clone());
+                merged_context
+            },
+            script_prelude: if target_config.script_prelude.is_empty() {
+                self.script_prelude.clone()
+            } else {
+                target_config.script_prelude.clone()
+            },
         })
     }
 }
@@ -1,14 +1,19 @@
 mod build_script;
 mod config;
+mod metadata;
 
 use build_script::BuildScriptContext;
-use config::{MojoBackendConfig, clean_project_name};
+use config::{DEFAULT_COMPILERS, MojoBackendConfig, clean_project_name};
+use metadata::MojoMetadataProvider;
 use miette::{Error, IntoDiagnostic};
-use pixi_build_backend::generated_recipe::DefaultMetadataProvider;
 use pixi_build_backend::{
-    compilers::add_compilers_and_stdlib_to_requirements,
-    generated_recipe::{GenerateRecipe, GeneratedRecipe, PythonParams},
+    compilers::{add_compilers_and_stdlib_to_requirements, with_variant_compilers},
+    error::BackendError,
+    generated_recipe::{
+        BackendConfig, BackendIdentifier, BuildParams, GenerateRecipe, GeneratedRecipe,
+    },
     intermediate_backend::IntermediateBackendInstantiator,
+    utils::BuildProfile,
 };
 use rattler_build::{NormalizedKey, recipe::variable::Variable};
 use rattler_conda_types::{PackageName, Platform};
@@ -20,6 +25,10 @@ use std::{
     sync::Arc,
 };
 
+/// The channels that distribute the mojo compiler, e.g.
+/// `https://conda.modular.com/max` and `https://conda.modular.com/max-nightly`.
+const MODULAR_CHANNEL: &str = "https://conda.modular.com/";
+
 #[derive(Default, Clone)]
 pub struct MojoGenerator {}
 
@@ -32,12 +41,17 @@ impl GenerateRecipe for MojoGenerator {
         config: &Self::Config,
         manifest_root: std::path::PathBuf,
         host_platform: rattler_conda_types::Platform,
-        _python_params: Option<PythonParams>,
+        build_params: Option<BuildParams>,
         variants: &HashSet<NormalizedKey>,
     ) -> miette::Result<GeneratedRecipe> {
-        let mut generated_recipe =
-            GeneratedRecipe::from_model(model.clone(), &mut DefaultMetadataProvider)
-                .into_diagnostic()?;
+        let mut metadata_provider = MojoMetadataProvider::new(&manifest_root);
+        let mut generated_recipe = GeneratedRecipe::from_model_with_default_version(
+            model.clone(),
+            &mut metadata_provider,
+            config.fallback_version(),
+            config.version_normalization(),
+        )
+        .map_err(BackendError::from)?;
 
         let cleaned_project_name = clean_project_name(
             generated_recipe
@@ -62,10 +76,14 @@ impl GenerateRecipe for MojoGenerator {
         );
 
         // Get the list of compilers from config, defaulting to ["mojo"] if not specified
-        let mut compilers = config
-            .compilers
-            .clone()
-            .unwrap_or_else(|| vec!["mojo".to_string()]);
+        let mut compilers = with_variant_compilers(
+            config
+                .compilers
+                .clone()
+                .unwrap_or_else(|| DEFAULT_COMPILERS.iter().map(ToString::to_string).collect()),
+            &config.variant_compilers,
+            variants,
+        );
 
         // Handle mojo compiler specially if it's in the list
         if let Some(idx) = compilers.iter().position(|name| name == "mojo") {
@@ -92,6 +110,7 @@ impl GenerateRecipe for MojoGenerator {
 
         add_compilers_and_stdlib_to_requirements(
             &compilers,
+            config.stdlibs.as_deref(),
             &mut requirements.build,
             &resolved_requirements.build,
             &host_platform,
@@ -102,6 +121,10 @@ impl GenerateRecipe for MojoGenerator {
             source_dir: manifest_root.display().to_string(),
             bins,
             pkg,
+            debug: build_params
+                .and_then(|params| params.profile)
+                .is_some_and(BuildProfile::is_dev),
+            prelude: config.script_prelude.clone(),
         }
         .render();
 
@@ -110,8 +133,44 @@ impl GenerateRecipe for MojoGenerator {
             env: config.env.clone(),
             ..Default::default()
         };
+        generated_recipe.recipe.build.merge_build_and_host_envs =
+            config.merge_build_and_host_envs.unwrap_or(false);
+        generated_recipe.add_run_constraints(&config.run_constraints)?;
+        generated_recipe.add_secrets(&config.secrets);
+        generated_recipe.move_to_build_requirements(&config.build_only)?;
+        generated_recipe.recipe.build.always_copy_files = config.always_copy_files.clone();
+        generated_recipe.add_path_sources(
+            &manifest_root,
+            &config.additional_sources,
+            config.additional_sources_use_gitignore,
+            config.additional_sources_filter.as_ref(),
+        )?;
+        if config.dedupe_requirements {
+            generated_recipe.dedupe_requirements();
+        }
+        generated_recipe.add_context(&config.context);
+        generated_recipe.discover_license_file(&manifest_root);
+        if let Some(build_string) = &config.build_string {
+            generated_recipe.recipe.build.string = Some(build_string.parse().into_diagnostic()?);
+        }
+        if config.build_number_from_git_count {
+            generated_recipe.set_build_number_from_git_count(&manifest_root);
+        }
+        if let Some(build_number_env) = &config.build_number_env {
+            generated_recipe.set_build_number_from_env(build_number_env);
+        }
+        if let Some(license_family) = &config.license_family {
+            generated_recipe
+                .recipe
+                .about
+                .get_or_insert_with(Default::default)
+                .license_family = Some(license_family.parse().into_diagnostic()?);
+        }
 
         generated_recipe.build_input_globs = Self::globs().collect::<BTreeSet<_>>();
+        if let Some(manifest_file_name) = metadata_provider.manifest_file_name() {
+            generated_recipe.record_input(manifest_file_name);
+        }
 
         Ok(generated_recipe)
     }
@@ -122,13 +181,43 @@ impl GenerateRecipe for MojoGenerator {
         _editable: bool,
     ) -> BTreeSet<String> {
         Self::globs()
+            .filter(|glob| !config.remove_input_globs.contains(glob))
             .chain(config.extra_input_globs.clone())
             .collect()
     }
 
-    fn default_variants(&self, _host_platform: Platform) -> BTreeMap<NormalizedKey, Vec<Variable>> {
+    fn default_variants(
+        &self,
+        _config: &Self::Config,
+        _host_platform: Platform,
+    ) -> BTreeMap<NormalizedKey, Vec<Variable>> {
         BTreeMap::new()
     }
+
+    fn supported_platforms(&self) -> Option<&[Platform]> {
+        // The platforms for which the mojo compiler is distributed.
+        Some(&[
+            Platform::Linux64,
+            Platform::LinuxAarch64,
+            Platform::OsxArm64,
+        ])
+    }
+
+    fn required_channels(&self) -> &[&'static str] {
+        // The mojo compiler is distributed through the modular channels.
+        &[MODULAR_CHANNEL]
+    }
+
+    fn expected_manifests(&self, _config: &Self::Config) -> &[&'static str] {
+        &metadata::MANIFEST_FILE_NAMES
+    }
+
+    fn backend_identifier(&self) -> Option<BackendIdentifier> {
+        Some(BackendIdentifier {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        })
+    }
 }
 
 impl MojoGenerator {
@@ -160,8 +249,15 @@ mod tests {
 
     use crate::config::{MojoBinConfig, MojoPkgConfig};
     use indexmap::IndexMap;
-    use pixi_build_types::ProjectModelV1;
+    use pixi_build_backend::protocol::ProtocolInstantiator;
+    use pixi_build_backend::utils::test::backend_manifest_path;
+    use pixi_build_types::{
+        ProjectModelV1,
+        procedures::{conda_outputs::CondaOutputsParams, initialize::InitializeParams},
+    };
+    use rattler_build::console_utils::LoggingOutputHandler;
     use recipe_stage0::recipe::{Item, Value};
+    use url::Url;
 
     use super::*;
 
@@ -227,6 +323,41 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_script_prelude_comes_first() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let generated_recipe = MojoGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &MojoBackendConfig {
+                    bins: Some(vec![MojoBinConfig {
+                        name: Some(String::from("example")),
+                        path: Some(String::from("./main.mojo")),
+                        extra_args: None,
+                    }]),
+                    script_prelude: vec![
+                        String::from("set -euo pipefail"),
+                        String::from("source ./env.sh"),
+                    ],
+                    ..Default::default()
+                },
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        assert_eq!(
+            generated_recipe.recipe.build.script.content[..3],
+            ["set -euo pipefail", "source ./env.sh", "mojo --version"]
+        );
+    }
+
     #[test]
     fn test_mojo_pkg_is_set() {
         let project_model = project_fixture!({
@@ -273,6 +404,52 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_dev_profile_builds_debug() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+        let generate = |profile| {
+            MojoGenerator::default()
+                .generate_recipe(
+                    &project_model,
+                    &MojoBackendConfig {
+                        bins: Some(vec![MojoBinConfig {
+                            name: Some(String::from("example")),
+                            path: Some(String::from("./main.mojo")),
+                            extra_args: None,
+                        }]),
+                        ..Default::default()
+                    },
+                    PathBuf::from("."),
+                    Platform::Linux64,
+                    Some(BuildParams {
+                        editable: false,
+                        profile,
+                    }),
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe")
+                .recipe
+                .build
+                .script
+                .content
+                .join("\n")
+        };
+
+        let script = generate(Some(BuildProfile::Dev));
+        assert!(
+            script.contains("mojo build -O0 --debug-level=full"),
+            "{script}"
+        );
+
+        let script = generate(Some(BuildProfile::Release));
+        assert!(!script.contains("--debug-level"), "{script}");
+        let script = generate(None);
+        assert!(!script.contains("--debug-level"), "{script}");
+    }
+
     #[test]
     fn test_compiler_is_in_build_requirements() {
         let project_model = project_fixture!({
@@ -312,6 +489,165 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn test_unsupported_platform_is_rejected() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let (backend, _) = IntermediateBackendInstantiator::<MojoGenerator>::new(
+            LoggingOutputHandler::default(),
+            Arc::default(),
+        )
+        .initialize(InitializeParams {
+            workspace_root: None,
+            source_dir: None,
+            manifest_path: PathBuf::from("pixi.toml"),
+            project_model: Some(project_model.into()),
+            configuration: None,
+            target_configuration: None,
+            cache_directory: None,
+        })
+        .await
+        .unwrap();
+
+        let err = backend
+            .conda_outputs(CondaOutputsParams {
+                channels: vec![],
+                host_platform: Platform::Win64,
+                build_platform: Platform::Win64,
+                variant_configuration: None,
+                work_directory: std::env::current_dir().unwrap(),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<BackendError>(),
+            Some(BackendError::UnsupportedPlatform(Platform::Win64, _))
+        ));
+        assert!(
+            err.to_string()
+                .contains("linux-64, linux-aarch64, osx-arm64")
+        );
+    }
+
+    #[test]
+    fn test_expected_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        // `backend_manifest_path` creates the backend for a `pixi.toml` in
+        // `dir`, which is also a mojo project manifest.
+        let err =
+            backend_manifest_path::<MojoGenerator>(dir.path(), serde_json::json!({})).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("`mojoproject.toml` or `pixi.toml`"),
+            "{err}"
+        );
+
+        fs_err::write(dir.path().join("pixi.toml"), "").unwrap();
+        assert_eq!(
+            backend_manifest_path::<MojoGenerator>(dir.path(), serde_json::json!({})).unwrap(),
+            Some(dir.path().join("pixi.toml"))
+        );
+
+        // A `mojoproject.toml` is preferred.
+        fs_err::write(dir.path().join("mojoproject.toml"), "").unwrap();
+        assert_eq!(
+            backend_manifest_path::<MojoGenerator>(dir.path(), serde_json::json!({})).unwrap(),
+            Some(dir.path().join("mojoproject.toml"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_modular_channel_is_reported() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(dir.path().join("pixi.toml"), "").unwrap();
+        fs_err::write(dir.path().join("main.mojo"), "").unwrap();
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        // Deny warnings, so the warning about the missing channel is returned
+        // as an error.
+        let (backend, _) = IntermediateBackendInstantiator::<MojoGenerator>::new(
+            LoggingOutputHandler::default(),
+            Arc::default(),
+        )
+        .initialize(InitializeParams {
+            workspace_root: None,
+            source_dir: Some(dir.path().to_path_buf()),
+            manifest_path: dir.path().join("pixi.toml"),
+            project_model: Some(project_model.into()),
+            configuration: Some(serde_json::json!({ "deny-warnings": true })),
+            target_configuration: None,
+            cache_directory: None,
+        })
+        .await
+        .unwrap();
+
+        let outputs_params = |channels: Vec<Url>| CondaOutputsParams {
+            channels,
+            host_platform: Platform::Linux64,
+            build_platform: Platform::Linux64,
+            variant_configuration: None,
+            work_directory: dir.path().to_path_buf(),
+        };
+        let conda_forge = Url::parse("https://prefix.dev/conda-forge").unwrap();
+        let err = backend
+            .conda_outputs(outputs_params(vec![conda_forge.clone()]))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(MODULAR_CHANNEL), "{err}");
+
+        let max = Url::parse("https://conda.modular.com/max-nightly").unwrap();
+        backend
+            .conda_outputs(outputs_params(vec![conda_forge, max]))
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_about_is_read_from_manifest() {
+        let project_model = project_fixture!({
+            "name": "mojo-example",
+            "version": "0.1.0",
+        });
+
+        let manifest_root =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mojoproject");
+        let generated_recipe = MojoGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &MojoBackendConfig::default(),
+                manifest_root,
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let about = generated_recipe.recipe.about.unwrap();
+        assert_eq!(
+            about.description.unwrap().to_string(),
+            "A small mojo library"
+        );
+        assert_eq!(about.license.unwrap().to_string(), "Apache-2.0");
+        assert_eq!(
+            about.homepage.unwrap().to_string(),
+            "https://example.com/mojo-example"
+        );
+        assert_eq!(
+            about.repository.unwrap().to_string(),
+            "https://github.com/example/mojo-example"
+        );
+
+        insta::assert_yaml_snapshot!(&generated_recipe.metadata_input_globs, @r###"
+        - mojoproject.toml
+        "###);
+    }
+
     #[test]
     fn test_env_vars_are_set() {
         let project_model = project_fixture!({
@@ -0,0 +1,223 @@
+use std::path::PathBuf;
+
+use miette::Diagnostic;
+use once_cell::unsync::OnceCell;
+use pixi_build_backend::generated_recipe::MetadataProvider;
+use serde::Deserialize;
+
+/// The manifests that are searched for metadata, in order of preference.
+pub(crate) const MANIFEST_FILE_NAMES: [&str; 2] = ["mojoproject.toml", "pixi.toml"];
+
+#[derive(Debug, thiserror::Error, Diagnostic)]
+pub enum MetadataError {
+    #[error("failed to parse {0}, {1}")]
+    Manifest(String, toml_edit::de::Error),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// A metadata field of the `package` table, which can also be inherited from
+/// the workspace with `{ workspace = true }`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ManifestValue {
+    Value(String),
+    #[allow(dead_code)]
+    Inherited {
+        workspace: bool,
+    },
+}
+
+impl ManifestValue {
+    fn value(&self) -> Option<&String> {
+        match self {
+            ManifestValue::Value(value) => Some(value),
+            ManifestValue::Inherited { .. } => None,
+        }
+    }
+}
+
+/// The metadata fields of a table in a mojo project manifest.
+#[derive(Debug, Default, Deserialize)]
+struct ManifestMetadata {
+    description: Option<ManifestValue>,
+    license: Option<ManifestValue>,
+    homepage: Option<ManifestValue>,
+    repository: Option<ManifestValue>,
+    documentation: Option<ManifestValue>,
+}
+
+/// The parts of a `mojoproject.toml` or `pixi.toml` manifest that contain
+/// metadata about the project.
+#[derive(Debug, Default, Deserialize)]
+struct Manifest {
+    package: Option<ManifestMetadata>,
+    workspace: Option<ManifestMetadata>,
+    project: Option<ManifestMetadata>,
+}
+
+impl Manifest {
+    /// Returns the first value that is set in the `package`, `workspace` or
+    /// `project` table, in that order. Values that the package inherits are
+    /// thereby taken from the workspace.
+    fn field(&self, get: impl Fn(&ManifestMetadata) -> Option<&ManifestValue>) -> Option<String> {
+        [&self.package, &self.workspace, &self.project]
+            .into_iter()
+            .flatten()
+            .find_map(|metadata| get(metadata).and_then(ManifestValue::value))
+            .cloned()
+    }
+}
+
+/// An implementation of [`MetadataProvider`] that reads metadata from a
+/// `mojoproject.toml` or `pixi.toml` manifest.
+///
+/// The name and version are not provided, these are always taken from the
+/// project model.
+pub struct MojoMetadataProvider {
+    manifest_root: PathBuf,
+    manifest: OnceCell<Option<(String, Manifest)>>,
+}
+
+impl MojoMetadataProvider {
+    /// Constructs a new `MojoMetadataProvider` with the given manifest root.
+    ///
+    /// # Arguments
+    ///
+    /// * `manifest_root` - The directory that contains the `mojoproject.toml`
+    ///   or `pixi.toml` file
+    pub fn new(manifest_root: impl Into<PathBuf>) -> Self {
+        Self {
+            manifest_root: manifest_root.into(),
+            manifest: OnceCell::default(),
+        }
+    }
+
+    /// Ensures that the manifest is loaded, returns `None` if the project has
+    /// no manifest.
+    fn ensure_manifest(&self) -> Result<Option<&Manifest>, MetadataError> {
+        let manifest = self.manifest.get_or_try_init(|| {
+            let Some(file_name) = MANIFEST_FILE_NAMES
+                .into_iter()
+                .find(|file_name| self.manifest_root.join(file_name).is_file())
+            else {
+                return Ok(None);
+            };
+            let content = fs_err::read_to_string(self.manifest_root.join(file_name))?;
+            let manifest = toml_edit::de::from_str(&content)
+                .map_err(|err| MetadataError::Manifest(file_name.to_string(), err))?;
+            Ok::<_, MetadataError>(Some((file_name.to_string(), manifest)))
+        })?;
+        Ok(manifest.as_ref().map(|(_, manifest)| manifest))
+    }
+
+    /// Returns the value of a metadata field from the manifest.
+    fn field(
+        &self,
+        get: impl Fn(&ManifestMetadata) -> Option<&ManifestValue>,
+    ) -> Result<Option<String>, MetadataError> {
+        Ok(self
+            .ensure_manifest()?
+            .and_then(|manifest| manifest.field(get)))
+    }
+
+    /// Returns the file name of the manifest that the metadata was read from,
+    /// if any.
+    pub fn manifest_file_name(&self) -> Option<&str> {
+        self.manifest
+            .get()
+            .and_then(Option::as_ref)
+            .map(|(file_name, _)| file_name.as_str())
+    }
+}
+
+impl MetadataProvider for MojoMetadataProvider {
+    type Error = MetadataError;
+
+    fn homepage(&mut self) -> Result<Option<String>, Self::Error> {
+        self.field(|metadata| metadata.homepage.as_ref())
+    }
+
+    fn license(&mut self) -> Result<Option<String>, Self::Error> {
+        self.field(|metadata| metadata.license.as_ref())
+    }
+
+    fn description(&mut self) -> Result<Option<String>, Self::Error> {
+        self.field(|metadata| metadata.description.as_ref())
+    }
+
+    fn documentation(&mut self) -> Result<Option<String>, Self::Error> {
+        self.field(|metadata| metadata.documentation.as_ref())
+    }
+
+    fn repository(&mut self) -> Result<Option<String>, Self::Error> {
+        self.field(|metadata| metadata.repository.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn test_metadata_from_mojoproject() {
+        let mut provider = MojoMetadataProvider::new(fixture("mojoproject"));
+
+        assert_eq!(
+            provider.description().unwrap(),
+            Some("A small mojo library".to_string())
+        );
+        assert_eq!(provider.license().unwrap(), Some("Apache-2.0".to_string()));
+        assert_eq!(
+            provider.homepage().unwrap(),
+            Some("https://example.com/mojo-example".to_string())
+        );
+        assert_eq!(
+            provider.repository().unwrap(),
+            Some("https://github.com/example/mojo-example".to_string())
+        );
+        assert_eq!(provider.name().unwrap(), None);
+        assert_eq!(provider.manifest_file_name(), Some("mojoproject.toml"));
+    }
+
+    #[test]
+    fn test_package_table_takes_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        fs_err::write(
+            dir.path().join("pixi.toml"),
+            r#"
+[workspace]
+description = "The workspace"
+license = "MIT"
+
+[package]
+description = "The package"
+license = { workspace = true }
+"#,
+        )
+        .unwrap();
+
+        let mut provider = MojoMetadataProvider::new(dir.path());
+        assert_eq!(
+            provider.description().unwrap(),
+            Some("The package".to_string())
+        );
+        assert_eq!(provider.license().unwrap(), Some("MIT".to_string()));
+    }
+
+    #[test]
+    fn test_without_manifest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut provider = MojoMetadataProvider::new(dir.path());
+        assert_eq!(provider.description().unwrap(), None);
+        assert_eq!(provider.manifest_file_name(), None);
+    }
+}
@@ -11,6 +11,9 @@ pub struct BuildScriptContext {
     pub installer: Installer,
     pub build_platform: BuildPlatform,
     pub editable: bool,
+    /// Whether the package is built in an isolated environment. If false,
+    /// `--no-build-isolation` is passed to the installer.
+    pub build_isolation: bool,
     pub manifest_root: PathBuf,
 }
 
@@ -60,3 +63,30 @@ impl BuildScriptContext {
         rendered.lines().map(|s| s.to_string()).collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(build_isolation: bool) -> String {
+        BuildScriptContext {
+            installer: Installer::Pip,
+            build_platform: BuildPlatform::Unix,
+            editable: false,
+            build_isolation,
+            manifest_root: PathBuf::from("my-project"),
+        }
+        .render()
+        .join("\n")
+    }
+
+    #[test]
+    fn test_build_script_with_build_isolation() {
+        insta::assert_snapshot!(render(true));
+    }
+
+    #[test]
+    fn test_build_script_without_build_isolation() {
+        insta::assert_snapshot!(render(false));
+    }
+}
@@ -1,7 +1,18 @@
 use indexmap::IndexMap;
-use pixi_build_backend::generated_recipe::BackendConfig;
+use pixi_build_backend::{
+    compilers::merge_compilers,
+    generated_recipe::BackendConfig,
+    utils::{BuildProfile, ExtraChannelPriority, SolveStrategy, VersionNormalization},
+};
+use recipe_stage0::recipe::SourceFilter;
 use serde::{Deserialize, Serialize};
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The compilers that are used if `compilers` is not configured.
+pub(crate) const DEFAULT_COMPILERS: &[&str] = &[];
 
 #[derive(Debug, Default, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
@@ -13,17 +24,197 @@ pub struct PythonBackendConfig {
     /// Environment Variables
     #[serde(default)]
     pub env: IndexMap<String, String>,
+    /// Names of environment variables that are passed to the build script as
+    /// secrets, their values are masked in the build log.
+    #[serde(default)]
+    pub secrets: Vec<String>,
     /// If set, internal state will be logged as files in that directory
     pub debug_dir: Option<PathBuf>,
     /// Extra input globs to include in addition to the default ones
     #[serde(default)]
     pub extra_input_globs: Vec<String>,
+    /// Default input globs of the backend that are removed, e.g. to not
+    /// rebuild the package when the documentation changes.
+    #[serde(default)]
+    pub remove_input_globs: Vec<String>,
     /// List of compilers to use (e.g., ["c", "cxx", "rust"])
     /// If not specified, no compilers are added (since most Python packages are pure Python)
     pub compilers: Option<Vec<String>>,
+    /// List of standard libraries to add (e.g., ["c", "fortran"]). Each one is
+    /// only added if the variants contain a matching `<name>_stdlib` key.
+    /// If not specified, the `c` standard library is used for the compilers
+    pub stdlibs: Option<Vec<String>>,
+    /// Compilers that are only added if the variants contain a matching
+    /// `<name>_compiler_version` key, e.g. `cuda` for `cuda_compiler_version`.
+    #[serde(default)]
+    pub variant_compilers: Vec<String>,
+    /// Number of times a failed build is retried before giving up. The
+    /// work directory is cleaned between attempts. Capped at 5.
+    pub build_retries: Option<u32>,
     /// Ignore the pyproject.toml manifest and rely only on the project model.
     #[serde(default)]
     pub ignore_pyproject_manifest: Option<bool>,
+    /// Constraints that are added to the run requirements of the package
+    /// without making them a hard dependency (e.g. `numpy >=1.20`).
+    #[serde(default)]
+    pub run_constraints: Vec<String>,
+    /// Names of declared dependencies that are only needed at build time.
+    /// These are moved from the host and run requirements into the build
+    /// requirements (e.g. `make`).
+    #[serde(default)]
+    pub build_only: Vec<String>,
+    /// Globs of files that are copied into the package instead of being
+    /// hardlinked, e.g. data files that are modified after installation.
+    #[serde(default)]
+    pub always_copy_files: Vec<String>,
+    /// Directories, relative to the manifest root, that are added as
+    /// additional path sources, e.g. a data directory that the build needs.
+    #[serde(default)]
+    pub additional_sources: Vec<String>,
+    /// Whether files that are ignored by git are left out of the
+    /// `additional_sources`. Defaults to `true`.
+    pub additional_sources_use_gitignore: Option<bool>,
+    /// Include and exclude globs, relative to every directory of the
+    /// `additional_sources`, that select the files that are part of it.
+    pub additional_sources_filter: Option<SourceFilter>,
+    /// Whether dependencies are removed from the host requirements if they
+    /// are already part of the build requirements with the same spec.
+    #[serde(default)]
+    pub dedupe_requirements: bool,
+    /// An explicit build string template, e.g. `${{ hash }}_custom`. If not
+    /// set, the build string is derived from the hash and the build number.
+    pub build_string: Option<String>,
+    /// The name of an environment variable that holds the build number, e.g.
+    /// `CONDA_BUILD_NUMBER`. If the variable is set, its value is used as the
+    /// build number of the package.
+    pub build_number_env: Option<String>,
+    /// Whether the build number is set to the number of commits in the git
+    /// repository of the manifest. The `build_number_env` takes precedence.
+    #[serde(default)]
+    pub build_number_from_git_count: bool,
+    /// The conda license family of the package, e.g. `MIT` or `APACHE`. If not
+    /// set, it is derived from the license when possible.
+    pub license_family: Option<String>,
+    /// Whether the installer builds the package in an isolated environment
+    /// (PEP 517). Unless it is enabled, the package is built without isolation
+    /// and the build-system requirements from the pyproject.toml are added to
+    /// the host requirements.
+    pub build_isolation: Option<bool>,
+    /// The name of a dependency group (PEP 735) in the pyproject.toml. If set,
+    /// a test is added to the recipe that runs the `test_script` with the
+    /// packages of this group installed.
+    pub test_dependency_group: Option<String>,
+    /// The commands of the test that is added for the `test_dependency_group`.
+    /// Defaults to `pytest` if the group contains `pytest`.
+    #[serde(default)]
+    pub test_script: Vec<String>,
+    /// Whether the build requirements are also installed in the build
+    /// environment of the generated script tests, e.g. to compile a test
+    /// program with the same toolchain as the package.
+    #[serde(default)]
+    pub build_deps_in_test: bool,
+    /// The archive types of the built packages, e.g. `["conda", "tar.bz2"]`.
+    /// Defaults to `["conda"]`.
+    #[serde(default)]
+    pub archive_types: Vec<String>,
+    /// Whether the package is installed in editable mode. If set, this takes
+    /// precedence over the deprecated `BUILD_EDITABLE_PYTHON` environment
+    /// variable and the value requested by the frontend.
+    pub editable: Option<bool>,
+    /// Whether the name and the version of the backend are recorded in the
+    /// extra metadata of the built packages. Defaults to `true`.
+    pub record_provenance: Option<bool>,
+    /// Whether the rendered recipe is stored in the `info/recipe` directory of
+    /// the built packages.
+    #[serde(default)]
+    pub store_recipe: bool,
+    /// The version that is used if neither the manifest nor the package
+    /// metadata define a version.
+    pub default_version: Option<String>,
+    /// Whether a missing version is an error, even if a `default_version` is
+    /// set.
+    #[serde(default)]
+    pub require_version: bool,
+    /// Turns warnings that occur while generating the recipe into errors.
+    #[serde(default)]
+    pub deny_warnings: bool,
+    /// Whether the license of the package must be a valid SPDX license
+    /// expression. Templated licenses are not checked.
+    #[serde(default)]
+    pub validate_license: bool,
+    /// Variant keys that don't influence the hash of the build string, e.g.
+    /// keys that only have a cosmetic effect on the package.
+    #[serde(default)]
+    pub hash_ignore_keys: Vec<String>,
+    /// The maximum duration of a single backend operation in seconds, e.g.
+    /// solving the environments and building the package. Operations that
+    /// take longer fail with a timeout error.
+    pub operation_timeout: Option<u64>,
+    /// A base directory for the build directories of the packages, e.g. on a
+    /// faster local disk. Every package is built in a subdirectory named after
+    /// the work directory that is requested by the frontend.
+    pub build_dir: Option<PathBuf>,
+    /// The directory that the rendered recipes are temporarily written to,
+    /// e.g. if the default location is on a small or slow disk. Defaults to the
+    /// output directory of the build.
+    pub temp_dir: Option<PathBuf>,
+    /// The strategy that selects the versions of the packages when the build
+    /// and host environments are solved, `highest` (the default), `lowest` or
+    /// `lowest-direct`.
+    pub solve_strategy: Option<SolveStrategy>,
+    /// How the version that is read from the manifest is converted to the
+    /// version of the recipe, e.g. `conda` to turn `1.0.0-alpha.1` into
+    /// `1.0.0a1`. Defaults to using the version as it is.
+    pub version_normalization: Option<VersionNormalization>,
+    /// The profile that the package is built with, e.g. `dev` for a debug
+    /// build. If not set, the defaults of the backend are used.
+    pub profile: Option<BuildProfile>,
+    /// Whether the fully resolved recipe of every output is written to the
+    /// `debug_dir`, after all templates and selectors have been evaluated.
+    #[serde(default)]
+    pub debug_resolved_recipes: bool,
+    /// Channels that are used in addition to the channels that are requested
+    /// by the frontend, either as url or as channel name.
+    #[serde(default)]
+    pub extra_channels: Vec<String>,
+    /// Whether the `extra_channels` are added after (`lowest`, the default)
+    /// or before (`highest`) the channels of the frontend.
+    pub extra_channel_priority: Option<ExtraChannelPriority>,
+    /// A YAML file with channels that are used in addition to the
+    /// `extra_channels`, either a list of channels or a `.condarc` file with a
+    /// `channels` key. Relative paths are resolved against the source directory.
+    pub channels_file: Option<PathBuf>,
+    /// Extra variables that are added to the context of the recipe, e.g. a
+    /// `cdn_base` that is referenced by the sources as `${{ cdn_base }}`.
+    /// Variables that are defined by the backend take precedence.
+    #[serde(default)]
+    pub context: IndexMap<String, String>,
+    /// Extra entry points of the package in the `name = module:function`
+    /// form. These are added to the entry points from the `pyproject.toml`,
+    /// replacing entry points with the same name.
+    #[serde(default)]
+    pub entry_points: Vec<String>,
+    /// Extra entry points that are only added when the host platform is
+    /// windows, e.g. for wrappers that only work on windows. These replace
+    /// entry points with the same name from `entry_points`.
+    #[serde(default)]
+    pub entry_points_windows: Vec<String>,
+    /// Extra entry points that are only added when the host platform is a
+    /// unix platform, like linux or macOS. These replace entry points with the
+    /// same name from `entry_points`.
+    #[serde(default)]
+    pub entry_points_unix: Vec<String>,
+    /// Whether a noarch python package gets a test that imports its top-level
+    /// module, derived from the package name. Defaults to `true`.
+    pub generate_import_test: Option<bool>,
+    /// Extras from the `[project.optional-dependencies]` of the pyproject.toml
+    /// whose dependencies are added as run constraints. The dependencies are
+    /// not installed, but have to be compatible if they are.
+    #[serde(default)]
+    pub extras_as_constraints: Vec<String>,
+    /// Whether `python` is added to the host and run requirements and the
+    /// installer to the host requirements. Defaults to `true`.
+    pub auto_python_deps: Option<bool>,
 }
 
 impl PythonBackendConfig {
@@ -32,6 +223,22 @@ impl PythonBackendConfig {
         self.noarch.unwrap_or(true)
     }
 
+    /// Whether entry points are configured that are only added for windows or
+    /// unix host platforms.
+    pub fn has_platform_entry_points(&self) -> bool {
+        !self.entry_points_windows.is_empty() || !self.entry_points_unix.is_empty()
+    }
+
+    /// Whether an import test is generated for noarch python packages.
+    pub fn generate_import_test(&self) -> bool {
+        self.generate_import_test.unwrap_or(true)
+    }
+
+    /// Whether `python` and the installer are added to the requirements.
+    pub fn auto_python_deps(&self) -> bool {
+        self.auto_python_deps.unwrap_or(true)
+    }
+
     /// Creates a new [`PythonBackendConfig`] with default values and
     /// `ignore_pyproject_manifest` set to `true`.
     #[cfg(test)]
@@ -43,21 +250,188 @@ impl PythonBackendConfig {
     }
 }
 
+/// The environment variable that was used to enforce editable installations
+/// before the `editable` option existed.
+const BUILD_EDITABLE_PYTHON_ENV: &str = "BUILD_EDITABLE_PYTHON";
+
+/// Determines whether to install the package in editable mode. The configured
+/// value wins over the environment variable, which in turn wins over the value
+/// that was requested by the frontend.
+fn resolve_editable(configured: Option<bool>, env_value: Option<&str>, default: bool) -> bool {
+    if let Some(editable) = configured {
+        return editable;
+    }
+    if let Some(value) = env_value {
+        tracing::warn!(
+            "`{BUILD_EDITABLE_PYTHON_ENV}` is deprecated, set `editable` or the `dev` profile in the build configuration instead"
+        );
+        return value == "true";
+    }
+    default
+}
+
 impl BackendConfig for PythonBackendConfig {
     fn debug_dir(&self) -> Option<&Path> {
         self.debug_dir.as_deref()
     }
 
+    fn build_retries(&self) -> Option<u32> {
+        self.build_retries
+    }
+
+    fn archive_types(&self) -> &[String] {
+        &self.archive_types
+    }
+
+    fn record_provenance(&self) -> bool {
+        self.record_provenance.unwrap_or(true)
+    }
+
+    fn store_recipe(&self) -> bool {
+        self.store_recipe
+    }
+
+    fn default_version(&self) -> Option<&str> {
+        self.default_version.as_deref()
+    }
+
+    fn require_version(&self) -> bool {
+        self.require_version
+    }
+
+    fn deny_warnings(&self) -> bool {
+        self.deny_warnings
+    }
+
+    fn validate_license(&self) -> bool {
+        self.validate_license
+    }
+
+    fn hash_ignore_keys(&self) -> &[String] {
+        &self.hash_ignore_keys
+    }
+
+    fn operation_timeout(&self) -> Option<Duration> {
+        self.operation_timeout.map(Duration::from_secs)
+    }
+
+    fn build_dir(&self) -> Option<&Path> {
+        self.build_dir.as_deref()
+    }
+
+    fn temp_dir(&self) -> Option<&Path> {
+        self.temp_dir.as_deref()
+    }
+
+    fn solve_strategy(&self) -> SolveStrategy {
+        self.solve_strategy.unwrap_or_default()
+    }
+
+    fn profile(&self) -> Option<BuildProfile> {
+        self.profile
+    }
+
+    /// The `dev` profile implies an editable install unless `editable` is
+    /// configured explicitly.
+    fn editable(&self, requested: bool) -> bool {
+        let configured = match self.profile {
+            Some(BuildProfile::Dev) => self.editable.or(Some(true)),
+            _ => self.editable,
+        };
+        resolve_editable(
+            configured,
+            std::env::var(BUILD_EDITABLE_PYTHON_ENV).ok().as_deref(),
+            requested,
+        )
+    }
+
+    fn version_normalization(&self) -> VersionNormalization {
+        self.version_normalization.unwrap_or_default()
+    }
+
+    fn debug_resolved_recipes(&self) -> bool {
+        self.debug_resolved_recipes
+    }
+
+    fn extra_channels(&self) -> &[String] {
+        &self.extra_channels
+    }
+
+    fn extra_channel_priority(&self) -> ExtraChannelPriority {
+        self.extra_channel_priority.unwrap_or_default()
+    }
+
+    fn channels_file(&self) -> Option<&Path> {
+        self.channels_file.as_deref()
+    }
+
     /// Merge this configuration with a target-specific configuration.
     /// Target-specific values override base values using the following rules:
     /// - noarch: Platform-specific takes precedence (critical for cross-platform)
     /// - env: Platform env vars override base, others merge
+    /// - secrets: Platform-specific completely replaces base
     /// - debug_dir: Not allowed to have target specific value
     /// - extra_input_globs: Platform-specific completely replaces base
+    /// - remove_input_globs: Platform-specific completely replaces base
+    /// - build_retries: Platform-specific takes precedence
+    /// - run_constraints: Platform-specific completely replaces base
+    /// - build_only: Platform-specific completely replaces base
+    /// - always_copy_files: Platform-specific completely replaces base
+    /// - additional_sources: Platform-specific completely replaces base
+    /// - additional_sources_use_gitignore: Platform-specific takes precedence
+    /// - additional_sources_filter: Platform-specific takes precedence
+    /// - dedupe_requirements: Enabled if either base or target enables it
+    /// - build_string: Platform-specific takes precedence
+    /// - build_number_env: Platform-specific takes precedence
+    /// - build_number_from_git_count: Enabled if either base or target enables it
+    /// - license_family: Platform-specific takes precedence
+    /// - build_isolation: Platform-specific takes precedence
+    /// - test_dependency_group: Platform-specific takes precedence
+    /// - test_script: Platform-specific completely replaces base
+    /// - build_deps_in_test: Enabled if either base or target enables it
+    /// - compilers: Platform-specific compilers are added to base, or to the
+    ///   default compilers if base doesn't set any
+    /// - stdlibs: Platform-specific completely replaces base
+    /// - variant_compilers: Platform-specific completely replaces base
+    /// - archive_types: Platform-specific completely replaces base
+    /// - editable: Platform-specific takes precedence
+    /// - record_provenance: Platform-specific takes precedence
+    /// - store_recipe: Enabled if either base or target enables it
+    /// - default_version: Platform-specific takes precedence
+    /// - require_version: Enabled if either base or target enables it
+    /// - deny_warnings: Enabled if either base or target enables it
+    /// - validate_license: Enabled if either base or target enables it
+    /// - hash_ignore_keys: Platform-specific completely replaces base
+    /// - operation_timeout: Not allowed to have target specific value
+    /// - build_dir: Not allowed to have target specific value
+    /// - temp_dir: Not allowed to have target specific value
+    /// - solve_strategy: Platform-specific takes precedence
+    /// - version_normalization: Platform-specific takes precedence
+    /// - profile: Platform-specific takes precedence
+    /// - debug_resolved_recipes: Enabled if either base or target enables it
+    /// - extra_channels: Platform-specific completely replaces base
+    /// - extra_channel_priority: Platform-specific takes precedence
+    /// - channels_file: Platform-specific takes precedence
+    /// - context: Platform context variables override base, others merge
+    /// - entry_points: Platform-specific completely replaces base
+    /// - entry_points_windows: Platform-specific completely replaces base
+    /// - entry_points_unix: Platform-specific completely replaces base
+    /// - generate_import_test: Platform-specific takes precedence
+    /// - extras_as_constraints: Platform-specific completely replaces base
+    /// - auto_python_deps: Platform-specific takes precedence
     fn merge_with_target_config(&self, target_config: &Self) -> miette::Result<Self> {
         if target_config.debug_dir.is_some() {
             miette::bail!("`debug_dir` cannot have a target specific value");
         }
+        if target_config.operation_timeout.is_some() {
+            miette::bail!("`operation_timeout` cannot have a target specific value");
+        }
+        if target_config.build_dir.is_some() {
+            miette::bail!("`build_dir` cannot have a target specific value");
+        }
+        if target_config.temp_dir.is_some() {
+            miette::bail!("`temp_dir` cannot have a target specific value");
+        }
 
         Ok(Self {
             noarch: target_config.noarch.or(self.noarch),
@@ -66,27 +440,175 @@ impl BackendConfig for PythonBackendConfig {
                 merged_env.extend(target_config.env.clone());
                 merged_env
             },
+            secrets: if target_config.secrets.is_empty() {
+                self.secrets.clone()
+            } else {
+                target_config.secrets.clone()
+            },
             debug_dir: self.debug_dir.clone(),
             extra_input_globs: if target_config.extra_input_globs.is_empty() {
                 self.extra_input_globs.clone()
             } else {
                 target_config.extra_input_globs.clone()
             },
-            compilers: target_config
-                .compilers
+            remove_input_globs: if target_config.remove_input_globs.is_empty() {
+                self.remove_input_globs.clone()
+            } else {
+                target_config.remove_input_globs.clone()
+            },
+            compilers: merge_compilers(
+                self.compilers.as_deref(),
+                target_config.compilers.as_deref(),
+                DEFAULT_COMPILERS,
+            ),
+            stdlibs: target_config
+                .stdlibs
                 .clone()
-                .or_else(|| self.compilers.clone()),
+                .or_else(|| self.stdlibs.clone()),
+            variant_compilers: if target_config.variant_compilers.is_empty() {
+                self.variant_compilers.clone()
+            } else {
+                target_config.variant_compilers.clone()
+            },
+            build_retries: target_config.build_retries.or(self.build_retries),
             ignore_pyproject_manifest: target_config
                 .ignore_pyproject_manifest
                 .or(self.ignore_pyproject_manifest),
+            run_constraints: if target_config.run_constraints.is_empty() {
+                self.run_constraints.clone()
+            } else {
+                target_config.run_constraints.clone()
+            },
+            build_only: if target_config.build_only.is_empty() {
+                self.build_only.clone()
+            } else {
+                target_config.build_only.clone()
+            },
+            always_copy_files: if target_config.always_copy_files.is_empty() {
+                self.always_copy_files.clone()
+            } else {
+                target_config.always_copy_files.clone()
+            },
+            additional_sources: if target_config.additional_sources.is_empty() {
+                self.additional_sources.clone()
+            } else {
+                target_config.additional_sources.clone()
+            },
+            additional_sources_use_gitignore: target_config
+                .additional_sources_use_gitignore
+                .or(self.additional_sources_use_gitignore),
+            additional_sources_filter: target_config
+                .additional_sources_filter
+                .clone()
+                .or_else(|| self.additional_sources_filter.clone()),
+            dedupe_requirements: self.dedupe_requirements || target_config.dedupe_requirements,
+            build_string: target_config
+                .build_string
+                .clone()
+                .or_else(|| self.build_string.clone()),
+            build_number_env: target_config
+                .build_number_env
+                .clone()
+                .or_else(|| self.build_number_env.clone()),
+            build_number_from_git_count: self.build_number_from_git_count
+                || target_config.build_number_from_git_count,
+            license_family: target_config
+                .license_family
+                .clone()
+                .or_else(|| self.license_family.clone()),
+            build_isolation: target_config.build_isolation.or(self.build_isolation),
+            test_dependency_group: target_config
+                .test_dependency_group
+                .clone()
+                .or_else(|| self.test_dependency_group.clone()),
+            test_script: if target_config.test_script.is_empty() {
+                self.test_script.clone()
+            } else {
+                target_config.test_script.clone()
+            },
+            build_deps_in_test: self.build_deps_in_test || target_config.build_deps_in_test,
+            archive_types: if target_config.archive_types.is_empty() {
+                self.archive_types.clone()
+            } else {
+                target_config.archive_types.clone()
+            },
+            editable: target_config.editable.or(self.editable),
+            record_provenance: target_config.record_provenance.or(self.record_provenance),
+            store_recipe: self.store_recipe || target_config.store_recipe,
+            default_version: target_config
+                .default_version
+                .clone()
+                .or(self.default_version.clone()),
+            require_version: self.require_version || target_config.require_version,
+            deny_warnings: self.deny_warnings || target_config.deny_warnings,
+            validate_license: self.validate_license || target_config.validate_license,
+            hash_ignore_keys: if target_config.hash_ignore_keys.is_empty() {
+                self.hash_ignore_keys.clone()
+            } else {
+                target_config.hash_ignore_keys.clone()
+            },
+            operation_timeout: self.operation_timeout,
+            build_dir: self.build_dir.clone(),
+            temp_dir: self.temp_dir.clone(),
+            solve_strategy: target_config.solve_strategy.or(self.solve_strategy),
+            version_normalization: target_config
+                .version_normalization
+                .or(self.version_normalization),
+            profile: target_config.profile.or(self.profile),
+            debug_resolved_recipes: self.debug_resolved_recipes
+                || target_config.debug_resolved_recipes,
+            extra_channels: if target_config.extra_channels.is_empty() {
+                self.extra_channels.clone()
+            } else {
+                target_config.extra_channels.clone()
+            },
+            extra_channel_priority: target_config
+                .extra_channel_priority
+                .or(self.extra_channel_priority),
+            channels_file: target_config
+                .channels_file
+                .clone()
+                .or_else(|| self.channels_file.clone()),
+            context: {
+                let mut merged_context = self.context.clone();
+                merged_context.extend(target_config.context.clone());
+                merged_context
+            },
+            entry_points: if target_config.entry_points.is_empty() {
+                self.entry_points.clone()
+            } else {
+                target_config.entry_points.clone()
+            },
+            entry_points_windows: if target_config.entry_points_windows.is_empty() {
+                self.entry_points_windows.clone()
+            } else {
+                target_config.entry_points_windows.clone()
+            },
+            entry_points_unix: if target_config.entry_points_unix.is_empty() {
+                self.entry_points_unix.clone()
+            } else {
+                target_config.entry_points_unix.clone()
+            },
+            generate_import_test: target_config
+                .generate_import_test
+                .or(self.generate_import_test),
+            extras_as_constraints: if target_config.extras_as_constraints.is_empty() {
+                self.extras_as_constraints.clone()
+            } else {
+                target_config.extras_as_constraints.clone()
+            },
+            auto_python_deps: target_config.auto_python_deps.or(self.auto_python_deps),
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::PythonBackendConfig;
-    use pixi_build_backend::generated_recipe::BackendConfig;
+    use super::{PythonBackendConfig, SourceFilter, resolve_editable};
+    use pixi_build_backend::{
+        generated_recipe::BackendConfig,
+        utils::{BuildProfile, ExtraChannelPriority, SolveStrategy, VersionNormalization},
+    };
     use serde_json::json;
     use std::path::PathBuf;
 
@@ -105,10 +627,56 @@ mod tests {
         let base_config = PythonBackendConfig {
             noarch: Some(true),
             env: base_env,
+            secrets: vec!["BASE_TOKEN".to_string()],
             debug_dir: Some(PathBuf::from("/base/debug")),
             extra_input_globs: vec!["*.base".to_string()],
+            remove_input_globs: vec![],
             compilers: Some(vec!["c".to_string()]),
+            stdlibs: None,
+            variant_compilers: vec![],
+            build_retries: Some(2),
             ignore_pyproject_manifest: Some(true),
+            run_constraints: vec!["numpy >=1.20".to_string()],
+            build_only: vec!["make".to_string()],
+            always_copy_files: vec!["share/data/**".to_string()],
+            additional_sources: vec![],
+            additional_sources_filter: None,
+            additional_sources_use_gitignore: None,
+            dedupe_requirements: false,
+            build_string: None,
+            build_number_env: Some("BASE_BUILD_NUMBER".to_string()),
+            build_number_from_git_count: false,
+            license_family: None,
+            build_isolation: None,
+            test_dependency_group: None,
+            test_script: vec![],
+            build_deps_in_test: false,
+            archive_types: vec![],
+            editable: Some(true),
+            record_provenance: None,
+            store_recipe: false,
+            default_version: Some("0.1.0".to_string()),
+            require_version: false,
+            deny_warnings: false,
+            validate_license: false,
+            hash_ignore_keys: vec!["flavor".to_string()],
+            operation_timeout: Some(600),
+            build_dir: Some(PathBuf::from("/base/build")),
+            temp_dir: Some(PathBuf::from("/base/tmp")),
+            solve_strategy: None,
+            version_normalization: None,
+            profile: None,
+            debug_resolved_recipes: false,
+            extra_channels: vec![],
+            extra_channel_priority: None,
+            channels_file: None,
+            context: indexmap::IndexMap::from([("cdn".to_string(), "base".to_string())]),
+            entry_points: vec!["foo = foo.cli:main".to_string()],
+            entry_points_windows: vec!["foo-win = foo.win:main".to_string()],
+            entry_points_unix: vec![],
+            generate_import_test: Some(true),
+            extras_as_constraints: vec!["cli".to_string()],
+            auto_python_deps: None,
         };
 
         let mut target_env = indexmap::IndexMap::new();
@@ -118,10 +686,59 @@ mod tests {
         let target_config = PythonBackendConfig {
             noarch: Some(false),
             env: target_env,
+            secrets: vec!["TARGET_TOKEN".to_string()],
             debug_dir: None,
             extra_input_globs: vec!["*.target".to_string()],
+            remove_input_globs: vec!["**/*.txt".to_string()],
             compilers: Some(vec!["cxx".to_string(), "rust".to_string()]),
+            stdlibs: Some(vec!["c".to_string(), "fortran".to_string()]),
+            variant_compilers: vec!["cuda".to_string()],
+            build_retries: None,
             ignore_pyproject_manifest: Some(false),
+            run_constraints: vec!["numpy >=1.22".to_string()],
+            build_only: vec!["ninja".to_string()],
+            always_copy_files: vec![],
+            additional_sources: vec!["data".to_string()],
+            additional_sources_filter: Some(SourceFilter {
+                include: vec![],
+                exclude: vec!["fixtures/**".to_string()],
+            }),
+            additional_sources_use_gitignore: Some(false),
+            dedupe_requirements: true,
+            build_string: Some("${{ hash }}_target".to_string()),
+            build_number_env: Some("CONDA_BUILD_NUMBER".to_string()),
+            build_number_from_git_count: true,
+            license_family: Some("MIT".to_string()),
+            build_isolation: Some(true),
+            test_dependency_group: Some("test".to_string()),
+            test_script: vec!["pytest -x".to_string()],
+            build_deps_in_test: true,
+            archive_types: vec!["conda".to_string(), "tar.bz2".to_string()],
+            editable: Some(false),
+            record_provenance: Some(false),
+            store_recipe: true,
+            default_version: Some("0.2.0".to_string()),
+            require_version: true,
+            deny_warnings: true,
+            validate_license: true,
+            hash_ignore_keys: vec!["cuda".to_string()],
+            operation_timeout: None,
+            build_dir: None,
+            temp_dir: None,
+            solve_strategy: Some(SolveStrategy::Lowest),
+            version_normalization: Some(VersionNormalization::Conda),
+            profile: Some(BuildProfile::Dev),
+            debug_resolved_recipes: true,
+            extra_channels: vec!["https://prefix.dev/build-tools".to_string()],
+            extra_channel_priority: Some(ExtraChannelPriority::Highest),
+            channels_file: Some(PathBuf::from("channels.yaml")),
+            context: indexmap::IndexMap::from([("cdn".to_string(), "target".to_string())]),
+            entry_points: vec!["bar = bar.cli:main".to_string()],
+            entry_points_windows: vec![],
+            entry_points_unix: vec!["bar-sh = bar.sh:main".to_string()],
+            generate_import_test: Some(false),
+            extras_as_constraints: vec!["gpu".to_string()],
+            auto_python_deps: Some(false),
         };
 
         let merged = base_config
@@ -147,14 +764,111 @@ mod tests {
 
         // extra_input_globs should be completely overridden
         assert_eq!(merged.extra_input_globs, vec!["*.target".to_string()]);
+        assert_eq!(merged.remove_input_globs, vec!["**/*.txt".to_string()]);
+        assert_eq!(merged.variant_compilers, vec!["cuda".to_string()]);
+        assert_eq!(merged.additional_sources, vec!["data".to_string()]);
+        assert_eq!(
+            merged.additional_sources_filter,
+            Some(SourceFilter {
+                include: vec![],
+                exclude: vec!["fixtures/**".to_string()],
+            })
+        );
+        assert_eq!(merged.additional_sources_use_gitignore, Some(false));
+        assert_eq!(merged.profile, Some(BuildProfile::Dev));
+
+        // build_retries should fall back to the base value
+        assert_eq!(merged.build_retries, Some(2));
 
-        // compilers should be completely overridden by target
+        // compilers should be added to the base compilers
         assert_eq!(
             merged.compilers,
-            Some(vec!["cxx".to_string(), "rust".to_string()])
+            Some(vec!["c".to_string(), "cxx".to_string(), "rust".to_string()])
         );
+
+        // stdlibs should be completely overridden by target
+        assert_eq!(
+            merged.stdlibs,
+            Some(vec!["c".to_string(), "fortran".to_string()])
+        );
+
         // ignore_pyproject_manifest should use target value
         assert_eq!(merged.ignore_pyproject_manifest, Some(false));
+        // run_constraints should be completely overridden
+        assert_eq!(merged.run_constraints, vec!["numpy >=1.22".to_string()]);
+        assert_eq!(merged.build_only, vec!["ninja".to_string()]);
+        assert_eq!(merged.always_copy_files, vec!["share/data/**".to_string()]);
+        assert!(merged.dedupe_requirements);
+        assert!(merged.build_number_from_git_count);
+        assert_eq!(merged.default_version, Some("0.2.0".to_string()));
+        assert!(merged.require_version);
+        assert!(merged.deny_warnings);
+        assert!(merged.validate_license);
+        assert!(merged.store_recipe);
+        assert_eq!(merged.hash_ignore_keys, vec!["cuda".to_string()]);
+        assert_eq!(merged.operation_timeout, Some(600));
+        assert_eq!(merged.build_dir, Some(PathBuf::from("/base/build")));
+        assert_eq!(merged.temp_dir, Some(PathBuf::from("/base/tmp")));
+        assert_eq!(merged.solve_strategy, Some(SolveStrategy::Lowest));
+        assert_eq!(
+            merged.version_normalization,
+            Some(VersionNormalization::Conda)
+        );
+        assert!(merged.debug_resolved_recipes);
+        assert_eq!(merged.secrets, vec!["TARGET_TOKEN".to_string()]);
+        assert_eq!(
+            merged.extra_channels,
+            vec!["https://prefix.dev/build-tools".to_string()]
+        );
+        assert_eq!(
+            merged.extra_channel_priority,
+            Some(ExtraChannelPriority::Highest)
+        );
+        assert_eq!(merged.channels_file, Some(PathBuf::from("channels.yaml")));
+        assert_eq!(merged.entry_points, vec!["bar = bar.cli:main".to_string()]);
+        assert_eq!(
+            merged.entry_points_windows,
+            vec!["foo-win = foo.win:main".to_string()]
+        );
+        assert_eq!(
+            merged.entry_points_unix,
+            vec!["bar-sh = bar.sh:main".to_string()]
+        );
+        assert_eq!(merged.generate_import_test, Some(false));
+        assert_eq!(merged.extras_as_constraints, vec!["gpu".to_string()]);
+        assert_eq!(merged.auto_python_deps, Some(false));
+        assert_eq!(
+            merged.build_number_env,
+            Some("CONDA_BUILD_NUMBER".to_string())
+        );
+        // build_string should use the target value
+        assert_eq!(merged.build_string, Some("${{ hash }}_target".to_string()));
+
+        // license_family should use the target value
+        assert_eq!(merged.license_family, Some("MIT".to_string()));
+
+        // build_isolation should use the target value
+        assert_eq!(merged.build_isolation, Some(true));
+
+        // test_dependency_group should use the target value
+        assert_eq!(merged.test_dependency_group, Some("test".to_string()));
+        assert_eq!(merged.test_script, vec!["pytest -x".to_string()]);
+        assert!(merged.build_deps_in_test);
+
+        // archive_types should be completely overridden
+        assert_eq!(
+            merged.archive_types,
+            vec!["conda".to_string(), "tar.bz2".to_string()]
+        );
+
+        // editable should use the target value
+        assert_eq!(merged.editable, Some(false));
+
+        // record_provenance should use the target value
+        assert_eq!(merged.record_provenance, Some(false));
+
+        // context should merge with target taking precedence
+        assert_eq!(merged.context.get("cdn"), Some(&"target".to_string()));
     }
 
     #[test]
@@ -165,10 +879,56 @@ mod tests {
         let base_config = PythonBackendConfig {
             noarch: Some(true),
             env: base_env,
+            secrets: vec!["BASE_TOKEN".to_string()],
             debug_dir: Some(PathBuf::from("/base/debug")),
             extra_input_globs: vec!["*.base".to_string()],
+            remove_input_globs: vec![],
             compilers: None,
+            stdlibs: None,
+            variant_compilers: vec![],
+            build_retries: Some(2),
             ignore_pyproject_manifest: Some(true),
+            run_constraints: vec!["numpy >=1.20".to_string()],
+            build_only: vec!["make".to_string()],
+            always_copy_files: vec![],
+            additional_sources: vec![],
+            additional_sources_filter: None,
+            additional_sources_use_gitignore: Some(false),
+            dedupe_requirements: false,
+            build_string: Some("${{ hash }}_base".to_string()),
+            build_number_env: Some("BASE_BUILD_NUMBER".to_string()),
+            build_number_from_git_count: false,
+            license_family: Some("BSD".to_string()),
+            build_isolation: Some(false),
+            test_dependency_group: Some("dev".to_string()),
+            test_script: vec!["pytest tests".to_string()],
+            build_deps_in_test: false,
+            archive_types: vec!["tar.bz2".to_string()],
+            editable: Some(true),
+            record_provenance: Some(true),
+            store_recipe: false,
+            default_version: Some("0.1.0".to_string()),
+            require_version: false,
+            deny_warnings: false,
+            validate_license: false,
+            hash_ignore_keys: vec!["flavor".to_string()],
+            operation_timeout: Some(600),
+            build_dir: Some(PathBuf::from("/base/build")),
+            temp_dir: Some(PathBuf::from("/base/tmp")),
+            solve_strategy: Some(SolveStrategy::LowestDirect),
+            version_normalization: None,
+            profile: None,
+            debug_resolved_recipes: false,
+            extra_channels: vec!["bioconda".to_string()],
+            extra_channel_priority: None,
+            channels_file: None,
+            context: indexmap::IndexMap::new(),
+            entry_points: vec!["foo = foo.cli:main".to_string()],
+            entry_points_windows: vec!["foo-win = foo.win:main".to_string()],
+            entry_points_unix: vec![],
+            generate_import_test: Some(false),
+            extras_as_constraints: vec!["docs".to_string()],
+            auto_python_deps: Some(true),
         };
 
         let empty_target_config = PythonBackendConfig::default();
@@ -177,13 +937,53 @@ mod tests {
             .merge_with_target_config(&empty_target_config)
             .unwrap();
 
+        // additional_sources_use_gitignore should fall back to the base value
+        assert_eq!(merged.additional_sources_use_gitignore, Some(false));
+
         // Should keep base values when target is empty
         assert_eq!(merged.noarch, Some(true));
         assert_eq!(merged.env.get("BASE_VAR"), Some(&"base_value".to_string()));
         assert_eq!(merged.debug_dir, Some(PathBuf::from("/base/debug")));
         assert_eq!(merged.extra_input_globs, vec!["*.base".to_string()]);
+        assert_eq!(merged.build_retries, Some(2));
         assert_eq!(merged.compilers, None);
         assert_eq!(merged.ignore_pyproject_manifest, Some(true));
+        assert_eq!(merged.run_constraints, vec!["numpy >=1.20".to_string()]);
+        assert_eq!(merged.build_only, vec!["make".to_string()]);
+        assert_eq!(merged.default_version, Some("0.1.0".to_string()));
+        assert!(!merged.require_version);
+        assert!(!merged.deny_warnings);
+        assert!(!merged.validate_license);
+        assert!(!merged.store_recipe);
+        assert_eq!(merged.hash_ignore_keys, vec!["flavor".to_string()]);
+        assert_eq!(merged.solve_strategy, Some(SolveStrategy::LowestDirect));
+        assert!(!merged.debug_resolved_recipes);
+        assert_eq!(merged.secrets, vec!["BASE_TOKEN".to_string()]);
+        assert_eq!(merged.extra_channels, vec!["bioconda".to_string()]);
+        assert_eq!(merged.extra_channel_priority, None);
+        assert_eq!(merged.channels_file, None);
+        assert_eq!(merged.entry_points, vec!["foo = foo.cli:main".to_string()]);
+        assert_eq!(
+            merged.entry_points_windows,
+            vec!["foo-win = foo.win:main".to_string()]
+        );
+        assert!(merged.entry_points_unix.is_empty());
+        assert_eq!(merged.generate_import_test, Some(false));
+        assert_eq!(merged.extras_as_constraints, vec!["docs".to_string()]);
+        assert_eq!(merged.auto_python_deps, Some(true));
+        assert_eq!(merged.build_string, Some("${{ hash }}_base".to_string()));
+        assert_eq!(
+            merged.build_number_env,
+            Some("BASE_BUILD_NUMBER".to_string())
+        );
+        assert_eq!(merged.license_family, Some("BSD".to_string()));
+        assert_eq!(merged.build_isolation, Some(false));
+        assert_eq!(merged.test_dependency_group, Some("dev".to_string()));
+        assert_eq!(merged.test_script, vec!["pytest tests".to_string()]);
+        assert_eq!(merged.archive_types, vec!["tar.bz2".to_string()]);
+        assert_eq!(merged.editable, Some(true));
+        assert_eq!(merged.record_provenance, Some(true));
+        assert!(merged.context.is_empty());
     }
 
     #[test]
@@ -259,4 +1059,38 @@ mod tests {
         let error_msg = result.unwrap_err().to_string();
         assert!(error_msg.contains("`debug_dir` cannot have a target specific value"));
     }
+
+    #[test]
+    fn test_editable_config_wins() {
+        assert!(resolve_editable(Some(true), Some("false"), false));
+        assert!(!resolve_editable(Some(false), Some("true"), true));
+    }
+
+    #[test]
+    fn test_editable_env_fallback() {
+        assert!(resolve_editable(None, Some("true"), false));
+        assert!(!resolve_editable(None, Some("false"), true));
+    }
+
+    #[test]
+    fn test_editable_param_default() {
+        assert!(resolve_editable(None, None, true));
+        assert!(!resolve_editable(None, None, false));
+    }
+
+    #[test]
+    fn test_dev_profile_is_editable() {
+        let config = |editable, profile| PythonBackendConfig {
+            editable,
+            profile: Some(profile),
+            ..Default::default()
+        };
+
+        assert!(config(None, BuildProfile::Dev).editable(false));
+
+        // An explicitly configured value wins over the profile.
+        assert!(!config(Some(false), BuildProfile::Dev).editable(true));
+
+        assert!(!config(None, BuildProfile::Release).editable(false));
+    }
 }
@@ -0,0 +1,143 @@
+//! A reader for the `[dependency-groups]` table of a pyproject.toml as
+//! specified by [PEP 735](https://peps.python.org/pep-0735/).
+
+use indexmap::IndexMap;
+use miette::IntoDiagnostic;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+struct PyProjectDependencyGroups {
+    #[serde(default, rename = "dependency-groups")]
+    dependency_groups: IndexMap<String, Vec<DependencyGroupEntry>>,
+}
+
+/// An entry of a dependency group, either a PEP 508 requirement or a
+/// reference to another group.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DependencyGroupEntry {
+    Requirement(String),
+    IncludeGroup {
+        #[serde(rename = "include-group")]
+        include_group: String,
+    },
+}
+
+/// Returns the PEP 508 requirements of the dependency group `group` from the
+/// contents of a pyproject.toml. Groups that are referenced with
+/// `{ include-group = "..." }` are expanded in place, and requirements that
+/// occur multiple times are only returned once.
+pub(crate) fn dependency_group_requirements(
+    pyproject_contents: &str,
+    group: &str,
+) -> miette::Result<Vec<String>> {
+    let pyproject: PyProjectDependencyGroups =
+        toml_edit::de::from_str(pyproject_contents).into_diagnostic()?;
+    let groups = pyproject
+        .dependency_groups
+        .into_iter()
+        .map(|(name, entries)| (normalize_group_name(&name), entries))
+        .collect::<IndexMap<_, _>>();
+
+    let mut requirements = Vec::new();
+    collect_requirements(
+        &groups,
+        &normalize_group_name(group),
+        &mut Vec::new(),
+        &mut requirements,
+    )?;
+    Ok(requirements)
+}
+
+fn collect_requirements(
+    groups: &IndexMap<String, Vec<DependencyGroupEntry>>,
+    group: &str,
+    parents: &mut Vec<String>,
+    requirements: &mut Vec<String>,
+) -> miette::Result<()> {
+    let Some(entries) = groups.get(group) else {
+        miette::bail!("the dependency group '{group}' is not defined in the pyproject.toml");
+    };
+    if parents.iter().any(|parent| parent == group) {
+        miette::bail!("the dependency group '{group}' includes itself");
+    }
+
+    parents.push(group.to_string());
+    for entry in entries {
+        match entry {
+            DependencyGroupEntry::Requirement(requirement) => {
+                if !requirements.contains(requirement) {
+                    requirements.push(requirement.clone());
+                }
+            }
+            DependencyGroupEntry::IncludeGroup { include_group } => collect_requirements(
+                groups,
+                &normalize_group_name(include_group),
+                parents,
+                requirements,
+            )?,
+        }
+    }
+    parents.pop();
+
+    Ok(())
+}
+
+/// Normalizes a group name the same way as a python package name, e.g.
+/// `Test_Deps` and `test-deps` refer to the same group.
+fn normalize_group_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    let mut previous_was_separator = false;
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !previous_was_separator {
+                normalized.push('-');
+            }
+            previous_was_separator = true;
+        } else {
+            normalized.push(c.to_ascii_lowercase());
+            previous_was_separator = false;
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PYPROJECT: &str = r#"
+[project]
+name = "foobar"
+
+[dependency-groups]
+coverage = ["pytest-cov", "coverage[toml]"]
+Test_Deps = [
+    "pytest>=8",
+    { include-group = "coverage" },
+    "pytest-cov",
+]
+cyclic = [{ include-group = "cyclic" }]
+"#;
+
+    #[test]
+    fn test_dependency_group_requirements() {
+        let requirements = dependency_group_requirements(PYPROJECT, "test-deps").unwrap();
+        assert_eq!(
+            requirements,
+            vec!["pytest>=8", "pytest-cov", "coverage[toml]"]
+        );
+    }
+
+    #[test]
+    fn test_missing_dependency_group() {
+        let error = dependency_group_requirements(PYPROJECT, "docs").unwrap_err();
+        assert!(error.to_string().contains("'docs' is not defined"));
+    }
+
+    #[test]
+    fn test_cyclic_dependency_group() {
+        let error = dependency_group_requirements(PYPROJECT, "cyclic").unwrap_err();
+        assert!(error.to_string().contains("'cyclic' includes itself"));
+    }
+}
@@ -1,20 +1,31 @@
 mod build_script;
 mod config;
+mod dependency_groups;
 mod metadata;
+mod setup_cfg;
 
 use build_script::{BuildPlatform, BuildScriptContext, Installer};
 use config::PythonBackendConfig;
-use miette::IntoDiagnostic;
+use miette::{Context, IntoDiagnostic};
 use pixi_build_backend::variants::NormalizedKey;
 use pixi_build_backend::{
-    compilers::add_compilers_and_stdlib_to_requirements,
-    generated_recipe::{GenerateRecipe, GeneratedRecipe, PythonParams},
+    compilers::{add_compilers_and_stdlib_to_requirements, with_variant_compilers},
+    error::BackendError,
+    generated_recipe::{
+        BackendConfig, BackendIdentifier, BuildParams, GenerateRecipe, GeneratedRecipe,
+    },
     intermediate_backend::IntermediateBackendInstantiator,
 };
 use pixi_build_types::ProjectModelV1;
-use pyproject_toml::PyProjectToml;
+use pyproject_toml::{
+    PyProjectToml,
+    pep508_rs::{Requirement, VersionOrUrl},
+};
 use rattler_conda_types::{PackageName, Platform, package::EntryPoint};
-use recipe_stage0::recipe::{ConditionalRequirements, NoArchKind, Python, Script};
+use recipe_stage0::matchspec::PackageDependency;
+use recipe_stage0::recipe::{
+    ConditionalRequirements, NoArchKind, Python, PythonTest, Script, Test, TestRequirements, Value,
+};
 use std::collections::HashSet;
 use std::{
     collections::BTreeSet,
@@ -25,6 +36,12 @@ use std::{
 
 use crate::metadata::PyprojectMetadataProvider;
 
+/// The problem that is reported if a noarch package has entry points that
+/// differ between platforms.
+const NOARCH_PLATFORM_ENTRY_POINTS_CONFLICT: &str = "`noarch = true` conflicts with \
+    `entry-points-windows` and `entry-points-unix`, a noarch package is installed on every \
+    platform";
+
 #[derive(Default, Clone)]
 pub struct PythonGenerator {}
 
@@ -46,6 +63,98 @@ impl PythonGenerator {
             })
             .collect()
     }
+
+    /// Read the console scripts from the `[options.entry_points]` section of a
+    /// `setup.cfg` file. This is used for legacy packages that do not declare
+    /// their scripts in the pyproject.toml.
+    pub(crate) fn setup_cfg_entry_points(setup_cfg: &str) -> Vec<EntryPoint> {
+        setup_cfg::console_scripts(setup_cfg)
+            .into_iter()
+            .flat_map(|entry_point| EntryPoint::from_str(&entry_point))
+            .collect()
+    }
+
+    /// Read the `[build-system] requires` from the pyproject.toml and return
+    /// them as conda package specs. Requirements with an environment marker,
+    /// e.g. `tomli; python_version < '3.11'`, can't be evaluated when the
+    /// recipe is generated, so they are returned separately as they are
+    /// written.
+    pub(crate) fn build_system_requirements(
+        pyproject_manifest: Option<&PyProjectToml>,
+    ) -> (Vec<String>, Vec<String>) {
+        let requires = pyproject_manifest
+            .and_then(|p| p.build_system.as_ref())
+            .map(|build_system| build_system.requires.as_slice());
+
+        let (unconditional, conditional): (Vec<_>, Vec<_>) = requires
+            .into_iter()
+            .flatten()
+            .partition(|requirement| requirement.marker.is_true());
+        (
+            unconditional
+                .into_iter()
+                .map(PythonGenerator::conda_spec)
+                .collect(),
+            conditional.into_iter().map(ToString::to_string).collect(),
+        )
+    }
+
+    /// Derives the top-level module that a package with the given name
+    /// provides, e.g. `foo_bar` for `foo-bar`. Returns `None` if the name
+    /// doesn't map to a valid module name.
+    pub(crate) fn import_module(package_name: &str) -> Option<String> {
+        let module = package_name.replace('-', "_");
+        let is_identifier = |part: &str| {
+            part.chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        };
+        module.split('.').all(is_identifier).then_some(module)
+    }
+
+    /// Read the optional dependencies of the given extras from the
+    /// pyproject.toml and return them as conda package specs. Dependencies
+    /// that occur in multiple extras are only returned once.
+    pub(crate) fn extras_requirements(
+        pyproject_manifest: Option<&PyProjectToml>,
+        extras: &[String],
+    ) -> miette::Result<Vec<String>> {
+        let optional_dependencies = pyproject_manifest
+            .and_then(|p| p.project.as_ref())
+            .and_then(|p| p.optional_dependencies.as_ref());
+        // Extra names are compared like package names, see PEP 685.
+        let normalize = |name: &str| name.to_lowercase().replace(['_', '.'], "-");
+
+        let mut requirements = Vec::new();
+        for extra in extras {
+            let Some((_, dependencies)) = optional_dependencies
+                .into_iter()
+                .flatten()
+                .find(|(name, _)| normalize(name) == normalize(extra))
+            else {
+                miette::bail!("the extra '{extra}' is not defined in the pyproject.toml");
+            };
+            for dependency in dependencies {
+                let spec = PythonGenerator::conda_spec(dependency);
+                if !requirements.contains(&spec) {
+                    requirements.push(spec);
+                }
+            }
+        }
+        Ok(requirements)
+    }
+
+    /// Converts a PEP 508 requirement to a conda package spec. Extras, markers
+    /// and URLs are ignored.
+    pub(crate) fn conda_spec(requirement: &Requirement) -> String {
+        match &requirement.version_or_url {
+            Some(VersionOrUrl::VersionSpecifier(specifiers)) if !specifiers.is_empty() => {
+                format!("{} {}", requirement.name, specifiers)
+            }
+            _ => requirement.name.to_string(),
+        }
+    }
 }
 
 impl GenerateRecipe for PythonGenerator {
@@ -57,10 +166,10 @@ impl GenerateRecipe for PythonGenerator {
         config: &Self::Config,
         manifest_root: PathBuf,
         host_platform: Platform,
-        python_params: Option<PythonParams>,
+        build_params: Option<BuildParams>,
         variants: &HashSet<NormalizedKey>,
     ) -> miette::Result<GeneratedRecipe> {
-        let params = python_params.unwrap_or_default();
+        let params = build_params.unwrap_or_default();
 
         let mut pyproject_metadata_provider = PyprojectMetadataProvider::new(
             &manifest_root,
@@ -69,9 +178,13 @@ impl GenerateRecipe for PythonGenerator {
                 .is_some_and(|ignore| ignore),
         );
 
-        let mut generated_recipe =
-            GeneratedRecipe::from_model(model.clone(), &mut pyproject_metadata_provider)
-                .into_diagnostic()?;
+        let mut generated_recipe = GeneratedRecipe::from_model_with_default_version(
+            model.clone(),
+            &mut pyproject_metadata_provider,
+            config.fallback_version(),
+            config.version_normalization(),
+        )
+        .map_err(BackendError::from)?;
 
         let requirements = &mut generated_recipe.recipe.requirements;
 
@@ -91,35 +204,44 @@ impl GenerateRecipe for PythonGenerator {
 
         let installer_name = installer.package_name().to_string();
 
-        // add installer in the host requirements
-        if !resolved_requirements
-            .host
-            .contains_key(&PackageName::new_unchecked(&installer_name))
-        {
-            requirements
+        // Unless disabled, the installer and python are added to the
+        // requirements.
+        if config.auto_python_deps() {
+            // add installer in the host requirements
+            if !resolved_requirements
                 .host
-                .push(installer_name.parse().into_diagnostic()?);
-        }
+                .contains_key(&PackageName::new_unchecked(&installer_name))
+            {
+                requirements
+                    .host
+                    .push(installer_name.parse().into_diagnostic()?);
+            }
 
-        // add python in both host and run requirements
-        if !resolved_requirements
-            .host
-            .contains_key(&PackageName::new_unchecked("python"))
-        {
-            requirements.host.push("python".parse().into_diagnostic()?);
-        }
-        if !resolved_requirements
-            .run
-            .contains_key(&PackageName::new_unchecked("python"))
-        {
-            requirements.run.push("python".parse().into_diagnostic()?);
+            // add python in both host and run requirements
+            if !resolved_requirements
+                .host
+                .contains_key(&PackageName::new_unchecked("python"))
+            {
+                requirements.host.push("python".parse().into_diagnostic()?);
+            }
+            if !resolved_requirements
+                .run
+                .contains_key(&PackageName::new_unchecked("python"))
+            {
+                requirements.run.push("python".parse().into_diagnostic()?);
+            }
         }
 
         // Get the list of compilers from config, defaulting to no compilers for pure
         // Python packages and add them to the build requirements.
-        let compilers = config.compilers.clone().unwrap_or_default();
+        let compilers = with_variant_compilers(
+            config.compilers.clone().unwrap_or_default(),
+            &config.variant_compilers,
+            variants,
+        );
         add_compilers_and_stdlib_to_requirements(
             &compilers,
+            config.stdlibs.as_deref(),
             &mut requirements.build,
             &resolved_requirements.build,
             &host_platform,
@@ -128,11 +250,12 @@ impl GenerateRecipe for PythonGenerator {
 
         let build_platform = Platform::current();
 
-        // TODO: remove this env var override as soon as we have profiles
-        let editable = std::env::var("BUILD_EDITABLE_PYTHON")
-            .map(|val| val == "true")
-            .unwrap_or(params.editable);
+        // The editable mode is already resolved by `BackendConfig::editable`.
+        let editable = params.editable;
 
+        // Packages are built without isolation unless it is enabled
+        // explicitly.
+        let build_isolation = config.build_isolation.unwrap_or(false);
         let build_script = BuildScriptContext {
             installer,
             build_platform: if build_platform.is_windows() {
@@ -141,6 +264,7 @@ impl GenerateRecipe for PythonGenerator {
                 BuildPlatform::Unix
             },
             editable,
+            build_isolation,
             manifest_root: manifest_root.clone(),
         }
         .render();
@@ -148,6 +272,9 @@ impl GenerateRecipe for PythonGenerator {
         // Determine whether the package should be built as a noarch package or as a
         // generic package.
         let has_compilers = !compilers.is_empty();
+        if config.noarch == Some(true) && config.has_platform_entry_points() {
+            miette::bail!("{NOARCH_PLATFORM_ENTRY_POINTS_CONFLICT}");
+        }
         let noarch_kind = if config.noarch == Some(true) {
             // The user explicitly requested a noarch package.
             Some(NoArchKind::Python)
@@ -157,6 +284,10 @@ impl GenerateRecipe for PythonGenerator {
         } else if has_compilers {
             // No specific user request, but we have compilers, not a noarch package.
             None
+        } else if config.has_platform_entry_points() {
+            // The entry points differ between platforms, so a single noarch
+            // package cannot be installed on every platform.
+            None
         } else {
             // Otherwise, default to a noarch package.
             // This is the default behavior for pure Python packages.
@@ -165,21 +296,159 @@ impl GenerateRecipe for PythonGenerator {
 
         // read pyproject.toml content if it exists
         let pyproject_manifest_path = manifest_root.join("pyproject.toml");
-        let pyproject_manifest = if pyproject_manifest_path.exists() {
+        let pyproject_contents = if pyproject_manifest_path.exists() {
             let contents = std::fs::read_to_string(&pyproject_manifest_path).into_diagnostic()?;
-            generated_recipe.build_input_globs =
-                BTreeSet::from([pyproject_manifest_path.to_string_lossy().to_string()]);
-            Some(toml_edit::de::from_str(&contents).into_diagnostic()?)
+            generated_recipe.record_input("pyproject.toml");
+            Some(contents)
         } else {
             None
         };
+        let pyproject_manifest: Option<PyProjectToml> = pyproject_contents
+            .as_deref()
+            .map(toml_edit::de::from_str)
+            .transpose()
+            .into_diagnostic()?;
+
+        // Without build isolation the build backend is not installed by the
+        // installer, so the build-system requirements must be available in
+        // the host environment.
+        if !build_isolation {
+            let (requirements, conditional_requirements) =
+                PythonGenerator::build_system_requirements(pyproject_manifest.as_ref());
+            for requirement in requirements {
+                let spec: PackageDependency = requirement.parse().into_diagnostic()?;
+                if !resolved_requirements
+                    .host
+                    .contains_key(&spec.package_name())
+                {
+                    generated_recipe.recipe.requirements.host.push(spec.into());
+                }
+            }
+            for requirement in conditional_requirements {
+                generated_recipe.warnings.push(format!(
+                    "the build-system requirement '{requirement}' has an environment marker that can't be evaluated, add it to the host dependencies if it is needed"
+                ));
+            }
+        }
 
-        // Construct python specific settings
-        let python = Python {
-            entry_points: PythonGenerator::entry_points(pyproject_manifest),
+        // Add a test that runs with the packages of the configured dependency
+        // group.
+        if let Some(group) = &config.test_dependency_group {
+            let Some(contents) = &pyproject_contents else {
+                miette::bail!(
+                    "cannot read the dependency group '{group}' because there is no pyproject.toml"
+                );
+            };
+            let requirements = dependency_groups::dependency_group_requirements(contents, group)?
+                .iter()
+                .map(|requirement| Requirement::from_str(requirement).into_diagnostic())
+                .collect::<miette::Result<Vec<_>>>()?;
+
+            // Without a configured script the tests are run with `pytest`, but
+            // only if the group provides it.
+            let script = if !config.test_script.is_empty() {
+                config.test_script.clone()
+            } else if requirements
+                .iter()
+                .any(|requirement| requirement.name.as_ref() == "pytest")
+            {
+                vec!["pytest".to_string()]
+            } else {
+                miette::bail!(
+                    "the dependency group '{group}' does not contain `pytest`, set `test-script` to the commands that run the tests"
+                );
+            };
+
+            let requirements = requirements
+                .iter()
+                .map(|requirement| {
+                    PythonGenerator::conda_spec(requirement)
+                        .parse()
+                        .into_diagnostic()
+                })
+                .collect::<miette::Result<Vec<_>>>()?;
+            generated_recipe.recipe.tests.push(Test {
+                script: Some(
+                    script
+                        .iter()
+                        .map(|command| command.parse().into_diagnostic())
+                        .collect::<miette::Result<_>>()?,
+                ),
+                requirements: Some(TestRequirements {
+                    run: requirements,
+                    ..TestRequirements::default()
+                }),
+                ..Test::default()
+            });
+        }
+
+        // The dependencies of the configured extras are not installed, but have
+        // to be compatible with the package if they are.
+        if !config.extras_as_constraints.is_empty() {
+            let constraints = PythonGenerator::extras_requirements(
+                pyproject_manifest.as_ref(),
+                &config.extras_as_constraints,
+            )?;
+            generated_recipe.add_run_constraints(&constraints)?;
+        }
+
+        // Check that the top-level module of noarch packages can be imported.
+        if noarch_kind.is_some() && config.generate_import_test() {
+            let module = match &generated_recipe.recipe.package.name {
+                Value::Concrete(name) => PythonGenerator::import_module(name),
+                Value::Template(_) => None,
+            };
+            match module {
+                Some(module) => generated_recipe.recipe.tests.push(Test {
+                    python: Some(PythonTest {
+                        imports: vec![module.parse().into_diagnostic()?],
+                        // Only the import is tested, the dependencies are
+                        // checked by the solver.
+                        pip_check: Some(false),
+                    }),
+                    ..Test::default()
+                }),
+                None => generated_recipe.warnings.push(format!(
+                    "cannot derive the module of '{}', no import test is generated",
+                    generated_recipe.recipe.package.name
+                )),
+            }
+        }
+
+        // Legacy packages declare their entry points in `setup.cfg` instead.
+        let mut entry_points = PythonGenerator::entry_points(pyproject_manifest);
+        let setup_cfg_path = manifest_root.join("setup.cfg");
+        if entry_points.is_empty() && setup_cfg_path.exists() {
+            let contents = std::fs::read_to_string(&setup_cfg_path).into_diagnostic()?;
+            generated_recipe.record_input("setup.cfg");
+            entry_points = PythonGenerator::setup_cfg_entry_points(&contents);
+        }
+
+        // Entry points from the configuration replace the ones with the same
+        // name, the platform specific ones are added last so they take
+        // precedence.
+        let platform_entry_points = if host_platform.is_windows() {
+            config.entry_points_windows.as_slice()
+        } else if host_platform.is_unix() {
+            config.entry_points_unix.as_slice()
+        } else {
+            &[]
         };
+        for entry_point in config.entry_points.iter().chain(platform_entry_points) {
+            let entry_point = EntryPoint::from_str(entry_point)
+                .into_diagnostic()
+                .wrap_err_with(|| format!("invalid entry point '{entry_point}'"))?;
+            entry_points.retain(|existing| existing.command != entry_point.command);
+            entry_points.push(entry_point);
+        }
+
+        // Construct python specific settings
+        let python = Python { entry_points };
 
         generated_recipe.recipe.build.python = python;
+        // Compiled packages are installed into a site-packages directory that
+        // depends on the python version.
+        generated_recipe.python_site_packages_path_aware = noarch_kind.is_none();
         generated_recipe.recipe.build.noarch = noarch_kind;
 
         generated_recipe.recipe.build.script = Script {
@@ -187,11 +456,45 @@ impl GenerateRecipe for PythonGenerator {
             env: config.env.clone(),
             ..Script::default()
         };
+        generated_recipe.add_run_constraints(&config.run_constraints)?;
+        generated_recipe.add_secrets(&config.secrets);
+        generated_recipe.move_to_build_requirements(&config.build_only)?;
+        generated_recipe.recipe.build.always_copy_files = config.always_copy_files.clone();
+        generated_recipe.add_path_sources(
+            &manifest_root,
+            &config.additional_sources,
+            config.additional_sources_use_gitignore,
+            config.additional_sources_filter.as_ref(),
+        )?;
+        if config.dedupe_requirements {
+            generated_recipe.dedupe_requirements();
+        }
+        if config.build_deps_in_test {
+            generated_recipe.add_build_requirements_to_tests();
+        }
+        generated_recipe.add_context(&config.context);
+        generated_recipe.discover_license_file(&manifest_root);
+        if let Some(build_string) = &config.build_string {
+            generated_recipe.recipe.build.string = Some(build_string.parse().into_diagnostic()?);
+        }
+        if config.build_number_from_git_count {
+            generated_recipe.set_build_number_from_git_count(&manifest_root);
+        }
+        if let Some(build_number_env) = &config.build_number_env {
+            generated_recipe.set_build_number_from_env(build_number_env);
+        }
+        if let Some(license_family) = &config.license_family {
+            generated_recipe
+                .recipe
+                .about
+                .get_or_insert_with(Default::default)
+                .license_family = Some(license_family.parse().into_diagnostic()?);
+        }
 
-        // Add the metadata input globs from the MetadataProvider
-        generated_recipe
-            .metadata_input_globs
-            .extend(pyproject_metadata_provider.input_globs());
+        // Record the files that were read by the MetadataProvider
+        for path in pyproject_metadata_provider.input_globs() {
+            generated_recipe.record_input(path);
+        }
 
         Ok(generated_recipe)
     }
@@ -247,9 +550,48 @@ impl GenerateRecipe for PythonGenerator {
             .iter()
             .chain(python_globs.iter())
             .map(|s| s.to_string())
+            .filter(|glob| !config.remove_input_globs.contains(glob))
             .chain(config.extra_input_globs.clone())
             .collect()
     }
+
+    fn validate(&self, _model: &ProjectModelV1, config: &Self::Config) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        let has_compilers = config
+            .compilers
+            .as_ref()
+            .is_some_and(|compilers| !compilers.is_empty());
+        if config.noarch == Some(true) && has_compilers {
+            problems.push(String::from(
+                "`noarch = true` conflicts with `compilers`, compiled packages cannot be noarch",
+            ));
+        }
+
+        if config.noarch == Some(true) && config.has_platform_entry_points() {
+            problems.push(String::from(NOARCH_PLATFORM_ENTRY_POINTS_CONFLICT));
+        }
+
+        problems
+    }
+
+    fn expected_manifests(&self, config: &Self::Config) -> &[&'static str] {
+        if config
+            .ignore_pyproject_manifest
+            .is_some_and(|ignore| ignore)
+        {
+            &[]
+        } else {
+            &["pyproject.toml", "setup.py"]
+        }
+    }
+
+    fn backend_identifier(&self) -> Option<BackendIdentifier> {
+        Some(BackendIdentifier {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        })
+    }
 }
 
 #[tokio::main]
@@ -266,8 +608,11 @@ pub async fn main() {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use indexmap::IndexMap;
-    use recipe_stage0::recipe::{Item, Value};
+    use pixi_build_backend::utils::test::{backend_manifest_path, intermediate_conda_outputs};
+    use recipe_stage0::recipe::{Item, Source, SourceFilter, Value};
 
     use super::*;
 
@@ -296,6 +641,52 @@ mod tests {
         insta::assert_debug_snapshot!(result);
     }
 
+    #[test]
+    fn test_input_globs_removes_default_globs() {
+        let config = PythonBackendConfig {
+            remove_input_globs: vec!["docs/**/*.md".to_string()],
+            ..Default::default()
+        };
+
+        let result =
+            PythonGenerator::extract_input_globs_from_build(&config, PathBuf::new(), false);
+
+        assert!(!result.contains("docs/**/*.md"));
+        assert!(result.contains("docs/**/*.rst"));
+        assert!(result.contains("pyproject.toml"));
+    }
+
+    #[test]
+    fn test_expected_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = backend_manifest_path::<PythonGenerator>(dir.path(), serde_json::json!({}))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("`pyproject.toml` or `setup.py`"),
+            "{err}"
+        );
+
+        // A `setup.py` is enough for projects without a `pyproject.toml`.
+        fs_err::write(dir.path().join("setup.py"), "").unwrap();
+        assert_eq!(
+            backend_manifest_path::<PythonGenerator>(dir.path(), serde_json::json!({})).unwrap(),
+            Some(dir.path().join("setup.py"))
+        );
+        fs_err::write(dir.path().join("pyproject.toml"), "").unwrap();
+        assert_eq!(
+            backend_manifest_path::<PythonGenerator>(dir.path(), serde_json::json!({})).unwrap(),
+            Some(dir.path().join("pyproject.toml"))
+        );
+
+        // The manifest is not required if it is ignored.
+        let dir = tempfile::tempdir().unwrap();
+        let config = serde_json::json!({ "ignore-pyproject-manifest": true });
+        assert_eq!(
+            backend_manifest_path::<PythonGenerator>(dir.path(), config).unwrap(),
+            None
+        );
+    }
+
     #[macro_export]
     macro_rules! project_fixture {
         ($($json:tt)+) => {
@@ -382,6 +773,97 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_python_deps_can_be_disabled() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+            "targets": {
+                "defaultTarget": {
+                    "runDependencies": {
+                        "boltons": { "binary": { "version": "*" } }
+                    }
+                },
+            }
+        });
+
+        let resolved_requirements = |auto_python_deps| {
+            let config = PythonBackendConfig {
+                auto_python_deps: Some(auto_python_deps),
+                ..PythonBackendConfig::default_with_ignore_pyproject_manifest()
+            };
+            let generated_recipe = PythonGenerator::default()
+                .generate_recipe(
+                    &project_model,
+                    &config,
+                    PathBuf::from("."),
+                    Platform::Linux64,
+                    None,
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe");
+            let resolved = generated_recipe
+                .recipe
+                .requirements
+                .resolve_for_platform(Platform::Linux64);
+            let names = |specs: &IndexMap<PackageName, PackageDependency>| {
+                specs
+                    .keys()
+                    .map(|name| name.as_normalized().to_string())
+                    .collect::<Vec<_>>()
+            };
+            (names(&resolved.host), names(&resolved.run))
+        };
+
+        let (host, run) = resolved_requirements(true);
+        assert_eq!(host, vec!["pip", "python"]);
+        assert_eq!(run, vec!["boltons", "python"]);
+
+        let (host, run) = resolved_requirements(false);
+        assert!(host.is_empty(), "{host:?}");
+        assert_eq!(run, vec!["boltons"]);
+    }
+
+    #[test]
+    fn test_dedupe_requirements_keeps_python_in_run() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let config = PythonBackendConfig {
+            dedupe_requirements: true,
+            ..PythonBackendConfig::default_with_ignore_pyproject_manifest()
+        };
+        let generated_recipe = PythonGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &config,
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        // `python` is required in both host and run, the host environment is
+        // not installed at runtime.
+        let resolved = generated_recipe
+            .recipe
+            .requirements
+            .resolve_for_platform(Platform::Linux64);
+        assert!(
+            resolved
+                .host
+                .contains_key(&PackageName::new_unchecked("python"))
+        );
+        assert!(
+            resolved
+                .run
+                .contains_key(&PackageName::new_unchecked("python"))
+        );
+    }
+
     #[test]
     fn test_env_vars_are_set() {
         let project_model = project_fixture!({
@@ -627,4 +1109,730 @@ mod tests {
             "explicit noarch=false should override absence of compilers"
         );
     }
+
+    #[test]
+    fn test_error_handling_ignore_manifest_with_empty_name() {
+        let project_model = project_fixture!({
+            "name": "",
+            "version": "0.1.0",
+            "targets": {
+                "defaultTarget": {}
+            }
+        });
+
+        let result = PythonGenerator::default().generate_recipe(
+            &project_model,
+            &PythonBackendConfig::default_with_ignore_pyproject_manifest(),
+            PathBuf::from("."),
+            Platform::Linux64,
+            None,
+            &HashSet::new(),
+        );
+
+        let error_message = result.err().unwrap().to_string();
+        assert!(error_message.contains("no name defined"));
+    }
+
+    #[test]
+    fn test_error_handling_invalid_name() {
+        let project_model = project_fixture!({
+            "name": "foo/bar",
+            "version": "0.1.0",
+            "targets": {
+                "defaultTarget": {}
+            }
+        });
+
+        let result = PythonGenerator::default().generate_recipe(
+            &project_model,
+            &PythonBackendConfig::default_with_ignore_pyproject_manifest(),
+            PathBuf::from("."),
+            Platform::Linux64,
+            None,
+            &HashSet::new(),
+        );
+
+        let error_message = result.err().unwrap().to_string();
+        assert!(error_message.contains("'foo/bar' is not a valid package name"));
+    }
+
+    #[test]
+    fn test_entry_points_are_read_from_setup_cfg() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("setup.cfg"),
+            r#"
+[metadata]
+name = foobar
+
+[options.entry_points]
+console_scripts =
+    foobar = foobar.cli:main
+    foobar-admin = foobar.admin:run
+"#,
+        )
+        .unwrap();
+
+        let generated_recipe = PythonGenerator::default()
+            .generate_recipe(
+                &minimal_project(),
+                &PythonBackendConfig::default_with_ignore_pyproject_manifest(),
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let entry_points = generated_recipe
+            .recipe
+            .build
+            .python
+            .entry_points
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entry_points,
+            vec![
+                "foobar = foobar.cli:main",
+                "foobar-admin = foobar.admin:run"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_entry_points_from_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            r#"
+[project]
+name = "foobar"
+version = "0.1.0"
+
+[project.scripts]
+foobar = "foobar.cli:main"
+foobar-admin = "foobar.admin:run"
+"#,
+        )
+        .unwrap();
+
+        let generated_recipe = PythonGenerator::default()
+            .generate_recipe(
+                &minimal_project(),
+                &PythonBackendConfig {
+                    entry_points: vec![
+                        "foobar-admin = foobar.admin:main".to_string(),
+                        "foobar-wrapper = foobar.wrapper:main".to_string(),
+                    ],
+                    ..Default::default()
+                },
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let mut entry_points = generated_recipe
+            .recipe
+            .build
+            .python
+            .entry_points
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        entry_points.sort();
+        assert_eq!(
+            entry_points,
+            vec![
+                "foobar = foobar.cli:main",
+                "foobar-admin = foobar.admin:main",
+                "foobar-wrapper = foobar.wrapper:main",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_platform_specific_entry_points() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = PythonBackendConfig {
+            entry_points: vec!["foobar = foobar.cli:main".to_string()],
+            entry_points_windows: vec!["foobar-bat = foobar.bat:main".to_string()],
+            entry_points_unix: vec!["foobar = foobar.unix:main".to_string()],
+            ..Default::default()
+        };
+
+        let entry_points = |platform: Platform| {
+            let generated_recipe = PythonGenerator::default()
+                .generate_recipe(
+                    &minimal_project(),
+                    &config,
+                    temp_dir.path().to_path_buf(),
+                    platform,
+                    None,
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe");
+            let mut entry_points = generated_recipe
+                .recipe
+                .build
+                .python
+                .entry_points
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            entry_points.sort();
+            entry_points
+        };
+
+        assert_eq!(
+            entry_points(Platform::Win64),
+            vec!["foobar = foobar.cli:main", "foobar-bat = foobar.bat:main"]
+        );
+        assert_eq!(
+            entry_points(Platform::Linux64),
+            vec!["foobar = foobar.unix:main"]
+        );
+        assert_eq!(
+            entry_points(Platform::OsxArm64),
+            vec!["foobar = foobar.unix:main"]
+        );
+
+        // The package is built for every platform on its own instead of as a
+        // noarch package.
+        let generated_recipe = PythonGenerator::default()
+            .generate_recipe(
+                &minimal_project(),
+                &config,
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+        assert!(generated_recipe.recipe.build.noarch.is_none());
+    }
+
+    #[test]
+    fn test_platform_specific_entry_points_conflict_with_noarch() {
+        let config = PythonBackendConfig {
+            noarch: Some(true),
+            entry_points_unix: vec!["foobar = foobar.unix:main".to_string()],
+            ..Default::default()
+        };
+
+        let problems = PythonGenerator::default().validate(&minimal_project(), &config);
+        assert_eq!(problems, vec![NOARCH_PLATFORM_ENTRY_POINTS_CONFLICT]);
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let result = PythonGenerator::default().generate_recipe(
+            &minimal_project(),
+            &config,
+            temp_dir.path().to_path_buf(),
+            Platform::Linux64,
+            None,
+            &HashSet::new(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_system_requires_are_added_without_build_isolation() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            r#"
+[build-system]
+requires = ["setuptools>=64", "wheel"]
+build-backend = "setuptools.build_meta"
+"#,
+        )
+        .unwrap();
+
+        let host_requirements = |build_isolation| {
+            let config = PythonBackendConfig {
+                build_isolation,
+                ..PythonBackendConfig::default_with_ignore_pyproject_manifest()
+            };
+            PythonGenerator::default()
+                .generate_recipe(
+                    &minimal_project(),
+                    &config,
+                    temp_dir.path().to_path_buf(),
+                    Platform::Linux64,
+                    None,
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe")
+                .recipe
+                .requirements
+                .host
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            host_requirements(None),
+            vec!["pip", "python", "setuptools >=64", "wheel"]
+        );
+        assert_eq!(host_requirements(Some(true)), vec!["pip", "python"]);
+        assert_eq!(
+            host_requirements(Some(false)),
+            vec!["pip", "python", "setuptools >=64", "wheel"]
+        );
+    }
+
+    #[test]
+    fn test_additional_sources_options() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("data")).unwrap();
+
+        let config = PythonBackendConfig {
+            additional_sources: vec!["data".to_string()],
+            additional_sources_use_gitignore: Some(false),
+            additional_sources_filter: Some(SourceFilter {
+                include: vec![],
+                exclude: vec!["fixtures/**".to_string()],
+            }),
+            ..PythonBackendConfig::default_with_ignore_pyproject_manifest()
+        };
+        let generated_recipe = PythonGenerator::default()
+            .generate_recipe(
+                &minimal_project(),
+                &config,
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let [Item::Value(Value::Concrete(Source::Path(source)))] =
+            generated_recipe.recipe.source.as_slice()
+        else {
+            panic!("expected a single path source");
+        };
+        assert_eq!(source.use_gitignore, Some(false));
+        assert_eq!(
+            source
+                .filter
+                .as_ref()
+                .map(|filter| filter.exclude.as_slice()),
+            Some(["fixtures/**".to_string()].as_slice())
+        );
+    }
+
+    #[test]
+    fn test_build_system_requires_with_markers_are_reported() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            r#"
+[build-system]
+requires = ["setuptools>=64", "tomli; python_version < '3.11'"]
+build-backend = "setuptools.build_meta"
+"#,
+        )
+        .unwrap();
+
+        let generated_recipe = PythonGenerator::default()
+            .generate_recipe(
+                &minimal_project(),
+                &PythonBackendConfig::default_with_ignore_pyproject_manifest(),
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let host_requirements = generated_recipe
+            .recipe
+            .requirements
+            .host
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(host_requirements, vec!["pip", "python", "setuptools >=64"]);
+        assert_eq!(generated_recipe.warnings.len(), 1);
+        assert!(
+            generated_recipe.warnings[0].contains("tomli"),
+            "unexpected warning: {}",
+            generated_recipe.warnings[0]
+        );
+    }
+
+    #[test]
+    fn test_dependency_group_is_added_as_test_requirements() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            r#"
+[project]
+name = "foobar"
+version = "0.1.0"
+
+[dependency-groups]
+lint = ["ruff"]
+coverage = ["pytest-cov"]
+test = ["pytest>=8", { include-group = "coverage" }]
+"#,
+        )
+        .unwrap();
+
+        let config = PythonBackendConfig {
+            test_dependency_group: Some("test".to_string()),
+            generate_import_test: Some(false),
+            ..PythonBackendConfig::default_with_ignore_pyproject_manifest()
+        };
+        let generated_recipe = PythonGenerator::default()
+            .generate_recipe(
+                &minimal_project(),
+                &config,
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let tests = &generated_recipe.recipe.tests;
+        assert_eq!(tests.len(), 1);
+        let test_requirements = tests[0]
+            .requirements
+            .iter()
+            .flat_map(|requirements| requirements.run.iter())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(test_requirements, vec!["pytest >=8", "pytest-cov"]);
+        let script = tests[0]
+            .script
+            .iter()
+            .flatten()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(script, vec!["pytest"]);
+    }
+
+    #[test]
+    fn test_dependency_group_test_script() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            r#"
+[project]
+name = "foobar"
+version = "0.1.0"
+
+[dependency-groups]
+test = ["hypothesis"]
+"#,
+        )
+        .unwrap();
+
+        let generate_recipe = |test_script: Vec<String>| {
+            let config = PythonBackendConfig {
+                test_dependency_group: Some("test".to_string()),
+                test_script,
+                generate_import_test: Some(false),
+                ..PythonBackendConfig::default_with_ignore_pyproject_manifest()
+            };
+            PythonGenerator::default().generate_recipe(
+                &minimal_project(),
+                &config,
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+        };
+
+        // The group doesn't provide `pytest`, so there is no default script.
+        let err = generate_recipe(vec![]).err().unwrap();
+        assert!(err.to_string().contains("test-script"), "{err}");
+
+        let generated_recipe =
+            generate_recipe(vec!["python -m unittest discover".to_string()]).unwrap();
+        let script = generated_recipe.recipe.tests[0]
+            .script
+            .iter()
+            .flatten()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(script, vec!["python -m unittest discover"]);
+    }
+
+    #[test]
+    fn test_build_deps_in_test() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            r#"
+[project]
+name = "foobar"
+version = "0.1.0"
+
+[dependency-groups]
+test = ["pytest>=8"]
+"#,
+        )
+        .unwrap();
+
+        let config = PythonBackendConfig {
+            test_dependency_group: Some("test".to_string()),
+            generate_import_test: Some(false),
+            compilers: Some(vec!["c".to_string()]),
+            build_deps_in_test: true,
+            ..PythonBackendConfig::default_with_ignore_pyproject_manifest()
+        };
+        let generated_recipe = PythonGenerator::default()
+            .generate_recipe(
+                &minimal_project(),
+                &config,
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let recipe = &generated_recipe.recipe;
+        let test_requirements = recipe.tests[0].requirements.as_ref().unwrap();
+        assert!(!recipe.requirements.build.is_empty());
+        assert_eq!(test_requirements.build, recipe.requirements.build);
+        assert!(
+            test_requirements
+                .build
+                .iter()
+                .any(|item| item.to_string() == "${{ compiler('c') }}")
+        );
+    }
+
+    #[test]
+    fn test_extras_are_added_as_run_constraints() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            r#"
+[project]
+name = "foobar"
+version = "0.1.0"
+
+[project.optional-dependencies]
+Plot = ["matplotlib>=3.8", "seaborn"]
+docs = ["sphinx"]
+"#,
+        )
+        .unwrap();
+
+        let config = PythonBackendConfig {
+            extras_as_constraints: vec!["plot".to_string()],
+            ..PythonBackendConfig::default_with_ignore_pyproject_manifest()
+        };
+        let generated_recipe = PythonGenerator::default()
+            .generate_recipe(
+                &minimal_project(),
+                &config,
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let run_constraints = generated_recipe
+            .recipe
+            .requirements
+            .run_constraints
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(run_constraints, vec!["matplotlib >=3.8", "seaborn"]);
+
+        // Extras that are not defined are an error.
+        let config = PythonBackendConfig {
+            extras_as_constraints: vec!["gpu".to_string()],
+            ..PythonBackendConfig::default_with_ignore_pyproject_manifest()
+        };
+        let error = PythonGenerator::default()
+            .generate_recipe(
+                &minimal_project(),
+                &config,
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("'gpu' is not defined"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_import_test_is_generated() {
+        let config = PythonBackendConfig::default_with_ignore_pyproject_manifest();
+        let generated_recipe = generate_test_recipe(&config).expect("Failed to generate recipe");
+
+        let imports = generated_recipe
+            .recipe
+            .tests
+            .iter()
+            .flat_map(|test| test.python.iter())
+            .flat_map(|python| python.imports.iter())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(imports, vec!["foobar"]);
+        assert!(generated_recipe.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_import_test_can_be_disabled() {
+        let config = PythonBackendConfig {
+            generate_import_test: Some(false),
+            ..PythonBackendConfig::default_with_ignore_pyproject_manifest()
+        };
+
+        let generated_recipe = generate_test_recipe(&config).expect("Failed to generate recipe");
+        assert!(generated_recipe.recipe.tests.is_empty());
+    }
+
+    #[test]
+    fn test_import_module() {
+        assert_eq!(
+            PythonGenerator::import_module("foo-bar"),
+            Some("foo_bar".to_string())
+        );
+        assert_eq!(
+            PythonGenerator::import_module("zope.interface"),
+            Some("zope.interface".to_string())
+        );
+        assert_eq!(PythonGenerator::import_module("3d-viewer"), None);
+        assert_eq!(PythonGenerator::import_module("foo+bar"), None);
+    }
+
+    #[test]
+    fn test_validate_accepts_default_config() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let problems =
+            PythonGenerator::default().validate(&project_model, &PythonBackendConfig::default());
+        assert!(problems.is_empty(), "unexpected problems: {problems:?}");
+    }
+
+    #[test]
+    fn test_validate_noarch_with_compilers() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let config = PythonBackendConfig {
+            noarch: Some(true),
+            compilers: Some(vec!["c".to_string()]),
+            ..Default::default()
+        };
+
+        let problems = PythonGenerator::default().validate(&project_model, &config);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("noarch"));
+    }
+
+    #[test]
+    fn test_site_packages_path_of_compiled_package() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+        let source_dir = tempfile::tempdir().unwrap();
+
+        let result = intermediate_conda_outputs::<PythonGenerator>(
+            Some(project_model),
+            Some(source_dir.path().to_path_buf()),
+            Platform::Linux64,
+            Some(BTreeMap::from([(
+                "python".to_string(),
+                vec!["3.12".to_string()],
+            )])),
+            Some(serde_json::json!({
+                "noarch": false,
+                "ignore-pyproject-manifest": true,
+            })),
+        );
+
+        assert_eq!(
+            result.outputs[0]
+                .metadata
+                .python_site_packages_path
+                .as_deref(),
+            Some("lib/python3.12/site-packages")
+        );
+    }
+
+    #[test]
+    fn test_read_files_are_metadata_inputs() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+        let source_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            source_dir.path().join("pyproject.toml"),
+            "[build-system]\nrequires = [\"setuptools\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            source_dir.path().join("setup.cfg"),
+            "[options.entry_points]\nconsole_scripts =\n    foobar = foobar.cli:main\n",
+        )
+        .unwrap();
+
+        let result = intermediate_conda_outputs::<PythonGenerator>(
+            Some(project_model),
+            Some(source_dir.path().to_path_buf()),
+            Platform::Linux64,
+            None,
+            Some(serde_json::json!({ "ignore-pyproject-manifest": true })),
+        );
+
+        // Both files are read while generating the recipe, even though the
+        // pyproject.toml is not used for the package metadata.
+        let input_globs = result.input_globs;
+        assert!(input_globs.contains("pyproject.toml"), "{input_globs:?}");
+        assert!(input_globs.contains("setup.cfg"), "{input_globs:?}");
+        assert!(
+            input_globs
+                .iter()
+                .all(|glob| !Path::new(glob).is_absolute()),
+            "{input_globs:?}"
+        );
+    }
+
+    #[test]
+    fn test_noarch_output_is_in_noarch_subdir() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+        let source_dir = tempfile::tempdir().unwrap();
+
+        let result = intermediate_conda_outputs::<PythonGenerator>(
+            Some(project_model),
+            Some(source_dir.path().to_path_buf()),
+            Platform::Linux64,
+            None,
+            Some(serde_json::json!({ "ignore-pyproject-manifest": true })),
+        );
+
+        let metadata = &result.outputs[0].metadata;
+        assert!(metadata.noarch.is_python());
+        assert_eq!(metadata.subdir, Platform::NoArch);
+    }
 }
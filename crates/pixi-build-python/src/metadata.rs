@@ -45,6 +45,26 @@ impl PyprojectMetadataProvider {
         Ok(self.ensure_manifest()?.project.as_ref())
     }
 
+    /// Returns the first url from the `project.urls` section whose label
+    /// matches one of the given labels. Labels are compared after normalizing
+    /// them like PEP 753 does, i.e. `Source Code` matches `sourcecode`.
+    fn project_url(&self, labels: &[&str]) -> Result<Option<String>, MetadataError> {
+        if self.ignore_pyproject_manifest {
+            return Ok(None);
+        }
+        let Some(urls) = self
+            .ensure_manifest_project()?
+            .and_then(|proj| proj.urls.as_ref())
+        else {
+            return Ok(None);
+        };
+        Ok(labels.iter().find_map(|label| {
+            urls.iter()
+                .find(|(key, _)| normalize_url_label(key) == *label)
+                .map(|(_, url)| url.clone())
+        }))
+    }
+
     /// Ensures that the manifest is loaded
     fn ensure_manifest(&self) -> Result<&PyProjectToml, MetadataError> {
         self.pyproject_manifest.get_or_try_init(move || {
@@ -133,13 +153,7 @@ impl MetadataProvider for PyprojectMetadataProvider {
     /// If `ignore_pyproject_manifest` is true, returns `None`. Otherwise, extracts
     /// the homepage from the project.urls section.
     fn homepage(&mut self) -> Result<Option<String>, Self::Error> {
-        if self.ignore_pyproject_manifest {
-            return Ok(None);
-        }
-        Ok(self
-            .ensure_manifest_project()?
-            .and_then(|proj| proj.urls.as_ref())
-            .and_then(|urls| urls.get("Homepage").cloned()))
+        self.project_url(&["homepage"])
     }
 
     /// Returns the package license from the pyproject.toml manifest.
@@ -192,17 +206,7 @@ impl MetadataProvider for PyprojectMetadataProvider {
     /// If `ignore_pyproject_manifest` is true, returns `None`. Otherwise, extracts
     /// the documentation URL from the project.urls section.
     fn documentation(&mut self) -> Result<Option<String>, Self::Error> {
-        if self.ignore_pyproject_manifest {
-            return Ok(None);
-        }
-        Ok(self
-            .ensure_manifest_project()?
-            .and_then(|proj| proj.urls.as_ref())
-            .and_then(|urls| {
-                urls.get("Documentation")
-                    .or_else(|| urls.get("Docs"))
-                    .cloned()
-            }))
+        self.project_url(&["documentation", "docs"])
     }
 
     /// Returns the package repository URL from the pyproject.toml manifest.
@@ -210,21 +214,20 @@ impl MetadataProvider for PyprojectMetadataProvider {
     /// If `ignore_pyproject_manifest` is true, returns `None`. Otherwise, extracts
     /// the repository URL from the project.urls section.
     fn repository(&mut self) -> Result<Option<String>, Self::Error> {
-        if self.ignore_pyproject_manifest {
-            return Ok(None);
-        }
-        Ok(self
-            .ensure_manifest_project()?
-            .and_then(|proj| proj.urls.as_ref())
-            .and_then(|urls| {
-                urls.get("Repository")
-                    .or_else(|| urls.get("Source"))
-                    .or_else(|| urls.get("Source Code"))
-                    .cloned()
-            }))
+        self.project_url(&["repository", "source", "sourcecode"])
     }
 }
 
+/// Normalizes the label of a project url by removing punctuation and
+/// whitespace and lowercasing it, e.g. `Source-Code` becomes `sourcecode`.
+fn normalize_url_label(label: &str) -> String {
+    label
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashSet, fs};
@@ -393,6 +396,50 @@ Docs = "https://docs.example.com"
         );
     }
 
+    #[test]
+    fn test_url_keys_are_case_insensitive() {
+        let pyproject_toml_content = r#"
+[project]
+name = "test-package"
+version = "1.0.0"
+
+[project.urls]
+homepage = "https://example.com"
+DOCUMENTATION = "https://docs.example.com"
+source-code = "https://github.com/example/test-package"
+"#;
+
+        let temp_dir = create_temp_pyproject_project(pyproject_toml_content);
+        let project_model = project_fixture!({
+            "name": "test-package",
+        });
+
+        let generated_recipe = PythonGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &PythonBackendConfig::default(),
+                temp_dir.path().to_path_buf(),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let about = generated_recipe.recipe.about.expect("about should be set");
+        assert_eq!(
+            about.homepage.map(|url| url.to_string()),
+            Some("https://example.com".to_string())
+        );
+        assert_eq!(
+            about.documentation.map(|url| url.to_string()),
+            Some("https://docs.example.com".to_string())
+        );
+        assert_eq!(
+            about.repository.map(|url| url.to_string()),
+            Some("https://github.com/example/test-package".to_string())
+        );
+    }
+
     #[test]
     fn test_invalid_version_format() {
         let pyproject_toml_content = r#"
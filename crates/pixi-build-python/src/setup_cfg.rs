@@ -0,0 +1,99 @@
+//! A minimal, static reader for the parts of a setuptools `setup.cfg` that are
+//! relevant to the backend. We never execute `setup.py`, so this is only able
+//! to pick up metadata that is declared declaratively.
+
+/// Returns the `console_scripts` declared in the `[options.entry_points]`
+/// section of a `setup.cfg` file, formatted as `name = module:function`.
+///
+/// ```ini
+/// [options.entry_points]
+/// console_scripts =
+///     foo = foo.cli:main
+/// ```
+pub(crate) fn console_scripts(contents: &str) -> Vec<String> {
+    let mut in_entry_points = false;
+    let mut in_console_scripts = false;
+    let mut scripts = Vec::new();
+
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+
+        // A new section starts.
+        if let Some(section) = trimmed
+            .strip_prefix('[')
+            .and_then(|section| section.strip_suffix(']'))
+        {
+            in_entry_points = section.trim() == "options.entry_points";
+            in_console_scripts = false;
+            continue;
+        }
+
+        if !in_entry_points {
+            continue;
+        }
+
+        // Indented lines continue the value of the previous key.
+        if line.starts_with(char::is_whitespace) {
+            if in_console_scripts {
+                scripts.push(trimmed.to_string());
+            }
+            continue;
+        }
+
+        match trimmed.split_once(['=', ':']) {
+            Some((key, value)) => {
+                in_console_scripts = key.trim() == "console_scripts";
+                if in_console_scripts && !value.trim().is_empty() {
+                    scripts.push(value.trim().to_string());
+                }
+            }
+            None => in_console_scripts = false,
+        }
+    }
+
+    scripts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_console_scripts() {
+        let setup_cfg = r#"
+[metadata]
+name = foobar
+
+[options.entry_points]
+# the scripts that are installed
+console_scripts =
+    foo = foobar.cli:main
+    bar = foobar.bar:run
+gui_scripts =
+    baz = foobar.gui:main
+
+[options]
+packages = find:
+"#;
+
+        assert_eq!(
+            console_scripts(setup_cfg),
+            vec!["foo = foobar.cli:main", "bar = foobar.bar:run"]
+        );
+    }
+
+    #[test]
+    fn test_no_entry_points_section() {
+        let setup_cfg = r#"
+[metadata]
+name = foobar
+console_scripts =
+    foo = foobar.cli:main
+"#;
+
+        assert!(console_scripts(setup_cfg).is_empty());
+    }
+}
@@ -1,4 +1,7 @@
-use pixi_build_backend::generated_recipe::BackendConfig;
+use pixi_build_backend::{
+    generated_recipe::BackendConfig,
+    utils::{ExtraChannelPriority, SolveStrategy},
+};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
@@ -10,6 +13,69 @@ pub struct RattlerBuildBackendConfig {
     /// Extra input globs to include in addition to the default ones
     #[serde(default)]
     pub extra_input_globs: Vec<String>,
+    /// Directory, relative to the manifest, that contains one sub-directory
+    /// per recipe (e.g. `recipes/*/recipe.yaml`). If set, all recipes in this
+    /// directory are discovered instead of a single recipe.
+    pub recipes_dir: Option<PathBuf>,
+    /// Channels that are used in addition to the channels that are requested
+    /// by the frontend, either as url or as channel name.
+    #[serde(default)]
+    pub extra_channels: Vec<String>,
+    /// Whether the `extra_channels` are added after (`lowest`, the default)
+    /// or before (`highest`) the channels of the frontend.
+    pub extra_channel_priority: Option<ExtraChannelPriority>,
+    /// The strategy that selects the versions of the packages when the build
+    /// and host environments are solved, `highest` (the default), `lowest` or
+    /// `lowest-direct`.
+    pub solve_strategy: Option<SolveStrategy>,
+    /// Whether the files that are tracked by git-lfs according to the
+    /// `.gitattributes` file are added to the input globs.
+    #[serde(default)]
+    pub lfs_input_globs: bool,
+    /// Sources that are added to the sources of the recipe, e.g. prebuilt
+    /// binaries for a specific platform.
+    #[serde(default)]
+    pub extra_sources: Vec<ExtraSource>,
+    /// Tests that are added to the tests of every output of the recipe, e.g.
+    /// smoke tests for recipes that don't define tests themselves.
+    #[serde(default)]
+    pub extra_tests: Vec<ExtraTest>,
+    /// Whether the rendered recipe is stored in the `info/recipe` directory
+    /// of the built packages.
+    #[serde(default)]
+    pub store_recipe: bool,
+    /// The directory that the rendered recipes are temporarily written to,
+    /// e.g. if the default location is on a small or slow disk. Defaults to
+    /// the output directory of the build.
+    pub temp_dir: Option<PathBuf>,
+}
+
+/// A source that is added to the sources of the recipe.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ExtraSource {
+    /// A rattler-build selector, e.g. `linux64` or `win`, that determines the
+    /// platforms the source is used for. If not set, the source is used for
+    /// all platforms.
+    #[serde(rename = "if")]
+    pub condition: Option<String>,
+    /// The source in the format of a rattler-build recipe, e.g. a `url` and
+    /// its `sha256`.
+    #[serde(flatten)]
+    pub source: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A test that is added to the tests of every output of the recipe.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ExtraTest {
+    /// A rattler-build selector, e.g. `unix` or `win`, that determines the
+    /// platforms the test is used for. If not set, the test is used for all
+    /// platforms.
+    #[serde(rename = "if")]
+    pub condition: Option<String>,
+    /// The test in the format of a rattler-build recipe, e.g. a `script`
+    /// with the commands to run.
+    #[serde(flatten)]
+    pub test: serde_json::Map<String, serde_json::Value>,
 }
 
 impl BackendConfig for RattlerBuildBackendConfig {
@@ -17,14 +83,46 @@ impl BackendConfig for RattlerBuildBackendConfig {
         self.debug_dir.as_deref()
     }
 
+    fn extra_channels(&self) -> &[String] {
+        &self.extra_channels
+    }
+
+    fn extra_channel_priority(&self) -> ExtraChannelPriority {
+        self.extra_channel_priority.unwrap_or_default()
+    }
+
+    fn solve_strategy(&self) -> SolveStrategy {
+        self.solve_strategy.unwrap_or_default()
+    }
+
+    fn store_recipe(&self) -> bool {
+        self.store_recipe
+    }
+
+    fn temp_dir(&self) -> Option<&Path> {
+        self.temp_dir.as_deref()
+    }
+
     /// Merge this configuration with a target-specific configuration.
     /// Target-specific values override base values using the following rules:
     /// - debug_dir: Not allowed to have target specific value
     /// - extra_input_globs: Platform-specific completely replaces base
+    /// - recipes_dir: Platform-specific takes precedence
+    /// - extra_channels: Platform-specific completely replaces base
+    /// - extra_channel_priority: Platform-specific takes precedence
+    /// - solve_strategy: Platform-specific takes precedence
+    /// - lfs_input_globs: Enabled if either base or target enables it
+    /// - extra_sources: Platform-specific completely replaces base
+    /// - extra_tests: Platform-specific completely replaces base
+    /// - store_recipe: Enabled if either base or target enables it
+    /// - temp_dir: Not allowed to have target specific value
     fn merge_with_target_config(&self, target_config: &Self) -> miette::Result<Self> {
         if target_config.debug_dir.is_some() {
             miette::bail!("`debug_dir` cannot have a target specific value");
         }
+        if target_config.temp_dir.is_some() {
+            miette::bail!("`temp_dir` cannot have a target specific value");
+        }
 
         Ok(Self {
             debug_dir: self.debug_dir.clone(),
@@ -33,6 +131,32 @@ impl BackendConfig for RattlerBuildBackendConfig {
             } else {
                 target_config.extra_input_globs.clone()
             },
+            recipes_dir: target_config
+                .recipes_dir
+                .clone()
+                .or_else(|| self.recipes_dir.clone()),
+            extra_channels: if target_config.extra_channels.is_empty() {
+                self.extra_channels.clone()
+            } else {
+                target_config.extra_channels.clone()
+            },
+            extra_channel_priority: target_config
+                .extra_channel_priority
+                .or(self.extra_channel_priority),
+            solve_strategy: target_config.solve_strategy.or(self.solve_strategy),
+            lfs_input_globs: self.lfs_input_globs || target_config.lfs_input_globs,
+            extra_sources: if target_config.extra_sources.is_empty() {
+                self.extra_sources.clone()
+            } else {
+                target_config.extra_sources.clone()
+            },
+            extra_tests: if target_config.extra_tests.is_empty() {
+                self.extra_tests.clone()
+            } else {
+                target_config.extra_tests.clone()
+            },
+            store_recipe: self.store_recipe || target_config.store_recipe,
+            temp_dir: self.temp_dir.clone(),
         })
     }
 }
@@ -40,7 +164,10 @@ impl BackendConfig for RattlerBuildBackendConfig {
 #[cfg(test)]
 mod tests {
     use super::RattlerBuildBackendConfig;
-    use pixi_build_backend::generated_recipe::BackendConfig;
+    use pixi_build_backend::{
+        generated_recipe::BackendConfig,
+        utils::{ExtraChannelPriority, SolveStrategy},
+    };
     use serde_json::json;
     use std::path::PathBuf;
 
@@ -50,16 +177,84 @@ mod tests {
         serde_json::from_value::<RattlerBuildBackendConfig>(json_data).unwrap();
     }
 
+    #[test]
+    fn test_deserialize_extra_sources() {
+        let config = serde_json::from_value::<RattlerBuildBackendConfig>(json!({
+            "extra-sources": [{
+                "if": "linux64",
+                "url": "https://example.com/foo-linux64.tar.gz",
+                "sha256": "0000000000000000000000000000000000000000000000000000000000000000",
+            }],
+        }))
+        .unwrap();
+
+        let extra_source = &config.extra_sources[0];
+        assert_eq!(extra_source.condition.as_deref(), Some("linux64"));
+        assert_eq!(
+            extra_source.source["url"],
+            "https://example.com/foo-linux64.tar.gz"
+        );
+        assert!(!extra_source.source.contains_key("if"));
+    }
+
+    #[test]
+    fn test_deserialize_extra_tests() {
+        let config = serde_json::from_value::<RattlerBuildBackendConfig>(json!({
+            "extra-tests": [{
+                "if": "win",
+                "script": ["foobar.bat --help"],
+            }],
+        }))
+        .unwrap();
+
+        let extra_test = &config.extra_tests[0];
+        assert_eq!(extra_test.condition.as_deref(), Some("win"));
+        assert_eq!(extra_test.test["script"], json!(["foobar.bat --help"]));
+        assert!(!extra_test.test.contains_key("if"));
+    }
+
+    #[test]
+    fn test_deserialize_solve_strategy() {
+        let config = serde_json::from_value::<RattlerBuildBackendConfig>(json!({
+            "solve-strategy": "lowest-direct",
+        }))
+        .unwrap();
+        assert_eq!(config.solve_strategy(), SolveStrategy::LowestDirect);
+
+        let result = serde_json::from_value::<RattlerBuildBackendConfig>(json!({
+            "solve-strategy": "newest",
+        }));
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_merge_with_target_config() {
         let base_config = RattlerBuildBackendConfig {
             debug_dir: Some(PathBuf::from("/base/debug")),
             extra_input_globs: vec!["*.base".to_string()],
+            recipes_dir: Some(PathBuf::from("recipes")),
+            extra_channels: vec!["bioconda".to_string()],
+            extra_channel_priority: None,
+            solve_strategy: Some(SolveStrategy::Lowest),
+            lfs_input_globs: false,
+            extra_sources: vec![],
+            extra_tests: vec![],
+            store_recipe: false,
+            temp_dir: Some(PathBuf::from("/base/tmp")),
         };
 
         let target_config = RattlerBuildBackendConfig {
             debug_dir: None,
             extra_input_globs: vec!["*.target".to_string()],
+            recipes_dir: None,
+            extra_channels: vec!["https://prefix.dev/build-tools".to_string()],
+            extra_channel_priority: Some(ExtraChannelPriority::Highest),
+            solve_strategy: Some(SolveStrategy::LowestDirect),
+            lfs_input_globs: true,
+            extra_sources: vec![],
+            extra_tests: vec![],
+            store_recipe: true,
+            temp_dir: None,
         };
 
         let merged = base_config
@@ -71,6 +266,23 @@ mod tests {
 
         // extra_input_globs should be completely overridden
         assert_eq!(merged.extra_input_globs, vec!["*.target".to_string()]);
+
+        // recipes_dir should fall back to the base value
+        assert_eq!(merged.recipes_dir, Some(PathBuf::from("recipes")));
+
+        // extra_channels should be completely overridden
+        assert_eq!(
+            merged.extra_channels,
+            vec!["https://prefix.dev/build-tools".to_string()]
+        );
+        assert_eq!(
+            merged.extra_channel_priority,
+            Some(ExtraChannelPriority::Highest)
+        );
+        assert_eq!(merged.solve_strategy, Some(SolveStrategy::LowestDirect));
+        assert!(merged.lfs_input_globs);
+        assert!(merged.store_recipe);
+        assert_eq!(merged.temp_dir, Some(PathBuf::from("/base/tmp")));
     }
 
     #[test]
@@ -78,6 +290,15 @@ mod tests {
         let base_config = RattlerBuildBackendConfig {
             debug_dir: Some(PathBuf::from("/base/debug")),
             extra_input_globs: vec!["*.base".to_string()],
+            recipes_dir: Some(PathBuf::from("recipes")),
+            extra_channels: vec!["bioconda".to_string()],
+            extra_channel_priority: Some(ExtraChannelPriority::Highest),
+            solve_strategy: None,
+            lfs_input_globs: false,
+            extra_sources: vec![],
+            extra_tests: vec![],
+            store_recipe: false,
+            temp_dir: Some(PathBuf::from("/base/tmp")),
         };
 
         let empty_target_config = RattlerBuildBackendConfig::default();
@@ -89,6 +310,14 @@ mod tests {
         // Should keep base values when target is empty
         assert_eq!(merged.debug_dir, Some(PathBuf::from("/base/debug")));
         assert_eq!(merged.extra_input_globs, vec!["*.base".to_string()]);
+        assert_eq!(merged.recipes_dir, Some(PathBuf::from("recipes")));
+        assert_eq!(merged.extra_channels, vec!["bioconda".to_string()]);
+        assert_eq!(
+            merged.extra_channel_priority,
+            Some(ExtraChannelPriority::Highest)
+        );
+        assert_eq!(merged.solve_strategy, None);
+        assert!(!merged.store_recipe);
     }
 
     #[test]
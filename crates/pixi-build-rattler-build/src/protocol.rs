@@ -5,17 +5,22 @@ use std::{
     sync::Arc,
 };
 
-use crate::{config::RattlerBuildBackendConfig, rattler_build::RattlerBuildBackend};
+use crate::{
+    config::RattlerBuildBackendConfig,
+    rattler_build::{RattlerBuildBackend, canonicalize_path},
+};
 use fs_err::tokio as tokio_fs;
 use itertools::Itertools;
 use miette::{Context, IntoDiagnostic};
 use pixi_build_backend::specs_conversion::from_build_v1_args_to_finalized_dependencies;
 use pixi_build_backend::{
     dependencies::{convert_binary_dependencies, convert_dependencies},
-    intermediate_backend::{conda_build_v1_directories, find_matching_output},
+    generated_recipe::BackendIdentifier,
+    intermediate_backend::{conda_build_v1_directories, find_matching_output, warn_no_outputs},
     protocol::{Protocol, ProtocolInstantiator},
+    source::Source,
     tools::{LoadedVariantConfig, RattlerBuild},
-    utils::TemporaryRenderedRecipe,
+    utils::{TemporaryRenderedRecipe, lfs_input_globs, output_subdir, with_extra_channels},
 };
 use pixi_build_types::{
     BackendCapabilities, CondaPackageMetadata, PathSpecV1, SourcePackageSpecV1, TargetV1,
@@ -81,6 +86,8 @@ impl Protocol for RattlerBuildBackend {
         &self,
         params: CondaMetadataParams,
     ) -> miette::Result<CondaMetadataResult> {
+        let recipe_source = self.single_recipe_source("conda/getMetadata")?;
+
         // Create the work directory if it does not exist
         tokio_fs::create_dir_all(&params.work_directory)
             .await
@@ -101,24 +108,28 @@ impl Protocol for RattlerBuildBackend {
         let selector_config = RattlerBuild::selector_config_from(&params);
 
         let rattler_build_tool = RattlerBuild::new(
-            self.recipe_source.clone(),
+            recipe_source.clone(),
             selector_config,
             params.work_directory.clone(),
         );
 
         let channel_config = ChannelConfig {
             channel_alias: params.channel_configuration.base_url,
-            root_dir: self
-                .recipe_source
+            root_dir: recipe_source
                 .path
                 .parent()
                 .expect("should have parent")
                 .to_path_buf(),
         };
 
-        let channels = params
-            .channel_base_urls
-            .unwrap_or_else(|| vec![Url::from_str("https://prefix.dev/conda-forge").unwrap()]);
+        let channels = with_extra_channels(
+            params
+                .channel_base_urls
+                .unwrap_or_else(|| vec![Url::from_str("https://prefix.dev/conda-forge").unwrap()]),
+            &self.config.extra_channels,
+            self.config.extra_channel_priority.unwrap_or_default(),
+            &channel_config,
+        )?;
 
         let discovered_outputs =
             rattler_build_tool.discover_outputs(&params.variant_configuration)?;
@@ -163,7 +174,8 @@ impl Protocol for RattlerBuildBackend {
         let mut solved_packages = vec![];
 
         for output in &outputs {
-            let temp_recipe = TemporaryRenderedRecipe::from_output(output)?;
+            let temp_recipe =
+                TemporaryRenderedRecipe::from_output(output, self.config.temp_dir.as_deref())?;
             let tool_config = &tool_config;
             let output = temp_recipe
                 .within_context_async(move || async move {
@@ -220,7 +232,10 @@ impl Protocol for RattlerBuildBackend {
                 version: output.version().clone(),
                 build: build_string.to_string(),
                 build_number: output.recipe.build.number,
-                subdir: output.build_configuration.target_platform,
+                subdir: output_subdir(
+                    output.recipe.build().noarch(),
+                    output.build_configuration.target_platform,
+                ),
                 depends: depends.map(MatchSpec::to_string).collect(),
                 constraints: finalized_deps
                     .constraints
@@ -238,9 +253,13 @@ impl Protocol for RattlerBuildBackend {
 
         let input_globs = Some(get_metadata_input_globs(
             &self.manifest_root,
-            &self.recipe_source.path,
+            &recipe_source.path,
         )?);
 
+        if solved_packages.is_empty() {
+            warn_no_outputs(host_platform);
+        }
+
         Ok(CondaMetadataResult {
             packages: solved_packages,
             input_globs,
@@ -251,204 +270,38 @@ impl Protocol for RattlerBuildBackend {
         &self,
         params: CondaOutputsParams,
     ) -> miette::Result<CondaOutputsResult> {
-        let build_platform = params.host_platform;
-
-        // Determine the variant configuration to use. This loads the variant
-        // configuration from disk as well as including the variants from the input
-        // parameters.
-        let selector_config_for_variants = SelectorConfig {
-            target_platform: params.host_platform,
-            host_platform: params.host_platform,
-            build_platform,
-            hash: None,
-            variant: Default::default(),
-            experimental: false,
-            allow_undefined: false,
-            recipe_path: Some(self.recipe_source.path.clone()),
+        let mut result = CondaOutputsResult {
+            outputs: Vec::new(),
+            input_globs: BTreeSet::new(),
         };
-        let variant_config = LoadedVariantConfig::from_recipe_path(
-            &self.source_dir,
-            &self.recipe_source.path,
-            &selector_config_for_variants,
-        )
-        .into_diagnostic()?
-        .extend_with_input_variants(&params.variant_configuration.unwrap_or_default());
-
-        // Find all outputs from the recipe
-        let output_nodes = find_outputs_from_src(self.recipe_source.clone())?;
-        let discovered_outputs = variant_config
-            .variant_config
-            .find_variants(
-                &output_nodes,
-                self.recipe_source.clone(),
-                &selector_config_for_variants,
-            )
-            .into_diagnostic()?;
-
-        // Construct a mapping that for packages that we want from source.
-        //
-        // By default, this includes all the outputs in the recipe. These should all be
-        // build from source, in particular from the current source.
-        let local_source_packages = discovered_outputs
-            .iter()
-            .map(|output| {
-                (
-                    output.name.clone(),
-                    SourcePackageSpecV1::Path(PathSpecV1 { path: ".".into() }),
-                )
-            })
-            .collect();
-
-        let mut subpackages = HashMap::new();
-        let mut outputs = Vec::new();
-        for discovered_output in discovered_outputs {
-            let variant = discovered_output.used_vars;
-            let hash = HashInfo::from_variant(&variant, &discovered_output.noarch_type);
-
-            // Construct the selector config for this particular output. We base this on the
-            // selector config that was used to determine the variants.
-            let selector_config = SelectorConfig {
-                variant: variant.clone(),
-                hash: Some(hash.clone()),
-                target_platform: discovered_output.target_platform,
-                ..selector_config_for_variants.clone()
-            };
 
-            // Convert this discovered output into a recipe.
-            let recipe = Recipe::from_node(&discovered_output.node, selector_config.clone())
-                .map_err(|err| {
-                    let errs: ParseErrors<_> = err
-                        .into_iter()
-                        .map(|err| ParsingError::from_partial(self.recipe_source.clone(), err))
-                        .collect::<Vec<_>>()
-                        .into();
-                    errs
-                })?;
-
-            // Skip this output if the recipe is marked as skipped
-            if recipe.build().skip() {
-                continue;
-            }
-
-            let jinja = Jinja::new(selector_config);
-            let build_number = recipe.build().number;
-            let build_string = recipe.build().string().resolve(&hash, build_number, &jinja);
-
-            subpackages.insert(
-                recipe.package().name().clone(),
-                PackageIdentifier {
-                    name: recipe.package().name().clone(),
-                    version: recipe.package().version().version().clone().into(),
-                    build_string: build_string.to_string(),
-                },
-            );
-
-            outputs.push(CondaOutput {
-                metadata: CondaOutputMetadata {
-                    name: recipe.package().name().clone(),
-                    version: recipe.package.version().clone(),
-                    build: build_string.to_string(),
-                    build_number,
-                    subdir: discovered_output.target_platform,
-                    license: recipe.about.license.map(|l| l.to_string()),
-                    license_family: recipe.about.license_family,
-                    noarch: recipe.build.noarch,
-                    purls: None,
-                    python_site_packages_path: None,
-                    variant: variant
-                        .iter()
-                        .map(|(k, v)| (k.0.clone(), v.to_string()))
-                        .collect(),
-                },
-                build_dependencies: Some(CondaOutputDependencies {
-                    depends: convert_dependencies(
-                        recipe.requirements.build,
-                        &variant,
-                        &subpackages,
-                        &local_source_packages,
-                    )?,
-                    constraints: Vec::new(),
-                }),
-                host_dependencies: Some(CondaOutputDependencies {
-                    depends: convert_dependencies(
-                        recipe.requirements.host,
-                        &variant,
-                        &subpackages,
-                        &local_source_packages,
-                    )?,
-                    constraints: Vec::new(),
-                }),
-                run_dependencies: CondaOutputDependencies {
-                    depends: convert_dependencies(
-                        recipe.requirements.run,
-                        &BTreeMap::default(), // Variants are not applied to run dependencies
-                        &subpackages,
-                        &local_source_packages,
-                    )?,
-                    constraints: convert_binary_dependencies(
-                        recipe.requirements.run_constraints,
-                        &BTreeMap::default(), // Variants are not applied to run constraints
-                        &subpackages,
-                    )?,
-                },
-                ignore_run_exports: CondaOutputIgnoreRunExports {
-                    by_name: recipe
-                        .requirements
-                        .ignore_run_exports
-                        .by_name
-                        .into_iter()
-                        .collect(),
-                    from_package: recipe
-                        .requirements
-                        .ignore_run_exports
-                        .from_package
-                        .into_iter()
-                        .collect(),
-                },
-                run_exports: CondaOutputRunExports {
-                    weak: convert_dependencies(
-                        recipe.requirements.run_exports.weak,
-                        &variant,
-                        &subpackages,
-                        &local_source_packages,
-                    )?,
-                    strong: convert_dependencies(
-                        recipe.requirements.run_exports.strong,
-                        &variant,
-                        &subpackages,
-                        &local_source_packages,
-                    )?,
-                    noarch: convert_dependencies(
-                        recipe.requirements.run_exports.noarch,
-                        &variant,
-                        &subpackages,
-                        &local_source_packages,
-                    )?,
-                    weak_constrains: convert_binary_dependencies(
-                        recipe.requirements.run_exports.weak_constraints,
-                        &variant,
-                        &subpackages,
-                    )?,
-                    strong_constrains: convert_binary_dependencies(
-                        recipe.requirements.run_exports.strong_constraints,
-                        &variant,
-                        &subpackages,
-                    )?,
-                },
+        // Every recipe is processed as a separate set of outputs.
+        for recipe_source in &self.recipe_sources {
+            let recipe_result = self.recipe_conda_outputs(recipe_source, &params)?;
+            result.outputs.extend(recipe_result.outputs);
+            result.input_globs.extend(recipe_result.input_globs);
+        }
+        if result.outputs.is_empty() {
+            warn_no_outputs(params.host_platform);
+        }
 
-                // The input globs are the same for all outputs
-                input_globs: None,
-                // TODO: Implement caching
-            });
+        // Adding or removing a recipe should invalidate the outputs.
+        if let Some(recipes_dir) = &self.config.recipes_dir {
+            let recipes_dir = recipes_dir.to_string_lossy().replace('\\', "/");
+            result
+                .input_globs
+                .insert(format!("{recipes_dir}/*/recipe.yaml"));
+            result
+                .input_globs
+                .insert(format!("{recipes_dir}/*/recipe.yml"));
         }
 
-        Ok(CondaOutputsResult {
-            outputs,
-            input_globs: variant_config.input_globs,
-        })
+        Ok(result)
     }
 
     async fn conda_build_v0(&self, params: CondaBuildParams) -> miette::Result<CondaBuildResult> {
+        let recipe_source = self.single_recipe_source("conda/build")?;
+
         // Create the work directory if it does not exist
         tokio_fs::create_dir_all(&params.work_directory)
             .await
@@ -470,7 +323,7 @@ impl Protocol for RattlerBuildBackend {
             variant: Default::default(),
             experimental: true,
             allow_undefined: false,
-            recipe_path: Some(self.recipe_source.path.clone()),
+            recipe_path: Some(recipe_source.path.clone()),
         };
 
         let host_vpkgs = params
@@ -495,20 +348,24 @@ impl Protocol for RattlerBuildBackend {
 
         let channel_config = ChannelConfig {
             channel_alias: params.channel_configuration.base_url,
-            root_dir: self
-                .recipe_source
+            root_dir: recipe_source
                 .path
                 .parent()
                 .expect("should have parent")
                 .to_path_buf(),
         };
 
-        let channels = params
-            .channel_base_urls
-            .unwrap_or_else(|| vec![Url::from_str("https://prefix.dev/conda-forge").unwrap()]);
+        let channels = with_extra_channels(
+            params
+                .channel_base_urls
+                .unwrap_or_else(|| vec![Url::from_str("https://prefix.dev/conda-forge").unwrap()]),
+            &self.config.extra_channels,
+            self.config.extra_channel_priority.unwrap_or_default(),
+            &channel_config,
+        )?;
 
         let rattler_build_tool = RattlerBuild::new(
-            self.recipe_source.clone(),
+            recipe_source.clone(),
             selector_config,
             params.work_directory.clone(),
         );
@@ -524,13 +381,15 @@ impl Protocol for RattlerBuildBackend {
                     name: Some(name),
                     version: Some(output.version.clone()),
                     build: output.recipe.build.string.clone().into(),
-                    subdir: Some(output.target_platform.to_string()),
+                    subdir: Some(
+                        output_subdir(&output.noarch_type, output.target_platform).to_string(),
+                    ),
                 };
                 outputs.contains(&id)
             });
         }
 
-        let outputs = rattler_build_tool.get_outputs(
+        let mut outputs = rattler_build_tool.get_outputs(
             &discovered_outputs,
             channels,
             build_vpkgs,
@@ -538,6 +397,11 @@ impl Protocol for RattlerBuildBackend {
             host_platform,
             build_platform,
         )?;
+        let solve_strategy = self.config.solve_strategy.unwrap_or_default();
+        for output in &mut outputs {
+            output.build_configuration.solve_strategy = solve_strategy.into();
+            output.build_configuration.store_recipe = self.config.store_recipe;
+        }
 
         let mut built = vec![];
 
@@ -554,7 +418,8 @@ impl Protocol for RattlerBuildBackend {
             .finish();
 
         for output in outputs {
-            let temp_recipe = TemporaryRenderedRecipe::from_output(&output)?;
+            let temp_recipe =
+                TemporaryRenderedRecipe::from_output(&output, self.config.temp_dir.as_deref())?;
 
             let tool_config = &tool_config;
 
@@ -590,14 +455,15 @@ impl Protocol for RattlerBuildBackend {
                 output_file: build_path,
                 input_globs: build_input_globs(
                     &self.manifest_root,
-                    &self.recipe_source.path,
+                    &recipe_source.path,
                     extract_mutable_package_sources(&output),
-                    self.config.extra_input_globs.clone(),
+                    self.extra_input_globs()?,
                 )?,
                 name: output.name().clone(),
                 version: output.version().to_string(),
                 build: build_string.to_string(),
-                subdir: output.target_platform().to_string(),
+                subdir: output_subdir(output.recipe.build().noarch(), *output.target_platform())
+                    .to_string(),
             });
         }
         Ok(CondaBuildResult { packages: built })
@@ -630,35 +496,53 @@ impl Protocol for RattlerBuildBackend {
             zip_keys: None,
         };
 
-        // Determine the variant configuration to use. This loads the variant
-        // configuration from disk as well as including the variants from the input
-        // parameters.
-        let selector_config_for_variants = SelectorConfig {
-            target_platform: host_platform,
-            host_platform,
-            build_platform,
-            hash: None,
-            variant: Default::default(),
-            experimental: false,
-            allow_undefined: false,
-            recipe_path: Some(self.recipe_source.path.clone()),
+        // Find the recipe that produces the requested output. Usually there is
+        // only a single recipe, but multiple recipes can be discovered from the
+        // `recipes_dir`.
+        let mut matching_output = None;
+        let mut last_error = None;
+        for recipe_source in &self.recipe_sources {
+            // Determine the variant configuration to use. This loads the variant
+            // configuration from disk as well as including the variants from the input
+            // parameters.
+            let selector_config_for_variants = SelectorConfig {
+                target_platform: host_platform,
+                host_platform,
+                build_platform,
+                hash: None,
+                variant: Default::default(),
+                experimental: false,
+                allow_undefined: false,
+                recipe_path: Some(recipe_source.path.clone()),
+            };
+            let outputs = find_outputs_from_src(recipe_source.clone())?;
+            let discovered_outputs = variant_config.find_variants(
+                &outputs,
+                recipe_source.clone(),
+                &selector_config_for_variants,
+            )?;
+            match find_matching_output(&params.output, discovered_outputs) {
+                Ok(discovered_output) => {
+                    matching_output = Some((recipe_source, discovered_output));
+                    break;
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+        let (recipe_source, discovered_output) = match matching_output {
+            Some(matching_output) => matching_output,
+            None => return Err(last_error.expect("there is always at least one recipe")),
         };
-        let outputs = find_outputs_from_src(self.recipe_source.clone())?;
-        let discovered_outputs = variant_config.find_variants(
-            &outputs,
-            self.recipe_source.clone(),
-            &selector_config_for_variants,
-        )?;
-        let discovered_output = find_matching_output(&params.output, discovered_outputs)?;
 
         // Set up the proper directories for the build.
         let directories = conda_build_v1_directories(
             params.host_prefix.as_ref().map(|p| p.prefix.as_path()),
             params.build_prefix.as_ref().map(|p| p.prefix.as_path()),
             params.work_directory,
+            None,
             self.cache_dir.as_deref(),
             params.output_directory.as_deref(),
-            self.recipe_source.path.clone(),
+            recipe_source.path.clone(),
         );
 
         let tool_config = Configuration::builder()
@@ -680,60 +564,292 @@ impl Protocol for RattlerBuildBackend {
                     platform: host_platform,
                     virtual_packages: vec![],
                 },
-                build_platform: PlatformWithVirtualPackages {
-                    platform: build_platform,
-                    virtual_packages: vec![],
+                build_platform: PlatformWithVirtualPackages {
+                    platform: build_platform,
+                    virtual_packages: vec![],
+                },
+                hash: discovered_output.hash,
+                variant: discovered_output.used_vars.clone(),
+                directories,
+                channels: vec![],
+                channel_priority: Default::default(),
+                solve_strategy: self.config.solve_strategy.unwrap_or_default().into(),
+                timestamp: chrono::Utc::now(),
+                subpackages: BTreeMap::new(),
+                packaging_settings: PackagingSettings::from_args(
+                    ArchiveType::Conda,
+                    CompressionLevel::default(),
+                ),
+                store_recipe: self.config.store_recipe,
+                force_colors: true,
+                sandbox_config: None,
+                debug: Debug::new(false),
+                exclude_newer: None,
+            },
+            finalized_dependencies: Some(from_build_v1_args_to_finalized_dependencies(
+                params.build_prefix,
+                params.host_prefix,
+                params.run_dependencies,
+                params.run_constraints,
+                params.run_exports,
+            )),
+            finalized_sources: None,
+            finalized_cache_dependencies: None,
+            finalized_cache_sources: None,
+            build_summary: Arc::default(),
+            system_tools: Default::default(),
+            extra_meta: None,
+        };
+
+        let (output, output_path) =
+            // WorkingDirectoryBehavior::Preserve is blocked by
+            // https://github.com/prefix-dev/rattler-build/issues/1825
+            run_build(output, &tool_config, WorkingDirectoryBehavior::Cleanup).await?;
+
+        Ok(CondaBuildV1Result {
+            output_file: output_path,
+            input_globs: build_input_globs(
+                &self.manifest_root,
+                &recipe_source.path,
+                extract_mutable_package_sources(&output),
+                self.extra_input_globs()?,
+            )?,
+            name: output.name().as_normalized().to_string(),
+            version: output.version().clone(),
+            build: output.build_string().into_owned(),
+            subdir: output_subdir(output.recipe.build().noarch(), *output.target_platform()),
+        })
+    }
+}
+
+impl RattlerBuildBackend {
+    /// Returns the recipe of the backend for the procedures that can only
+    /// handle a single recipe, or an error if `recipes-dir` contains more than
+    /// one recipe.
+    fn single_recipe_source(&self, procedure: &str) -> miette::Result<&Source> {
+        match self.recipe_sources.as_slice() {
+            [recipe_source] => Ok(recipe_source),
+            recipe_sources => miette::bail!(
+                help = "use a frontend that supports the `conda/outputs` procedure",
+                "`{procedure}` supports a single recipe, but `recipes-dir` contains {} recipes",
+                recipe_sources.len()
+            ),
+        }
+    }
+
+    /// Returns the extra input globs of the configuration, together with the
+    /// files that are tracked by git-lfs if `lfs_input_globs` is enabled.
+    fn extra_input_globs(&self) -> miette::Result<Vec<String>> {
+        let mut input_globs = self.config.extra_input_globs.clone();
+        if self.config.lfs_input_globs {
+            input_globs.extend(lfs_input_globs(&self.manifest_root)?);
+        }
+        Ok(input_globs)
+    }
+
+    /// Determines the outputs of a single recipe.
+    fn recipe_conda_outputs(
+        &self,
+        recipe_source: &Source,
+        params: &CondaOutputsParams,
+    ) -> miette::Result<CondaOutputsResult> {
+        let build_platform = params.host_platform;
+
+        // Determine the variant configuration to use. This loads the variant
+        // configuration from disk as well as including the variants from the input
+        // parameters.
+        let selector_config_for_variants = SelectorConfig {
+            target_platform: params.host_platform,
+            host_platform: params.host_platform,
+            build_platform,
+            hash: None,
+            variant: Default::default(),
+            experimental: false,
+            allow_undefined: false,
+            recipe_path: Some(recipe_source.path.clone()),
+        };
+        let variant_config = LoadedVariantConfig::from_recipe_path(
+            &self.source_dir,
+            &recipe_source.path,
+            &selector_config_for_variants,
+        )
+        .into_diagnostic()?
+        .extend_with_input_variants(&params.variant_configuration.clone().unwrap_or_default());
+
+        // Find all outputs from the recipe
+        let output_nodes = find_outputs_from_src(recipe_source.clone())?;
+        let discovered_outputs = variant_config
+            .variant_config
+            .find_variants(
+                &output_nodes,
+                recipe_source.clone(),
+                &selector_config_for_variants,
+            )
+            .into_diagnostic()?;
+
+        // Construct a mapping that for packages that we want from source.
+        //
+        // By default, this includes all the outputs in the recipe. These should all be
+        // build from source, in particular from the current source.
+        let local_source_packages = discovered_outputs
+            .iter()
+            .map(|output| {
+                (
+                    output.name.clone(),
+                    SourcePackageSpecV1::Path(PathSpecV1 { path: ".".into() }),
+                )
+            })
+            .collect();
+
+        let mut subpackages = HashMap::new();
+        let mut outputs = Vec::new();
+        for discovered_output in discovered_outputs {
+            let variant = discovered_output.used_vars;
+            let hash = HashInfo::from_variant(&variant, &discovered_output.noarch_type);
+
+            // Construct the selector config for this particular output. We base this on the
+            // selector config that was used to determine the variants.
+            let selector_config = SelectorConfig {
+                variant: variant.clone(),
+                hash: Some(hash.clone()),
+                target_platform: discovered_output.target_platform,
+                ..selector_config_for_variants.clone()
+            };
+
+            // Convert this discovered output into a recipe.
+            let recipe = Recipe::from_node(&discovered_output.node, selector_config.clone())
+                .map_err(|err| {
+                    let errs: ParseErrors<_> = err
+                        .into_iter()
+                        .map(|err| ParsingError::from_partial(recipe_source.clone(), err))
+                        .collect::<Vec<_>>()
+                        .into();
+                    errs
+                })?;
+
+            // Skip this output if the recipe is marked as skipped
+            if recipe.build().skip() {
+                continue;
+            }
+
+            let jinja = Jinja::new(selector_config);
+            let build_number = recipe.build().number;
+            let build_string = recipe.build().string().resolve(&hash, build_number, &jinja);
+
+            subpackages.insert(
+                recipe.package().name().clone(),
+                PackageIdentifier {
+                    name: recipe.package().name().clone(),
+                    version: recipe.package().version().version().clone().into(),
+                    build_string: build_string.to_string(),
+                },
+            );
+
+            outputs.push(CondaOutput {
+                metadata: CondaOutputMetadata {
+                    name: recipe.package().name().clone(),
+                    version: recipe.package.version().clone(),
+                    build: build_string.to_string(),
+                    build_number,
+                    subdir: output_subdir(
+                        &discovered_output.noarch_type,
+                        discovered_output.target_platform,
+                    ),
+                    license: recipe.about.license.map(|l| l.to_string()),
+                    license_family: recipe.about.license_family,
+                    noarch: recipe.build.noarch,
+                    purls: None,
+                    python_site_packages_path: None,
+                    variant: variant
+                        .iter()
+                        .map(|(k, v)| (k.0.clone(), v.to_string()))
+                        .collect(),
+                },
+                build_dependencies: Some(CondaOutputDependencies {
+                    depends: convert_dependencies(
+                        recipe.requirements.build,
+                        &variant,
+                        &subpackages,
+                        &local_source_packages,
+                    )?,
+                    constraints: Vec::new(),
+                }),
+                host_dependencies: Some(CondaOutputDependencies {
+                    depends: convert_dependencies(
+                        recipe.requirements.host,
+                        &variant,
+                        &subpackages,
+                        &local_source_packages,
+                    )?,
+                    constraints: Vec::new(),
+                }),
+                run_dependencies: CondaOutputDependencies {
+                    depends: convert_dependencies(
+                        recipe.requirements.run,
+                        &BTreeMap::default(), // Variants are not applied to run dependencies
+                        &subpackages,
+                        &local_source_packages,
+                    )?,
+                    constraints: convert_binary_dependencies(
+                        recipe.requirements.run_constraints,
+                        &BTreeMap::default(), // Variants are not applied to run constraints
+                        &subpackages,
+                    )?,
+                },
+                ignore_run_exports: CondaOutputIgnoreRunExports {
+                    by_name: recipe
+                        .requirements
+                        .ignore_run_exports
+                        .by_name
+                        .into_iter()
+                        .collect(),
+                    from_package: recipe
+                        .requirements
+                        .ignore_run_exports
+                        .from_package
+                        .into_iter()
+                        .collect(),
+                },
+                run_exports: CondaOutputRunExports {
+                    weak: convert_dependencies(
+                        recipe.requirements.run_exports.weak,
+                        &variant,
+                        &subpackages,
+                        &local_source_packages,
+                    )?,
+                    strong: convert_dependencies(
+                        recipe.requirements.run_exports.strong,
+                        &variant,
+                        &subpackages,
+                        &local_source_packages,
+                    )?,
+                    noarch: convert_dependencies(
+                        recipe.requirements.run_exports.noarch,
+                        &variant,
+                        &subpackages,
+                        &local_source_packages,
+                    )?,
+                    weak_constrains: convert_binary_dependencies(
+                        recipe.requirements.run_exports.weak_constraints,
+                        &variant,
+                        &subpackages,
+                    )?,
+                    strong_constrains: convert_binary_dependencies(
+                        recipe.requirements.run_exports.strong_constraints,
+                        &variant,
+                        &subpackages,
+                    )?,
                 },
-                hash: discovered_output.hash,
-                variant: discovered_output.used_vars.clone(),
-                directories,
-                channels: vec![],
-                channel_priority: Default::default(),
-                solve_strategy: Default::default(),
-                timestamp: chrono::Utc::now(),
-                subpackages: BTreeMap::new(),
-                packaging_settings: PackagingSettings::from_args(
-                    ArchiveType::Conda,
-                    CompressionLevel::default(),
-                ),
-                store_recipe: false,
-                force_colors: true,
-                sandbox_config: None,
-                debug: Debug::new(false),
-                exclude_newer: None,
-            },
-            finalized_dependencies: Some(from_build_v1_args_to_finalized_dependencies(
-                params.build_prefix,
-                params.host_prefix,
-                params.run_dependencies,
-                params.run_constraints,
-                params.run_exports,
-            )),
-            finalized_sources: None,
-            finalized_cache_dependencies: None,
-            finalized_cache_sources: None,
-            build_summary: Arc::default(),
-            system_tools: Default::default(),
-            extra_meta: None,
-        };
 
-        let (output, output_path) =
-            // WorkingDirectoryBehavior::Preserve is blocked by
-            // https://github.com/prefix-dev/rattler-build/issues/1825
-            run_build(output, &tool_config, WorkingDirectoryBehavior::Cleanup).await?;
+                // The input globs are the same for all outputs
+                input_globs: None,
+                // TODO: Implement caching
+            });
+        }
 
-        Ok(CondaBuildV1Result {
-            output_file: output_path,
-            input_globs: build_input_globs(
-                &self.manifest_root,
-                &self.recipe_source.path,
-                extract_mutable_package_sources(&output),
-                self.config.extra_input_globs.clone(),
-            )?,
-            name: output.name().as_normalized().to_string(),
-            version: output.version().clone(),
-            build: output.build_string().into_owned(),
-            subdir: *output.target_platform(),
+        Ok(CondaOutputsResult {
+            outputs,
+            input_globs: variant_config.input_globs,
         })
     }
 }
@@ -788,6 +904,11 @@ fn build_input_globs(
     package_sources: Option<Vec<PathBuf>>,
     extra_globs: Vec<String>,
 ) -> miette::Result<BTreeSet<String>> {
+    // Resolve symlinks so that a symlinked manifest root and the paths that
+    // rattler-build resolved for the sources share the same prefix.
+    let manifest_root = &canonicalize_path(manifest_root);
+    let source = &canonicalize_path(source);
+
     // Get parent directory path
     let parent = if source.is_file() {
         // use the parent path as glob
@@ -808,6 +929,7 @@ fn build_input_globs(
             } else {
                 parent.join(source)
             };
+            let source = canonicalize_path(&source);
             input_globs.insert(build_relative_glob(manifest_root, &source)?);
         }
     }
@@ -824,7 +946,10 @@ fn get_metadata_input_globs(
     manifest_root: &Path,
     recipe_source_path: &Path,
 ) -> miette::Result<BTreeSet<String>> {
-    match build_relative_glob(manifest_root, recipe_source_path) {
+    match build_relative_glob(
+        &canonicalize_path(manifest_root),
+        &canonicalize_path(recipe_source_path),
+    ) {
         Ok(rel) if !rel.is_empty() => Ok(BTreeSet::from_iter([rel])),
         Ok(_) => Ok(Default::default()),
         Err(e) => Err(e),
@@ -904,6 +1029,13 @@ impl ProtocolInstantiator for RattlerBuildBackendInstantiator {
             capabilities: default_capabilities(),
         })
     }
+
+    fn backend_identifier(&self) -> Option<BackendIdentifier> {
+        Some(BackendIdentifier {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        })
+    }
 }
 
 pub(crate) fn default_capabilities() -> BackendCapabilities {
@@ -1061,6 +1193,49 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_store_recipe() {
+        let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let recipe = manifest_dir.join("../../tests/recipe/boltons/recipe.yaml");
+
+        let factory = RattlerBuildBackendInstantiator::new(LoggingOutputHandler::default())
+            .initialize(InitializeParams {
+                workspace_root: None,
+                source_dir: None,
+                manifest_path: recipe,
+                project_model: None,
+                configuration: Some(serde_json::json!({ "store-recipe": true })),
+                target_configuration: None,
+                cache_directory: None,
+            })
+            .await
+            .unwrap();
+
+        let work_dir = tempdir().unwrap();
+        let result = factory
+            .0
+            .conda_build_v0(CondaBuildParams {
+                build_platform_virtual_packages: None,
+                host_platform: None,
+                channel_base_urls: None,
+                channel_configuration: ChannelConfiguration {
+                    base_url: Url::from_str("https://prefix.dev").unwrap(),
+                },
+                outputs: None,
+                work_directory: work_dir.path().to_path_buf(),
+                variant_configuration: None,
+                editable: false,
+            })
+            .await
+            .unwrap();
+
+        // The rendered recipe is part of the package.
+        let package_dir = work_dir.path().join("package");
+        rattler_package_streaming::fs::extract(&result.packages[0].output_file, &package_dir)
+            .unwrap();
+        assert!(package_dir.join("info/recipe/recipe.yaml").is_file());
+    }
+
     const FAKE_RECIPE: &str = r#"
     package:
       name: foobar
@@ -1088,12 +1263,12 @@ mod tests {
             try_initialize(&tmp.path().join("pixi.toml"))
                 .await
                 .unwrap()
-                .recipe_source
+                .recipe_sources[0]
                 .path,
             recipe
         );
         assert_eq!(
-            try_initialize(&recipe).await.unwrap().recipe_source.path,
+            try_initialize(&recipe).await.unwrap().recipe_sources[0].path,
             recipe
         );
 
@@ -1104,12 +1279,12 @@ mod tests {
             try_initialize(&tmp.path().join("pixi.toml"))
                 .await
                 .unwrap()
-                .recipe_source
+                .recipe_sources[0]
                 .path,
             recipe
         );
         assert_eq!(
-            try_initialize(&recipe).await.unwrap().recipe_source.path,
+            try_initialize(&recipe).await.unwrap().recipe_sources[0].path,
             recipe
         );
 
@@ -1122,7 +1297,7 @@ mod tests {
             try_initialize(&tmp.path().join("pixi.toml"))
                 .await
                 .unwrap()
-                .recipe_source
+                .recipe_sources[0]
                 .path,
             recipe
         );
@@ -1136,12 +1311,245 @@ mod tests {
             try_initialize(&tmp.path().join("pixi.toml"))
                 .await
                 .unwrap()
-                .recipe_source
+                .recipe_sources[0]
                 .path,
             recipe
         );
     }
 
+    #[tokio::test]
+    async fn test_recipes_dir_discovery() {
+        let tmp = tempdir().unwrap();
+        for name in ["foo", "bar"] {
+            let recipe_dir = tmp.path().join("recipes").join(name);
+            std::fs::create_dir_all(&recipe_dir).unwrap();
+            std::fs::write(
+                recipe_dir.join("recipe.yaml"),
+                FAKE_RECIPE.replace("foobar", name),
+            )
+            .unwrap();
+        }
+        // Directories without a recipe are ignored.
+        std::fs::create_dir_all(tmp.path().join("recipes").join("empty")).unwrap();
+
+        let backend = RattlerBuildBackend::new(
+            None,
+            &tmp.path().join("pixi.toml"),
+            LoggingOutputHandler::default(),
+            None,
+            RattlerBuildBackendConfig {
+                recipes_dir: Some(PathBuf::from("recipes")),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(backend.recipe_sources.len(), 2);
+
+        let result = backend
+            .conda_outputs(CondaOutputsParams {
+                channels: vec![],
+                host_platform: Platform::Linux64,
+                build_platform: Platform::Linux64,
+                variant_configuration: None,
+                work_directory: tmp.path().to_path_buf(),
+            })
+            .await
+            .unwrap();
+
+        let names = result
+            .outputs
+            .iter()
+            .map(|output| output.metadata.name.as_normalized().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["bar", "foo"]);
+        assert!(result.input_globs.contains("recipes/*/recipe.yaml"));
+
+        // The legacy procedures can only handle a single recipe.
+        let err = backend
+            .conda_get_metadata(CondaMetadataParams {
+                host_platform: None,
+                build_platform: None,
+                channel_configuration: ChannelConfiguration {
+                    base_url: Url::from_str("https://prefix.dev").unwrap(),
+                },
+                channel_base_urls: None,
+                work_directory: tmp.path().to_path_buf(),
+                variant_configuration: None,
+            })
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("`recipes-dir` contains 2 recipes"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extra_sources_are_platform_specific() {
+        let tmp = tempdir().unwrap();
+        std::fs::write(tmp.path().join("recipe.yaml"), FAKE_RECIPE).unwrap();
+
+        let url = "https://example.com/foobar-linux64.tar.gz";
+        let backend = RattlerBuildBackend::new(
+            None,
+            &tmp.path().join("pixi.toml"),
+            LoggingOutputHandler::default(),
+            None,
+            RattlerBuildBackendConfig {
+                extra_sources: serde_json::from_value(serde_json::json!([{
+                    "if": "linux64",
+                    "url": url,
+                    "sha256": "0000000000000000000000000000000000000000000000000000000000000000",
+                }]))
+                .unwrap(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        for (platform, expect_source) in [(Platform::Linux64, true), (Platform::Win64, false)] {
+            let selector_config = SelectorConfig {
+                target_platform: platform,
+                host_platform: platform,
+                build_platform: platform,
+                hash: None,
+                variant: Default::default(),
+                experimental: false,
+                allow_undefined: false,
+                recipe_path: Some(backend.recipe_sources[0].path.clone()),
+            };
+            let output_nodes = find_outputs_from_src(backend.recipe_sources[0].clone()).unwrap();
+            let recipe = Recipe::from_node(&output_nodes[0], selector_config).unwrap();
+            assert_eq!(
+                serde_yaml::to_string(&recipe).unwrap().contains(url),
+                expect_source,
+                "unexpected sources for {platform}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extra_tests_are_added_to_every_output() {
+        let tmp = tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("recipe.yaml"),
+            r#"
+recipe:
+  name: foobar
+  version: 0.1.0
+
+outputs:
+  - package:
+      name: foo
+  - package:
+      name: bar
+    tests:
+      - script:
+          - bar --version
+"#,
+        )
+        .unwrap();
+
+        let backend = RattlerBuildBackend::new(
+            None,
+            &tmp.path().join("pixi.toml"),
+            LoggingOutputHandler::default(),
+            None,
+            RattlerBuildBackendConfig {
+                extra_tests: serde_json::from_value(serde_json::json!([
+                    { "script": ["smoke-test --all"] },
+                    { "if": "win", "script": ["smoke-test.bat"] },
+                ]))
+                .unwrap(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        for (platform, expect_win_test) in [(Platform::Linux64, false), (Platform::Win64, true)] {
+            let selector_config = SelectorConfig {
+                target_platform: platform,
+                host_platform: platform,
+                build_platform: platform,
+                hash: None,
+                variant: Default::default(),
+                experimental: false,
+                allow_undefined: false,
+                recipe_path: Some(backend.recipe_sources[0].path.clone()),
+            };
+            let output_nodes = find_outputs_from_src(backend.recipe_sources[0].clone()).unwrap();
+            assert_eq!(output_nodes.len(), 2);
+            for output_node in &output_nodes {
+                let recipe = Recipe::from_node(output_node, selector_config.clone()).unwrap();
+                let recipe = serde_yaml::to_string(&recipe).unwrap();
+                assert!(recipe.contains("smoke-test --all"));
+                assert_eq!(
+                    recipe.contains("smoke-test.bat"),
+                    expect_win_test,
+                    "unexpected tests for {platform}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_all_outputs_skipped() {
+        let tmp = tempdir().unwrap();
+        std::fs::write(
+            tmp.path().join("recipe.yaml"),
+            r#"
+package:
+  name: foobar
+  version: 0.1.0
+
+build:
+  skip:
+    - linux
+"#,
+        )
+        .unwrap();
+        let backend = try_initialize(tmp.path().join("pixi.toml")).await.unwrap();
+
+        let params = |platform| CondaOutputsParams {
+            channels: vec![],
+            host_platform: platform,
+            build_platform: platform,
+            variant_configuration: None,
+            work_directory: tmp.path().join("work"),
+        };
+
+        // A platform on which all outputs are skipped is not an error.
+        let result = backend
+            .conda_outputs(params(Platform::Linux64))
+            .await
+            .unwrap();
+        assert!(result.outputs.is_empty());
+
+        let result = backend
+            .conda_outputs(params(Platform::Win64))
+            .await
+            .unwrap();
+        assert_eq!(result.outputs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_recipes_dir_without_recipes() {
+        let tmp = tempdir().unwrap();
+        std::fs::create_dir_all(tmp.path().join("recipes").join("empty")).unwrap();
+
+        let result = RattlerBuildBackend::new(
+            None,
+            &tmp.path().join("pixi.toml"),
+            LoggingOutputHandler::default(),
+            None,
+            RattlerBuildBackendConfig {
+                recipes_dir: Some(PathBuf::from("recipes")),
+                ..Default::default()
+            },
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_relative_path_joined() {
         use std::path::Path;
@@ -1289,6 +1697,44 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_build_input_globs_with_symlinked_root() {
+        use std::fs;
+
+        use tempfile::tempdir;
+
+        // Create a real directory with a recipe and a package source, and a
+        // symlink that points to it.
+        let temp = tempdir().unwrap();
+        let real_root = temp.path().join("real");
+        let linked_root = temp.path().join("linked");
+        fs::create_dir_all(real_root.join("pkg")).unwrap();
+        fs::write(real_root.join("recipe.yaml"), "fake").unwrap();
+        std::os::unix::fs::symlink(&real_root, &linked_root).unwrap();
+
+        let expected = BTreeSet::from([String::from("*/**"), String::from("pkg/**")]);
+
+        // The manifest root is the symlink, while the sources were resolved to
+        // the real directory (and the other way around).
+        let combinations = [(&linked_root, &real_root), (&real_root, &linked_root)];
+        for (manifest_root, source_root) in combinations {
+            let globs = super::build_input_globs(
+                manifest_root,
+                &source_root.join("recipe.yaml"),
+                Some(vec![source_root.join("pkg")]),
+                Vec::new(),
+            )
+            .unwrap();
+            assert_eq!(globs, expected);
+
+            let globs =
+                super::get_metadata_input_globs(manifest_root, &source_root.join("recipe.yaml"))
+                    .unwrap();
+            assert_eq!(globs, BTreeSet::from([String::from("recipe.yaml")]));
+        }
+    }
+
     #[test]
     fn test_get_metadata_input_globs() {
         use std::path::PathBuf;
@@ -1,20 +1,24 @@
 use std::{
     ffi::OsStr,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
-use miette::IntoDiagnostic;
+use miette::{Context, IntoDiagnostic};
 use pixi_build_backend::source::Source;
 use rattler_build::console_utils::LoggingOutputHandler;
 
-use crate::config::RattlerBuildBackendConfig;
+use crate::config::{ExtraSource, ExtraTest, RattlerBuildBackendConfig};
 
 pub struct RattlerBuildBackend {
     pub(crate) logging_output_handler: LoggingOutputHandler,
     pub(crate) source_dir: PathBuf,
     /// In case of rattler-build, manifest is the raw recipe
-    /// We need to apply later the selectors to get the final recipe
-    pub(crate) recipe_source: Source,
+    /// We need to apply later the selectors to get the final recipe.
+    ///
+    /// If `recipes_dir` is configured this contains every recipe in that
+    /// directory, otherwise it only contains the recipe of the manifest.
+    pub(crate) recipe_sources: Vec<Source>,
     pub(crate) manifest_root: PathBuf,
     pub(crate) cache_dir: Option<PathBuf>,
     pub(crate) config: RattlerBuildBackendConfig,
@@ -30,59 +34,220 @@ impl RattlerBuildBackend {
         cache_dir: Option<PathBuf>,
         config: RattlerBuildBackendConfig,
     ) -> miette::Result<Self> {
-        // Locate the recipe
-        let manifest_file_name = manifest_path.file_name().and_then(OsStr::to_str);
-        let (recipe_path, source_dir) = match manifest_file_name {
-            Some("recipe.yaml") | Some("recipe.yml") => {
-                let source_dir = source_dir.unwrap_or_else(|| {
-                    manifest_path
-                        .parent()
-                        .expect("file always has parent")
-                        .to_path_buf()
-                });
-                (manifest_path.to_path_buf(), source_dir)
-            }
-            _ => {
-                // The manifest is not a recipe, so we need to find the recipe.yaml file.
-                let source_dir = source_dir.unwrap_or_else(|| {
-                    manifest_path
-                        .parent()
-                        .unwrap_or(manifest_path)
-                        .to_path_buf()
-                });
-                let recipe_path = manifest_path.parent().and_then(|manifest_dir| {
-                    [
-                        "recipe.yaml",
-                        "recipe.yml",
-                        "recipe/recipe.yaml",
-                        "recipe/recipe.yml",
-                    ]
-                    .into_iter()
-                    .find_map(|relative_path| {
-                        let recipe_path = manifest_dir.join(relative_path);
-                        recipe_path.is_file().then_some(recipe_path)
-                    })
-                });
-
-                (recipe_path.ok_or_else(|| miette::miette!("Could not find a recipe.yaml in the source directory to use as the recipe manifest."))?, source_dir)
-            }
+        // Resolve symlinks in the directories up front so that the recipe paths
+        // and the input globs that are derived from them are consistent
+        // between requests.
+        let manifest_root =
+            canonicalize_path(manifest_path.parent().expect("manifest must have a root"));
+        let manifest_path = &manifest_path
+            .file_name()
+            .map(|file_name| manifest_root.join(file_name))
+            .unwrap_or_else(|| manifest_path.to_path_buf());
+        let source_dir = source_dir.map(|source_dir| canonicalize_path(&source_dir));
+
+        // Locate the recipes
+        let (recipe_paths, source_dir) = if let Some(recipes_dir) = &config.recipes_dir {
+            let source_dir = source_dir.unwrap_or_else(|| manifest_root.clone());
+            (
+                discover_recipes(&manifest_root.join(recipes_dir))?,
+                source_dir,
+            )
+        } else {
+            let manifest_file_name = manifest_path.file_name().and_then(OsStr::to_str);
+            let (recipe_path, source_dir) = match manifest_file_name {
+                Some("recipe.yaml") | Some("recipe.yml") => {
+                    let source_dir = source_dir.unwrap_or_else(|| {
+                        manifest_path
+                            .parent()
+                            .expect("file always has parent")
+                            .to_path_buf()
+                    });
+                    (manifest_path.to_path_buf(), source_dir)
+                }
+                _ => {
+                    // The manifest is not a recipe, so we need to find the recipe.yaml file.
+                    let source_dir = source_dir.unwrap_or_else(|| {
+                        manifest_path
+                            .parent()
+                            .unwrap_or(manifest_path)
+                            .to_path_buf()
+                    });
+                    let recipe_path = manifest_path.parent().and_then(|manifest_dir| {
+                        [
+                            "recipe.yaml",
+                            "recipe.yml",
+                            "recipe/recipe.yaml",
+                            "recipe/recipe.yml",
+                        ]
+                        .into_iter()
+                        .find_map(|relative_path| {
+                            let recipe_path = manifest_dir.join(relative_path);
+                            recipe_path.is_file().then_some(recipe_path)
+                        })
+                    });
+
+                    (recipe_path.ok_or_else(|| miette::miette!("Could not find a recipe.yaml in the source directory to use as the recipe manifest."))?, source_dir)
+                }
+            };
+            (vec![recipe_path], source_dir)
         };
 
-        // Load the manifest from the source directory
-        let manifest_root = manifest_path
-            .parent()
-            .expect("manifest must have a root")
-            .to_path_buf();
-        let recipe_source =
-            Source::from_rooted_path(&manifest_root, recipe_path).into_diagnostic()?;
+        // Load the recipes from the source directory
+        let recipe_sources = recipe_paths
+            .into_iter()
+            .map(|recipe_path| {
+                let recipe =
+                    Source::from_rooted_path(&manifest_root, recipe_path).into_diagnostic()?;
+                let recipe = add_extra_sources(recipe, &config.extra_sources)?;
+                add_extra_tests(recipe, &config.extra_tests)
+            })
+            .collect::<miette::Result<Vec<_>>>()?;
 
         Ok(Self {
             logging_output_handler,
             source_dir,
-            recipe_source,
+            recipe_sources,
             manifest_root,
             cache_dir,
             config,
         })
     }
 }
+
+/// Adds the `extra_sources` of the configuration to the sources of the recipe.
+/// Sources with a condition are added as `if`/`then` items, so rattler-build
+/// only uses them for the matching platforms.
+fn add_extra_sources(recipe: Source, extra_sources: &[ExtraSource]) -> miette::Result<Source> {
+    if extra_sources.is_empty() {
+        return Ok(recipe);
+    }
+
+    let mut yaml = serde_yaml::from_str::<serde_yaml::Value>(&recipe.code)
+        .into_diagnostic()
+        .with_context(|| format!("failed to parse the recipe '{}'", recipe.name))?;
+    let Some(mapping) = yaml.as_mapping_mut() else {
+        miette::bail!("the recipe '{}' is not a mapping", recipe.name);
+    };
+
+    let sources = extra_sources
+        .iter()
+        .map(|extra_source| {
+            let source = serde_yaml::to_value(&extra_source.source).into_diagnostic()?;
+            Ok(conditional_item(extra_source.condition.as_deref(), source))
+        })
+        .collect::<miette::Result<Vec<_>>>()?;
+    append_to_sequence(mapping, "source", sources);
+
+    Ok(Source {
+        code: Arc::from(serde_yaml::to_string(&yaml).into_diagnostic()?.as_str()),
+        ..recipe
+    })
+}
+
+/// Adds the `extra_tests` of the configuration to the tests of every output
+/// of the recipe. For recipes with a single output the tests are added to the
+/// top-level `tests`.
+fn add_extra_tests(recipe: Source, extra_tests: &[ExtraTest]) -> miette::Result<Source> {
+    if extra_tests.is_empty() {
+        return Ok(recipe);
+    }
+
+    let mut yaml = serde_yaml::from_str::<serde_yaml::Value>(&recipe.code)
+        .into_diagnostic()
+        .with_context(|| format!("failed to parse the recipe '{}'", recipe.name))?;
+    let Some(mapping) = yaml.as_mapping_mut() else {
+        miette::bail!("the recipe '{}' is not a mapping", recipe.name);
+    };
+
+    let tests = extra_tests
+        .iter()
+        .map(|extra_test| {
+            let test = serde_yaml::to_value(&extra_test.test).into_diagnostic()?;
+            Ok(conditional_item(extra_test.condition.as_deref(), test))
+        })
+        .collect::<miette::Result<Vec<_>>>()?;
+
+    match mapping.get_mut("outputs") {
+        Some(serde_yaml::Value::Sequence(outputs)) => {
+            for output in outputs {
+                // Conditional outputs are `if`/`then` items.
+                let output = if output.get("then").is_some() {
+                    &mut output["then"]
+                } else {
+                    output
+                };
+                if let Some(output) = output.as_mapping_mut() {
+                    append_to_sequence(output, "tests", tests.clone());
+                }
+            }
+        }
+        _ => append_to_sequence(mapping, "tests", tests),
+    }
+
+    Ok(Source {
+        code: Arc::from(serde_yaml::to_string(&yaml).into_diagnostic()?.as_str()),
+        ..recipe
+    })
+}
+
+/// Wraps a recipe item in an `if`/`then` item if there is a condition.
+fn conditional_item(condition: Option<&str>, item: serde_yaml::Value) -> serde_yaml::Value {
+    match condition {
+        Some(condition) => serde_yaml::Value::Mapping(serde_yaml::Mapping::from_iter([
+            ("if".into(), condition.into()),
+            ("then".into(), item),
+        ])),
+        None => item,
+    }
+}
+
+/// Appends `items` to the sequence at `key` of the mapping, creating it if it
+/// doesn't exist yet.
+fn append_to_sequence(mapping: &mut serde_yaml::Mapping, key: &str, items: Vec<serde_yaml::Value>) {
+    let mut sequence = match mapping.remove(key) {
+        None => Vec::new(),
+        Some(serde_yaml::Value::Sequence(sequence)) => sequence,
+        Some(item) => vec![item],
+    };
+    sequence.extend(items);
+    mapping.insert(key.into(), serde_yaml::Value::Sequence(sequence));
+}
+
+/// Resolves all symlinks in `path` if it exists. Paths that cannot be
+/// canonicalized are returned as-is. On Windows this avoids the `\\?\` prefix
+/// where possible.
+pub(crate) fn canonicalize_path(path: &Path) -> PathBuf {
+    dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Discovers the recipes in the direct sub-directories of `recipes_dir`, e.g.
+/// `recipes/*/recipe.yaml`. The recipes are sorted by path so the order of the
+/// outputs is stable.
+fn discover_recipes(recipes_dir: &Path) -> miette::Result<Vec<PathBuf>> {
+    let entries = fs_err::read_dir(recipes_dir).into_diagnostic()?;
+
+    let mut recipes = Vec::new();
+    for entry in entries {
+        let path = entry.into_diagnostic()?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        if let Some(recipe_path) = ["recipe.yaml", "recipe.yml"]
+            .into_iter()
+            .map(|file_name| path.join(file_name))
+            .find(|recipe_path| recipe_path.is_file())
+        {
+            recipes.push(recipe_path);
+        }
+    }
+    recipes.sort();
+
+    if recipes.is_empty() {
+        miette::bail!(
+            "Could not find a recipe.yaml in any of the sub-directories of '{}'.",
+            recipes_dir.display()
+        );
+    }
+
+    Ok(recipes)
+}
@@ -6,17 +6,65 @@ pub struct BuildScriptContext {
     /// The location of the source
     pub source_dir: String,
 
+    /// A subdirectory of the source that the build runs in, if set.
+    pub build_subdir: Option<String>,
+
     /// Any additional args to pass to `cargo`
     pub extra_args: Vec<String>,
 
-    /// True if `openssl` is part of the build environment
-    pub has_openssl: bool,
+    /// The number of parallel jobs that cargo uses, if set.
+    pub jobs: Option<usize>,
+
+    /// The binary that is installed, if only a single binary of the crate
+    /// should be installed.
+    pub bin: Option<String>,
+
+    /// Build with the `dev` profile of cargo instead of the `release` profile.
+    pub debug: bool,
+
+    /// The environment variables of the system libraries that are part of
+    /// the build environment, e.g. `OPENSSL_DIR` for `openssl`.
+    pub system_libs: Vec<SystemLibEnv>,
 
     /// True if `sccache` is available.
     pub has_sccache: bool,
 
     /// The platform that is running the build.
     pub is_bash: bool,
+
+    /// Commands that are run before the generated commands.
+    pub prelude: Vec<String>,
+}
+
+/// An environment variable that makes the `-sys` crate of a system library
+/// link the library of the environment instead of a vendored copy.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SystemLibEnv {
+    /// The name of the environment variable.
+    pub key: &'static str,
+    /// The value of the environment variable, or `None` for the prefix of the
+    /// environment.
+    pub value: Option<&'static str>,
+}
+
+impl SystemLibEnv {
+    /// The names of the system libraries that have a known environment
+    /// variable.
+    pub const SUPPORTED: &[&str] = &["openssl", "libgit2", "libssh2", "zstd"];
+
+    /// Returns the environment variable of a system library, e.g.
+    /// `OPENSSL_DIR` for `openssl`, or `None` if the library is not
+    /// supported.
+    pub fn for_lib(name: &str) -> Option<Self> {
+        let (key, value) = match name {
+            "openssl" => ("OPENSSL_DIR", None),
+            "libgit2" => ("LIBGIT2_NO_VENDOR", Some("1")),
+            "libssh2" => ("LIBSSH2_SYS_USE_PKG_CONFIG", Some("1")),
+            "zstd" => ("ZSTD_SYS_USE_PKG_CONFIG", Some("1")),
+            _ => return None,
+        };
+        Some(Self { key, value })
+    }
 }
 
 impl BuildScriptContext {
@@ -42,10 +90,15 @@ mod test {
     fn test_build_script(#[values(true, false)] is_bash: bool) {
         let context = super::BuildScriptContext {
             source_dir: String::from("my-prefix-dir"),
+            build_subdir: None,
             extra_args: vec![],
-            has_openssl: false,
+            jobs: None,
+            bin: None,
+            debug: false,
+            system_libs: vec![],
             has_sccache: false,
             is_bash,
+            prelude: vec![],
         };
         let script = context.render();
 
@@ -60,10 +113,15 @@ mod test {
     fn test_sccache(#[values(true, false)] is_bash: bool) {
         let context = super::BuildScriptContext {
             source_dir: String::from("my-prefix-dir"),
+            build_subdir: None,
             extra_args: vec![],
-            has_openssl: false,
+            jobs: None,
+            bin: None,
+            debug: false,
+            system_libs: vec![],
             has_sccache: true,
             is_bash,
+            prelude: vec![],
         };
         let script = context.render();
 
@@ -78,10 +136,38 @@ mod test {
     fn test_openssl(#[values(true, false)] is_bash: bool) {
         let context = super::BuildScriptContext {
             source_dir: String::from("my-prefix-dir"),
+            build_subdir: None,
+            extra_args: vec![],
+            jobs: None,
+            bin: None,
+            debug: false,
+            system_libs: vec![super::SystemLibEnv::for_lib("openssl").unwrap()],
+            has_sccache: false,
+            is_bash,
+            prelude: vec![],
+        };
+        let script = context.render();
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_suffix(if is_bash { "bash" } else { "cmdexe" });
+        settings.bind(|| {
+            insta::assert_snapshot!(script.join("\n"));
+        });
+    }
+
+    #[rstest]
+    fn test_jobs(#[values(true, false)] is_bash: bool) {
+        let context = super::BuildScriptContext {
+            source_dir: String::from("my-prefix-dir"),
+            build_subdir: None,
             extra_args: vec![],
-            has_openssl: true,
+            jobs: Some(4),
+            bin: None,
+            debug: false,
+            system_libs: vec![],
             has_sccache: false,
             is_bash,
+            prelude: vec![],
         };
         let script = context.render();
 
@@ -91,4 +177,94 @@ mod test {
             insta::assert_snapshot!(script.join("\n"));
         });
     }
+
+    #[rstest]
+    fn test_prelude(#[values(true, false)] is_bash: bool) {
+        let context = super::BuildScriptContext {
+            source_dir: String::from("my-prefix-dir"),
+            build_subdir: None,
+            extra_args: vec![],
+            jobs: None,
+            bin: None,
+            debug: false,
+            system_libs: vec![super::SystemLibEnv::for_lib("openssl").unwrap()],
+            has_sccache: false,
+            is_bash,
+            prelude: vec![
+                String::from("echo Starting build"),
+                String::from("setup-env"),
+            ],
+        };
+        let script = context.render();
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_suffix(if is_bash { "bash" } else { "cmdexe" });
+        settings.bind(|| {
+            insta::assert_snapshot!(script.join("\n"));
+        });
+    }
+
+    #[rstest]
+    fn test_bin(#[values(true, false)] is_bash: bool) {
+        let context = super::BuildScriptContext {
+            source_dir: String::from("my-prefix-dir"),
+            build_subdir: None,
+            extra_args: vec![],
+            jobs: None,
+            bin: Some(String::from("foo")),
+            debug: false,
+            system_libs: vec![],
+            has_sccache: false,
+            is_bash,
+            prelude: vec![],
+        };
+        let script = context.render();
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_suffix(if is_bash { "bash" } else { "cmdexe" });
+        settings.bind(|| {
+            insta::assert_snapshot!(script.join("\n"));
+        });
+    }
+
+    #[rstest]
+    fn test_build_subdir(#[values(true, false)] is_bash: bool) {
+        let context = super::BuildScriptContext {
+            source_dir: String::from("my-prefix-dir"),
+            build_subdir: Some(String::from("packages/foo")),
+            extra_args: vec![],
+            jobs: None,
+            bin: None,
+            debug: false,
+            system_libs: vec![],
+            has_sccache: false,
+            is_bash,
+            prelude: vec![],
+        };
+        let script = context.render();
+
+        let mut settings = insta::Settings::clone_current();
+        settings.set_snapshot_suffix(if is_bash { "bash" } else { "cmdexe" });
+        settings.bind(|| {
+            insta::assert_snapshot!(script.join("\n"));
+        });
+    }
+
+    #[test]
+    fn test_no_jobs_by_default() {
+        let context = super::BuildScriptContext {
+            source_dir: String::from("my-prefix-dir"),
+            build_subdir: None,
+            extra_args: vec![],
+            jobs: None,
+            bin: None,
+            debug: false,
+            system_libs: vec![],
+            has_sccache: false,
+            is_bash: true,
+            prelude: vec![],
+        };
+        let script = context.render().join("\n");
+        assert!(!script.contains("--jobs"), "{script}");
+    }
 }
@@ -1,9 +1,23 @@
 use indexmap::IndexMap;
-use pixi_build_backend::generated_recipe::BackendConfig;
-use std::path::{Path, PathBuf};
+use pixi_build_backend::{
+    compilers::merge_compilers,
+    generated_recipe::BackendConfig,
+    utils::{
+        BuildProfile, DynamicLinkingConfig, ExtraChannelPriority, SolveStrategy,
+        VersionNormalization,
+    },
+};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
+use recipe_stage0::recipe::SourceFilter;
 use serde::{Deserialize, Serialize};
 
+/// The compilers that are used if `compilers` is not configured.
+pub(crate) const DEFAULT_COMPILERS: &[&str] = &["rust"];
+
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct RustBackendConfig {
@@ -13,20 +27,209 @@ pub struct RustBackendConfig {
     /// Environment Variables
     #[serde(default)]
     pub env: IndexMap<String, String>,
+    /// Names of environment variables that are passed to the build script as
+    /// secrets, their values are masked in the build log.
+    #[serde(default)]
+    pub secrets: Vec<String>,
     /// If set, internal state will be logged as files in that directory
     pub debug_dir: Option<PathBuf>,
     /// Extra input globs to include in addition to the default ones
     #[serde(default)]
     pub extra_input_globs: Vec<String>,
+    /// Default input globs of the backend that are removed, e.g. to not
+    /// rebuild the package when the documentation changes.
+    #[serde(default)]
+    pub remove_input_globs: Vec<String>,
     /// Ignore the cargo manifest and depend only on the project model.
     #[serde(default)]
     pub ignore_cargo_manifest: Option<bool>,
     /// List of compilers to use (e.g., ["rust", "c", "cxx"])
     /// If not specified, a default will be used
     pub compilers: Option<Vec<String>>,
+    /// List of standard libraries to add (e.g., ["c", "fortran"]). Each one is
+    /// only added if the variants contain a matching `<name>_stdlib` key.
+    /// If not specified, the `c` standard library is used for the compilers
+    pub stdlibs: Option<Vec<String>>,
+    /// Compilers that are only added if the variants contain a matching
+    /// `<name>_compiler_version` key, e.g. `cuda` for `cuda_compiler_version`.
+    #[serde(default)]
+    pub variant_compilers: Vec<String>,
+    /// Number of times a failed build is retried before giving up. The
+    /// work directory is cleaned between attempts. Capped at 5.
+    pub build_retries: Option<u32>,
+    /// Merge the build and host environments into a single environment.
+    /// Required for some cross-compilation setups. Defaults to `false`.
+    pub merge_build_and_host_envs: Option<bool>,
+    /// Constraints that are added to the run requirements of the package
+    /// without making them a hard dependency (e.g. `numpy >=1.20`).
+    #[serde(default)]
+    pub run_constraints: Vec<String>,
+    /// Names of declared dependencies that are only needed at build time.
+    /// These are moved from the host and run requirements into the build
+    /// requirements (e.g. `make`).
+    #[serde(default)]
+    pub build_only: Vec<String>,
+    /// Globs of files that are copied into the package instead of being
+    /// hardlinked, e.g. data files that are modified after installation.
+    #[serde(default)]
+    pub always_copy_files: Vec<String>,
+    /// Directories, relative to the manifest root, that are added as
+    /// additional path sources, e.g. a data directory that the build needs.
+    #[serde(default)]
+    pub additional_sources: Vec<String>,
+    /// Whether files that are ignored by git are left out of the
+    /// `additional_sources`. Defaults to `true`.
+    pub additional_sources_use_gitignore: Option<bool>,
+    /// Include and exclude globs, relative to every directory of the
+    /// `additional_sources`, that select the files that are part of it.
+    pub additional_sources_filter: Option<SourceFilter>,
+    /// Configuration of the overlinking and overdepending checks of
+    /// rattler-build, e.g. to allow intentional linkage against system
+    /// libraries.
+    #[serde(default)]
+    pub dynamic_linking: Option<DynamicLinkingConfig>,
+    /// A subdirectory of the source that the build runs in, e.g. the
+    /// directory of the package in a monorepo. The default input globs are
+    /// scoped to this directory.
+    #[serde(default)]
+    pub build_subdir: Option<String>,
+    /// Whether dependencies are removed from the host requirements if they
+    /// are already part of the build requirements with the same spec.
+    #[serde(default)]
+    pub dedupe_requirements: bool,
+    /// An explicit build string template, e.g. `${{ hash }}_custom`. If not
+    /// set, the build string is derived from the hash and the build number.
+    pub build_string: Option<String>,
+    /// The name of an environment variable that holds the build number, e.g.
+    /// `CONDA_BUILD_NUMBER`. If the variable is set, its value is used as the
+    /// build number of the package.
+    pub build_number_env: Option<String>,
+    /// Whether the build number is set to the number of commits in the git
+    /// repository of the manifest. The `build_number_env` takes precedence.
+    #[serde(default)]
+    pub build_number_from_git_count: bool,
+    /// The conda license family of the package, e.g. `MIT` or `APACHE`. If not
+    /// set, it is derived from the license when possible.
+    pub license_family: Option<String>,
+    /// The archive types of the built packages, e.g. `["conda", "tar.bz2"]`.
+    /// Defaults to `["conda"]`.
+    #[serde(default)]
+    pub archive_types: Vec<String>,
+    /// Whether the name and the version of the backend are recorded in the
+    /// extra metadata of the built packages. Defaults to `true`.
+    pub record_provenance: Option<bool>,
+    /// Whether the rendered recipe is stored in the `info/recipe` directory of
+    /// the built packages.
+    #[serde(default)]
+    pub store_recipe: bool,
+    /// The version that is used if neither the manifest nor the package
+    /// metadata define a version.
+    pub default_version: Option<String>,
+    /// Whether a missing version is an error, even if a `default_version` is
+    /// set.
+    #[serde(default)]
+    pub require_version: bool,
+    /// Turns warnings that occur while generating the recipe into errors.
+    #[serde(default)]
+    pub deny_warnings: bool,
+    /// Whether the license of the package must be a valid SPDX license
+    /// expression. Templated licenses are not checked.
+    #[serde(default)]
+    pub validate_license: bool,
+    /// Variant keys that don't influence the hash of the build string, e.g.
+    /// keys that only have a cosmetic effect on the package.
+    #[serde(default)]
+    pub hash_ignore_keys: Vec<String>,
+    /// The maximum duration of a single backend operation in seconds, e.g.
+    /// solving the environments and building the package. Operations that
+    /// take longer fail with a timeout error.
+    pub operation_timeout: Option<u64>,
+    /// A base directory for the build directories of the packages, e.g. on a
+    /// faster local disk. Every package is built in a subdirectory named after
+    /// the work directory that is requested by the frontend.
+    pub build_dir: Option<PathBuf>,
+    /// The directory that the rendered recipes are temporarily written to,
+    /// e.g. if the default location is on a small or slow disk. Defaults to the
+    /// output directory of the build.
+    pub temp_dir: Option<PathBuf>,
+    /// The strategy that selects the versions of the packages when the build
+    /// and host environments are solved, `highest` (the default), `lowest` or
+    /// `lowest-direct`.
+    pub solve_strategy: Option<SolveStrategy>,
+    /// How the version that is read from the manifest is converted to the
+    /// version of the recipe, e.g. `conda` to turn `1.0.0-alpha.1` into
+    /// `1.0.0a1`. Defaults to using the version as it is.
+    pub version_normalization: Option<VersionNormalization>,
+    /// The profile that the package is built with, e.g. `dev` for a debug
+    /// build. If not set, the defaults of the backend are used.
+    pub profile: Option<BuildProfile>,
+    /// Whether the fully resolved recipe of every output is written to the
+    /// `debug_dir`, after all templates and selectors have been evaluated.
+    #[serde(default)]
+    pub debug_resolved_recipes: bool,
+    /// Whether the files that are tracked by git-lfs according to the
+    /// `.gitattributes` file are added to the input globs.
+    #[serde(default)]
+    pub lfs_input_globs: bool,
+    /// Channels that are used in addition to the channels that are requested
+    /// by the frontend, either as url or as channel name.
+    #[serde(default)]
+    pub extra_channels: Vec<String>,
+    /// Whether the `extra_channels` are added after (`lowest`, the default)
+    /// or before (`highest`) the channels of the frontend.
+    pub extra_channel_priority: Option<ExtraChannelPriority>,
+    /// A YAML file with channels that are used in addition to the
+    /// `extra_channels`, either a list of channels or a `.condarc` file with a
+    /// `channels` key. Relative paths are resolved against the source directory.
+    pub channels_file: Option<PathBuf>,
+    /// Extra variables that are added to the context of the recipe, e.g. a
+    /// `cdn_base` that is referenced by the sources as `${{ cdn_base }}`.
+    /// Variables that are defined by the backend take precedence.
+    #[serde(default)]
+    pub context: IndexMap<String, String>,
+    /// The number of parallel jobs that cargo uses (`--jobs`). If not set,
+    /// cargo's own default is used.
+    pub jobs: Option<usize>,
+    /// Whether a `pin_subpackage` weak run export is added for crates that
+    /// build a `cdylib` or `staticlib`, so that consumers that link against the
+    /// library get a compatible version at runtime.
+    #[serde(default)]
+    pub auto_run_exports: bool,
+    /// The interpreter that runs the build script, either `bash` or `cmd`.
+    /// If not set, rattler-build picks `bash` or `cmd` based on the platform.
+    pub interpreter: Option<String>,
+    /// Commands that are run at the start of the build script, before the
+    /// generated commands, e.g. `set -euo pipefail` or sourcing a setup script.
+    #[serde(default)]
+    pub script_prelude: Vec<String>,
+    /// A url that the sources of the crate are fetched from instead of the
+    /// manifest directory, e.g. a crates.io download url. The version of the
+    /// package can be referenced as `${{ version }}`.
+    pub source_url: Option<String>,
+    /// The sha256 hash of the archive at `source_url`.
+    pub source_sha256: Option<String>,
+    /// Only install the `default-run` binary of the crate instead of all of
+    /// its binaries. If the Cargo.toml does not define `default-run`, the crate
+    /// must have exactly one binary.
+    #[serde(default)]
+    pub only_default_run: bool,
+    /// Libraries of the host environment that the `-sys` crates link instead
+    /// of building a vendored copy, e.g. `libgit2`. The build script sets the
+    /// environment variables of a library if it is a host dependency. Defaults
+    /// to `openssl`.
+    pub link_system_libs: Option<Vec<String>>,
 }
 
 impl RustBackendConfig {
+    /// The system libraries that are linked from the host environment,
+    /// defaults to `openssl`.
+    pub fn link_system_libs(&self) -> Vec<&str> {
+        match &self.link_system_libs {
+            Some(libs) => libs.iter().map(String::as_str).collect(),
+            None => vec!["openssl"],
+        }
+    }
+
     /// Creates a new [`RustBackendConfig`] with default values and
     /// `ignore_cargo_manifest` set to `true`.
     #[cfg(test)]
@@ -43,16 +246,150 @@ impl BackendConfig for RustBackendConfig {
         self.debug_dir.as_deref()
     }
 
+    fn build_retries(&self) -> Option<u32> {
+        self.build_retries
+    }
+
+    fn archive_types(&self) -> &[String] {
+        &self.archive_types
+    }
+
+    fn record_provenance(&self) -> bool {
+        self.record_provenance.unwrap_or(true)
+    }
+
+    fn store_recipe(&self) -> bool {
+        self.store_recipe
+    }
+
+    fn default_version(&self) -> Option<&str> {
+        self.default_version.as_deref()
+    }
+
+    fn require_version(&self) -> bool {
+        self.require_version
+    }
+
+    fn deny_warnings(&self) -> bool {
+        self.deny_warnings
+    }
+
+    fn validate_license(&self) -> bool {
+        self.validate_license
+    }
+
+    fn hash_ignore_keys(&self) -> &[String] {
+        &self.hash_ignore_keys
+    }
+
+    fn operation_timeout(&self) -> Option<Duration> {
+        self.operation_timeout.map(Duration::from_secs)
+    }
+
+    fn build_dir(&self) -> Option<&Path> {
+        self.build_dir.as_deref()
+    }
+
+    fn temp_dir(&self) -> Option<&Path> {
+        self.temp_dir.as_deref()
+    }
+
+    fn solve_strategy(&self) -> SolveStrategy {
+        self.solve_strategy.unwrap_or_default()
+    }
+
+    fn profile(&self) -> Option<BuildProfile> {
+        self.profile
+    }
+
+    fn version_normalization(&self) -> VersionNormalization {
+        self.version_normalization.unwrap_or_default()
+    }
+
+    fn debug_resolved_recipes(&self) -> bool {
+        self.debug_resolved_recipes
+    }
+
+    fn extra_channels(&self) -> &[String] {
+        &self.extra_channels
+    }
+
+    fn extra_channel_priority(&self) -> ExtraChannelPriority {
+        self.extra_channel_priority.unwrap_or_default()
+    }
+
+    fn channels_file(&self) -> Option<&Path> {
+        self.channels_file.as_deref()
+    }
+
     /// Merge this configuration with a target-specific configuration.
     /// Target-specific values override base values using the following rules:
     /// - extra_args: Platform-specific completely replaces base
     /// - env: Platform env vars override base, others merge
+    /// - secrets: Platform-specific completely replaces base
     /// - debug_dir: Not allowed to have target specific value
     /// - extra_input_globs: Platform-specific completely replaces base
+    /// - remove_input_globs: Platform-specific completely replaces base
+    /// - build_retries: Platform-specific takes precedence
+    /// - merge_build_and_host_envs: Platform-specific takes precedence
+    /// - run_constraints: Platform-specific completely replaces base
+    /// - build_only: Platform-specific completely replaces base
+    /// - always_copy_files: Platform-specific completely replaces base
+    /// - additional_sources: Platform-specific completely replaces base
+    /// - additional_sources_use_gitignore: Platform-specific takes precedence
+    /// - additional_sources_filter: Platform-specific takes precedence
+    /// - dynamic_linking: Platform-specific takes precedence
+    /// - build_subdir: Platform-specific takes precedence
+    /// - dedupe_requirements: Enabled if either base or target enables it
+    /// - build_string: Platform-specific takes precedence
+    /// - build_number_env: Platform-specific takes precedence
+    /// - build_number_from_git_count: Enabled if either base or target enables it
+    /// - license_family: Platform-specific takes precedence
+    /// - compilers: Platform-specific compilers are added to base, or to the
+    ///   default compilers if base doesn't set any
+    /// - stdlibs: Platform-specific completely replaces base
+    /// - variant_compilers: Platform-specific completely replaces base
+    /// - archive_types: Platform-specific completely replaces base
+    /// - record_provenance: Platform-specific takes precedence
+    /// - store_recipe: Enabled if either base or target enables it
+    /// - default_version: Platform-specific takes precedence
+    /// - require_version: Enabled if either base or target enables it
+    /// - deny_warnings: Enabled if either base or target enables it
+    /// - validate_license: Enabled if either base or target enables it
+    /// - hash_ignore_keys: Platform-specific completely replaces base
+    /// - operation_timeout: Not allowed to have target specific value
+    /// - build_dir: Not allowed to have target specific value
+    /// - temp_dir: Not allowed to have target specific value
+    /// - solve_strategy: Platform-specific takes precedence
+    /// - version_normalization: Platform-specific takes precedence
+    /// - profile: Platform-specific takes precedence
+    /// - debug_resolved_recipes: Enabled if either base or target enables it
+    /// - lfs_input_globs: Enabled if either base or target enables it
+    /// - extra_channels: Platform-specific completely replaces base
+    /// - extra_channel_priority: Platform-specific takes precedence
+    /// - channels_file: Platform-specific takes precedence
+    /// - context: Platform context variables override base, others merge
+    /// - jobs: Platform-specific takes precedence
+    /// - auto_run_exports: Enabled if either base or target enables it
+    /// - interpreter: Platform-specific takes precedence
+    /// - script_prelude: Platform-specific completely replaces base
+    /// - source_url: Platform-specific takes precedence
+    /// - source_sha256: Platform-specific takes precedence
+    /// - only_default_run: Enabled if either base or target enables it
+    /// - link_system_libs: Platform-specific takes precedence
     fn merge_with_target_config(&self, target_config: &Self) -> miette::Result<Self> {
         if target_config.debug_dir.is_some() {
             miette::bail!("`debug_dir` cannot have a target specific value");
         }
+        if target_config.operation_timeout.is_some() {
+            miette::bail!("`operation_timeout` cannot have a target specific value");
+        }
+        if target_config.build_dir.is_some() {
+            miette::bail!("`build_dir` cannot have a target specific value");
+        }
+        if target_config.temp_dir.is_some() {
+            miette::bail!("`temp_dir` cannot have a target specific value");
+        }
 
         Ok(Self {
             extra_args: if target_config.extra_args.is_empty() {
@@ -65,27 +402,178 @@ impl BackendConfig for RustBackendConfig {
                 merged_env.extend(target_config.env.clone());
                 merged_env
             },
+            secrets: if target_config.secrets.is_empty() {
+                self.secrets.clone()
+            } else {
+                target_config.secrets.clone()
+            },
             debug_dir: self.debug_dir.clone(),
             extra_input_globs: if target_config.extra_input_globs.is_empty() {
                 self.extra_input_globs.clone()
             } else {
                 target_config.extra_input_globs.clone()
             },
+            remove_input_globs: if target_config.remove_input_globs.is_empty() {
+                self.remove_input_globs.clone()
+            } else {
+                target_config.remove_input_globs.clone()
+            },
             ignore_cargo_manifest: target_config
                 .ignore_cargo_manifest
                 .or(self.ignore_cargo_manifest),
-            compilers: target_config
-                .compilers
+            compilers: merge_compilers(
+                self.compilers.as_deref(),
+                target_config.compilers.as_deref(),
+                DEFAULT_COMPILERS,
+            ),
+            stdlibs: target_config
+                .stdlibs
+                .clone()
+                .or_else(|| self.stdlibs.clone()),
+            variant_compilers: if target_config.variant_compilers.is_empty() {
+                self.variant_compilers.clone()
+            } else {
+                target_config.variant_compilers.clone()
+            },
+            build_retries: target_config.build_retries.or(self.build_retries),
+            merge_build_and_host_envs: target_config
+                .merge_build_and_host_envs
+                .or(self.merge_build_and_host_envs),
+            run_constraints: if target_config.run_constraints.is_empty() {
+                self.run_constraints.clone()
+            } else {
+                target_config.run_constraints.clone()
+            },
+            build_only: if target_config.build_only.is_empty() {
+                self.build_only.clone()
+            } else {
+                target_config.build_only.clone()
+            },
+            always_copy_files: if target_config.always_copy_files.is_empty() {
+                self.always_copy_files.clone()
+            } else {
+                target_config.always_copy_files.clone()
+            },
+            additional_sources: if target_config.additional_sources.is_empty() {
+                self.additional_sources.clone()
+            } else {
+                target_config.additional_sources.clone()
+            },
+            additional_sources_use_gitignore: target_config
+                .additional_sources_use_gitignore
+                .or(self.additional_sources_use_gitignore),
+            additional_sources_filter: target_config
+                .additional_sources_filter
+                .clone()
+                .or_else(|| self.additional_sources_filter.clone()),
+            dynamic_linking: target_config
+                .dynamic_linking
+                .clone()
+                .or_else(|| self.dynamic_linking.clone()),
+            build_subdir: target_config
+                .build_subdir
+                .clone()
+                .or_else(|| self.build_subdir.clone()),
+            dedupe_requirements: self.dedupe_requirements || target_config.dedupe_requirements,
+            build_string: target_config
+                .build_string
+                .clone()
+                .or_else(|| self.build_string.clone()),
+            build_number_env: target_config
+                .build_number_env
+                .clone()
+                .or_else(|| self.build_number_env.clone()),
+            build_number_from_git_count: self.build_number_from_git_count
+                || target_config.build_number_from_git_count,
+            license_family: target_config
+                .license_family
+                .clone()
+                .or_else(|| self.license_family.clone()),
+            archive_types: if target_config.archive_types.is_empty() {
+                self.archive_types.clone()
+            } else {
+                target_config.archive_types.clone()
+            },
+            record_provenance: target_config.record_provenance.or(self.record_provenance),
+            store_recipe: self.store_recipe || target_config.store_recipe,
+            default_version: target_config
+                .default_version
+                .clone()
+                .or(self.default_version.clone()),
+            require_version: self.require_version || target_config.require_version,
+            deny_warnings: self.deny_warnings || target_config.deny_warnings,
+            validate_license: self.validate_license || target_config.validate_license,
+            hash_ignore_keys: if target_config.hash_ignore_keys.is_empty() {
+                self.hash_ignore_keys.clone()
+            } else {
+                target_config.hash_ignore_keys.clone()
+            },
+            operation_timeout: self.operation_timeout,
+            build_dir: self.build_dir.clone(),
+            temp_dir: self.temp_dir.clone(),
+            solve_strategy: target_config.solve_strategy.or(self.solve_strategy),
+            version_normalization: target_config
+                .version_normalization
+                .or(self.version_normalization),
+            profile: target_config.profile.or(self.profile),
+            debug_resolved_recipes: self.debug_resolved_recipes
+                || target_config.debug_resolved_recipes,
+            lfs_input_globs: self.lfs_input_globs || target_config.lfs_input_globs,
+            extra_channels: if target_config.extra_channels.is_empty() {
+                self.extra_channels.clone()
+            } else {
+                target_config.extra_channels.clone()
+            },
+            extra_channel_priority: target_config
+                .extra_channel_priority
+                .or(self.extra_channel_priority),
+            channels_file: target_config
+                .channels_file
+                .clone()
+                .or_else(|| self.channels_file.clone()),
+            context: {
+                let mut merged_context = self.context.clone();
+                merged_context.extend(target_config.context.clone());
+                merged_context
+            },
+            jobs: target_config.jobs.or(self.jobs),
+            auto_run_exports: self.auto_run_exports || target_config.auto_run_exports,
+            interpreter: target_config
+                .interpreter
+                .clone()
+                .or_else(|| self.interpreter.clone()),
+            script_prelude: if target_config.script_prelude.is_empty() {
+                self.script_prelude.clone()
+            } else {
+                target_config.script_prelude.clone()
+            },
+            source_url: target_config
+                .source_url
+                .clone()
+                .or_else(|| self.source_url.clone()),
+            source_sha256: target_config
+                .source_sha256
                 .clone()
-                .or_else(|| self.compilers.clone()),
+                .or_else(|| self.source_sha256.clone()),
+            only_default_run: self.only_default_run || target_config.only_default_run,
+            link_system_libs: target_config
+                .link_system_libs
+                .clone()
+                .or_else(|| self.link_system_libs.clone()),
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::RustBackendConfig;
-    use pixi_build_backend::generated_recipe::BackendConfig;
+    use super::{RustBackendConfig, SourceFilter};
+    use pixi_build_backend::{
+        generated_recipe::BackendConfig,
+        utils::{
+            BuildProfile, DynamicLinkingConfig, ExtraChannelPriority, SolveStrategy,
+            VersionNormalization,
+        },
+    };
     use serde_json::json;
     use std::path::PathBuf;
 
@@ -104,10 +592,57 @@ mod tests {
         let base_config = RustBackendConfig {
             extra_args: vec!["--base-arg".to_string()],
             env: base_env,
+            secrets: vec!["BASE_TOKEN".to_string()],
             debug_dir: Some(PathBuf::from("/base/debug")),
             extra_input_globs: vec!["*.base".to_string()],
+            remove_input_globs: vec![],
             ignore_cargo_manifest: None,
             compilers: Some(vec!["rust".to_string()]),
+            stdlibs: None,
+            variant_compilers: vec![],
+            build_retries: Some(2),
+            merge_build_and_host_envs: Some(true),
+            run_constraints: vec!["numpy >=1.20".to_string()],
+            build_only: vec!["make".to_string()],
+            always_copy_files: vec!["share/data/**".to_string()],
+            additional_sources: vec![],
+            additional_sources_filter: None,
+            additional_sources_use_gitignore: None,
+            dynamic_linking: Some(DynamicLinkingConfig::default()),
+            build_subdir: Some("packages/base".to_string()),
+            dedupe_requirements: false,
+            build_string: None,
+            build_number_env: Some("BASE_BUILD_NUMBER".to_string()),
+            build_number_from_git_count: false,
+            license_family: None,
+            archive_types: vec![],
+            record_provenance: None,
+            store_recipe: false,
+            default_version: Some("0.1.0".to_string()),
+            require_version: false,
+            deny_warnings: false,
+            validate_license: false,
+            hash_ignore_keys: vec!["flavor".to_string()],
+            operation_timeout: Some(600),
+            build_dir: Some(PathBuf::from("/base/build")),
+            temp_dir: Some(PathBuf::from("/base/tmp")),
+            solve_strategy: None,
+            version_normalization: None,
+            profile: None,
+            debug_resolved_recipes: false,
+            lfs_input_globs: false,
+            extra_channels: vec![],
+            extra_channel_priority: None,
+            channels_file: None,
+            context: indexmap::IndexMap::from([("cdn".to_string(), "base".to_string())]),
+            jobs: Some(8),
+            auto_run_exports: true,
+            interpreter: Some("bash".to_string()),
+            script_prelude: vec!["set -euo pipefail".to_string()],
+            source_url: None,
+            source_sha256: None,
+            only_default_run: false,
+            link_system_libs: None,
         };
 
         let mut target_env = indexmap::IndexMap::new();
@@ -117,10 +652,60 @@ mod tests {
         let target_config = RustBackendConfig {
             extra_args: vec!["--target-arg".to_string()],
             env: target_env,
+            secrets: vec!["TARGET_TOKEN".to_string()],
             debug_dir: None,
             extra_input_globs: vec!["*.target".to_string()],
+            remove_input_globs: vec!["**/*.txt".to_string()],
             ignore_cargo_manifest: Some(true),
             compilers: Some(vec!["c".to_string(), "rust".to_string()]),
+            stdlibs: Some(vec!["c".to_string()]),
+            variant_compilers: vec!["cuda".to_string()],
+            build_retries: None,
+            merge_build_and_host_envs: Some(false),
+            run_constraints: vec!["numpy >=1.22".to_string()],
+            build_only: vec!["ninja".to_string()],
+            always_copy_files: vec![],
+            additional_sources: vec!["data".to_string()],
+            additional_sources_filter: Some(SourceFilter {
+                include: vec![],
+                exclude: vec!["fixtures/**".to_string()],
+            }),
+            additional_sources_use_gitignore: Some(false),
+            dynamic_linking: None,
+            build_subdir: Some("packages/target".to_string()),
+            dedupe_requirements: true,
+            build_string: Some("${{ hash }}_target".to_string()),
+            build_number_env: Some("CONDA_BUILD_NUMBER".to_string()),
+            build_number_from_git_count: true,
+            license_family: Some("MIT".to_string()),
+            archive_types: vec!["conda".to_string(), "tar.bz2".to_string()],
+            record_provenance: Some(false),
+            store_recipe: true,
+            default_version: Some("0.2.0".to_string()),
+            require_version: true,
+            deny_warnings: true,
+            validate_license: true,
+            hash_ignore_keys: vec!["cuda".to_string()],
+            operation_timeout: None,
+            build_dir: None,
+            temp_dir: None,
+            solve_strategy: Some(SolveStrategy::Lowest),
+            version_normalization: Some(VersionNormalization::Conda),
+            profile: Some(BuildProfile::Dev),
+            debug_resolved_recipes: true,
+            lfs_input_globs: true,
+            extra_channels: vec!["https://prefix.dev/build-tools".to_string()],
+            extra_channel_priority: Some(ExtraChannelPriority::Highest),
+            channels_file: Some(PathBuf::from("channels.yaml")),
+            context: indexmap::IndexMap::from([("cdn".to_string(), "target".to_string())]),
+            jobs: Some(2),
+            auto_run_exports: false,
+            interpreter: Some("pwsh".to_string()),
+            script_prelude: vec!["call setup.bat".to_string()],
+            source_url: Some("https://example.com/foo-${{ version }}.crate".to_string()),
+            source_sha256: None,
+            only_default_run: true,
+            link_system_libs: Some(vec!["libgit2".to_string()]),
         };
 
         let merged = base_config
@@ -146,12 +731,106 @@ mod tests {
 
         // extra_input_globs should be completely overridden
         assert_eq!(merged.extra_input_globs, vec!["*.target".to_string()]);
+        assert_eq!(merged.remove_input_globs, vec!["**/*.txt".to_string()]);
+        assert_eq!(merged.variant_compilers, vec!["cuda".to_string()]);
+        assert_eq!(merged.additional_sources, vec!["data".to_string()]);
+        assert_eq!(
+            merged.additional_sources_filter,
+            Some(SourceFilter {
+                include: vec![],
+                exclude: vec!["fixtures/**".to_string()],
+            })
+        );
+        assert_eq!(merged.additional_sources_use_gitignore, Some(false));
+        assert_eq!(merged.profile, Some(BuildProfile::Dev));
+        assert_eq!(
+            merged.dynamic_linking,
+            Some(DynamicLinkingConfig::default())
+        );
+        assert_eq!(merged.build_subdir, Some("packages/target".to_string()));
+
+        // build_retries should fall back to the base value
+        assert_eq!(merged.build_retries, Some(2));
+
+        // merge_build_and_host_envs should use the target value, even to
+        // disable it
+        assert_eq!(merged.merge_build_and_host_envs, Some(false));
+
+        // run_constraints should be completely overridden
+        assert_eq!(merged.run_constraints, vec!["numpy >=1.22".to_string()]);
+        assert_eq!(merged.build_only, vec!["ninja".to_string()]);
+        assert_eq!(merged.always_copy_files, vec!["share/data/**".to_string()]);
+        assert!(merged.dedupe_requirements);
+        assert!(merged.build_number_from_git_count);
+        assert_eq!(merged.default_version, Some("0.2.0".to_string()));
+        assert!(merged.require_version);
+        assert!(merged.deny_warnings);
+        assert!(merged.validate_license);
+        assert!(merged.store_recipe);
+        assert_eq!(merged.hash_ignore_keys, vec!["cuda".to_string()]);
+        assert_eq!(merged.operation_timeout, Some(600));
+        assert_eq!(merged.build_dir, Some(PathBuf::from("/base/build")));
+        assert_eq!(merged.temp_dir, Some(PathBuf::from("/base/tmp")));
+        assert_eq!(merged.solve_strategy, Some(SolveStrategy::Lowest));
+        assert_eq!(
+            merged.version_normalization,
+            Some(VersionNormalization::Conda)
+        );
+        assert!(merged.debug_resolved_recipes);
+        assert_eq!(merged.secrets, vec!["TARGET_TOKEN".to_string()]);
+        assert_eq!(merged.script_prelude, vec!["call setup.bat".to_string()]);
+        assert_eq!(
+            merged.source_url,
+            Some("https://example.com/foo-${{ version }}.crate".to_string())
+        );
+        assert!(merged.only_default_run);
+        assert_eq!(merged.link_system_libs, Some(vec!["libgit2".to_string()]));
+        assert!(merged.lfs_input_globs);
+        assert_eq!(
+            merged.extra_channels,
+            vec!["https://prefix.dev/build-tools".to_string()]
+        );
+        assert_eq!(
+            merged.extra_channel_priority,
+            Some(ExtraChannelPriority::Highest)
+        );
+        assert_eq!(merged.channels_file, Some(PathBuf::from("channels.yaml")));
+
+        assert_eq!(
+            merged.build_number_env,
+            Some("CONDA_BUILD_NUMBER".to_string())
+        );
+        // build_string should use the target value
+        assert_eq!(merged.build_string, Some("${{ hash }}_target".to_string()));
+
+        // license_family should use the target value
+        assert_eq!(merged.license_family, Some("MIT".to_string()));
 
-        // compilers should be completely overridden by target
+        // compilers should be added to the base compilers
         assert_eq!(
             merged.compilers,
-            Some(vec!["c".to_string(), "rust".to_string()])
+            Some(vec!["rust".to_string(), "c".to_string()])
         );
+
+        // stdlibs should be completely overridden by target
+        assert_eq!(merged.stdlibs, Some(vec!["c".to_string()]));
+
+        // archive_types should be completely overridden
+        assert_eq!(
+            merged.archive_types,
+            vec!["conda".to_string(), "tar.bz2".to_string()]
+        );
+
+        // record_provenance should use the target value
+        assert_eq!(merged.record_provenance, Some(false));
+
+        // context should merge with target taking precedence
+        assert_eq!(merged.context.get("cdn"), Some(&"target".to_string()));
+
+        // jobs should use the target value
+        assert_eq!(merged.jobs, Some(2));
+        assert!(merged.auto_run_exports);
+        assert_eq!(merged.interpreter, Some("pwsh".to_string()));
     }
 
     #[test]
@@ -162,10 +841,57 @@ mod tests {
         let base_config = RustBackendConfig {
             extra_args: vec!["--base-arg".to_string()],
             env: base_env,
+            secrets: vec!["BASE_TOKEN".to_string()],
             debug_dir: Some(PathBuf::from("/base/debug")),
             extra_input_globs: vec!["*.base".to_string()],
+            remove_input_globs: vec![],
             ignore_cargo_manifest: None,
             compilers: Some(vec!["rust".to_string()]),
+            stdlibs: None,
+            variant_compilers: vec![],
+            build_retries: Some(2),
+            merge_build_and_host_envs: Some(true),
+            run_constraints: vec!["numpy >=1.20".to_string()],
+            build_only: vec!["make".to_string()],
+            always_copy_files: vec![],
+            additional_sources: vec![],
+            additional_sources_filter: None,
+            additional_sources_use_gitignore: Some(false),
+            dynamic_linking: None,
+            build_subdir: None,
+            dedupe_requirements: false,
+            build_string: Some("${{ hash }}_base".to_string()),
+            build_number_env: Some("BASE_BUILD_NUMBER".to_string()),
+            build_number_from_git_count: false,
+            license_family: Some("BSD".to_string()),
+            archive_types: vec!["tar.bz2".to_string()],
+            record_provenance: Some(true),
+            store_recipe: false,
+            default_version: Some("0.1.0".to_string()),
+            require_version: false,
+            deny_warnings: false,
+            validate_license: false,
+            hash_ignore_keys: vec!["flavor".to_string()],
+            operation_timeout: Some(600),
+            build_dir: Some(PathBuf::from("/base/build")),
+            temp_dir: Some(PathBuf::from("/base/tmp")),
+            solve_strategy: Some(SolveStrategy::LowestDirect),
+            version_normalization: None,
+            profile: None,
+            debug_resolved_recipes: false,
+            lfs_input_globs: false,
+            extra_channels: vec!["bioconda".to_string()],
+            extra_channel_priority: None,
+            channels_file: None,
+            context: indexmap::IndexMap::new(),
+            jobs: Some(4),
+            auto_run_exports: true,
+            interpreter: None,
+            script_prelude: vec!["source ./env.sh".to_string()],
+            source_url: None,
+            source_sha256: None,
+            only_default_run: false,
+            link_system_libs: None,
         };
 
         let empty_target_config = RustBackendConfig::default();
@@ -174,12 +900,45 @@ mod tests {
             .merge_with_target_config(&empty_target_config)
             .unwrap();
 
+        // additional_sources_use_gitignore should fall back to the base value
+        assert_eq!(merged.additional_sources_use_gitignore, Some(false));
+
         // Should keep base values when target is empty
         assert_eq!(merged.extra_args, vec!["--base-arg".to_string()]);
         assert_eq!(merged.env.get("BASE_VAR"), Some(&"base_value".to_string()));
         assert_eq!(merged.debug_dir, Some(PathBuf::from("/base/debug")));
         assert_eq!(merged.extra_input_globs, vec!["*.base".to_string()]);
+        assert_eq!(merged.build_retries, Some(2));
+        assert_eq!(merged.merge_build_and_host_envs, Some(true));
+        assert_eq!(merged.run_constraints, vec!["numpy >=1.20".to_string()]);
+        assert_eq!(merged.build_only, vec!["make".to_string()]);
+        assert_eq!(merged.default_version, Some("0.1.0".to_string()));
+        assert!(!merged.require_version);
+        assert!(!merged.deny_warnings);
+        assert!(!merged.validate_license);
+        assert!(!merged.store_recipe);
+        assert_eq!(merged.hash_ignore_keys, vec!["flavor".to_string()]);
+        assert_eq!(merged.solve_strategy, Some(SolveStrategy::LowestDirect));
+        assert!(!merged.debug_resolved_recipes);
+        assert_eq!(merged.secrets, vec!["BASE_TOKEN".to_string()]);
+        assert_eq!(merged.script_prelude, vec!["source ./env.sh".to_string()]);
+        assert!(!merged.lfs_input_globs);
+        assert_eq!(merged.extra_channels, vec!["bioconda".to_string()]);
+        assert_eq!(merged.extra_channel_priority, None);
+        assert_eq!(merged.channels_file, None);
+        assert_eq!(merged.build_string, Some("${{ hash }}_base".to_string()));
+        assert_eq!(
+            merged.build_number_env,
+            Some("BASE_BUILD_NUMBER".to_string())
+        );
+        assert_eq!(merged.license_family, Some("BSD".to_string()));
         assert_eq!(merged.compilers, Some(vec!["rust".to_string()]));
+        assert_eq!(merged.archive_types, vec!["tar.bz2".to_string()]);
+        assert_eq!(merged.record_provenance, Some(true));
+        assert!(merged.context.is_empty());
+        assert_eq!(merged.jobs, Some(4));
+        assert!(merged.auto_run_exports);
+        assert_eq!(merged.interpreter, None);
     }
 
     #[test]
@@ -199,4 +958,20 @@ mod tests {
         let error_msg = result.unwrap_err().to_string();
         assert!(error_msg.contains("`debug_dir` cannot have a target specific value"));
     }
+
+    #[test]
+    fn test_require_version_disables_default_version() {
+        let config: RustBackendConfig = serde_json::from_value(json!({
+            "default-version": "0.1.0",
+        }))
+        .unwrap();
+        assert_eq!(config.fallback_version(), Some("0.1.0"));
+
+        let config: RustBackendConfig = serde_json::from_value(json!({
+            "default-version": "0.1.0",
+            "require-version": true,
+        }))
+        .unwrap();
+        assert_eq!(config.fallback_version(), None);
+    }
 }
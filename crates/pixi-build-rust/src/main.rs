@@ -2,22 +2,27 @@ mod build_script;
 mod config;
 mod metadata;
 
-use build_script::BuildScriptContext;
-use config::RustBackendConfig;
+use build_script::{BuildScriptContext, SystemLibEnv};
+use config::{DEFAULT_COMPILERS, RustBackendConfig};
 use metadata::CargoMetadataProvider;
 use miette::IntoDiagnostic;
 use pixi_build_backend::variants::NormalizedKey;
 use pixi_build_backend::{
     cache::{sccache_envs, sccache_tools},
-    compilers::add_compilers_and_stdlib_to_requirements,
-    generated_recipe::{GenerateRecipe, GeneratedRecipe, PythonParams},
+    compilers::{add_compilers_and_stdlib_to_requirements, with_variant_compilers},
+    error::BackendError,
+    generated_recipe::{
+        BackendConfig, BackendIdentifier, BuildParams, GenerateRecipe, GeneratedRecipe,
+        RunExportsConfig,
+    },
     intermediate_backend::IntermediateBackendInstantiator,
+    utils::{BuildProfile, lfs_input_globs, scope_input_glob},
 };
 use pixi_build_types::ProjectModelV1;
 use rattler_conda_types::Platform;
 use recipe_stage0::{
     matchspec::PackageDependency,
-    recipe::{ConditionalRequirements, Item, Script},
+    recipe::{ConditionalRequirements, Item, Script, Source, UrlSource, Value},
 };
 use std::collections::HashSet;
 use std::{
@@ -26,6 +31,16 @@ use std::{
     sync::Arc,
 };
 
+/// The interpreters that the build script can be rendered for.
+const SUPPORTED_INTERPRETERS: &[&str] = &["bash", "cmd"];
+
+fn unsupported_interpreter(interpreter: &str) -> String {
+    format!(
+        "the interpreter '{interpreter}' is not supported, supported are {}",
+        SUPPORTED_INTERPRETERS.join(", ")
+    )
+}
+
 #[derive(Default, Clone)]
 pub struct RustGenerator {}
 
@@ -38,19 +53,42 @@ impl GenerateRecipe for RustGenerator {
         config: &Self::Config,
         manifest_root: PathBuf,
         host_platform: Platform,
-        _python_params: Option<PythonParams>,
+        build_params: Option<BuildParams>,
         variants: &HashSet<NormalizedKey>,
     ) -> miette::Result<GeneratedRecipe> {
+        // With a remote source the subdirectory is only available once the
+        // source is downloaded.
+        let missing_subdir = config.build_subdir.as_ref().filter(|build_subdir| {
+            config.source_url.is_none() && !manifest_root.join(build_subdir).is_dir()
+        });
+        if let Some(build_subdir) = missing_subdir {
+            miette::bail!(
+                "the build subdirectory '{build_subdir}' does not exist in '{}'",
+                manifest_root.display()
+            );
+        }
+
+        // The crate lives in the build subdirectory if one is configured.
+        let crate_root = match (&config.build_subdir, &config.source_url) {
+            (Some(build_subdir), None) => manifest_root.join(build_subdir),
+            _ => manifest_root.clone(),
+        };
+
         // Construct a CargoMetadataProvider to read the Cargo.toml file
         // and extract metadata from it.
         let mut cargo_metadata = CargoMetadataProvider::new(
-            &manifest_root,
+            &crate_root,
             config.ignore_cargo_manifest.is_some_and(|ignore| ignore),
         );
 
         // Create the recipe
-        let mut generated_recipe =
-            GeneratedRecipe::from_model(model.clone(), &mut cargo_metadata).into_diagnostic()?;
+        let mut generated_recipe = GeneratedRecipe::from_model_with_default_version(
+            model.clone(),
+            &mut cargo_metadata,
+            config.fallback_version(),
+            config.version_normalization(),
+        )
+        .map_err(BackendError::from)?;
 
         // we need to add compilers
         let requirements = &mut generated_recipe.recipe.requirements;
@@ -68,18 +106,30 @@ impl GenerateRecipe for RustGenerator {
         let compilers = config
             .compilers
             .clone()
-            .unwrap_or_else(|| vec!["rust".to_string()]);
+            .unwrap_or_else(|| DEFAULT_COMPILERS.iter().map(ToString::to_string).collect());
+        let compilers = with_variant_compilers(compilers, &config.variant_compilers, variants);
 
         // Add configured compilers to build requirements
         add_compilers_and_stdlib_to_requirements(
             &compilers,
+            config.stdlibs.as_deref(),
             &mut requirements.build,
             &resolved_requirements.build,
             &host_platform,
             variants,
         );
 
-        let has_openssl = resolved_requirements.contains(&"openssl".parse().into_diagnostic()?);
+        // The system libraries that are linked from the environment, if they
+        // are part of it.
+        let mut system_libs = Vec::new();
+        for name in config.link_system_libs() {
+            let Some(lib_env) = SystemLibEnv::for_lib(name) else {
+                continue;
+            };
+            if resolved_requirements.contains(&name.parse().into_diagnostic()?) {
+                system_libs.push(lib_env);
+            }
+        }
 
         let mut has_sccache = false;
 
@@ -133,12 +183,51 @@ impl GenerateRecipe for RustGenerator {
             has_sccache = true;
         }
 
+        // Fetch the sources from the configured url instead of building the
+        // crate in the manifest directory. The url can reference the version of
+        // the package through the context.
+        let source_dir = match &config.source_url {
+            Some(source_url) => {
+                let version = generated_recipe.recipe.package.version.clone();
+                generated_recipe
+                    .recipe
+                    .context
+                    .entry(String::from("version"))
+                    .or_insert(version);
+                generated_recipe.recipe.source = vec![Item::from(Source::Url(UrlSource {
+                    url: source_url.parse().into_diagnostic()?,
+                    sha256: config.source_sha256.clone().map(Value::Concrete),
+                    target_directory: None,
+                }))];
+                String::from(".")
+            }
+            None => manifest_root.display().to_string(),
+        };
+
         let build_script = BuildScriptContext {
-            source_dir: manifest_root.display().to_string(),
+            source_dir,
+            build_subdir: config.build_subdir.clone(),
             extra_args: config.extra_args.clone(),
-            has_openssl,
+            jobs: config.jobs,
+            bin: if config.only_default_run {
+                Some(cargo_metadata.default_run_binary()?)
+            } else {
+                None
+            },
+            debug: build_params
+                .and_then(|params| params.profile)
+                .is_some_and(BuildProfile::is_dev),
+            system_libs,
             has_sccache,
-            is_bash: !Platform::current().is_windows(),
+            is_bash: match config.interpreter.as_deref() {
+                Some("bash") => true,
+                Some("cmd") => false,
+                Some(interpreter) => {
+                    miette::bail!("{}", unsupported_interpreter(interpreter))
+                }
+                None => !Platform::current().is_windows(),
+            },
+            prelude: config.script_prelude.clone(),
         }
         .render();
 
@@ -146,12 +235,82 @@ impl GenerateRecipe for RustGenerator {
             content: build_script,
             env: config_env,
             secrets: sccache_secrets,
+            interpreter: config.interpreter.clone(),
         };
+        generated_recipe.recipe.build.merge_build_and_host_envs =
+            config.merge_build_and_host_envs.unwrap_or(false);
+        generated_recipe.add_run_constraints(&config.run_constraints)?;
+        generated_recipe.add_secrets(&config.secrets);
+        generated_recipe.move_to_build_requirements(&config.build_only)?;
+        generated_recipe.recipe.build.always_copy_files = config.always_copy_files.clone();
+        generated_recipe.recipe.build.dynamic_linking = config
+            .dynamic_linking
+            .clone()
+            .map(Into::into)
+            .unwrap_or_default();
+        generated_recipe.add_path_sources(
+            &manifest_root,
+            &config.additional_sources,
+            config.additional_sources_use_gitignore,
+            config.additional_sources_filter.as_ref(),
+        )?;
+        if config.dedupe_requirements {
+            generated_recipe.dedupe_requirements();
+        }
+        generated_recipe.add_context(&config.context);
+        // A license file of the package is only found in the manifest root if
+        // it is also the source of the package.
+        if config.source_url.is_none() {
+            generated_recipe.discover_license_file(&manifest_root);
+        }
+        if let Some(build_string) = &config.build_string {
+            generated_recipe.recipe.build.string = Some(build_string.parse().into_diagnostic()?);
+        }
+        if config.build_number_from_git_count {
+            generated_recipe.set_build_number_from_git_count(&manifest_root);
+        }
+        if let Some(build_number_env) = &config.build_number_env {
+            generated_recipe.set_build_number_from_env(build_number_env);
+        }
+        if let Some(license_family) = &config.license_family {
+            generated_recipe
+                .recipe
+                .about
+                .get_or_insert_with(Default::default)
+                .license_family = Some(license_family.parse().into_diagnostic()?);
+        }
+
+        // Consumers that link against the library of the crate need a
+        // compatible version of it at runtime.
+        if config.auto_run_exports && cargo_metadata.has_linkable_library()? {
+            if let Value::Concrete(name) = &generated_recipe.recipe.package.name {
+                let pin = format!("${{{{ pin_subpackage('{name}', upper_bound='x.x') }}}}");
+                generated_recipe.add_run_exports(&RunExportsConfig {
+                    weak: vec![pin],
+                    ..RunExportsConfig::default()
+                })?;
+            }
+        }
 
-        // Add the input globs from the Cargo metadata provider
+        // Add the input globs from the Cargo metadata provider, they are
+        // relative to the crate root.
         generated_recipe
             .metadata_input_globs
-            .extend(cargo_metadata.input_globs());
+            .extend(cargo_metadata.input_globs().into_iter().map(
+                |glob| match &config.build_subdir {
+                    Some(build_subdir) if config.source_url.is_none() => {
+                        scope_input_glob(build_subdir, &glob)
+                    }
+                    _ => glob,
+                },
+            ));
+
+        // Changes to files that are tracked by git-lfs should trigger a rebuild.
+        if config.lfs_input_globs {
+            generated_recipe
+                .build_input_globs
+                .extend(lfs_input_globs(&manifest_root)?);
+        }
 
         Ok(generated_recipe)
     }
@@ -172,9 +331,81 @@ impl GenerateRecipe for RustGenerator {
         ]
         .iter()
         .map(|s| s.to_string())
+        .filter(|glob| !config.remove_input_globs.contains(glob))
+        .map(|glob| match &config.build_subdir {
+            Some(build_subdir) => scope_input_glob(build_subdir, &glob),
+            None => glob,
+        })
+        // The lockfile of a cargo workspace lives in the workspace root.
+        .chain(
+            config
+                .build_subdir
+                .as_ref()
+                .filter(|_| !config.remove_input_globs.iter().any(|g| g == "Cargo.lock"))
+                .map(|_| String::from("Cargo.lock")),
+        )
         .chain(config.extra_input_globs.clone())
         .collect()
     }
+
+    fn validate(&self, _model: &ProjectModelV1, config: &Self::Config) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        // The extra arguments are joined into the cargo command line, so an
+        // argument can contain multiple flags. Arguments after `--` are not
+        // passed to cargo itself.
+        let args = config
+            .extra_args
+            .iter()
+            .flat_map(|arg| arg.split_whitespace())
+            .take_while(|arg| *arg != "--")
+            .collect::<Vec<_>>();
+        let all_features = args.iter().any(|arg| *arg == "--all-features");
+        let features = args.iter().any(|arg| {
+            *arg == "--features" || arg.starts_with("--features=") || arg.starts_with("-F")
+        });
+        if all_features && features {
+            problems.push(String::from(
+                "`--all-features` and `--features` in `extra-args` conflict with each other",
+            ));
+        }
+
+        if let Some(interpreter) = config
+            .interpreter
+            .as_deref()
+            .filter(|interpreter| !SUPPORTED_INTERPRETERS.contains(interpreter))
+        {
+            problems.push(unsupported_interpreter(interpreter));
+        }
+
+        for name in config.link_system_libs() {
+            if SystemLibEnv::for_lib(name).is_none() {
+                problems.push(format!(
+                    "the system library '{name}' in `link-system-libs` is not supported, supported \
+                     are {}",
+                    SystemLibEnv::SUPPORTED.join(", ")
+                ));
+            }
+        }
+
+        problems
+    }
+
+    fn expected_manifests(&self, config: &Self::Config) -> &[&'static str] {
+        // A remote source or a subdirectory brings its own `Cargo.toml`.
+        if config.source_url.is_some() || config.build_subdir.is_some() {
+            &[]
+        } else {
+            &["Cargo.toml"]
+        }
+    }
+
+    fn backend_identifier(&self) -> Option<BackendIdentifier> {
+        Some(BackendIdentifier {
+            name: env!("CARGO_PKG_NAME"),
+            version: env!("CARGO_PKG_VERSION"),
+        })
+    }
 }
 
 #[tokio::main]
@@ -193,6 +424,7 @@ pub async fn main() {
 mod tests {
     use cargo_toml::Manifest;
     use indexmap::IndexMap;
+    use pixi_build_backend::utils::test::backend_manifest_path;
     use recipe_stage0::recipe::{Item, Value};
 
     use super::*;
@@ -222,6 +454,75 @@ mod tests {
         assert!(result.contains("build.rs"));
     }
 
+    #[test]
+    fn test_build_subdir_scopes_input_globs() {
+        let config = RustBackendConfig {
+            build_subdir: Some("packages/foo".to_string()),
+            ..Default::default()
+        };
+
+        let result = RustGenerator::extract_input_globs_from_build(&config, PathBuf::new(), false);
+
+        insta::assert_debug_snapshot!(result);
+    }
+
+    #[test]
+    fn test_build_subdir_reads_cargo_metadata() {
+        let project_model = project_fixture!({
+            "name": "",
+            "targets": {
+                "defaultTarget": {}
+            }
+        });
+        let fixtures_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+
+        let generated_recipe = RustGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &RustBackendConfig {
+                    build_subdir: Some("cdylib".to_string()),
+                    ..Default::default()
+                },
+                fixtures_dir,
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        assert_eq!(
+            generated_recipe.recipe.package.name.to_string(),
+            "cdylib-fixture"
+        );
+        assert!(
+            generated_recipe
+                .metadata_input_globs
+                .contains("cdylib/Cargo.toml")
+        );
+    }
+
+    #[test]
+    fn test_expected_manifests() {
+        let dir = tempfile::tempdir().unwrap();
+        let err =
+            backend_manifest_path::<RustGenerator>(dir.path(), serde_json::json!({})).unwrap_err();
+        assert!(err.to_string().contains("`Cargo.toml`"), "{err}");
+
+        fs_err::write(dir.path().join("Cargo.toml"), "").unwrap();
+        assert_eq!(
+            backend_manifest_path::<RustGenerator>(dir.path(), serde_json::json!({})).unwrap(),
+            Some(dir.path().join("Cargo.toml"))
+        );
+
+        // A subdirectory brings its own `Cargo.toml`.
+        let dir = tempfile::tempdir().unwrap();
+        let config = serde_json::json!({ "build-subdir": "crates/foo" });
+        assert_eq!(
+            backend_manifest_path::<RustGenerator>(dir.path(), config).unwrap(),
+            None
+        );
+    }
+
     #[macro_export]
     macro_rules! project_fixture {
         ($($json:tt)+) => {
@@ -349,6 +650,192 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_link_system_libs() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+            "targets": {
+                "defaultTarget": {
+                    "hostDependencies": {
+                        "libgit2": {
+                            "binary": {
+                                "version": "*"
+                            }
+                        }
+                    }
+                },
+            }
+        });
+
+        let script = |link_system_libs: Option<Vec<String>>| {
+            let generated_recipe = RustGenerator::default()
+                .generate_recipe(
+                    &project_model,
+                    &RustBackendConfig {
+                        link_system_libs,
+                        ignore_cargo_manifest: Some(true),
+                        ..Default::default()
+                    },
+                    PathBuf::from("."),
+                    Platform::Linux64,
+                    None,
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe");
+            generated_recipe.recipe.build.script.content.join("\n")
+        };
+
+        // `libgit2` is only linked from the environment if it is configured.
+        assert!(!script(None).contains("LIBGIT2_NO_VENDOR"));
+        let script = script(Some(vec!["libgit2".to_string()]));
+        assert!(script.contains("export LIBGIT2_NO_VENDOR=1"), "{script}");
+    }
+
+    #[test]
+    fn test_interpreter_is_set() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let generated_recipe = RustGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &RustBackendConfig {
+                    interpreter: Some("cmd".to_string()),
+                    ignore_cargo_manifest: Some(true),
+                    ..Default::default()
+                },
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        assert_eq!(
+            generated_recipe.recipe.build.script.interpreter,
+            Some("cmd".to_string())
+        );
+        let yaml = generated_recipe.recipe.to_yaml().unwrap();
+        assert!(yaml.contains("interpreter: cmd"), "{yaml}");
+        // The script is rendered for the interpreter, not for the platform.
+        assert!(yaml.contains("if errorlevel 1 exit 1"), "{yaml}");
+    }
+
+    #[test]
+    fn test_unsupported_interpreter_is_rejected() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+        let config = RustBackendConfig {
+            interpreter: Some("pwsh".to_string()),
+            ignore_cargo_manifest: Some(true),
+            ..Default::default()
+        };
+
+        let err = RustGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &config,
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("'pwsh'"), "{err}");
+
+        let problems = RustGenerator::default().validate(&project_model, &config);
+        assert_eq!(problems, vec![unsupported_interpreter("pwsh")]);
+    }
+
+    #[test]
+    fn test_secrets_are_set() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let generated_recipe = RustGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &RustBackendConfig {
+                    secrets: vec!["CARGO_REGISTRY_TOKEN".to_string()],
+                    ignore_cargo_manifest: Some(true),
+                    ..Default::default()
+                },
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        assert!(
+            generated_recipe
+                .recipe
+                .build
+                .script
+                .secrets
+                .contains(&"CARGO_REGISTRY_TOKEN".to_string())
+        );
+    }
+
+    #[test]
+    fn test_source_url_is_templated() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let generated_recipe = RustGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &RustBackendConfig {
+                    source_url: Some(
+                        "https://static.crates.io/crates/foobar/foobar-${{ version }}.crate"
+                            .to_string(),
+                    ),
+                    source_sha256: Some("abc123".to_string()),
+                    ignore_cargo_manifest: Some(true),
+                    ..Default::default()
+                },
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let recipe = &generated_recipe.recipe;
+        assert_eq!(
+            recipe.context.get("version"),
+            Some(&Value::Concrete("0.1.0".to_string()))
+        );
+        assert_eq!(
+            recipe.source,
+            vec![Item::from(Source::Url(UrlSource {
+                url: Value::Template(
+                    "https://static.crates.io/crates/foobar/foobar-${{ version }}.crate"
+                        .to_string()
+                ),
+                sha256: Some(Value::Concrete("abc123".to_string())),
+                target_directory: None,
+            }))]
+        );
+        assert!(
+            recipe
+                .build
+                .script
+                .content
+                .iter()
+                .any(|line| line.contains("--path . "))
+        );
+    }
+
     #[test]
     fn test_sccache_is_enabled() {
         let project_model = project_fixture!({
@@ -395,6 +882,52 @@ mod tests {
         ".build.script.content" => "[ ... script ... ]",
         });
     }
+    #[test]
+    fn test_build_only_dependencies_are_moved_to_build() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+            "targets": {
+                "default_target": {
+                    "run_dependencies": {
+                        "boltons": "*",
+                        "make": "*"
+                    }
+                },
+            }
+        });
+
+        let generated_recipe = RustGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &RustBackendConfig {
+                    build_only: vec!["make".to_string()],
+                    ignore_cargo_manifest: Some(true),
+                    ..Default::default()
+                },
+                PathBuf::from("."),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let names = |items: &Vec<Item<PackageDependency>>| {
+            items
+                .iter()
+                .filter_map(|item| match item {
+                    Item::Value(Value::Concrete(dep)) => {
+                        Some(dep.package_name().as_normalized().to_owned())
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let requirements = &generated_recipe.recipe.requirements;
+        assert!(names(&requirements.build).contains(&"make".to_string()));
+        assert_eq!(names(&requirements.run), vec!["boltons"]);
+    }
+
     #[test]
     fn test_with_cargo_manifest() {
         let project_model = project_fixture!({
@@ -492,6 +1025,154 @@ mod tests {
         "###);
     }
 
+    #[test]
+    fn test_cdylib_run_exports() {
+        let project_model = project_fixture!({
+            "name": "",
+            "targets": {
+                "defaultTarget": {}
+            }
+        });
+        let cdylib_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cdylib");
+        let generate = |auto_run_exports| {
+            RustGenerator::default()
+                .generate_recipe(
+                    &project_model,
+                    &RustBackendConfig {
+                        auto_run_exports,
+                        ..Default::default()
+                    },
+                    cdylib_dir.clone(),
+                    Platform::Linux64,
+                    None,
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe")
+        };
+
+        let generated_recipe = generate(true);
+        let weak = generated_recipe
+            .recipe
+            .requirements
+            .run_exports
+            .weak
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            weak,
+            vec!["${{ pin_subpackage('cdylib-fixture', upper_bound='x.x') }}"]
+        );
+
+        let generated_recipe = generate(false);
+        assert!(generated_recipe.recipe.requirements.run_exports.is_empty());
+    }
+
+    #[test]
+    fn test_only_default_run() {
+        let project_model = project_fixture!({
+            "name": "",
+            "targets": {
+                "defaultTarget": {}
+            }
+        });
+        let fixture_dir =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/multiple-bins");
+        let generate = |only_default_run| {
+            RustGenerator::default()
+                .generate_recipe(
+                    &project_model,
+                    &RustBackendConfig {
+                        only_default_run,
+                        ..Default::default()
+                    },
+                    fixture_dir.clone(),
+                    Platform::Linux64,
+                    None,
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe")
+                .recipe
+                .build
+                .script
+                .content
+                .join("\n")
+        };
+
+        let script = generate(true);
+        assert!(script.contains("--bin server "), "{script}");
+
+        let script = generate(false);
+        assert!(!script.contains("--bin"), "{script}");
+    }
+
+    #[test]
+    fn test_dev_profile_builds_debug() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+            "targets": {
+                "defaultTarget": {}
+            }
+        });
+        let generate = |profile| {
+            RustGenerator::default()
+                .generate_recipe(
+                    &project_model,
+                    &RustBackendConfig::default_with_ignore_cargo_manifest(),
+                    PathBuf::from("."),
+                    Platform::Linux64,
+                    Some(BuildParams {
+                        editable: false,
+                        profile,
+                    }),
+                    &HashSet::new(),
+                )
+                .expect("Failed to generate recipe")
+                .recipe
+                .build
+                .script
+                .content
+                .join("\n")
+        };
+
+        let script = generate(Some(BuildProfile::Dev));
+        assert!(script.contains("--debug "), "{script}");
+        assert!(script.contains("CARGO_PROFILE_DEV_STRIP=false"), "{script}");
+
+        let script = generate(Some(BuildProfile::Release));
+        assert!(!script.contains("--debug"), "{script}");
+        let script = generate(None);
+        assert!(!script.contains("--debug"), "{script}");
+        assert!(!script.contains("CARGO_PROFILE_DEV_STRIP"), "{script}");
+    }
+
+    #[test]
+    fn test_keywords_are_added_to_extra() {
+        let project_model = project_fixture!({
+            "name": "",
+            "targets": {
+                "defaultTarget": {}
+            }
+        });
+        let generated_recipe = RustGenerator::default()
+            .generate_recipe(
+                &project_model,
+                &RustBackendConfig::default(),
+                Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/keywords"),
+                Platform::Linux64,
+                None,
+                &HashSet::new(),
+            )
+            .expect("Failed to generate recipe");
+
+        let extra = generated_recipe.recipe.extra.expect("extra should be set");
+        assert_eq!(
+            extra.keywords,
+            vec!["cli", "parser", "command-line-utilities"]
+        );
+    }
+
     #[test]
     fn test_error_handling_missing_cargo_manifest() {
         let project_model = project_fixture!({
@@ -666,4 +1347,92 @@ mod tests {
             "Default compiler should be rust"
         );
     }
+
+    #[test]
+    fn test_validate_accepts_default_config() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let problems =
+            RustGenerator::default().validate(&project_model, &RustBackendConfig::default());
+        assert!(problems.is_empty(), "unexpected problems: {problems:?}");
+    }
+
+    #[test]
+    fn test_validate_conflicting_features() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let config = RustBackendConfig {
+            extra_args: vec!["--all-features".to_string(), "--features=foo".to_string()],
+            ..Default::default()
+        };
+
+        let problems = RustGenerator::default().validate(&project_model, &config);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("--all-features"));
+    }
+
+    #[test]
+    fn test_validate_conflicting_features_from_manifest() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let problems = |extra_args: &str| {
+            let tmp_dir = tempfile::tempdir().unwrap();
+            let manifest_path = tmp_dir.path().join("pixi.toml");
+            fs_err::write(
+                &manifest_path,
+                format!("[package.build.configuration]\nextra-args = {extra_args}\n"),
+            )
+            .unwrap();
+
+            let configuration =
+                pixi_build_backend::project::read_build_configuration(&manifest_path)
+                    .unwrap()
+                    .unwrap();
+            let config: RustBackendConfig = serde_json::from_value(configuration).unwrap();
+            RustGenerator::default().validate(&project_model, &config)
+        };
+
+        for conflicting in [
+            r#"["--all-features", "--features", "foo"]"#,
+            r#"["--all-features", "-F", "foo"]"#,
+            r#"["--all-features", "-Ffoo"]"#,
+            r#"["--all-features --features=foo"]"#,
+        ] {
+            assert_eq!(problems(conflicting).len(), 1, "{conflicting}");
+        }
+
+        for valid in [
+            r#"["--all-features"]"#,
+            r#"["--features", "foo"]"#,
+            r#"["--all-features", "--", "--features"]"#,
+        ] {
+            assert!(problems(valid).is_empty(), "{valid}");
+        }
+    }
+
+    #[test]
+    fn test_validate_unsupported_system_lib() {
+        let project_model = project_fixture!({
+            "name": "foobar",
+            "version": "0.1.0",
+        });
+
+        let config = RustBackendConfig {
+            link_system_libs: Some(vec!["openssl".to_string(), "libfoo".to_string()]),
+            ..Default::default()
+        };
+
+        let problems = RustGenerator::default().validate(&project_model, &config);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("'libfoo'"));
+    }
 }
@@ -1,9 +1,14 @@
-use std::{collections::BTreeSet, path::PathBuf, str::FromStr};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use cargo_toml::{
     AbstractFilesystem, Error as CargoTomlError, Filesystem, Inheritable, Manifest, Package,
     PackageTemplate,
 };
+use indexmap::IndexSet;
 use miette::Diagnostic;
 use once_cell::unsync::OnceCell;
 use pixi_build_backend::generated_recipe::MetadataProvider;
@@ -19,6 +24,11 @@ pub enum MetadataError {
     IoError(#[from] std::io::Error),
     #[error("missing inherited value from workspace {0}")]
     MissingInheritedValue(String),
+    #[error("the crate has multiple binaries ({0}) but does not define `default-run`")]
+    #[diagnostic(help("set `default-run` in the Cargo.toml or disable `only-default-run`"))]
+    NoDefaultRun(String),
+    #[error("the crate does not have a binary to install")]
+    NoBinary,
 }
 
 /// An implementation of [`MetadataProvider`] that reads metadata from a
@@ -122,6 +132,121 @@ impl CargoMetadataProvider {
 
         input_globs
     }
+
+    /// Returns true if the crate builds a library that other packages link
+    /// against, i.e. a `cdylib` or `staticlib`.
+    ///
+    /// If `ignore_cargo_manifest` is true, returns `false`.
+    pub fn has_linkable_library(&self) -> Result<bool, MetadataError> {
+        if self.ignore_cargo_manifest {
+            return Ok(false);
+        }
+        let manifest = self.ensure_manifest()?;
+        Ok(manifest
+            .lib
+            .iter()
+            .flat_map(|lib| &lib.crate_type)
+            .any(|crate_type| crate_type == "cdylib" || crate_type == "staticlib"))
+    }
+
+    /// Returns the names of the binaries of the crate. These are the `[[bin]]`
+    /// targets of the Cargo.toml, and unless `autobins` is disabled, the
+    /// binaries that cargo discovers in `src/main.rs` and `src/bin`.
+    pub fn binary_names(&self) -> Result<BTreeSet<String>, MetadataError> {
+        let manifest = self.ensure_manifest()?;
+        let Some(package) = &manifest.package else {
+            return Ok(BTreeSet::new());
+        };
+
+        let explicit_paths = manifest
+            .bin
+            .iter()
+            .filter_map(|bin| bin.path.as_deref().map(Path::new))
+            .collect::<Vec<_>>();
+        let mut names = manifest
+            .bin
+            .iter()
+            .filter_map(|bin| bin.name.clone())
+            .collect::<BTreeSet<_>>();
+        if !package.autobins {
+            return Ok(names);
+        }
+
+        // The binaries that cargo discovers, unless a `[[bin]]` target is built
+        // from the same file.
+        let mut discovered = vec![(package.name.clone(), PathBuf::from("src/main.rs"))];
+        if let Ok(entries) = fs_err::read_dir(self.manifest_root.join("src/bin")) {
+            for entry in entries {
+                let path = entry?.path();
+                let (Some(stem), Some(file_name)) = (path.file_stem(), path.file_name()) else {
+                    continue;
+                };
+                let name = stem.to_string_lossy().into_owned();
+                let relative_path = Path::new("src/bin").join(file_name);
+                if path.extension().is_some_and(|extension| extension == "rs") {
+                    discovered.push((name, relative_path));
+                } else {
+                    discovered.push((name, relative_path.join("main.rs")));
+                }
+            }
+        }
+        names.extend(
+            discovered
+                .into_iter()
+                .filter(|(_, path)| {
+                    self.manifest_root.join(path).is_file()
+                        && !explicit_paths.contains(&path.as_path())
+                })
+                .map(|(name, _)| name),
+        );
+        Ok(names)
+    }
+
+    /// Returns the values of a list field of the package section, taken from
+    /// the workspace if the field is inherited.
+    fn inherited_list(
+        &self,
+        value: &Inheritable<Vec<String>>,
+        field: &str,
+        get: impl Fn(&PackageTemplate) -> Option<&Vec<String>>,
+    ) -> Result<Vec<String>, MetadataError> {
+        match value {
+            Inheritable::Set(values) => Ok(values.clone()),
+            Inheritable::Inherited => self
+                .ensure_workspace_manifest()?
+                .and_then(get)
+                .cloned()
+                .ok_or_else(|| {
+                    MetadataError::MissingInheritedValue(format!("workspace.package.{field}"))
+                }),
+        }
+    }
+
+    /// Returns the binary that `cargo run` runs by default. This is the
+    /// `default-run` binary of the Cargo.toml or, if not set, the only binary
+    /// of the crate.
+    pub fn default_run_binary(&self) -> Result<String, MetadataError> {
+        let default_run = self
+            .ensure_manifest_package()?
+            .and_then(|package| package.default_run.clone());
+        if let Some(default_run) = default_run {
+            return Ok(default_run);
+        }
+
+        let names = self.binary_names()?;
+        let mut names_iter = names.iter();
+        match (names_iter.next(), names_iter.next()) {
+            (Some(name), None) => Ok(name.clone()),
+            (None, _) => Err(MetadataError::NoBinary),
+            (Some(_), Some(_)) => Err(MetadataError::NoDefaultRun(
+                names
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )),
+        }
+    }
 }
 
 impl MetadataProvider for CargoMetadataProvider {
@@ -339,6 +464,33 @@ impl MetadataProvider for CargoMetadataProvider {
         };
         Ok(Some(repository.clone()))
     }
+
+    /// Returns the keywords and categories of the package from the Cargo.toml
+    /// manifest.
+    ///
+    /// If `ignore_cargo_manifest` is true, returns no keywords. Otherwise,
+    /// extracts the keywords followed by the categories from the package
+    /// section, handling workspace inheritance if needed.
+    fn keywords(&mut self) -> Result<Vec<String>, Self::Error> {
+        if self.ignore_cargo_manifest {
+            return Ok(Vec::new());
+        }
+        let Some(package) = self.ensure_manifest_package()? else {
+            return Ok(Vec::new());
+        };
+
+        let keywords = self.inherited_list(&package.keywords, "keywords", |template| {
+            template.keywords.as_ref()
+        })?;
+        let categories = self.inherited_list(&package.categories, "categories", |template| {
+            template.categories.as_ref()
+        })?;
+        let keywords = keywords
+            .into_iter()
+            .chain(categories)
+            .collect::<IndexSet<_>>();
+        Ok(keywords.into_iter().collect())
+    }
 }
 
 #[cfg(test)]
@@ -456,6 +608,139 @@ documentation.workspace = true
         );
     }
 
+    #[test]
+    fn test_workspace_inheritance_from_workspace_root() {
+        let member_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/workspace-inheritance/member");
+        let mut provider = create_metadata_provider(&member_dir);
+
+        // All inherited fields should be resolved from the workspace root manifest
+        assert_eq!(provider.name().unwrap(), Some("member".to_string()));
+        assert_eq!(provider.version().unwrap().unwrap().to_string(), "3.1.4");
+        assert_eq!(
+            provider.description().unwrap(),
+            Some("A member crate that inherits its metadata".to_string())
+        );
+        assert_eq!(
+            provider.license().unwrap(),
+            Some("BSD-3-Clause".to_string())
+        );
+        assert_eq!(
+            provider.repository().unwrap(),
+            Some("https://github.com/prefix-dev/pixi-build-backends".to_string())
+        );
+
+        // Fields that are not set at all should not be inherited
+        assert_eq!(provider.homepage().unwrap(), None);
+
+        // The workspace root manifest influences the metadata
+        assert_eq!(
+            provider.input_globs(),
+            BTreeSet::from(["Cargo.toml".to_string(), "../**/Cargo.toml".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_has_linkable_library() {
+        let cdylib_dir =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/cdylib");
+        let provider = create_metadata_provider(&cdylib_dir);
+        assert!(provider.has_linkable_library().unwrap());
+
+        // The manifest is not read if it should be ignored.
+        let provider = CargoMetadataProvider::new(&cdylib_dir, true);
+        assert!(!provider.has_linkable_library().unwrap());
+
+        let temp_dir = create_temp_cargo_project(
+            r#"
+[package]
+name = "test-package"
+version = "0.1.0"
+
+[lib]
+crate-type = ["rlib"]
+"#,
+        );
+        let provider = create_metadata_provider(temp_dir.path());
+        assert!(!provider.has_linkable_library().unwrap());
+    }
+
+    #[test]
+    fn test_default_run_binary() {
+        let fixture_dir =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/multiple-bins");
+        let provider = create_metadata_provider(&fixture_dir);
+        assert_eq!(
+            provider.binary_names().unwrap(),
+            BTreeSet::from([
+                "client".to_string(),
+                "multiple-bins".to_string(),
+                "server".to_string(),
+            ])
+        );
+        assert_eq!(provider.default_run_binary().unwrap(), "server");
+    }
+
+    #[test]
+    fn test_keywords() {
+        let fixture_dir =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/keywords");
+        let mut provider = create_metadata_provider(&fixture_dir);
+        assert_eq!(
+            provider.keywords().unwrap(),
+            vec!["cli", "parser", "command-line-utilities"]
+        );
+    }
+
+    #[test]
+    fn test_keywords_inherited_from_workspace() {
+        let temp_dir = create_temp_cargo_project(
+            r#"
+[workspace]
+members = []
+
+[workspace.package]
+keywords = ["conda", "packaging"]
+
+[package]
+name = "test-package"
+version = "0.1.0"
+keywords.workspace = true
+categories = ["development-tools"]
+"#,
+        );
+        let mut provider = create_metadata_provider(temp_dir.path());
+        assert_eq!(
+            provider.keywords().unwrap(),
+            vec!["conda", "packaging", "development-tools"]
+        );
+    }
+
+    #[test]
+    fn test_default_run_binary_without_default_run() {
+        let temp_dir = create_temp_cargo_project(
+            r#"
+[package]
+name = "test-package"
+version = "0.1.0"
+"#,
+        );
+        fs::create_dir_all(temp_dir.path().join("src/bin")).unwrap();
+        fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+
+        // The only binary of the crate is picked.
+        let provider = create_metadata_provider(temp_dir.path());
+        assert_eq!(provider.default_run_binary().unwrap(), "test-package");
+
+        // With multiple binaries it is unclear which one to pick.
+        fs::write(temp_dir.path().join("src/bin/tool.rs"), "fn main() {}").unwrap();
+        let provider = create_metadata_provider(temp_dir.path());
+        assert!(matches!(
+            provider.default_run_binary(),
+            Err(MetadataError::NoDefaultRun(names)) if names == "test-package, tool"
+        ));
+    }
+
     #[test]
     fn test_inheritance_without_workspace_version() {
         let cargo_toml_content = r#"
@@ -5,8 +5,9 @@ use marked_yaml::{Node as MarkedNode, Span};
 pub type MappingHash = LinkedHashMap<MarkedScalarNode, MarkedNode>;
 
 use crate::recipe::{
-    About, Build, Conditional, ConditionalList, ConditionalRequirements, Extra, IntermediateRecipe,
-    Item, ListOrItem, Package, PackageContents, Source, Test, Value,
+    About, Build, Conditional, ConditionalList, ConditionalRequirements, DynamicLinking, Extra,
+    IntermediateRecipe, Item, ListOrItem, Package, PackageContents, PythonTest, RunExports, Source,
+    SourceFilter, Test, TestRequirements, Value,
 };
 
 // Trait for converting to marked YAML nodes
@@ -110,6 +111,27 @@ impl ToMarkedYaml for Source {
                         sha256.to_marked_yaml(),
                     );
                 }
+                if let Some(use_gitignore) = path.use_gitignore {
+                    mapping.insert(
+                        MarkedScalarNode::new(Span::new_blank(), "use_gitignore"),
+                        MarkedNode::Scalar(MarkedScalarNode::new(
+                            Span::new_blank(),
+                            use_gitignore.to_string(),
+                        )),
+                    );
+                }
+                if let Some(ref filter) = path.filter {
+                    mapping.insert(
+                        MarkedScalarNode::new(Span::new_blank(), "filter"),
+                        filter.to_marked_yaml(),
+                    );
+                }
+                if let Some(ref target_directory) = path.target_directory {
+                    mapping.insert(
+                        MarkedScalarNode::new(Span::new_blank(), "target_directory"),
+                        target_directory.to_marked_yaml(),
+                    );
+                }
             }
             Source::Url(url) => {
                 mapping.insert(
@@ -122,6 +144,75 @@ impl ToMarkedYaml for Source {
                         sha256.to_marked_yaml(),
                     );
                 }
+                if let Some(ref target_directory) = url.target_directory {
+                    mapping.insert(
+                        MarkedScalarNode::new(Span::new_blank(), "target_directory"),
+                        target_directory.to_marked_yaml(),
+                    );
+                }
+            }
+        }
+
+        MarkedNode::Mapping(MarkedMappingNode::new(Span::new_blank(), mapping))
+    }
+}
+
+impl ToMarkedYaml for SourceFilter {
+    fn to_marked_yaml(&self) -> MarkedNode {
+        let mut mapping = MappingHash::new();
+
+        for (key, globs) in [("include", &self.include), ("exclude", &self.exclude)] {
+            if !globs.is_empty() {
+                let nodes: Vec<MarkedNode> = globs
+                    .iter()
+                    .map(|glob| MarkedNode::Scalar(MarkedScalarNode::new(Span::new_blank(), glob)))
+                    .collect();
+                mapping.insert(
+                    MarkedScalarNode::new(Span::new_blank(), key),
+                    MarkedNode::Sequence(MarkedSequenceNode::new(Span::new_blank(), nodes)),
+                );
+            }
+        }
+
+        MarkedNode::Mapping(MarkedMappingNode::new(Span::new_blank(), mapping))
+    }
+}
+
+impl ToMarkedYaml for DynamicLinking {
+    fn to_marked_yaml(&self) -> MarkedNode {
+        let mut mapping = MappingHash::new();
+
+        for (key, values) in [
+            ("rpaths", &self.rpaths),
+            ("missing_dso_allowlist", &self.missing_dso_allowlist),
+            ("rpath_allowlist", &self.rpath_allowlist),
+        ] {
+            if !values.is_empty() {
+                let nodes: Vec<MarkedNode> = values
+                    .iter()
+                    .map(|value| {
+                        MarkedNode::Scalar(MarkedScalarNode::new(Span::new_blank(), value))
+                    })
+                    .collect();
+                mapping.insert(
+                    MarkedScalarNode::new(Span::new_blank(), key),
+                    MarkedNode::Sequence(MarkedSequenceNode::new(Span::new_blank(), nodes)),
+                );
+            }
+        }
+
+        for (key, behavior) in [
+            ("overdepending_behavior", self.overdepending_behavior),
+            ("overlinking_behavior", self.overlinking_behavior),
+        ] {
+            if let Some(behavior) = behavior {
+                mapping.insert(
+                    MarkedScalarNode::new(Span::new_blank(), key),
+                    MarkedNode::Scalar(MarkedScalarNode::new(
+                        Span::new_blank(),
+                        behavior.to_string(),
+                    )),
+                );
             }
         }
 
@@ -140,6 +231,39 @@ impl ToMarkedYaml for Build {
             );
         }
 
+        if let Some(ref string) = self.string {
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "string"),
+                string.to_marked_yaml(),
+            );
+        }
+
+        if self.merge_build_and_host_envs {
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "merge_build_and_host_envs"),
+                MarkedNode::Scalar(MarkedScalarNode::new(Span::new_blank(), "true")),
+            );
+        }
+
+        if !self.always_copy_files.is_empty() {
+            let nodes: Vec<MarkedNode> = self
+                .always_copy_files
+                .iter()
+                .map(|glob| MarkedNode::Scalar(MarkedScalarNode::new(Span::new_blank(), glob)))
+                .collect();
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "always_copy_files"),
+                MarkedNode::Sequence(MarkedSequenceNode::new(Span::new_blank(), nodes)),
+            );
+        }
+
+        if !self.dynamic_linking.is_default() {
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "dynamic_linking"),
+                self.dynamic_linking.to_marked_yaml(),
+            );
+        }
+
         MarkedNode::Mapping(MarkedMappingNode::new(Span::new_blank(), mapping))
     }
 }
@@ -176,6 +300,36 @@ impl ToMarkedYaml for ConditionalRequirements {
             );
         }
 
+        if !self.run_exports.is_empty() {
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "run_exports"),
+                self.run_exports.to_marked_yaml(),
+            );
+        }
+
+        MarkedNode::Mapping(MarkedMappingNode::new(Span::new_blank(), mapping))
+    }
+}
+
+impl ToMarkedYaml for RunExports {
+    fn to_marked_yaml(&self) -> MarkedNode {
+        let mut mapping = MappingHash::new();
+
+        for (key, value) in [
+            ("weak", &self.weak),
+            ("strong", &self.strong),
+            ("noarch", &self.noarch),
+            ("weak_constraints", &self.weak_constraints),
+            ("strong_constraints", &self.strong_constraints),
+        ] {
+            if !value.is_empty() {
+                mapping.insert(
+                    MarkedScalarNode::new(Span::new_blank(), key),
+                    value.to_marked_yaml(),
+                );
+            }
+        }
+
         MarkedNode::Mapping(MarkedMappingNode::new(Span::new_blank(), mapping))
     }
 }
@@ -213,6 +367,70 @@ impl ToMarkedYaml for Test {
             );
         }
 
+        if let Some(ref script) = self.script {
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "script"),
+                script.to_marked_yaml(),
+            );
+        }
+
+        if let Some(ref requirements) = self.requirements {
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "requirements"),
+                requirements.to_marked_yaml(),
+            );
+        }
+
+        if let Some(ref python) = self.python {
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "python"),
+                python.to_marked_yaml(),
+            );
+        }
+
+        MarkedNode::Mapping(MarkedMappingNode::new(Span::new_blank(), mapping))
+    }
+}
+
+impl ToMarkedYaml for PythonTest {
+    fn to_marked_yaml(&self) -> MarkedNode {
+        let mut mapping = MappingHash::new();
+
+        mapping.insert(
+            MarkedScalarNode::new(Span::new_blank(), "imports"),
+            self.imports.to_marked_yaml(),
+        );
+
+        if let Some(pip_check) = self.pip_check {
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "pip_check"),
+                MarkedNode::Scalar(MarkedScalarNode::new(
+                    Span::new_blank(),
+                    pip_check.to_string(),
+                )),
+            );
+        }
+
+        MarkedNode::Mapping(MarkedMappingNode::new(Span::new_blank(), mapping))
+    }
+}
+
+impl ToMarkedYaml for TestRequirements {
+    fn to_marked_yaml(&self) -> MarkedNode {
+        let mut mapping = MappingHash::new();
+
+        mapping.insert(
+            MarkedScalarNode::new(Span::new_blank(), "run"),
+            self.run.to_marked_yaml(),
+        );
+
+        if !self.build.is_empty() {
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "build"),
+                self.build.to_marked_yaml(),
+            );
+        }
+
         MarkedNode::Mapping(MarkedMappingNode::new(Span::new_blank(), mapping))
     }
 }
@@ -235,6 +453,13 @@ impl ToMarkedYaml for About {
             );
         }
 
+        if let Some(ref license_family) = self.license_family {
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "license_family"),
+                license_family.to_marked_yaml(),
+            );
+        }
+
         if let Some(ref license_file) = self.license_file {
             mapping.insert(
                 MarkedScalarNode::new(Span::new_blank(), "license_file"),
@@ -285,6 +510,20 @@ impl ToMarkedYaml for Extra {
             );
         }
 
+        if !self.keywords.is_empty() {
+            let nodes: Vec<MarkedNode> = self
+                .keywords
+                .iter()
+                .map(|keyword| {
+                    MarkedNode::Scalar(MarkedScalarNode::new(Span::new_blank(), keyword))
+                })
+                .collect();
+            mapping.insert(
+                MarkedScalarNode::new(Span::new_blank(), "keywords"),
+                MarkedNode::Sequence(MarkedSequenceNode::new(Span::new_blank(), nodes)),
+            );
+        }
+
         MarkedNode::Mapping(MarkedMappingNode::new(Span::new_blank(), mapping))
     }
 }
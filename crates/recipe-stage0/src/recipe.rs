@@ -352,6 +352,72 @@ pub struct IntermediateRecipe {
     pub extra: Option<Extra>,
 }
 
+/// A recipe with multiple outputs that share a single context.
+///
+/// This is used when generating a recipe for a workspace that contains
+/// multiple packages, each package becomes one of the outputs.
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct MultiOutputRecipe {
+    #[serde(default)]
+    pub context: IndexMap<String, Value<String>>,
+    #[serde(default)]
+    pub outputs: Vec<Output>,
+}
+
+/// Returned when recipes that are combined into a [`MultiOutputRecipe`] define
+/// the same context variable with different values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictingContextError {
+    pub key: String,
+    pub first: String,
+    pub second: String,
+}
+
+impl Display for ConflictingContextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "the context variable '{}' is defined as both '{}' and '{}'",
+            self.key, self.first, self.second
+        )
+    }
+}
+
+impl std::error::Error for ConflictingContextError {}
+
+/// A single output of a [`MultiOutputRecipe`].
+#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+pub struct Output {
+    #[serde(default)]
+    pub package: Package,
+    #[serde(default)]
+    pub source: ConditionalList<Source>,
+    #[serde(default)]
+    pub build: Build,
+    #[serde(default)]
+    pub requirements: ConditionalRequirements,
+    #[serde(default)]
+    pub tests: Vec<Test>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub about: Option<About>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra: Option<Extra>,
+}
+
+impl From<IntermediateRecipe> for Output {
+    fn from(recipe: IntermediateRecipe) -> Self {
+        Output {
+            package: recipe.package,
+            source: recipe.source,
+            build: recipe.build,
+            requirements: recipe.requirements,
+            tests: recipe.tests,
+            about: recipe.about,
+            extra: recipe.extra,
+        }
+    }
+}
+
 pub struct EvaluatedDependencies {
     pub build: Option<Vec<SerializableMatchSpec>>,
     pub host: Option<Vec<SerializableMatchSpec>>,
@@ -395,6 +461,7 @@ impl Source {
         Source::Url(UrlSource {
             url: Value::Concrete(url),
             sha256: None,
+            target_directory: None,
         })
     }
 
@@ -402,6 +469,9 @@ impl Source {
         Source::Path(PathSource {
             path: Value::Concrete(path),
             sha256: None,
+            use_gitignore: None,
+            filter: None,
+            target_directory: None,
         })
     }
 
@@ -438,11 +508,15 @@ impl FromStr for Source {
             Ok(Source::Url(UrlSource {
                 url: Value::Concrete(s.to_string()),
                 sha256: None,
+                target_directory: None,
             }))
         } else {
             Ok(Source::Path(PathSource {
                 path: Value::Concrete(s.to_string()),
                 sha256: None,
+                use_gitignore: None,
+                filter: None,
+                target_directory: None,
             }))
         }
     }
@@ -473,12 +547,40 @@ impl Display for Source {
 pub struct UrlSource {
     pub url: Value<String>,
     pub sha256: Option<Value<String>>,
+    /// The directory, relative to the work directory, that the source is
+    /// extracted into. If not set, the source is extracted into the work
+    /// directory itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_directory: Option<Value<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct PathSource {
     pub path: Value<String>,
     pub sha256: Option<Value<String>>,
+    /// Whether files that are ignored by git are excluded from the source.
+    /// Defaults to `true` when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_gitignore: Option<bool>,
+    /// Globs that select the files of the path that are part of the source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<SourceFilter>,
+    /// The directory, relative to the work directory, that the source is
+    /// copied into. If not set, the source is copied into the work directory
+    /// itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_directory: Option<Value<String>>,
+}
+
+/// Include and exclude globs of a path source. If `include` is empty, all
+/// files that are not excluded are part of the source.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SourceFilter {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
@@ -488,6 +590,10 @@ pub struct Script {
     pub env: IndexMap<String, String>,
     #[serde(default)]
     pub secrets: Vec<String>,
+    /// The interpreter that runs the script, e.g. `bash`, `cmd`, `nu` or
+    /// `python`. If not set, rattler-build picks one based on the platform.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interpreter: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -531,14 +637,85 @@ impl Display for Python {
     }
 }
 
+/// The behavior of the overlinking and overdepending checks of rattler-build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkingCheckBehavior {
+    /// The check only emits a warning.
+    Ignore,
+    /// The check fails the build.
+    Error,
+}
+
+impl Display for LinkingCheckBehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkingCheckBehavior::Ignore => write!(f, "ignore"),
+            LinkingCheckBehavior::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Configuration of the dynamic linking checks that rattler-build performs
+/// on the binaries of a package.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DynamicLinking {
+    /// The rpaths that are set on the binaries, relative to the prefix.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rpaths: Vec<String>,
+    /// Globs of shared libraries that may be linked without being provided
+    /// by a run dependency, e.g. system libraries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing_dso_allowlist: Vec<String>,
+    /// Globs of rpaths that are allowed even though they point outside of the
+    /// prefix.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rpath_allowlist: Vec<String>,
+    /// What happens when a run dependency is not linked by any binary.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overdepending_behavior: Option<LinkingCheckBehavior>,
+    /// What happens when a binary links a library that is not provided by a
+    /// run dependency.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub overlinking_behavior: Option<LinkingCheckBehavior>,
+}
+
+impl DynamicLinking {
+    /// Returns true if this is the default dynamic linking configuration.
+    pub fn is_default(&self) -> bool {
+        self.rpaths.is_empty()
+            && self.missing_dso_allowlist.is_empty()
+            && self.rpath_allowlist.is_empty()
+            && self.overdepending_behavior.is_none()
+            && self.overlinking_behavior.is_none()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct Build {
     pub number: Option<Value<u64>>,
+    /// An explicit build string, e.g. `${{ hash }}_git${{ git_hash }}`. If not
+    /// set, the build string is derived from the variant hash and the build
+    /// number.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub string: Option<Value<String>>,
     pub script: Script,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub noarch: Option<NoArchKind>,
     #[serde(default, skip_serializing_if = "Python::is_default")]
     pub python: Python,
+    /// Whether the build and host environments should be merged into a single
+    /// environment. This is required for some cross-compilation setups where
+    /// build tools need to be available when resolving the host environment.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub merge_build_and_host_envs: bool,
+    /// Globs of files that are copied into the package instead of being
+    /// hardlinked.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub always_copy_files: Vec<String>,
+    /// Configuration of the overlinking and overdepending checks.
+    #[serde(default, skip_serializing_if = "DynamicLinking::is_default")]
+    pub dynamic_linking: DynamicLinking,
 }
 
 impl Build {
@@ -554,14 +731,9 @@ impl Build {
     }
 }
 
-/// A struct to hold the fully resolved, non-conditional requirements.
-#[derive(Default)]
-pub struct ResolvedRequirements {
-    pub build: Vec<PackageDependency>,
-    pub host: Vec<PackageDependency>,
-    pub run: Vec<PackageDependency>,
-    pub run_constraints: Vec<PackageDependency>,
-}
+/// The fully resolved, non-conditional requirements for a single platform,
+/// keyed by the name of the package.
+pub type ResolvedRequirements = PackageSpecDependencies<PackageDependency>;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, Clone)]
 pub enum Target {
@@ -580,17 +752,64 @@ pub struct ConditionalRequirements {
     pub run: ConditionalList<PackageDependency>,
     #[serde(default)]
     pub run_constraints: ConditionalList<PackageDependency>,
+    #[serde(default, skip_serializing_if = "RunExports::is_empty")]
+    pub run_exports: RunExports,
+}
+
+/// The run exports of a package, these are added to the requirements of
+/// packages that depend on it. Entries can also be jinja expressions like
+/// `${{ pin_subpackage('foo', upper_bound='x.x') }}`.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct RunExports {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weak: ConditionalList<PackageDependency>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strong: ConditionalList<PackageDependency>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub noarch: ConditionalList<PackageDependency>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weak_constraints: ConditionalList<PackageDependency>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub strong_constraints: ConditionalList<PackageDependency>,
+}
+
+impl RunExports {
+    /// Returns true if no run exports are defined.
+    pub fn is_empty(&self) -> bool {
+        self.weak.is_empty()
+            && self.strong.is_empty()
+            && self.noarch.is_empty()
+            && self.weak_constraints.is_empty()
+            && self.strong_constraints.is_empty()
+    }
 }
 
 impl ConditionalRequirements {
-    /// Resolves the conditional requirements for a given platform.
+    /// Resolves the requirements for the given platform.
+    ///
+    /// Conditional requirements are evaluated against the platform, e.g. a
+    /// requirement with the `unix` condition is included for `linux-64` but
+    /// not for `win-64`. Requirements that are defined multiple times are
+    /// deduplicated by their package name, the last definition wins.
+    pub fn resolve_for_platform(&self, platform: Platform) -> ResolvedRequirements {
+        Self::resolve(
+            &self.build,
+            &self.host,
+            &self.run,
+            &self.run_constraints,
+            Some(platform),
+        )
+    }
+
+    /// Resolves the conditional requirements for a given platform. If no
+    /// platform is given, all conditional requirements are ignored.
     pub fn resolve(
         build: &ConditionalList<PackageDependency>,
         host: &ConditionalList<PackageDependency>,
         run: &ConditionalList<PackageDependency>,
         run_constraints: &ConditionalList<PackageDependency>,
         platform: Option<Platform>,
-    ) -> PackageSpecDependencies<PackageDependency> {
+    ) -> ResolvedRequirements {
         PackageSpecDependencies {
             build: Self::resolve_list(build, platform),
             host: Self::resolve_list(host, platform),
@@ -624,8 +843,7 @@ impl ConditionalRequirements {
 
             Item::Conditional(cond) => {
                 if let Some(p) = platform {
-                    // This is a simple string comparison
-                    let dependencies = if cond.condition == *p.as_str() {
+                    let dependencies = if condition_matches_platform(&cond.condition, p) {
                         cond.then.clone().0.to_vec()
                     } else {
                         cond.else_value.clone().0.to_vec()
@@ -646,6 +864,18 @@ impl ConditionalRequirements {
     }
 }
 
+/// Returns true if the condition of a conditional item, e.g. `unix` or
+/// `linux-64`, applies to the given platform.
+fn condition_matches_platform(condition: &str, platform: Platform) -> bool {
+    match condition {
+        "unix" => platform.is_unix(),
+        "linux" => platform.is_linux(),
+        "win" => platform.is_windows(),
+        "osx" => platform.is_osx(),
+        _ => condition == platform.as_str(),
+    }
+}
+
 impl Display for ConditionalRequirements {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -669,15 +899,86 @@ pub(crate) struct Requirements {
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct Test {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub package_contents: Option<PackageContents>,
+    /// The commands of a script test.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<ConditionalList<String>>,
+    /// The packages that are required to run the test script.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requirements: Option<TestRequirements>,
+    /// The python modules that are imported by a python test.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub python: Option<PythonTest>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PythonTest {
+    /// The modules that must be importable.
+    pub imports: ConditionalList<String>,
+    /// Whether `pip check` is run as part of the test. rattler-build runs it
+    /// by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pip_check: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TestRequirements {
+    /// The packages that are installed in the environment in which the test
+    /// script runs, in addition to the package itself.
+    #[serde(default)]
+    pub run: ConditionalList<PackageDependency>,
+    /// The packages that are installed in the build environment of the test,
+    /// e.g. compilers that build a test program.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub build: ConditionalList<PackageDependency>,
+}
+
+impl Display for RunExports {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ weak: {}, strong: {}, noarch: {}, weak_constraints: {}, strong_constraints: {} }}",
+            self.weak.iter().format(", "),
+            self.strong.iter().format(", "),
+            self.noarch.iter().format(", "),
+            self.weak_constraints.iter().format(", "),
+            self.strong_constraints.iter().format(", "),
+        )
+    }
 }
 
 impl Display for Test {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Test {{ package_contents: {} }}",
-            self.package_contents.as_ref().into_iter().format("")
+            "Test {{ package_contents: {}, script: {}, requirements: {}, python: {} }}",
+            self.package_contents.as_ref().into_iter().format(""),
+            self.script.as_ref().into_iter().flatten().format(", "),
+            self.requirements.as_ref().into_iter().format(""),
+            self.python.as_ref().into_iter().format(""),
+        )
+    }
+}
+
+impl Display for PythonTest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "PythonTest {{ imports: {}, pip_check: {} }}",
+            self.imports.iter().format(", "),
+            self.pip_check.into_iter().format(""),
+        )
+    }
+}
+
+impl Display for TestRequirements {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TestRequirements {{ run: {}, build: {} }}",
+            self.run.iter().format(", "),
+            self.build.iter().format(", ")
         )
     }
 }
@@ -703,7 +1004,11 @@ impl Display for PackageContents {
 pub struct About {
     pub homepage: Option<Value<String>>,
     pub license: Option<Value<String>>,
-    pub license_file: Option<Value<String>>,
+    /// The conda license family, e.g. `MIT` or `APACHE`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_family: Option<Value<String>>,
+    /// One or more license files that are included in the package.
+    pub license_file: Option<ListOrItem<Value<String>>>,
     pub summary: Option<Value<String>>,
     pub description: Option<Value<String>>,
     pub documentation: Option<Value<String>>,
@@ -714,9 +1019,10 @@ impl Display for About {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "About {{ homepage: {}, license: {}, license_file: {}, summary: {}, description: {}, documentation: {}, repository: {} }}",
+            "About {{ homepage: {}, license: {}, license_family: {}, license_file: {}, summary: {}, description: {}, documentation: {}, repository: {} }}",
             self.homepage.as_ref().into_iter().format(", "),
             self.license.as_ref().into_iter().format(", "),
+            self.license_family.as_ref().into_iter().format(", "),
             self.license_file.as_ref().into_iter().format(", "),
             self.summary.as_ref().into_iter().format(", "),
             self.description.as_ref().into_iter().format(", "),
@@ -730,14 +1036,19 @@ impl Display for About {
 pub struct Extra {
     #[serde(rename = "recipe-maintainers")]
     pub recipe_maintainers: ConditionalList<String>,
+    /// Keywords that describe the package, e.g. the keywords and categories
+    /// of a crate.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
 }
 
 impl Display for Extra {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{{ recipe_maintainers: {} }}",
-            self.recipe_maintainers.iter().format(", ")
+            "{{ recipe_maintainers: {}, keywords: {} }}",
+            self.recipe_maintainers.iter().format(", "),
+            self.keywords.iter().format(", ")
         )
     }
 }
@@ -762,6 +1073,50 @@ impl IntermediateRecipe {
     }
 }
 
+impl MultiOutputRecipe {
+    /// Combines multiple single output recipes into one recipe with an
+    /// output per recipe.
+    ///
+    /// The context of all recipes is merged into the shared context of the
+    /// recipe. Returns an error if multiple recipes define the same key with
+    /// different values, because every output would see the same value.
+    pub fn from_recipes(
+        recipes: impl IntoIterator<Item = IntermediateRecipe>,
+    ) -> Result<Self, ConflictingContextError> {
+        let mut context: IndexMap<String, Value<String>> = IndexMap::new();
+        let mut outputs = Vec::new();
+        for mut recipe in recipes {
+            for (key, value) in std::mem::take(&mut recipe.context) {
+                match context.get(&key) {
+                    Some(existing) if *existing != value => {
+                        return Err(ConflictingContextError {
+                            key,
+                            first: existing.to_string(),
+                            second: value.to_string(),
+                        });
+                    }
+                    Some(_) => {}
+                    None => {
+                        context.insert(key, value);
+                    }
+                }
+            }
+            outputs.push(Output::from(recipe));
+        }
+        Ok(MultiOutputRecipe { context, outputs })
+    }
+
+    /// Converts the recipe to YAML string
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+
+    /// Creates a recipe from YAML string
+    pub fn from_yaml(yaml: &str) -> Result<MultiOutputRecipe, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+}
+
 impl<T: ToString + Default + Debug> Conditional<T> {
     pub fn new(condition: String, then_value: ListOrItem<T>) -> Self {
         Self {
@@ -808,6 +1163,7 @@ mod tests {
                         .parse()
                         .unwrap(),
                 ),
+                target_directory: None,
             }
             .into(),
         )]);
@@ -837,12 +1193,21 @@ mod tests {
                 ],
                 run: vec!["xtl >=0.7,<0.8".parse().unwrap()],
                 run_constraints: vec!["xsimd >=8.0.3,<10".parse().unwrap()],
+                run_exports: RunExports {
+                    weak: vec![
+                        "${{ pin_subpackage('xtensor', upper_bound='x.x') }}"
+                            .parse()
+                            .unwrap(),
+                    ],
+                    ..Default::default()
+                },
             },
             about: Some(About {
                 homepage: Some(Value::Concrete(
                     "https://github.com/xtensor-stack/xtensor".to_string(),
                 )),
                 license: Some("BSD-3-Clause".parse().unwrap()),
+                license_family: None,
                 license_file: Some("LICENSE".parse().unwrap()),
                 summary: Some("The C++ tensor algebra library".parse().unwrap()),
                 description: Some(
@@ -855,10 +1220,432 @@ mod tests {
             }),
             extra: Some(Extra {
                 recipe_maintainers: vec!["some-maintainer".parse().unwrap()],
+                ..Default::default()
             }),
             ..Default::default()
         };
 
         insta::assert_yaml_snapshot!(recipe)
     }
+
+    #[test]
+    fn test_merge_build_and_host_envs_round_trip() {
+        let recipe = IntermediateRecipe {
+            build: Build {
+                merge_build_and_host_envs: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let yaml = recipe.to_yaml().unwrap();
+        assert!(yaml.contains("merge_build_and_host_envs: true"));
+
+        let parsed = IntermediateRecipe::from_yaml(&yaml).unwrap();
+        assert!(parsed.build.merge_build_and_host_envs);
+
+        // The field is omitted when it is not set.
+        let yaml = IntermediateRecipe::default().to_yaml().unwrap();
+        assert!(!yaml.contains("merge_build_and_host_envs"));
+    }
+
+    #[test]
+    fn test_always_copy_files_round_trip() {
+        let recipe = IntermediateRecipe {
+            build: Build {
+                always_copy_files: vec!["share/data/**".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let yaml = recipe.to_yaml().unwrap();
+        assert!(yaml.contains("always_copy_files"));
+
+        let parsed = IntermediateRecipe::from_yaml(&yaml).unwrap();
+        assert_eq!(
+            parsed.build.always_copy_files,
+            vec!["share/data/**".to_string()]
+        );
+
+        // The field is omitted when it is not set.
+        let yaml = IntermediateRecipe::default().to_yaml().unwrap();
+        assert!(!yaml.contains("always_copy_files"));
+    }
+
+    #[test]
+    fn test_dynamic_linking_round_trip() {
+        let recipe = IntermediateRecipe {
+            build: Build {
+                dynamic_linking: DynamicLinking {
+                    rpaths: vec!["lib/".to_string()],
+                    missing_dso_allowlist: vec!["/usr/lib/libGL.so*".to_string()],
+                    overlinking_behavior: Some(LinkingCheckBehavior::Error),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // The keys and values match the `build.dynamic_linking` schema of
+        // rattler-build.
+        let yaml = recipe.to_yaml().unwrap();
+        let value: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        let dynamic_linking = &value["build"]["dynamic_linking"];
+        assert_eq!(dynamic_linking["rpaths"][0].as_str(), Some("lib/"));
+        assert_eq!(
+            dynamic_linking["missing_dso_allowlist"][0].as_str(),
+            Some("/usr/lib/libGL.so*")
+        );
+        assert_eq!(
+            dynamic_linking["overlinking_behavior"].as_str(),
+            Some("error")
+        );
+        assert!(dynamic_linking.get("rpath_allowlist").is_none());
+        assert!(dynamic_linking.get("overdepending_behavior").is_none());
+
+        let parsed = IntermediateRecipe::from_yaml(&yaml).unwrap();
+        assert_eq!(
+            parsed.build.dynamic_linking.overlinking_behavior,
+            Some(LinkingCheckBehavior::Error)
+        );
+        assert_eq!(
+            parsed.build.dynamic_linking.rpaths,
+            vec!["lib/".to_string()]
+        );
+
+        // The section is omitted when it is not set.
+        let yaml = IntermediateRecipe::default().to_yaml().unwrap();
+        assert!(!yaml.contains("dynamic_linking"));
+    }
+
+    #[test]
+    fn test_script_interpreter_round_trip() {
+        let recipe = IntermediateRecipe {
+            build: Build {
+                script: Script {
+                    content: vec!["print('hello')".to_string()],
+                    interpreter: Some("python".to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let yaml = recipe.to_yaml().unwrap();
+        assert!(yaml.contains("interpreter: python"), "{yaml}");
+
+        let parsed = IntermediateRecipe::from_yaml(&yaml).unwrap();
+        assert_eq!(parsed.build.script.interpreter, Some("python".to_string()));
+
+        // The field is omitted when it is not set.
+        let yaml = IntermediateRecipe::default().to_yaml().unwrap();
+        assert!(!yaml.contains("interpreter"));
+    }
+
+    #[test]
+    fn test_build_string_round_trip() {
+        let recipe = IntermediateRecipe {
+            build: Build {
+                string: Some("${{ hash }}_custom".parse().unwrap()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let yaml = recipe.to_yaml().unwrap();
+        assert!(yaml.contains("string:"), "{yaml}");
+
+        let parsed = IntermediateRecipe::from_yaml(&yaml).unwrap();
+        assert_eq!(
+            parsed.build.string.map(|string| string.to_string()),
+            Some("${{ hash }}_custom".to_string())
+        );
+
+        // The field is omitted when it is not set.
+        let yaml = IntermediateRecipe::default().to_yaml().unwrap();
+        assert!(!yaml.contains("string:"));
+    }
+
+    #[test]
+    fn test_license_family_round_trip() {
+        let recipe = IntermediateRecipe {
+            about: Some(About {
+                license: Some("MIT".parse().unwrap()),
+                license_family: Some("MIT".parse().unwrap()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let yaml = recipe.to_yaml().unwrap();
+        assert!(yaml.contains("license_family: MIT"), "{yaml}");
+
+        let parsed = IntermediateRecipe::from_yaml(&yaml).unwrap();
+        assert_eq!(
+            parsed
+                .about
+                .and_then(|about| about.license_family)
+                .map(|family| family.to_string()),
+            Some("MIT".to_string())
+        );
+
+        // The field is omitted when it is not set.
+        let yaml = IntermediateRecipe {
+            about: Some(About::default()),
+            ..Default::default()
+        }
+        .to_yaml()
+        .unwrap();
+        assert!(!yaml.contains("license_family"));
+    }
+
+    #[test]
+    fn test_script_test_round_trip() {
+        let recipe = IntermediateRecipe {
+            tests: vec![Test {
+                script: Some(vec!["pytest".parse().unwrap()]),
+                requirements: Some(TestRequirements {
+                    run: vec!["pytest >=8".parse().unwrap()],
+                    build: vec!["${{ compiler('c') }}".parse().unwrap()],
+                }),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let yaml = recipe.to_yaml().unwrap();
+        assert!(!yaml.contains("package_contents"), "{yaml}");
+
+        let parsed = IntermediateRecipe::from_yaml(&yaml).unwrap();
+        let test = &parsed.tests[0];
+        assert_eq!(
+            test.script
+                .iter()
+                .flatten()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["pytest"]
+        );
+        assert_eq!(
+            test.requirements
+                .iter()
+                .flat_map(|requirements| requirements.run.iter())
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["pytest >=8"]
+        );
+        assert_eq!(
+            test.requirements
+                .iter()
+                .flat_map(|requirements| requirements.build.iter())
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["${{ compiler('c') }}"]
+        );
+    }
+
+    #[test]
+    fn test_path_source_use_gitignore() {
+        let recipe = IntermediateRecipe {
+            source: vec![Item::from(Source::Path(PathSource {
+                path: Value::Concrete(".".to_string()),
+                sha256: None,
+                use_gitignore: Some(false),
+                filter: None,
+                target_directory: None,
+            }))],
+            ..Default::default()
+        };
+
+        let yaml = recipe.to_yaml().unwrap();
+        assert!(yaml.contains("use_gitignore: false"), "{yaml}");
+
+        let parsed = IntermediateRecipe::from_yaml(&yaml).unwrap();
+        let Some(Item::Value(Value::Concrete(Source::Path(path_source)))) = parsed.source.first()
+        else {
+            panic!("expected a path source, got {:?}", parsed.source);
+        };
+        assert_eq!(path_source.use_gitignore, Some(false));
+
+        // The flag is omitted when it is not set.
+        let yaml = IntermediateRecipe {
+            source: vec![Item::from(Source::path(".".to_string()))],
+            ..Default::default()
+        }
+        .to_yaml()
+        .unwrap();
+        assert!(!yaml.contains("use_gitignore"), "{yaml}");
+    }
+
+    #[test]
+    fn test_path_source_filter() {
+        let recipe = IntermediateRecipe {
+            source: vec![Item::from(Source::Path(PathSource {
+                path: Value::Concrete(".".to_string()),
+                sha256: None,
+                use_gitignore: None,
+                filter: Some(SourceFilter {
+                    include: Vec::new(),
+                    exclude: vec!["tests/fixtures/**".to_string()],
+                }),
+                target_directory: None,
+            }))],
+            ..Default::default()
+        };
+
+        let yaml = recipe.to_yaml().unwrap();
+        let parsed = IntermediateRecipe::from_yaml(&yaml).unwrap();
+        let Some(Item::Value(Value::Concrete(Source::Path(path_source)))) = parsed.source.first()
+        else {
+            panic!("expected a path source, got {:?}", parsed.source);
+        };
+        assert_eq!(
+            path_source.filter,
+            Some(SourceFilter {
+                include: Vec::new(),
+                exclude: vec!["tests/fixtures/**".to_string()],
+            })
+        );
+        assert!(!yaml.contains("include"), "{yaml}");
+    }
+
+    #[test]
+    fn test_source_target_directory_round_trip() {
+        let source = vec![
+            Item::from(Source::Url(UrlSource {
+                url: Value::Concrete("https://example.com/foobar-0.1.0.tar.gz".to_string()),
+                sha256: None,
+                target_directory: Some(Value::Concrete("foobar".to_string())),
+            })),
+            Item::from(Source::Path(PathSource {
+                path: Value::Concrete("../data".to_string()),
+                sha256: None,
+                use_gitignore: None,
+                filter: None,
+                target_directory: Some(Value::Concrete("data".to_string())),
+            })),
+        ];
+        let recipe = IntermediateRecipe {
+            source: source.clone(),
+            ..Default::default()
+        };
+
+        let yaml = recipe.to_yaml().unwrap();
+        assert!(yaml.contains("target_directory: foobar"), "{yaml}");
+        assert!(yaml.contains("target_directory: data"), "{yaml}");
+
+        let parsed = IntermediateRecipe::from_yaml(&yaml).unwrap();
+        assert_eq!(parsed.source, source);
+
+        // The field is omitted when it is not set.
+        let yaml = IntermediateRecipe {
+            source: vec![Item::from(Source::path(".".to_string()))],
+            ..Default::default()
+        }
+        .to_yaml()
+        .unwrap();
+        assert!(!yaml.contains("target_directory"), "{yaml}");
+    }
+
+    #[test]
+    fn test_multi_output_recipe_shares_context() {
+        let recipe = |name: &str, cdn: &str| {
+            let mut context = IndexMap::new();
+            context.insert("cdn".to_string(), Value::Concrete(cdn.to_string()));
+            context.insert(name.to_string(), Value::Concrete(name.to_string()));
+            IntermediateRecipe {
+                context,
+                package: Package {
+                    name: Value::Concrete(name.to_string()),
+                    version: Value::Concrete("1.0".to_string()),
+                },
+                ..Default::default()
+            }
+        };
+
+        let cdn = "https://cdn.example.com";
+        let multi =
+            MultiOutputRecipe::from_recipes([recipe("foo", cdn), recipe("bar", cdn)]).unwrap();
+
+        assert_eq!(
+            multi.context.keys().collect::<Vec<_>>(),
+            vec!["cdn", "foo", "bar"]
+        );
+        assert_eq!(
+            multi
+                .outputs
+                .iter()
+                .map(|output| output.package.name.to_string())
+                .collect::<Vec<_>>(),
+            vec!["foo", "bar"]
+        );
+
+        let yaml = multi.to_yaml().unwrap();
+        let parsed = MultiOutputRecipe::from_yaml(&yaml).unwrap();
+        assert_eq!(parsed.outputs.len(), 2);
+    }
+
+    #[test]
+    fn test_multi_output_recipe_rejects_conflicting_context() {
+        let recipe = |name: &str, version: &str| {
+            let mut context = IndexMap::new();
+            context.insert("version".to_string(), Value::Concrete(version.to_string()));
+            IntermediateRecipe {
+                context,
+                package: Package {
+                    name: Value::Concrete(name.to_string()),
+                    version: Value::Template("${{ version }}".to_string()),
+                },
+                ..Default::default()
+            }
+        };
+
+        let err = MultiOutputRecipe::from_recipes([recipe("foo", "1.0"), recipe("bar", "2.0")])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the context variable 'version' is defined as both '1.0' and '2.0'"
+        );
+    }
+
+    #[test]
+    fn test_resolve_for_platform() {
+        let requirements = ConditionalRequirements {
+            build: vec![
+                "cmake".parse().unwrap(),
+                Conditional {
+                    condition: "unix".to_owned(),
+                    then: "make".parse().unwrap(),
+                    else_value: "ninja".parse().unwrap(),
+                }
+                .into(),
+            ],
+            host: vec![
+                Conditional {
+                    condition: "win-64".to_owned(),
+                    then: "vs2022_win-64".parse().unwrap(),
+                    else_value: ListOrItem::default(),
+                }
+                .into(),
+            ],
+            ..Default::default()
+        };
+
+        let names = |deps: &IndexMap<PackageName, PackageDependency>| {
+            deps.keys()
+                .map(|name| name.as_normalized().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let linux = requirements.resolve_for_platform(Platform::Linux64);
+        assert_eq!(names(&linux.build), vec!["cmake", "make"]);
+        assert!(linux.host.is_empty());
+
+        let windows = requirements.resolve_for_platform(Platform::Win64);
+        assert_eq!(names(&windows.build), vec!["cmake", "ninja"]);
+        assert_eq!(names(&windows.host), vec!["vs2022_win-64"]);
+    }
 }
@@ -28,6 +28,7 @@ class PythonBackendConfig:
     env: Optional[Dict[str, str]] = None
     debug_dir: Optional[Path] = None
     extra_input_globs: Optional[List[str]] = None
+    editable: Optional[bool] = None
 
     def is_noarch(self) -> bool:
         """Whether to build a noarch package or a platform-specific package."""
@@ -82,7 +83,7 @@ class PythonGenerator(GenerateRecipeProtocol):
         build_platform = BuildPlatform.current()
 
         # Get editable setting
-        editable = get_editable_setting(python_params)
+        editable = get_editable_setting(backend_config, python_params)
 
         # Generate build script
         build_script_context = BuildScriptContext(
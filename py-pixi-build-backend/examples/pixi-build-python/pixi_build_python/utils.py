@@ -2,6 +2,7 @@ import os
 from pathlib import Path
 from typing import Any, Optional, Dict, List
 import re
+import warnings
 import toml
 
 
@@ -143,11 +144,26 @@ def get_build_input_globs(config: Any, workdir: Path, editable: bool) -> List[st
     return all_globs
 
 
-def get_editable_setting(python_params: Any) -> bool:
-    """Get editable setting from environment or params."""
-    env_editable = os.environ.get("BUILD_EDITABLE_PYTHON", "").lower() == "true"
-    if env_editable:
-        return True
+def get_editable_setting(config: Any, python_params: Any) -> bool:
+    """
+    Get the editable setting.
+
+    The configured `editable` value takes precedence. The deprecated
+    `BUILD_EDITABLE_PYTHON` environment variable is only used as a fallback,
+    otherwise the value requested in the params is used.
+    """
+    configured = getattr(config, "editable", None)
+    if configured is not None:
+        return bool(configured)
+
+    env_editable = os.environ.get("BUILD_EDITABLE_PYTHON")
+    if env_editable is not None:
+        warnings.warn(
+            "`BUILD_EDITABLE_PYTHON` is deprecated, set `editable` in the build configuration instead",
+            DeprecationWarning,
+            stacklevel=2,
+        )
+        return env_editable.lower() == "true"
 
     if python_params and hasattr(python_params, "editable"):
         return bool(python_params.editable)
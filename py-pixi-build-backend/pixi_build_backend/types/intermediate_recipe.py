@@ -202,6 +202,17 @@ class Build:
         """Set the build number."""
         self._inner.number = value._inner if value else None
 
+    @property
+    def string(self) -> Optional["ValueString"]:
+        """Get the explicit build string."""
+        inner_string = self._inner.string
+        return ValueString._from_inner(inner_string) if inner_string else None
+
+    @string.setter
+    def string(self, value: Optional["ValueString"]) -> None:
+        """Set the explicit build string."""
+        self._inner.string = value._inner if value else None
+
     @property
     def script(self) -> "Script":
         """Get the build script."""
@@ -233,6 +244,26 @@ class Build:
         """Set the Python configuration."""
         self._inner.python = value._inner
 
+    @property
+    def merge_build_and_host_envs(self) -> bool:
+        """Get whether the build and host environments are merged."""
+        return self._inner.merge_build_and_host_envs
+
+    @merge_build_and_host_envs.setter
+    def merge_build_and_host_envs(self, value: bool) -> None:
+        """Set whether the build and host environments are merged."""
+        self._inner.merge_build_and_host_envs = value
+
+    @property
+    def always_copy_files(self) -> List[str]:
+        """Get the globs of files that are copied instead of hardlinked."""
+        return self._inner.always_copy_files
+
+    @always_copy_files.setter
+    def always_copy_files(self, value: List[str]) -> None:
+        """Set the globs of files that are copied instead of hardlinked."""
+        self._inner.always_copy_files = value
+
     @classmethod
     def _from_inner(cls, inner: PyBuild) -> "Build":
         """Create a Build from a PyBuild."""
@@ -780,6 +811,11 @@ class Extra:
         """Get the recipe maintainers."""
         return self._inner.recipe_maintainers
 
+    @property
+    def keywords(self) -> List[str]:
+        """Get the keywords."""
+        return self._inner.keywords
+
 
 class PackageSpecDependencies:
     """A package spec dependencies wrapper."""
@@ -1,5 +1,6 @@
 use std::error::Error;
 
+use pixi_build_backend::error::BackendError;
 use pyo3::{PyErr, create_exception, exceptions::PyException};
 use thiserror::Error;
 
@@ -14,6 +15,9 @@ pub enum PyPixiBuildBackendError {
 
     #[error(transparent)]
     YamlSerialization(#[from] serde_yaml::Error),
+
+    #[error(transparent)]
+    Backend(#[from] BackendError),
 }
 
 fn pretty_print_error(mut err: &dyn Error) -> String {
@@ -35,6 +39,18 @@ impl From<PyPixiBuildBackendError> for PyErr {
             PyPixiBuildBackendError::YamlSerialization(err) => {
                 YamlSerializationException::new_err(pretty_print_error(&err))
             }
+            PyPixiBuildBackendError::Backend(err) => {
+                let message = pretty_print_error(&err);
+                match err {
+                    BackendError::MissingName => MissingNameException::new_err(message),
+                    BackendError::ManifestNotFound(_) => {
+                        ManifestNotFoundException::new_err(message)
+                    }
+                    BackendError::InvalidConfig(..) => InvalidConfigException::new_err(message),
+                    BackendError::RecipeParse(_) => RecipeParseException::new_err(message),
+                    _ => GeneratedRecipeException::new_err(message),
+                }
+            }
         }
     }
 }
@@ -42,3 +58,7 @@ impl From<PyPixiBuildBackendError> for PyErr {
 create_exception!(exceptions, CliException, PyException);
 create_exception!(exceptions, GeneratedRecipeException, PyException);
 create_exception!(exceptions, YamlSerializationException, PyException);
+create_exception!(exceptions, MissingNameException, GeneratedRecipeException);
+create_exception!(exceptions, ManifestNotFoundException, GeneratedRecipeException);
+create_exception!(exceptions, InvalidConfigException, GeneratedRecipeException);
+create_exception!(exceptions, RecipeParseException, GeneratedRecipeException);
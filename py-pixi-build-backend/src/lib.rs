@@ -1,6 +1,9 @@
 use pyo3::prelude::*;
 
-use crate::error::{CliException, GeneratedRecipeException};
+use crate::error::{
+    CliException, GeneratedRecipeException, InvalidConfigException, ManifestNotFoundException,
+    MissingNameException, RecipeParseException,
+};
 
 mod cli;
 pub mod error;
@@ -89,6 +92,7 @@ fn pixi_build_backend(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<recipe_stage0::recipe::PyBuild>()?;
     m.add_class::<recipe_stage0::recipe::PyScript>()?;
     m.add_class::<recipe_stage0::recipe::PyPython>()?;
+    m.add_class::<recipe_stage0::recipe::PyDynamicLinking>()?;
     m.add_class::<recipe_stage0::recipe::PyNoArchKind>()?;
     m.add_class::<recipe_stage0::recipe::PyValueString>()?;
     m.add_class::<recipe_stage0::recipe::PyValueU64>()?;
@@ -129,6 +133,13 @@ fn pixi_build_backend(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
         "GeneratedRecipeError",
         py.get_type::<GeneratedRecipeException>(),
     )?;
+    m.add("MissingNameError", py.get_type::<MissingNameException>())?;
+    m.add(
+        "ManifestNotFoundError",
+        py.get_type::<ManifestNotFoundException>(),
+    )?;
+    m.add("InvalidConfigError", py.get_type::<InvalidConfigException>())?;
+    m.add("RecipeParseError", py.get_type::<RecipeParseException>())?;
 
     Ok(())
 }
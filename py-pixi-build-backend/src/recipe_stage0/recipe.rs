@@ -18,8 +18,9 @@ use pyo3::{
 };
 use rattler_conda_types::package::EntryPoint;
 use recipe_stage0::recipe::{
-    About, Build, ConditionalRequirements, Extra, IntermediateRecipe, Item, NoArchKind, Package,
-    PathSource, Python as RecipePython, Script, Source, Test, UrlSource, Value,
+    About, Build, ConditionalRequirements, DynamicLinking, Extra, IntermediateRecipe, Item,
+    NoArchKind, Package, PathSource, Python as RecipePython, RunExports, Script, Source, Test,
+    UrlSource, Value,
 };
 
 use std::fmt::{Display, Formatter};
@@ -365,6 +366,7 @@ impl PyUrlSource {
                     .parse()
                     .map_err(|e| PyValueError::new_err(format!("Invalid URL: {e}")))?,
                 sha256: sha256.map(Value::Concrete),
+                target_directory: None,
             },
         })
     }
@@ -381,6 +383,11 @@ impl PyUrlSource {
             .clone()
             .and_then(|v| v.concrete().cloned())
     }
+
+    #[getter]
+    pub fn target_directory(&self) -> Option<String> {
+        self.inner.target_directory.as_ref().map(ToString::to_string)
+    }
 }
 
 #[pyclass]
@@ -397,6 +404,9 @@ impl PyPathSource {
             inner: PathSource {
                 path: Value::Concrete(path),
                 sha256: sha256.map(Value::Concrete),
+                use_gitignore: None,
+                filter: None,
+                target_directory: None,
             },
         }
     }
@@ -413,6 +423,16 @@ impl PyPathSource {
             .clone()
             .and_then(|v| v.concrete().cloned())
     }
+
+    #[getter]
+    pub fn use_gitignore(&self) -> Option<bool> {
+        self.inner.use_gitignore
+    }
+
+    #[getter]
+    pub fn target_directory(&self) -> Option<String> {
+        self.inner.target_directory.as_ref().map(ToString::to_string)
+    }
 }
 
 create_py_wrap!(PyOptionValueU64, Option<PyValueU64>, |opt: &Option<
@@ -427,6 +447,17 @@ create_py_wrap!(PyOptionValueU64, Option<PyValueU64>, |opt: &Option<
     }
 });
 
+create_py_wrap!(
+    PyOptionValueString,
+    Option<PyValueString>,
+    |opt: &Option<PyValueString>, f: &mut Formatter<'_>| {
+        match opt {
+            Some(value) => write!(f, "{}", value),
+            None => write!(f, "None"),
+        }
+    }
+);
+
 create_py_wrap!(
     PyOptionPyNoArchKind,
     Option<PyNoArchKind>,
@@ -442,9 +473,13 @@ create_py_wrap!(
 #[derive(Clone, Serialize, Deserialize)]
 pub struct PyBuild {
     pub number: Py<PyOptionValueU64>,
+    pub string: Py<PyOptionValueString>,
     pub script: Py<PyScript>,
     pub noarch: Py<PyOptionPyNoArchKind>,
     pub python: Py<PyPython>,
+    pub merge_build_and_host_envs: bool,
+    pub always_copy_files: Vec<String>,
+    pub dynamic_linking: Py<PyDynamicLinking>,
 }
 
 impl PyBuild {
@@ -463,9 +498,18 @@ impl PyBuild {
                 .clone()
                 .as_ref()
                 .map(|n| n.deref().clone()),
+            string: self
+                .string
+                .borrow(py)
+                .clone()
+                .as_ref()
+                .map(|s| s.deref().clone()),
             script: self.script.borrow(py).clone().into_script(py),
             noarch,
             python: self.python.borrow(py).inner.clone(),
+            merge_build_and_host_envs: self.merge_build_and_host_envs,
+            always_copy_files: self.always_copy_files,
+            dynamic_linking: self.dynamic_linking.borrow(py).inner.clone(),
         }
     }
 
@@ -473,15 +517,22 @@ impl PyBuild {
         let py_value = build.number.map(PyValueU64::from);
         let py_value: PyOptionValueU64 = py_value.into();
 
+        let py_string = build.string.map(PyValueString::from);
+        let py_string: PyOptionValueString = py_string.into();
+
         let py_noarch = build.noarch.map(PyNoArchKind::from);
 
         let py_noarch_value: PyOptionPyNoArchKind = py_noarch.into();
 
         PyBuild {
             number: Py::new(py, py_value).unwrap(),
+            string: Py::new(py, py_string).unwrap(),
             script: Py::new(py, PyScript::from_script(py, build.script)).unwrap(),
             noarch: Py::new(py, py_noarch_value).unwrap(),
             python: Py::new(py, Into::<PyPython>::into(build.python)).unwrap(),
+            merge_build_and_host_envs: build.merge_build_and_host_envs,
+            always_copy_files: build.always_copy_files,
+            dynamic_linking: Py::new(py, PyDynamicLinking::from(build.dynamic_linking)).unwrap(),
         }
     }
 }
@@ -492,9 +543,13 @@ impl PyBuild {
     pub fn new(py: Python) -> Self {
         PyBuild {
             number: Py::new(py, PyOptionValueU64::default()).unwrap(),
+            string: Py::new(py, PyOptionValueString::default()).unwrap(),
             script: Py::new(py, PyScript::new(py, None, None, None)).unwrap(),
             noarch: Py::new(py, PyOptionPyNoArchKind::default()).unwrap(),
             python: Py::new(py, PyPython::new(None).unwrap()).unwrap(),
+            merge_build_and_host_envs: false,
+            always_copy_files: Vec::new(),
+            dynamic_linking: Py::new(py, PyDynamicLinking::new()).unwrap(),
         }
     }
 }
@@ -503,8 +558,15 @@ impl Display for PyBuild {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{{ number: {}, script: {}, noarch: {}, python: {} }}",
-            self.number, self.script, self.noarch, self.python
+            "{{ number: {}, string: {}, script: {}, noarch: {}, python: {}, merge_build_and_host_envs: {}, always_copy_files: [{}], dynamic_linking: {} }}",
+            self.number,
+            self.string,
+            self.script,
+            self.noarch,
+            self.python,
+            self.merge_build_and_host_envs,
+            self.always_copy_files.join(", "),
+            self.dynamic_linking
         )
     }
 }
@@ -648,6 +710,69 @@ impl Display for PyPython {
     }
 }
 
+#[pyclass(str)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct PyDynamicLinking {
+    pub(crate) inner: DynamicLinking,
+}
+
+#[pymethods]
+impl PyDynamicLinking {
+    #[new]
+    pub fn new() -> Self {
+        PyDynamicLinking::default()
+    }
+
+    #[getter]
+    pub fn rpaths(&self) -> Vec<String> {
+        self.inner.rpaths.clone()
+    }
+
+    #[getter]
+    pub fn missing_dso_allowlist(&self) -> Vec<String> {
+        self.inner.missing_dso_allowlist.clone()
+    }
+
+    #[getter]
+    pub fn rpath_allowlist(&self) -> Vec<String> {
+        self.inner.rpath_allowlist.clone()
+    }
+
+    #[getter]
+    pub fn overdepending_behavior(&self) -> Option<String> {
+        self.inner
+            .overdepending_behavior
+            .map(|behavior| behavior.to_string())
+    }
+
+    #[getter]
+    pub fn overlinking_behavior(&self) -> Option<String> {
+        self.inner
+            .overlinking_behavior
+            .map(|behavior| behavior.to_string())
+    }
+}
+
+impl From<DynamicLinking> for PyDynamicLinking {
+    fn from(dynamic_linking: DynamicLinking) -> Self {
+        PyDynamicLinking {
+            inner: dynamic_linking,
+        }
+    }
+}
+
+impl Display for PyDynamicLinking {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{{ rpaths: [{}], missing_dso_allowlist: [{}], rpath_allowlist: [{}] }}",
+            self.inner.rpaths.join(", "),
+            self.inner.missing_dso_allowlist.join(", "),
+            self.inner.rpath_allowlist.join(", ")
+        )
+    }
+}
+
 #[pyclass(str)]
 #[derive(Clone, Serialize, Deserialize)]
 pub struct PyNoArchKind {
@@ -781,8 +906,11 @@ pub struct PyConditionalRequirements {
     pub(crate) run: Py<PyVecItemPackageDependency>,
     // #[serde(default)]
     pub(crate) run_constraints: Py<PyVecItemPackageDependency>,
+    pub(crate) run_exports: Py<PyRunExports>,
 }
 
+create_py_wrap!(PyRunExports, RunExports);
+
 #[pymethods]
 impl PyConditionalRequirements {
     #[new]
@@ -797,6 +925,7 @@ impl PyConditionalRequirements {
             host: Py::new(py, host).unwrap(),
             run: Py::new(py, run).unwrap(),
             run_constraints: Py::new(py, run_constraints).unwrap(),
+            run_exports: Py::new(py, PyRunExports::default()).unwrap(),
         }
     }
 
@@ -850,6 +979,7 @@ impl PyConditionalRequirements {
                 .clone()
                 .into_iter()
                 .collect(),
+            run_exports: self.run_exports.borrow(py).inner.clone(),
         }
     }
 
@@ -861,12 +991,14 @@ impl PyConditionalRequirements {
         let host: PyVecItemPackageDependency = requirements.host.into();
         let run: PyVecItemPackageDependency = requirements.run.into();
         let run_constraints: PyVecItemPackageDependency = requirements.run_constraints.into();
+        let run_exports: PyRunExports = requirements.run_exports.into();
 
         PyConditionalRequirements {
             build: Py::new(py, build).unwrap(),
             host: Py::new(py, host).unwrap(),
             run: Py::new(py, run).unwrap(),
             run_constraints: Py::new(py, run_constraints).unwrap(),
+            run_exports: Py::new(py, run_exports).unwrap(),
         }
     }
 }
@@ -914,6 +1046,14 @@ impl PyAbout {
             .and_then(|v| v.concrete().cloned())
     }
 
+    #[getter]
+    pub fn license_family(&self) -> Option<String> {
+        self.inner
+            .license_family
+            .clone()
+            .and_then(|v| v.concrete().cloned())
+    }
+
     #[getter]
     pub fn summary(&self) -> Option<String> {
         self.inner
@@ -975,6 +1115,11 @@ impl PyExtra {
             Ok(list.unbind())
         })
     }
+
+    #[getter]
+    pub fn keywords(&self) -> Vec<String> {
+        self.inner.keywords.clone()
+    }
 }
 
 impl From<Extra> for PyExtra {
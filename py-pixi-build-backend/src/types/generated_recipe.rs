@@ -1,20 +1,20 @@
 use std::collections::{BTreeSet, HashSet};
 
 use miette::IntoDiagnostic;
-use pixi_build_backend::generated_recipe::{
-    DefaultMetadataProvider, GenerateRecipe, GeneratedRecipe,
-};
 use pixi_build_backend::variants::NormalizedKey;
+use pixi_build_backend::{
+    error::BackendError,
+    generated_recipe::{DefaultMetadataProvider, GenerateRecipe, GeneratedRecipe},
+};
 use pyo3::{
-    Py, PyErr, PyObject, PyResult, Python,
-    exceptions::PyValueError,
-    pyclass, pymethods,
+    Py, PyObject, PyResult, Python, pyclass, pymethods,
     types::{PyAnyMethods, PyString},
 };
 use recipe_stage0::recipe::IntermediateRecipe;
 
 use crate::{
     create_py_wrap,
+    error::PyPixiBuildBackendError,
     recipe_stage0::recipe::PyIntermediateRecipe,
     types::{PyBackendConfig, PyPlatform, PyProjectModelV1, PyPythonParams},
 };
@@ -49,7 +49,7 @@ impl PyGeneratedRecipe {
     pub fn from_model(py: Python, model: PyProjectModelV1) -> PyResult<Self> {
         let generated_recipe =
             GeneratedRecipe::from_model(model.inner.clone(), &mut DefaultMetadataProvider)
-                .map_err(|e| PyErr::new::<PyValueError, _>(e.to_string()))?;
+                .map_err(|e| PyPixiBuildBackendError::from(BackendError::from(e)))?;
 
         let py_recipe = Py::new(
             py,
@@ -99,6 +99,7 @@ impl PyGeneratedRecipe {
             recipe,
             metadata_input_globs,
             build_input_globs,
+            ..Default::default()
         }
     }
 }
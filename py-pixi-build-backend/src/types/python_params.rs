@@ -19,7 +19,10 @@ impl PyPythonParams {
     #[pyo3(signature = (editable = false))]
     pub fn new(editable: bool) -> Self {
         PyPythonParams {
-            inner: PythonParams { editable },
+            inner: PythonParams {
+                editable,
+                ..Default::default()
+            },
         }
     }
 